@@ -0,0 +1,90 @@
+//! Allocation and timing benchmark for the work done on a single TUI keystroke.
+//!
+//! Run with `cargo bench --bench distribution`.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use wordlebot::solver::Solver;
+use wordlebot::wordle::{create_word_from_string, Guess, LetterStatus::*};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn measure<F: FnMut()>(name: &str, iterations: usize, mut f: F) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    let now = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    let elapsed = now.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes;
+    println!(
+        "{:<24} {:>10.2?}/iter {:>8} allocs/iter {:>12} bytes/iter",
+        name,
+        elapsed / iterations as u32,
+        allocations / iterations,
+        bytes / iterations
+    );
+}
+
+fn main() {
+    let solver = Solver::new().expect("Error initializing solver");
+    let guesses = [
+        Guess::new("slate", [Misplaced, Absent, Misplaced, Absent, Correct]),
+        Guess::new("crane", [Absent, Misplaced, Correct, Absent, Correct]),
+    ];
+
+    // Recomputing the evaluation of every entered guess is what the TUI does
+    // after each keystroke that changes a valid guess.
+    measure("keystroke evaluation", 20, || {
+        for i in 0..guesses.len() {
+            let remaining = solver.get_remaining_words_idx(&guesses[0..i]).unwrap();
+            solver
+                .evaluate_guess(
+                    &guesses[i].word,
+                    &remaining,
+                    Some(guesses[i].get_status()),
+                    false,
+                )
+                .unwrap();
+        }
+    });
+
+    let remaining = solver.get_remaining_words_idx(&guesses[0..1]).unwrap();
+    measure("suggestions", 3, || {
+        solver.guess(15, &remaining, 0.1).unwrap();
+    });
+
+    // Ranking all guesses for all answers, like before the first guess
+    let remaining = solver.get_frequent_word_idx();
+    measure("full suggestions", 3, || {
+        solver.guess(15, &remaining, 0.0).unwrap();
+    });
+
+    let word = create_word_from_string("slate");
+    measure("single evaluation", 20, || {
+        solver
+            .evaluate_guess(&word, &remaining, None, false)
+            .unwrap();
+    });
+}
@@ -0,0 +1,132 @@
+//! The version of wordlebot and of its word list, and the check for a newer
+//! release.
+//!
+//! Updates of the word list change the suggestions, so the About screen of
+//! the TUI shows a fingerprint of the words and priors next to the version.
+//! Looking for a newer release asks GitHub with `curl`, and only if it is
+//! enabled with `--check-updates`.
+use anyhow::{bail, Context, Result};
+use std::process::{Command, Stdio};
+
+use crate::json::{self, Value};
+use crate::solver::Solver;
+
+/// The version of this build
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The latest release on GitHub
+pub const RELEASES_URL: &str =
+    "https://api.github.com/repos/maximilian-heeg/wordle-solver/releases/latest";
+
+/// The lines of the release notes that are shown
+const MAX_NOTES: usize = 8;
+
+/// A release of wordlebot
+#[derive(Clone, Debug, PartialEq)]
+pub struct Release {
+    /// The version without a leading `v`, e.g. `0.3.0`
+    pub version: String,
+    /// The first lines of the release notes
+    pub notes: Vec<String>,
+}
+
+impl Release {
+    /// Read a release from the JSON of the GitHub API
+    pub fn parse(json: &str) -> Result<Release> {
+        let fields = json::parse_object(json).context("Invalid release")?;
+        let text = |key: &str| match fields.get(key) {
+            Some(Value::Str(text)) => Some(text.clone()),
+            _ => None,
+        };
+        let Some(tag) = text("tag_name") else {
+            bail!("The release has no tag")
+        };
+        let notes = text("body").unwrap_or_default();
+        Ok(Release {
+            version: tag.trim_start_matches('v').to_string(),
+            notes: notes
+                .lines()
+                .map(str::trim_end)
+                .filter(|line| !line.trim().is_empty())
+                .take(MAX_NOTES)
+                .map(String::from)
+                .collect(),
+        })
+    }
+
+    /// Whether the release is newer than this build
+    pub fn is_newer(&self) -> bool {
+        is_newer(&self.version, VERSION)
+    }
+}
+
+/// The latest release, fetched with `curl`
+pub fn latest_release() -> Result<Release> {
+    let output = Command::new("curl")
+        .args(["-fsSL", "--max-time", "10", RELEASES_URL])
+        .args(["-H", "Accept: application/vnd.github+json"])
+        .stdin(Stdio::null())
+        .output()
+        .context("Error running curl")?;
+    if !output.status.success() {
+        bail!(
+            "Error fetching the latest release: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    }
+    Release::parse(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// The word list of a solver for the About screen, e.g. `14855 words, 3189
+/// answers, fingerprint 1f2e3d4c5b6a7988`
+pub fn data_version(solver: &Solver) -> String {
+    format!(
+        "{} words, {} answers, fingerprint {:016x}",
+        solver.n_words(),
+        solver.get_frequent_word_idx().len(),
+        solver.fingerprint()
+    )
+}
+
+/// Whether the version `a` is newer than `b`. Versions are compared by
+/// their numbers, e.g. `0.10.0` is newer than `0.9.1`.
+pub fn is_newer(a: &str, b: &str) -> bool {
+    let numbers = |version: &str| -> Vec<u64> {
+        version
+            .split(['.', '-', '+'])
+            .map_while(|part| part.parse().ok())
+            .collect()
+    };
+    numbers(a) > numbers(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_release() {
+        let json = r#"{"url": "x", "author": {"login": "a"}, "assets": [{"size": 1}],
+            "tag_name" : "v0.3.0", "name": "0.3.0",
+            "body": "New word list\r\n\r\n- \"slate\" \u00e9\n- faster"}"#;
+        let release = Release::parse(json).unwrap();
+        assert_eq!(release.version, "0.3.0");
+        assert_eq!(
+            release.notes,
+            ["New word list", "- \"slate\" é", "- faster"]
+        );
+        assert!(Release::parse(r#"{"body": "x"}"#).is_err());
+        let release = Release::parse(r#"{"tag_name": "1.0"}"#).unwrap();
+        assert!(release.notes.is_empty());
+    }
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("0.10.0", "0.9.1"));
+        assert!(is_newer("0.2.1", "0.2.0"));
+        assert!(is_newer("1.0", "0.2.0"));
+        assert!(!is_newer("0.2.0", "0.2.0"));
+        assert!(!is_newer("0.1.9", "0.2.0"));
+        assert!(!is_newer("0.2.0-beta", "0.2.0"));
+    }
+}
@@ -0,0 +1,283 @@
+//! Defaults for the command line and the TUI from a config file.
+//!
+//! The file is read from `$XDG_CONFIG_HOME/wordle-solver/config.toml`, or
+//! `~/.config/wordle-solver/config.toml`. It is a flat TOML table, e.g.
+//!
+//! ```toml
+//! # Defaults of wordlebot
+//! starting_word = "slate"
+//! two_level = true
+//! max_rounds = 6
+//! penalty = [0.1, 0.5]
+//! theme = "high-contrast"
+//! wordlist = "~/words.tsv"
+//! eco = true
+//! max_cpu = 2
+//! uniform = false
+//! ```
+//!
+//! Every key is optional and the flags of the command line override it.
+use anyhow::{bail, Context, Result};
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// The defaults of the config file. `None` if the file does not set it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Config {
+    pub starting_word: Option<String>,
+    pub two_level: Option<bool>,
+    pub max_rounds: Option<usize>,
+    /// The penalty per round, see the `--penalty` flag
+    pub penalty: Option<Vec<f32>>,
+    /// The name of the color theme of the TUI
+    pub theme: Option<String>,
+    pub wordlist: Option<PathBuf>,
+    /// Save energy, see the `--eco` flag
+    pub eco: Option<bool>,
+    /// The number of worker threads
+    pub max_cpu: Option<usize>,
+    /// Ignore the priors, see the `--uniform` flag
+    pub uniform: Option<bool>,
+}
+
+/// A value of the TOML subset
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    Array(Vec<Value>),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Str(text) => write!(f, "{:?}", text),
+            Value::Num(n) => write!(f, "{}", n),
+            Value::Bool(on) => write!(f, "{}", on),
+            Value::Array(values) => {
+                let values: Vec<String> = values.iter().map(Value::to_string).collect();
+                write!(f, "[{}]", values.join(", "))
+            }
+        }
+    }
+}
+
+impl Config {
+    /// The path of the config file in the user's config directory
+    pub fn path() -> Option<PathBuf> {
+        let dir = match env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+        };
+        Some(dir.join("wordle-solver").join("config.toml"))
+    }
+
+    /// Read a config file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Config> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Error reading config {}", path.display()))?;
+        text.parse()
+            .with_context(|| format!("Error parsing config {}", path.display()))
+    }
+
+    /// Read the config file in the user's config directory. Without a file
+    /// nothing is configured.
+    pub fn load_default() -> Result<Config> {
+        match Config::path().filter(|path| path.is_file()) {
+            Some(path) => Config::load(path),
+            None => Ok(Config::default()),
+        }
+    }
+
+    fn set(&mut self, key: &str, value: Value) -> Result<()> {
+        match (key, value) {
+            ("starting_word", Value::Str(word)) => self.starting_word = Some(word),
+            ("two_level", Value::Bool(on)) => self.two_level = Some(on),
+            ("max_rounds", Value::Num(n)) if n >= 1.0 && n.fract() == 0.0 => {
+                self.max_rounds = Some(n as usize)
+            }
+            ("penalty", Value::Num(p)) => self.penalty = Some(vec![p as f32]),
+            ("penalty", Value::Array(values)) if !values.is_empty() => {
+                let penalty = values
+                    .into_iter()
+                    .map(|value| match value {
+                        Value::Num(p) => Ok(p as f32),
+                        _ => bail!("The penalty must be a list of numbers"),
+                    })
+                    .collect::<Result<Vec<f32>>>()?;
+                self.penalty = Some(penalty)
+            }
+            ("theme", Value::Str(name)) => self.theme = Some(name),
+            ("wordlist", Value::Str(path)) => self.wordlist = Some(expand_home(&path)),
+            ("eco", Value::Bool(on)) => self.eco = Some(on),
+            ("max_cpu", Value::Num(n)) if n >= 1.0 && n.fract() == 0.0 => {
+                self.max_cpu = Some(n as usize)
+            }
+            ("uniform", Value::Bool(on)) => self.uniform = Some(on),
+            (
+                "starting_word" | "two_level" | "max_rounds" | "penalty" | "theme" | "wordlist"
+                | "eco" | "max_cpu" | "uniform",
+                value,
+            ) => bail!("Invalid value {} for {}", value, key),
+            _ => bail!("Unknown key '{}'", key),
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Config {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut config = Config::default();
+        parse_table(s, |key, value| config.set(key, value))?;
+        Ok(config)
+    }
+}
+
+/// Parse a flat TOML table and pass every key and value to `set`, e.g. the
+/// manifest of a word pack. Errors name the line.
+pub(crate) fn parse_table(s: &str, mut set: impl FnMut(&str, Value) -> Result<()>) -> Result<()> {
+    for (i, line) in s.lines().enumerate() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut parse = || -> Result<()> {
+            if line.starts_with('[') {
+                bail!("Tables are not supported, only key = value")
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                bail!("Expected key = value, got '{}'", line)
+            };
+            set(key.trim(), parse_value(value.trim())?)
+        };
+        parse().with_context(|| format!("Line {}", i + 1))?;
+    }
+    Ok(())
+}
+
+/// The line without a `#` comment outside of strings
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(s: &str) -> Result<Value> {
+    if let Some(rest) = s.strip_prefix('"') {
+        let mut text = String::new();
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if chars.as_str().trim().is_empty() => return Ok(Value::Str(text)),
+                '"' => bail!("Unexpected text after the string {}", s),
+                '\\' => match chars.next() {
+                    Some('"') => text.push('"'),
+                    Some('\\') => text.push('\\'),
+                    _ => bail!("Invalid escape in {}", s),
+                },
+                _ => text.push(c),
+            }
+        }
+        bail!("The string {} is not closed", s)
+    }
+    if let Some(inner) = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+        return inner
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(parse_value)
+            .collect::<Result<Vec<Value>>>()
+            .map(Value::Array);
+    }
+    match s {
+        "true" => Ok(Value::Bool(true)),
+        "false" => Ok(Value::Bool(false)),
+        _ => match s.parse::<f64>() {
+            Ok(n) if n.is_finite() => Ok(Value::Num(n)),
+            _ => bail!("Invalid value {}", s),
+        },
+    }
+}
+
+/// A path with a leading `~/` in the home directory
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let text = r#"
+# Defaults
+starting_word = "slate" # the opener
+two_level = true
+max_rounds = 8
+penalty = [0.1, 0.5,]
+theme = "high # contrast"
+wordlist = "words.tsv"
+eco = false
+max_cpu = 2
+uniform = true
+"#;
+        let config: Config = text.parse().unwrap();
+        assert_eq!(
+            config,
+            Config {
+                starting_word: Some("slate".to_string()),
+                two_level: Some(true),
+                max_rounds: Some(8),
+                penalty: Some(vec![0.1, 0.5]),
+                theme: Some("high # contrast".to_string()),
+                wordlist: Some(PathBuf::from("words.tsv")),
+                eco: Some(false),
+                max_cpu: Some(2),
+                uniform: Some(true),
+            }
+        );
+        let config: Config = "penalty = 0.3".parse().unwrap();
+        assert_eq!(config.penalty, Some(vec![0.3]));
+        assert_eq!("".parse::<Config>().unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_invalid() {
+        for text in [
+            "[solver]",
+            "two_level",
+            "two_level = yes",
+            "max_rounds = 0",
+            "max_rounds = 2.5",
+            "max_cpu = 0",
+            "penalty = []",
+            "penalty = [0.1, true]",
+            "starting_word = \"slate",
+            "starting_word = \"sl\"ate\"",
+            "starting_wrd = \"slate\"",
+        ] {
+            assert!(text.parse::<Config>().is_err(), "{}", text);
+        }
+        let error = "\n\nmax_rounds = 0".parse::<Config>().unwrap_err();
+        assert_eq!(error.to_string(), "Line 3");
+    }
+}
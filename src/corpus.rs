@@ -0,0 +1,164 @@
+//! A regression corpus of the patterns of words with repeated letters.
+//!
+//! Repeated letters are where the coloring goes wrong most easily: a
+//! letter of the guess is only misplaced as often as the answer has it
+//! left over. The corpus holds the pattern of every answer and guess of
+//! [`DUPLICATE_LETTER_WORDS`] with the same length, one `ANSWER GUESS
+//! PATTERN` per line, e.g. `geese eerie ygbbg`. The fixture
+//! `tests/duplicate_letters.txt` is checked by the tests, and `wordlebot
+//! corpus` shows how it changes or writes it again after an intended
+//! change.
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::wordle::*;
+
+/// Words with repeated letters in every arrangement: twice, three times,
+/// next to each other and apart
+pub const DUPLICATE_LETTER_WORDS: &[&str] = &[
+    "abbey", "added", "alley", "array", "banal", "belle", "berry", "bobby", "cocoa", "daddy",
+    "eagle", "eerie", "elope", "emcee", "error", "fluff", "geese", "gorge", "kayak", "level",
+    "lever", "llama", "mamma", "mommy", "nanny", "radar", "revel", "sassy", "sense", "sissy",
+    "sleet", "speed", "steel", "teeth", "tepee", "vivid",
+];
+
+/// The corpus of the words, see the [module](self)
+pub fn generate(words: &[Word]) -> String {
+    let mut corpus = String::from(
+        "# The pattern of every answer and guess, see src/corpus.rs.\n\
+         # Regenerate with `wordlebot corpus --write`.\n",
+    );
+    for answer in words {
+        for guess in words.iter().filter(|w| w.n_letters() == answer.n_letters()) {
+            let pattern = pattern_to_string(answer.pattern(guess), guess.n_letters());
+            corpus.push_str(&format!("{} {} {}\n", answer, guess, pattern).to_lowercase());
+        }
+    }
+    corpus
+}
+
+/// The corpus of [`DUPLICATE_LETTER_WORDS`]
+pub fn generate_default() -> String {
+    let words: Vec<Word> = DUPLICATE_LETTER_WORDS
+        .iter()
+        .map(|w| create_word_from_string(w))
+        .collect();
+    generate(&words)
+}
+
+/// The patterns of a corpus by answer and guess. Comments and lines that
+/// can not be read are skipped.
+fn entries(corpus: &str) -> BTreeMap<(&str, &str), &str> {
+    corpus
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(
+            |line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [answer, guess, pattern] => Some(((answer, guess), pattern)),
+                _ => None,
+            },
+        )
+        .collect()
+}
+
+/// The pairs whose pattern differs between two corpora, e.g. `geese eerie:
+/// ybbyg -> bbbyg`
+pub fn diff(old: &str, new: &str) -> Vec<String> {
+    let old = entries(old);
+    let new = entries(new);
+    let mut pairs: Vec<&(&str, &str)> = old.keys().chain(new.keys()).collect();
+    pairs.sort();
+    pairs.dedup();
+    pairs
+        .into_iter()
+        .filter_map(|pair| match (old.get(pair), new.get(pair)) {
+            (Some(a), Some(b)) if a == b => None,
+            (a, b) => Some(format!(
+                "{} {}: {} -> {}",
+                pair.0,
+                pair.1,
+                a.unwrap_or(&"missing"),
+                b.unwrap_or(&"missing")
+            )),
+        })
+        .collect()
+}
+
+/// Check every line of a corpus against [`Word::compare`] and
+/// [`Word::is_valid`]: the answer must get the pattern, and of all words of
+/// the corpus exactly those that get the same pattern must be valid for
+/// the guess. Returns a description of every problem.
+pub fn verify(corpus: &str) -> Vec<String> {
+    let entries = entries(corpus);
+    let words: Vec<Word> = entries
+        .keys()
+        .map(|&(answer, _)| answer)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter_map(|answer| parse_word(answer).ok())
+        .collect();
+    let mut problems = vec![];
+    for (&(answer, guess), &pattern) in &entries {
+        let (Ok(answer), Ok(word)) = (parse_word(answer), parse_word(guess)) else {
+            problems.push(format!("{} {}: invalid word", answer, guess));
+            continue;
+        };
+        let Ok(pattern) = parse_pattern(pattern) else {
+            problems.push(format!("{} {}: invalid pattern {}", answer, word, pattern));
+            continue;
+        };
+        let status = decode_status(pattern, word.n_letters());
+        if answer.compare(&word) != status {
+            problems.push(format!(
+                "{} {}: compare gives {}, not {}",
+                answer,
+                word,
+                pattern_to_string(answer.pattern(&word), word.n_letters()),
+                pattern_to_string(pattern, word.n_letters())
+            ));
+        }
+        let guess = Guess::from_word(word, &status);
+        for other in words.iter().filter(|w| w.n_letters() == word.n_letters()) {
+            if other.is_valid(&guess) != (other.pattern(&word) == pattern) {
+                problems.push(format!(
+                    "{} {}: is_valid of {} is {}",
+                    answer,
+                    word,
+                    other,
+                    other.is_valid(&guess)
+                ));
+            }
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../tests/duplicate_letters.txt");
+
+    #[test]
+    fn test_fixture() {
+        // Run `wordlebot corpus` to see what changed
+        assert_eq!(diff(FIXTURE, &generate_default()), Vec::<String>::new());
+        assert_eq!(verify(FIXTURE), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = "# comment\ngeese eerie ybbyg\nsense geese bgbbg\n";
+        let new = "geese eerie bbbyg\nsense sense ggggg\n";
+        assert_eq!(
+            diff(old, new),
+            [
+                "geese eerie: ybbyg -> bbbyg",
+                "sense geese: bgbbg -> missing",
+                "sense sense: missing -> ggggg",
+            ]
+        );
+        assert_eq!(diff(old, old), Vec::<String>::new());
+        // A wrong pattern is found
+        assert_eq!(verify("geese eerie ggggg\n").len(), 1);
+    }
+}
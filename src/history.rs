@@ -0,0 +1,446 @@
+//! The history of the games played, for the statistics of `wordlebot stats`
+//! and the Stats screen of the TUI.
+//!
+//! Every finished game is appended to a local file as a line of JSON, e.g.
+//! `{"date":"2024-05-01","mode":"tui","answer":"crane","guesses":["slate:bybbb","crane:ggggg"],"solved":true}`.
+//! A practice game with a seed also records it, e.g. `"seed":42,"game":3`.
+//! Unlike the telemetry, the answer and the guesses are recorded, and
+//! nothing is ever sent anywhere.
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::json::{parse_object, Value};
+use crate::telemetry::today;
+use crate::wordle::{parse_guess, parse_word, pattern_to_string, Guess, Word, MAX_GUESSES};
+
+/// Openers are only ranked after this many games
+pub const MIN_OPENER_GAMES: usize = 2;
+
+/// A finished game
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameRecord {
+    /// The date the game was played as YYYY-MM-DD
+    pub date: String,
+    /// How the game was played, e.g. `play-random` in the TUI or `tui` and
+    /// `solve` for a game on another site
+    pub mode: String,
+    pub answer: Word,
+    /// The guesses that used a turn, with their patterns
+    pub guesses: Vec<Guess>,
+    /// Whether the answer was found in time
+    pub solved: bool,
+    /// The seed of the practice session the answer was drawn in
+    pub session: Option<SessionGame>,
+}
+
+/// A game of a practice session with a seed: the same seed draws the same
+/// answers in the same order, so e.g. game 3 of seed 42 can be shared
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SessionGame {
+    pub seed: u32,
+    /// The number of the game in the session, from 1
+    pub game: usize,
+}
+
+impl fmt::Display for SessionGame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "seed {}, game {}", self.seed, self.game)
+    }
+}
+
+impl GameRecord {
+    /// A game played today. It is solved if the last guess is the answer
+    /// within `MAX_GUESSES` guesses.
+    pub fn today(mode: &str, answer: Word, guesses: &[Guess]) -> GameRecord {
+        let solved = guesses.len() <= MAX_GUESSES && guesses.last().map(|g| g.word) == Some(answer);
+        GameRecord {
+            date: today(),
+            mode: mode.to_string(),
+            answer,
+            guesses: guesses.to_vec(),
+            solved,
+            session: None,
+        }
+    }
+
+    /// The number of turns the game took
+    pub fn turns(&self) -> usize {
+        self.guesses.len()
+    }
+
+    pub fn to_json(&self) -> String {
+        let guesses: Vec<String> = self
+            .guesses
+            .iter()
+            .map(|g| {
+                let pattern = pattern_to_string(g.status, g.word.n_letters());
+                format!(r#""{}:{}""#, word(g.word), pattern.to_lowercase())
+            })
+            .collect();
+        let session = self.session.map_or(String::new(), |session| {
+            format!(r#","seed":{},"game":{}"#, session.seed, session.game)
+        });
+        format!(
+            r#"{{"date":"{}","mode":"{}","answer":"{}","guesses":[{}],"solved":{}{}}}"#,
+            self.date,
+            self.mode,
+            word(self.answer),
+            guesses.join(","),
+            self.solved,
+            session
+        )
+    }
+
+    /// Parse a line of the history. Only the flat objects that
+    /// [`GameRecord::to_json`] writes are read.
+    pub fn parse(line: &str) -> Result<GameRecord> {
+        let mut fields = parse_object(line)?;
+        let (seed, game) = (fields.remove("seed"), fields.remove("game"));
+        let mut field = |key: &str| {
+            fields
+                .remove(key)
+                .with_context(|| format!("Missing {}", key))
+        };
+        let text = |value: Value| match value {
+            Value::Str(text) => Ok(text),
+            _ => bail!("Expected a string"),
+        };
+        let guesses = match field("guesses")? {
+            Value::Array(guesses) => guesses
+                .into_iter()
+                .map(|guess| parse_guess(&text(guess)?))
+                .collect::<Result<Vec<_>>>()?,
+            _ => bail!("Expected the guesses as an array"),
+        };
+        let number = |value: Option<Value>, key: &str| match value {
+            Some(Value::Number(n)) if n >= 0.0 && n.fract() == 0.0 => Ok(Some(n)),
+            None => Ok(None),
+            _ => bail!("Expected {} to be a whole number", key),
+        };
+        let session = match (number(seed, "seed")?, number(game, "game")?) {
+            (Some(seed), Some(game)) => Some(SessionGame {
+                seed: seed as u32,
+                game: game as usize,
+            }),
+            _ => None,
+        };
+        Ok(GameRecord {
+            date: text(field("date")?)?,
+            mode: text(field("mode")?)?,
+            answer: parse_word(&text(field("answer")?)?)?,
+            guesses,
+            solved: match field("solved")? {
+                Value::Bool(solved) => solved,
+                _ => bail!("Expected solved to be true or false"),
+            },
+            session,
+        })
+    }
+}
+
+fn word(word: Word) -> String {
+    word.to_string().to_lowercase()
+}
+
+/// Appends finished games to a local file
+#[derive(Clone, Debug)]
+pub struct History {
+    path: PathBuf,
+}
+
+impl History {
+    pub fn new(path: PathBuf) -> History {
+        History { path }
+    }
+
+    /// The history in the user's data directory,
+    /// `$XDG_DATA_HOME/wordle-solver/history.jsonl` or
+    /// `~/.local/share/wordle-solver/history.jsonl`
+    pub fn default_path() -> Option<PathBuf> {
+        let dir = match env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(env::var_os("HOME")?)
+                .join(".local")
+                .join("share"),
+        };
+        Some(dir.join("wordle-solver").join("history.jsonl"))
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    pub fn record(&self, game: &GameRecord) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Error creating directory {}", dir.display()))?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Error opening history {}", self.path.display()))?;
+        writeln!(file, "{}", game.to_json()).context("Error writing history")
+    }
+
+    /// The recorded games, the oldest first. Without a file no game was
+    /// played yet.
+    pub fn read(&self) -> Result<Vec<GameRecord>> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+        let text = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Error reading history {}", self.path.display()))?;
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| GameRecord::parse(line).with_context(|| format!("Line {}", i + 1)))
+            .collect()
+    }
+}
+
+/// How the games with an opener went
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpenerRecord {
+    pub opener: Word,
+    pub games: usize,
+    pub won: usize,
+    /// The average number of turns, a lost game counts as one turn more
+    /// than allowed
+    pub mean_turns: f64,
+}
+
+/// The statistics of the recorded games
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Summary {
+    pub played: usize,
+    pub won: usize,
+    /// The games won in a row up to the latest one
+    pub streak: usize,
+    pub max_streak: usize,
+    /// The number of games won in 1 to `MAX_GUESSES` turns
+    pub distribution: Vec<usize>,
+    /// The average number of turns of the games won
+    pub mean_turns: Option<f64>,
+    /// The openers of at least [`MIN_OPENER_GAMES`] games, the best first
+    pub openers: Vec<OpenerRecord>,
+}
+
+impl Summary {
+    pub fn new(games: &[GameRecord]) -> Summary {
+        let mut summary = Summary {
+            distribution: vec![0; MAX_GUESSES],
+            ..Summary::default()
+        };
+        let mut streak = 0;
+        let mut turns = 0;
+        let mut openers: HashMap<Word, (usize, usize, usize)> = HashMap::new();
+        for game in games {
+            summary.played += 1;
+            if game.solved {
+                summary.won += 1;
+                summary.distribution[game.turns() - 1] += 1;
+                turns += game.turns();
+                streak += 1;
+                summary.max_streak = summary.max_streak.max(streak);
+            } else {
+                streak = 0;
+            }
+            if let Some(opener) = game.guesses.first() {
+                let entry = openers.entry(opener.word).or_default();
+                entry.0 += 1;
+                entry.1 += usize::from(game.solved);
+                entry.2 += match game.solved {
+                    true => game.turns(),
+                    false => MAX_GUESSES + 1,
+                };
+            }
+        }
+        summary.streak = streak;
+        summary.mean_turns = (summary.won > 0).then(|| turns as f64 / summary.won as f64);
+        summary.openers = openers
+            .into_iter()
+            .filter(|(_, (games, _, _))| *games >= MIN_OPENER_GAMES)
+            .map(|(opener, (games, won, turns))| OpenerRecord {
+                opener,
+                games,
+                won,
+                mean_turns: turns as f64 / games as f64,
+            })
+            .collect();
+        // The most played first among openers that are equally good
+        summary.openers.sort_by(|a, b| {
+            a.mean_turns
+                .total_cmp(&b.mean_turns)
+                .then(b.games.cmp(&a.games))
+                .then(a.opener.to_string().cmp(&b.opener.to_string()))
+        });
+        summary
+    }
+
+    /// The share of the games that were won in percent, 0 without games
+    pub fn win_rate(&self) -> usize {
+        100 * self.won / self.played.max(1)
+    }
+
+    /// The best and the worst openers, at most `n` of each. An opener is
+    /// only among the worst if it is not among the best.
+    pub fn best_and_worst_openers(&self, n: usize) -> (&[OpenerRecord], &[OpenerRecord]) {
+        let best = n.min(self.openers.len());
+        let worst = n.min(self.openers.len() - best);
+        (
+            &self.openers[..best],
+            &self.openers[self.openers.len() - worst..],
+        )
+    }
+}
+
+impl fmt::Display for OpenerRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}  {:.2} turns in {} games, {} won",
+            self.opener, self.mean_turns, self.games, self.won
+        )
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Played {}  Won {}%  Streak {}  Best streak {}",
+            self.played,
+            self.win_rate(),
+            self.streak,
+            self.max_streak
+        )?;
+        if let Some(mean) = self.mean_turns {
+            writeln!(f, "Average turns of the games won: {:.2}", mean)?;
+        }
+        let most = self.distribution.iter().copied().max().unwrap_or(0).max(1);
+        for (i, &count) in self.distribution.iter().enumerate() {
+            let bar = "#".repeat((30 * count).div_ceil(most));
+            writeln!(f, "{} {:<30} {}", i + 1, bar, count)?;
+        }
+        let (best, worst) = self.best_and_worst_openers(3);
+        if best.is_empty() {
+            return write!(
+                f,
+                "Openers are ranked after {} games with them",
+                MIN_OPENER_GAMES
+            );
+        }
+        writeln!(f, "Best openers:")?;
+        for opener in best {
+            writeln!(f, "  {}", opener)?;
+        }
+        if !worst.is_empty() {
+            writeln!(f, "Worst openers:")?;
+            for opener in worst {
+                writeln!(f, "  {}", opener)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(guesses: &[&str]) -> GameRecord {
+        let guesses: Vec<Guess> = guesses.iter().map(|g| g.parse().unwrap()).collect();
+        let answer = guesses.last().unwrap().word;
+        let mut game = GameRecord::today("tui", answer, &guesses);
+        game.date = "2024-05-01".to_string();
+        game
+    }
+
+    #[test]
+    fn test_record_roundtrip() {
+        let record = game(&["slate:bybbb", "crane:ggggg"]);
+        assert!(record.solved);
+        assert_eq!(
+            record.to_json(),
+            r#"{"date":"2024-05-01","mode":"tui","answer":"crane","guesses":["slate:bybbb","crane:ggggg"],"solved":true}"#
+        );
+        assert_eq!(GameRecord::parse(&record.to_json()).unwrap(), record);
+        // The order of the fields and the spaces do not matter
+        let line = r#"{ "solved": false, "guesses": [], "answer": "crane", "mode": "play-random", "date": "2024-05-02" }"#;
+        let lost = GameRecord::parse(line).unwrap();
+        assert_eq!((lost.solved, lost.turns()), (false, 0));
+
+        let mut seeded = record.clone();
+        seeded.session = Some(SessionGame { seed: 42, game: 3 });
+        assert!(seeded
+            .to_json()
+            .ends_with(r#""solved":true,"seed":42,"game":3}"#));
+        assert_eq!(GameRecord::parse(&seeded.to_json()).unwrap(), seeded);
+        assert_eq!(seeded.session.unwrap().to_string(), "seed 42, game 3");
+
+        assert!(GameRecord::parse(r#"{"date":"2024-05-01"}"#).is_err());
+        assert!(GameRecord::parse("date").is_err());
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut lost = game(&["slate:bybbb", "crane:bbbbb"]);
+        lost.answer = parse_word("quirk").unwrap();
+        lost.solved = false;
+        let games = vec![
+            game(&["slate:bybbb", "crane:ggggg"]),
+            game(&["slate:bybbb", "crane:gybbb", "crate:ggggg"]),
+            lost,
+            game(&["tarse:bybbb", "crane:ggggg"]),
+            game(&["tarse:bybbb", "crane:ggggg"]),
+        ];
+        let summary = Summary::new(&games);
+        assert_eq!(
+            (summary.played, summary.won, summary.win_rate()),
+            (5, 4, 80)
+        );
+        assert_eq!((summary.streak, summary.max_streak), (2, 2));
+        assert_eq!(summary.distribution, [0, 3, 1, 0, 0, 0]);
+        assert_eq!(summary.mean_turns, Some(2.25));
+        let openers: Vec<(String, usize, f64)> = summary
+            .openers
+            .iter()
+            .map(|o| (o.opener.to_string(), o.games, o.mean_turns))
+            .collect();
+        assert_eq!(
+            openers,
+            [("TARSE".to_string(), 2, 2.0), ("SLATE".to_string(), 3, 4.0)]
+        );
+        let (best, worst) = summary.best_and_worst_openers(1);
+        assert_eq!(
+            (best[0].opener, worst[0].opener),
+            (games[3].guesses[0].word, games[0].guesses[0].word)
+        );
+        let (best, worst) = summary.best_and_worst_openers(3);
+        assert_eq!((best.len(), worst.len()), (2, 0));
+
+        let empty = Summary::new(&[]);
+        assert_eq!(
+            (empty.played, empty.win_rate(), empty.mean_turns),
+            (0, 0, None)
+        );
+    }
+
+    #[test]
+    fn test_history_file() {
+        let path = env::temp_dir().join(format!("wordlebot-history-{}.jsonl", std::process::id()));
+        let history = History::new(path.clone());
+        assert!(history.read().unwrap().is_empty());
+        let record = game(&["slate:ggggg"]);
+        history.record(&record).unwrap();
+        history.record(&record).unwrap();
+        assert_eq!(history.read().unwrap(), vec![record.clone(), record]);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,115 @@
+//! Normalized key input of the TUI.
+//!
+//! Terminals report the same key in different ways. On Windows every key
+//! is reported when it is pressed and again when it is released, AltGr
+//! arrives as Ctrl+Alt, Ctrl+letter may arrive as an ASCII control
+//! character, and Backspace may arrive as `\x08` or `\x7f`. [`Key::from_event`]
+//! maps all of them to the same [`Key`], so the key bindings only have to
+//! handle one form.
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+/// A key press, independent of how the terminal reported it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+    /// A character without Ctrl or Alt, including characters typed with
+    /// AltGr
+    Char(char),
+    /// Ctrl and a lowercase letter
+    Ctrl(char),
+    Enter,
+    Esc,
+    Tab,
+    BackTab,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    ShiftLeft,
+    ShiftRight,
+    PageUp,
+    PageDown,
+    F(u8),
+}
+
+impl Key {
+    /// The key of a terminal event, or `None` for events that are not a
+    /// key press, e.g. key releases
+    pub fn from_event(event: &Event) -> Option<Key> {
+        match event {
+            Event::Key(key) => Key::from_key_event(key),
+            _ => None,
+        }
+    }
+
+    /// The key of a key event. Repeats of held keys only count for keys
+    /// that enter or delete letters or move the cursor, so holding a toggle
+    /// does not flicker.
+    pub fn from_key_event(event: &KeyEvent) -> Option<Key> {
+        let key = normalize(event.code, event.modifiers)?;
+        match event.kind {
+            KeyEventKind::Press => Some(key),
+            KeyEventKind::Repeat if key.repeats() => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Whether holding the key repeats it
+    pub fn repeats(&self) -> bool {
+        matches!(
+            self,
+            Key::Char(_)
+                | Key::Backspace
+                | Key::Delete
+                | Key::Left
+                | Key::Right
+                | Key::Up
+                | Key::Down
+                | Key::ShiftLeft
+                | Key::ShiftRight
+                | Key::PageUp
+                | Key::PageDown
+        )
+    }
+}
+
+fn normalize(code: KeyCode, modifiers: KeyModifiers) -> Option<Key> {
+    let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+    let alt = modifiers.contains(KeyModifiers::ALT);
+    let shift = modifiers.contains(KeyModifiers::SHIFT);
+    let key = match code {
+        // Control characters instead of the keys
+        KeyCode::Char('\r' | '\n') => Key::Enter,
+        KeyCode::Char('\t') => Key::Tab,
+        KeyCode::Char('\x1b') => Key::Esc,
+        KeyCode::Char('\x08' | '\x7f') => Key::Backspace,
+        // Ctrl+A to Ctrl+Z as ASCII control characters
+        KeyCode::Char(c @ '\x01'..='\x1a') => Key::Ctrl((c as u8 - 1 + b'a') as char),
+        // AltGr is reported as Ctrl+Alt, e.g. for [ on a German keyboard
+        KeyCode::Char(c) if ctrl && alt && !c.is_ascii_alphabetic() => Key::Char(c),
+        KeyCode::Char(c) if ctrl && !alt && c.is_ascii_alphabetic() => {
+            Key::Ctrl(c.to_ascii_lowercase())
+        }
+        KeyCode::Char(_) if ctrl || alt => return None,
+        KeyCode::Char(c) => Key::Char(c),
+        KeyCode::Enter => Key::Enter,
+        KeyCode::Esc => Key::Esc,
+        KeyCode::Tab if shift => Key::BackTab,
+        KeyCode::Tab => Key::Tab,
+        KeyCode::BackTab => Key::BackTab,
+        KeyCode::Backspace => Key::Backspace,
+        KeyCode::Delete => Key::Delete,
+        KeyCode::Left if shift => Key::ShiftLeft,
+        KeyCode::Right if shift => Key::ShiftRight,
+        KeyCode::Left => Key::Left,
+        KeyCode::Right => Key::Right,
+        KeyCode::Up => Key::Up,
+        KeyCode::Down => Key::Down,
+        KeyCode::PageUp => Key::PageUp,
+        KeyCode::PageDown => Key::PageDown,
+        KeyCode::F(n) => Key::F(n),
+        _ => return None,
+    };
+    Some(key)
+}
@@ -0,0 +1,233 @@
+//! A minimal reader and writer of JSON, for the flat objects of the history
+//! and `wordlebot pipe`, the releases of the about screen and the guesses of
+//! the JavaScript bindings.
+use anyhow::{bail, Context, Result};
+use std::collections::{BTreeMap, HashMap};
+
+/// A value of an object
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Str(String),
+    Number(f64),
+    Bool(bool),
+    Null,
+    Array(Vec<Value>),
+    Object(HashMap<String, Value>),
+}
+
+impl Value {
+    pub fn to_json(&self) -> String {
+        match self {
+            Value::Str(text) => string(text),
+            Value::Number(number) => number.to_string(),
+            Value::Bool(value) => value.to_string(),
+            Value::Null => "null".to_string(),
+            Value::Array(values) => {
+                let values: Vec<String> = values.iter().map(Value::to_json).collect();
+                format!("[{}]", values.join(","))
+            }
+            Value::Object(fields) => {
+                // Sorted, so the same object is always written alike
+                let fields: BTreeMap<&String, &Value> = fields.iter().collect();
+                let fields: Vec<String> = fields
+                    .into_iter()
+                    .map(|(key, value)| format!("{}:{}", string(key), value.to_json()))
+                    .collect();
+                format!("{{{}}}", fields.join(","))
+            }
+        }
+    }
+}
+
+/// The value of a JSON text, e.g. `["slate:bybbg"]`
+pub fn parse(text: &str) -> Result<Value> {
+    let (value, rest) = parse_value(text.trim_start())?;
+    if !rest.trim().is_empty() {
+        bail!("Unexpected text after the value: {}", rest.trim())
+    }
+    Ok(value)
+}
+
+/// The fields of an object, e.g. `{"cmd":"reset"}`
+pub fn parse_object(text: &str) -> Result<HashMap<String, Value>> {
+    match text.trim_start().starts_with('{') {
+        true => match parse(text)? {
+            Value::Object(fields) => Ok(fields),
+            _ => unreachable!("A value that starts with {{ is an object"),
+        },
+        false => bail!("Expected a JSON object"),
+    }
+}
+
+/// The text as a JSON string with its quotes
+pub fn string(text: &str) -> String {
+    let mut escaped = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' | '\\' => escaped.extend(['\\', c]),
+            c if c.is_control() => escaped += &format!("\\u{:04x}", c as u32),
+            c => escaped.push(c),
+        }
+    }
+    escaped + "\""
+}
+
+/// The string at the start of the text and the text after it, with its
+/// escapes decoded
+fn parse_string(text: &str) -> Result<(String, &str)> {
+    let text = text.strip_prefix('"').context("Expected a string")?;
+    let mut value = String::new();
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((value, &text[i + 1..])),
+            '\\' => {
+                let Some((_, escape)) = chars.next() else {
+                    break;
+                };
+                value.push(match escape {
+                    '"' | '\\' | '/' => escape,
+                    'b' => '\u{8}',
+                    'f' => '\u{c}',
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    'u' => parse_unicode(&mut chars)?,
+                    c => bail!("Invalid escape \\{}", c),
+                });
+            }
+            c => value.push(c),
+        }
+    }
+    bail!("Unterminated string")
+}
+
+/// The character of a `\uXXXX` escape after the `u`, or of a surrogate
+/// pair of two escapes
+fn parse_unicode(chars: &mut std::str::CharIndices) -> Result<char> {
+    let hex = |chars: &mut std::str::CharIndices| -> Result<u32> {
+        let code: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+        match code.len() == 4 && code.chars().all(|c| c.is_ascii_hexdigit()) {
+            true => Ok(u32::from_str_radix(&code, 16)?),
+            false => bail!("Invalid escape \\u{}", code),
+        }
+    };
+    let high = hex(chars)?;
+    if !(0xd800..0xdc00).contains(&high) {
+        return char::from_u32(high).context("Invalid escape, a lone low surrogate");
+    }
+    let low = match (chars.next(), chars.next()) {
+        (Some((_, '\\')), Some((_, 'u'))) => hex(chars)?,
+        _ => bail!("Invalid escape, a high surrogate without a low one"),
+    };
+    if !(0xdc00..0xe000).contains(&low) {
+        bail!("Invalid escape, a high surrogate without a low one")
+    }
+    let code = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+    char::from_u32(code).context("Invalid surrogate pair")
+}
+
+/// The value at the start of the text and the text after it
+fn parse_value(text: &str) -> Result<(Value, &str)> {
+    for (keyword, value) in [
+        ("true", Value::Bool(true)),
+        ("false", Value::Bool(false)),
+        ("null", Value::Null),
+    ] {
+        if let Some(rest) = text.strip_prefix(keyword) {
+            return Ok((value, rest));
+        }
+    }
+    if let Some(mut rest) = text.strip_prefix('{') {
+        let mut fields = HashMap::new();
+        loop {
+            rest = rest.trim_start();
+            if let Some(after) = rest.strip_prefix('}') {
+                return Ok((Value::Object(fields), after));
+            }
+            let (key, after) = parse_string(rest)?;
+            let after = after
+                .trim_start()
+                .strip_prefix(':')
+                .context("Expected ':'")?;
+            let (value, after) = parse_value(after.trim_start())?;
+            fields.insert(key, value);
+            rest = after.trim_start();
+            rest = rest.strip_prefix(',').unwrap_or(rest);
+        }
+    }
+    if let Some(mut rest) = text.strip_prefix('[') {
+        let mut values = vec![];
+        loop {
+            rest = rest.trim_start();
+            if let Some(after) = rest.strip_prefix(']') {
+                return Ok((Value::Array(values), after));
+            }
+            let (value, after) = parse_value(rest)?;
+            values.push(value);
+            rest = after.trim_start();
+            rest = rest.strip_prefix(',').unwrap_or(rest);
+        }
+    }
+    let end = text
+        .find(|c: char| !(c.is_ascii_digit() || "+-.eE".contains(c)))
+        .unwrap_or(text.len());
+    if end > 0 {
+        let number = text[..end]
+            .parse()
+            .with_context(|| format!("Invalid number {}", &text[..end]))?;
+        return Ok((Value::Number(number), &text[end..]));
+    }
+    let (text, rest) = parse_string(text)?;
+    Ok((Value::Str(text), rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_object() {
+        let fields =
+            parse_object(r#"{"cmd": "suggest", "n": 3, "id": -1.5e1, "x": [true, null, "a\"b"]}"#)
+                .unwrap();
+        assert_eq!(fields["cmd"], Value::Str("suggest".to_string()));
+        assert_eq!(fields["n"], Value::Number(3.0));
+        assert_eq!(fields["id"], Value::Number(-15.0));
+        assert_eq!(fields["x"].to_json(), r#"[true,null,"a\"b"]"#);
+        assert!(parse_object("{}").unwrap().is_empty());
+
+        assert!(parse_object("cmd").is_err());
+        assert!(parse_object(r#"{"n": 1.2.3}"#).is_err());
+        assert!(parse_object(r#"{"cmd": "reset}"#).is_err());
+        assert!(parse_object(r#"["reset"]"#).is_err());
+    }
+
+    #[test]
+    fn test_escapes() {
+        // As written by Python's json.dumps
+        let fields = parse_object(r#"{"guesses": ["m\u00f6wen:bbbbb"]}"#).unwrap();
+        let guesses = Value::Array(vec![Value::Str("möwen:bbbbb".to_string())]);
+        assert_eq!(fields["guesses"], guesses);
+
+        let text = r#""\" \\ \/ \b \f \n \r \t \u00e9 \ud83d\ude00""#;
+        let expected = "\" \\ / \u{8} \u{c} \n \r \t é 😀";
+        assert_eq!(parse(text).unwrap(), Value::Str(expected.to_string()));
+        // What is written is read again
+        let value = Value::Str(expected.to_string());
+        assert_eq!(parse(&value.to_json()).unwrap(), value);
+
+        for invalid in [r#""\x""#, r#""\u00e""#, r#""\ud83d""#, r#""\ude00""#] {
+            assert!(parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_nested() {
+        let text = "{\"tag\": \"v1\",\n \"author\": {\"login\": \"x\", \"ids\": [1, {}]}}";
+        let fields = parse_object(text).unwrap();
+        assert_eq!(fields["tag"], Value::Str("v1".to_string()));
+        assert_eq!(fields["author"].to_json(), r#"{"ids":[1,{}],"login":"x"}"#);
+        assert!(parse("[1] 2").is_err());
+    }
+}
@@ -1,2 +1,32 @@
+//! A Wordle solver that ranks guesses by their expected information
+//!
+//! [`prelude`] holds the types most programs need. A [`Solver`] is built for
+//! the embedded word list with [`Solver::new`], for a word list file with
+//! [`Solver::from_wordlist`] or for custom words with
+//! [`Solver::from_words`].
+//!
+//! [`Solver`]: solver::Solver
+//! [`Solver::new`]: solver::Solver::new
+//! [`Solver::from_wordlist`]: solver::Solver::from_wordlist
+//! [`Solver::from_words`]: solver::Solver::from_words
+
+#[cfg(feature = "tui")]
+pub mod about;
+pub mod config;
+pub mod corpus;
+pub mod history;
+#[cfg(feature = "tui")]
+pub mod input;
+pub mod json;
+pub mod pipe;
+#[cfg(feature = "cli")]
+pub mod platform;
+pub mod prelude;
+#[cfg(feature = "cli")]
+pub mod progress;
+pub mod season;
 pub mod solver;
+pub mod telemetry;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 pub mod wordle;
@@ -1,15 +1,55 @@
 use anyhow::{Context, Result};
-use clap::{Args, Parser, Subcommand};
+use clap::{
+    parser::ValueSource, ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand,
+};
 use colored::Colorize;
-use indicatif::{ParallelProgressIterator, ProgressStyle};
+use rand::{rngs::StdRng, SeedableRng};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "tui")]
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use wordlebot::{
     self,
-    solver::*,
-    wordle::{create_word_from_string, decode_status, Guess, LetterStatus::*, Word},
+    config::Config,
+    corpus,
+    history::{self, GameRecord, History},
+    pipe::{self, PipeCommand, Request},
+    platform,
+    progress::{Progress, ProgressMode},
+    season,
+    solver::{
+        book::OpeningBook,
+        cache::{Cache, DEFAULT_CACHE_SIZE},
+        export::PatternRow,
+        formula::Formula,
+        line::LineOutcome,
+        multi::MultiSolver,
+        opening::{OpenerSort, OpenerStats},
+        oracle::{Adversarial, FeedbackOracle, FixedAnswer, HumanEntered, Noisy},
+        pack::WordPack,
+        ranking::RankingConfig,
+        regression::{self, BenchmarkStats},
+        report::GuideOptions,
+        score::ScoreStrategy,
+        search::Search,
+        snapshot::{Comparison, StrategySnapshot},
+        streak::MAX_STREAK_ROUNDS,
+        tree::DecisionTree,
+        width::WidthSchedule,
+        *,
+    },
+    telemetry::{aggregate, Outcome, Telemetry},
+    wordle::{
+        encode_status, parse_guess_log, parse_share_grid, parse_share_string, pattern_to_string,
+        solved_pattern, Guess, LetterStatus::*, Pattern, ScoringRule, Word, MAX_GUESSES,
+    },
 };
 
+#[cfg(feature = "tui")]
 mod tui;
 
 /// Wordle solver
@@ -19,160 +59,2710 @@ struct Arguments {
     #[command(subcommand)]
     command: Option<Commands>,
 
+    #[command(flatten)]
+    strategy: StrategyArgs,
+
+    /// Keyboard layout of the TUI: qwerty, azerty, qwertz or the path to a
+    /// layout file with one row of keys per line
+    #[cfg(feature = "tui")]
+    #[arg(short, long, default_value = "qwerty")]
+    layout: String,
+
+    /// Colors of the letters in the TUI: default, or high-contrast for
+    /// orange and blue instead of green and yellow
+    #[cfg(feature = "tui")]
+    #[arg(long)]
+    theme: Option<String>,
+
+    /// Read the defaults of the flags from this file instead of
+    /// ~/.config/wordle-solver/config.toml. The flags override it.
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Follow a decision tree from a file (one line per answer, e.g.
+    /// "salet BBBYB1 courd GGGGG2") and fall back to the solver where the
+    /// tree has no guess
+    #[arg(long)]
+    tree: Option<PathBuf>,
+
+    /// Play the first two guesses from an opening book file (see `book
+    /// build`) and fall back to the solver after them
+    #[arg(long, conflicts_with = "tree")]
+    book: Option<PathBuf>,
+
+    /// Compare the strategy with a snapshot saved by `strategy save`, by
+    /// its name or the path of its file: side by side in `benchmark`, and
+    /// in the F2 panel of the TUI
+    #[arg(long)]
+    compare: Option<String>,
+
+    /// Opt in to telemetry: append the date, strategy, opener and number of
+    /// guesses of every game to this local file. Nothing is sent anywhere.
+    #[arg(long)]
+    telemetry: Option<PathBuf>,
+
+    /// Keep the finished games of the TUI and of `solve --interactive` in
+    /// this file instead of ~/.local/share/wordle-solver/history.jsonl, for
+    /// `stats` and the F11 screen. Nothing is sent anywhere
+    #[arg(long)]
+    history: Option<PathBuf>,
+
+    /// Do not keep the finished games
+    #[arg(long, conflicts_with = "history")]
+    no_history: bool,
+
+    /// Use the words from this file instead of the built-in word list. One
+    /// word per line, optionally followed by a tab and its prior. Words with
+    /// a prior of 0 are allowed guesses, but not possible answers.
+    #[arg(long, alias = "words")]
+    wordlist: Option<PathBuf>,
+
+    /// Use the word pack of a Wordle variant instead of the built-in word
+    /// list: a directory with a pack.toml that names the word list, the
+    /// answers and the feedback rule of the variant
+    #[arg(long, conflicts_with = "wordlist")]
+    pack: Option<PathBuf>,
+
+    /// Add the words from this file to the word list, in the same format.
+    /// Words that are already in the list only get the new prior, e.g. 0 to
+    /// remove an answer. The TUI reloads the file when it changes.
+    #[arg(long)]
+    overlay: Option<PathBuf>,
+
+    /// Pick the overlay file in a dialog, or type its path if there is no
+    /// dialog
+    #[arg(long, conflicts_with = "overlay")]
+    pick_overlay: bool,
+
+    /// Ignore the priors: every word of the word list is a possible answer
+    /// and all are equally likely, like in the analyses of the original
+    /// Wordle
+    #[arg(long)]
+    uniform: bool,
+
+    /// How the priors of the word list are weighted: "csv" as they are,
+    /// "flat" for all possible answers alike, or "sigmoid:TEMPERATURE" to
+    /// favor common words more (below 1) or less (above 1)
+    #[arg(long, default_value = "csv", conflicts_with = "uniform")]
+    prior_model: PriorModel,
+
+    /// How the host colors a letter the guess repeats more often than the
+    /// answer has it: "nyt" yellow only as often as the answer has it, or
+    /// "greedy-yellow" yellow every time, like some clones. Defaults to the
+    /// feedback rule of the pack, or "nyt"
+    #[arg(long)]
+    scoring: Option<ScoringRule>,
+
+    /// Compute the patterns of a guess when they are needed instead of
+    /// those of all words at start, and keep only the recently used ones.
+    /// Starts instantly and needs a few MB instead of ~220MB, but ranking
+    /// all words is slower
+    #[arg(long)]
+    low_memory: bool,
+
+    /// The number of results each cache of the solver keeps, e.g. the
+    /// suggestions and evaluations for the remaining words. The least
+    /// recently used are evicted; 0 disables the caches
+    #[arg(long, default_value_t = DEFAULT_CACHE_SIZE)]
+    cache_size: usize,
+
+    /// Look for a newer release on GitHub when the About screen of the TUI
+    /// is opened. Needs curl
+    #[cfg(feature = "tui")]
+    #[arg(long)]
+    check_updates: bool,
+
+    /// Number of worker threads, all cores by default. --eco uses one
+    #[arg(long)]
+    max_cpu: Option<usize>,
+}
+
+impl Arguments {
+    /// The history of the finished games, see --history
+    fn history(&self) -> Option<History> {
+        if self.no_history {
+            return None;
+        }
+        self.history
+            .clone()
+            .or_else(History::default_path)
+            .map(History::new)
+    }
+
+    /// How the solver stores the patterns, see --low-memory
+    fn mapping_backend(&self) -> MappingBackend {
+        match self.low_memory {
+            true => MappingBackend::Lazy {
+                rows: LOW_MEMORY_ROWS,
+            },
+            false => MappingBackend::Dense,
+        }
+    }
+
+    /// Use the values of the config file for the flags that are not given
+    /// on the command line
+    fn apply_config(&mut self, config: Config, matches: &ArgMatches) {
+        let from_cli = |matches: &ArgMatches, id| {
+            !matches!(
+                matches.value_source(id),
+                None | Some(ValueSource::DefaultValue)
+            )
+        };
+        // Two level entropy does not go together with the other rankings
+        let other_ranking = self.strategy.formula.is_some() || from_cli(matches, "score");
+        if let (Some(two_level), false) = (config.two_level, other_ranking) {
+            self.strategy.two_level |= two_level;
+        }
+        if let (Some(penalty), false) = (config.penalty, from_cli(matches, "penalty")) {
+            self.strategy.penalty = penalty;
+        }
+        self.strategy.eco |= config.eco.unwrap_or(false);
+        self.uniform |= config.uniform.unwrap_or(false);
+        #[cfg(feature = "tui")]
+        {
+            self.theme = self.theme.take().or(config.theme);
+        }
+        self.wordlist = self.wordlist.take().or(config.wordlist);
+        self.max_cpu = self.max_cpu.or(config.max_cpu);
+
+        let Some((_, matches)) = matches.subcommand() else {
+            return;
+        };
+        if let Some(cli_args) = self.command.as_mut().and_then(Commands::cli_args_mut) {
+            cli_args.starting_word = cli_args.starting_word.take().or(config.starting_word);
+            if let (Some(max_rounds), false) = (config.max_rounds, from_cli(matches, "max_rounds"))
+            {
+                cli_args.max_rounds = max_rounds;
+            }
+        }
+    }
+}
+
+#[derive(Args, Debug, Clone)]
+struct StrategyArgs {
     // Two level entropy calculation
     #[arg(short, long)]
     two_level: bool,
+
+    /// Number of candidates evaluated with two level entropy. Either a
+    /// constant or a schedule of MIN_REMAINING:WIDTH steps, e.g.
+    /// "1000:20,50:10,0:5"
+    #[arg(short, long, default_value = "10")]
+    width: WidthSchedule,
+
+    /// Weight of the prior of possible answers in the ranking, per round
+    /// starting with the second guess. The last value is used for all later
+    /// rounds, e.g. "0.1" or "0.1,0.3,1.0"
+    #[arg(short, long, value_delimiter = ',', default_value = "0.1")]
+    penalty: Vec<f32>,
+
+    /// Rank the guesses with a custom formula instead of the entropy and the
+    /// penalized prior, e.g. "entropy - max_group / 1000". Either the
+    /// formula or a file that contains it.
+    #[arg(long, value_parser = parse_formula)]
+    formula: Option<Formula>,
+
+    /// What the ranking optimizes: "entropy" for the expected information,
+    /// "expected-turns" for the expected number of turns to find the
+    /// answer, estimated from the sizes of the groups a guess leaves, or
+    /// "minimax" for the smallest largest group
+    #[arg(long, default_value = "entropy", conflicts_with_all = ["formula", "two_level", "depth"])]
+    score: ScoreStrategy,
+
+    /// Look this many guesses ahead, a deeper --two-level: the --width best
+    /// guesses are ranked by their bits plus the expected bits of the best
+    /// guesses after them
+    #[arg(long, value_parser = parse_depth, conflicts_with_all = ["formula", "two_level"])]
+    depth: Option<usize>,
+
+    /// Number of guesses tried for the words left at every later level of
+    /// --depth, the best of the default ranking
+    #[arg(long, default_value_t = 3, requires = "depth")]
+    beam: usize,
+
+    /// Read the weights of the default ranking from a file with one
+    /// "name = value" per line, e.g. "prior_weight = 0.1". The weight flags
+    /// override it.
+    #[arg(long)]
+    ranking_config: Option<PathBuf>,
+
+    /// Weight of the expected bits in the ranking [default: 1]
+    #[arg(long)]
+    entropy_weight: Option<f32>,
+
+    /// Weight of the prior of possible answers, times the penalty of the
+    /// round [default: 0.05]
+    #[arg(long)]
+    prior_weight: Option<f32>,
+
+    /// Bits added to the rank of possible answers [default: 0]
+    #[arg(long, allow_hyphen_values = true)]
+    possible_bonus: Option<f32>,
+
+    /// Factor of the penalty when ranking by the two level bits [default: 2]
+    #[arg(long)]
+    two_level_weight: Option<f32>,
+
+    /// Save energy, e.g. on battery: one worker thread unless --max-cpu is
+    /// given, one level entropy, at most 3 candidates for a formula with
+    /// two level bits, no lookahead search, and the precomputed openers
+    /// instead of ranking all words for the first guess
+    #[arg(long, conflicts_with = "depth")]
+    eco: bool,
+
+    /// Only suggest the remaining possible answers, never a probe word
+    /// that cannot win. `benchmark` shows what it costs
+    #[arg(long)]
+    answers_only: bool,
+}
+
+impl StrategyArgs {
+    /// The words the solver suggests, see --answers-only
+    fn pool(&self) -> CandidatePool {
+        match self.answers_only {
+            true => CandidatePool::RemainingAnswers,
+            false => CandidatePool::AllWords,
+        }
+    }
+
+    /// The cheaper strategy of --eco
+    fn apply_eco(&mut self) {
+        // The flags conflict, but --eco of the config file still meets
+        // --depth of the command line
+        self.two_level = false;
+        self.depth = None;
+        self.width = self.width.capped(ECO_WIDTH);
+    }
+
+    /// The lookahead search of --depth
+    fn search(&self) -> Option<Search> {
+        let depth = self.depth?;
+        Some(Search {
+            depth,
+            beam: self.beam.max(1),
+        })
+    }
+
+    /// The weights of the default ranking from the config file and the
+    /// flags
+    fn ranking(&self) -> Result<RankingConfig> {
+        let mut ranking = match &self.ranking_config {
+            Some(path) => RankingConfig::from_file(path)?,
+            None => RankingConfig::default(),
+        };
+        let weights = [
+            ("entropy_weight", self.entropy_weight),
+            ("prior_weight", self.prior_weight),
+            ("possible_bonus", self.possible_bonus),
+            ("two_level_weight", self.two_level_weight),
+        ];
+        for (name, value) in weights {
+            if let Some(value) = value {
+                ranking.set(name, value)?;
+            }
+        }
+        Ok(ranking)
+    }
+}
+
+/// The number of candidates evaluated in depth with --eco
+const ECO_WIDTH: usize = 3;
+
+/// How often `suggest --follow` reads the log again
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The rows of patterns kept with --low-memory, about 30MB for the
+/// embedded word list
+const LOW_MEMORY_ROWS: usize = 2048;
+
+fn parse_depth(arg: &str) -> Result<usize> {
+    let depth: usize = arg.parse()?;
+    if depth < 2 {
+        anyhow::bail!("The depth must be at least 2, 1 is the default ranking")
+    }
+    Ok(depth)
+}
+
+fn parse_formula(arg: &str) -> Result<Formula> {
+    let path = std::path::Path::new(arg);
+    if !path.is_file() {
+        return arg.parse();
+    }
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Error reading formula {}", path.display()))?;
+    text.parse()
+        .with_context(|| format!("Error parsing formula {}", path.display()))
+}
+
+#[derive(Args, Debug)]
+struct CliArgs {
+    /// Choose a manual starting word
+    #[arg(short, long)]
+    starting_word: Option<String>,
+
+    /// Maximal number of rounds
+    #[arg(short, long, default_value_t = MAX_GUESSES)]
+    max_rounds: usize,
+}
+
+#[derive(Subcommand, Debug)]
+enum BookCommand {
+    /// Write the opening book of the current strategy: its opener and the
+    /// second guess after every pattern of the opener
+    Build {
+        /// The file to write the book to
+        output: PathBuf,
+
+        /// Choose a manual starting word
+        #[arg(short, long)]
+        starting_word: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum StrategyCommand {
+    /// Save the settings of the current strategy, its opener and the
+    /// fingerprint of the word list under a name, for --compare
+    Save {
+        /// The name of the snapshot
+        name: String,
+
+        /// Choose a manual starting word
+        #[arg(short, long)]
+        starting_word: Option<String>,
+    },
+
+    /// List the saved snapshots
+    List {},
+}
+
+/// The settings of a snapshot, parsed like the flags of the command line
+#[derive(Parser, Debug)]
+struct SnapshotArgs {
+    #[command(flatten)]
+    strategy: StrategyArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Default. Launch with graphical interface
+    #[cfg(feature = "tui")]
+    Tui {},
+
+    /// Play against a secret answer in the graphical interface. The answer
+    /// is picked at random, weighted by the priors
+    #[cfg(feature = "tui")]
+    Play {
+        /// Draw the answers from this seed, so the same games come in the
+        /// same order every time, e.g. to play "seed 42, game 3" with
+        /// friends. The seed is recorded in the history with the results.
+        #[arg(long)]
+        seed: Option<u32>,
+    },
+
+    /// Play the daily pack in the graphical interface: today's puzzle,
+    /// today's hard mode puzzle and a random word
+    #[cfg(feature = "tui")]
+    Pack {},
+
+    /// Play against an adversarial host like Absurdle in the terminal. There
+    /// is no fixed answer: after every guess the host keeps the largest
+    /// group of the remaining words
+    Absurdle {},
+
+    /// Solve several boards at once, like Quordle or Octordle. Without
+    /// answers the TUI is started with empty boards
+    Multi {
+        /// The answers of the boards
+        words: Vec<String>,
+
+        /// Number of boards in the TUI
+        #[cfg(feature = "tui")]
+        #[arg(short, long, default_value_t = 4)]
+        boards: usize,
+
+        /// Maximal number of rounds, five more than the number of boards by
+        /// default
+        #[arg(short, long)]
+        max_rounds: Option<usize>,
+    },
+
+    /// Benchmark against all words in file
+    Benchmark {
+        #[command(flatten)]
+        cli_args: CliArgs,
+
+        /// Print the answers whose decisions took the longest in total
+        /// (default 20), with the time of every guess
+        #[arg(long, num_args = 0..=1, default_missing_value = "20")]
+        slowest: Option<usize>,
+
+        /// Write the time of every decision as folded stacks for flamegraph
+        /// tools: the earlier guesses and their patterns are the frames
+        #[arg(long)]
+        folded: Option<PathBuf>,
+
+        /// How to show the progress: auto (a bar in a terminal, a line
+        /// every few seconds when the output is piped), bar, plain or none
+        #[arg(long, default_value = "auto")]
+        progress: ProgressMode,
+
+        /// Play only the answers in this file, one per line, e.g. the
+        /// official solutions or a list of hard words. They have to be
+        /// possible answers of the word list
+        #[arg(long)]
+        answers: Option<PathBuf>,
+
+        /// Play a random sample of this many of the answers, e.g. for quick
+        /// iterations
+        #[arg(long)]
+        sample: Option<usize>,
+
+        /// The seed of --sample, to draw the same answers again [default:
+        /// random, and printed]
+        #[arg(long, requires = "sample")]
+        seed: Option<u64>,
+    },
+
+    /// Get the best strategy to solve words
+    Solve {
+        /// The words to solve
+        words: Vec<String>,
+
+        /// Solve against an adversarial host like Absurdle instead of the
+        /// words. Plays the strategy and searches the fewest guesses that
+        /// win, trying the --width guesses with the smallest largest group
+        /// at every step.
+        #[arg(long, conflicts_with = "words")]
+        absurdle: bool,
+
+        /// Color the guesses wrong with this probability, one letter per
+        /// guess, to see how the strategy copes with mistakes
+        #[arg(long, conflicts_with = "absurdle")]
+        noise: Option<f64>,
+
+        /// Enter the pattern of every guess yourself, e.g. to be guided
+        /// through a game on another site
+        #[arg(long, conflicts_with_all = ["words", "absurdle"])]
+        interactive: bool,
+
+        #[command(flatten)]
+        cli_args: CliArgs,
+    },
+
+    /// Find the best penalty by benchmarking a range of penalties on a
+    /// sample of answers
+    Tune {
+        /// Smallest constant penalty
+        #[arg(long, default_value_t = 0.0)]
+        from: f32,
+
+        /// Largest constant penalty
+        #[arg(long, default_value_t = 1.0)]
+        to: f32,
+
+        /// Step between the constant penalties
+        #[arg(long, default_value_t = 0.1)]
+        step: f32,
+
+        /// Additional per round penalty schedules to test, e.g. "0.1,0.5"
+        #[arg(long)]
+        schedule: Vec<String>,
+
+        /// Number of answers to benchmark each setting on
+        #[arg(long, default_value_t = 500)]
+        sample: usize,
+
+        /// Write the results as CSV to this file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        cli_args: CliArgs,
+    },
+
+    /// Compare the best opening words: their expected bits, two level bits
+    /// and largest group over all answers, and a benchmark of the strategy
+    /// with each of them on a sample of answers
+    Openers {
+        /// Number of openers, the best of the default ranking
+        #[arg(long, default_value_t = 50)]
+        top: usize,
+
+        /// The column to sort by: bits, two-level, worst (the smallest
+        /// largest group first) or mean (the fewest failures and guesses of
+        /// the benchmark first)
+        #[arg(long, default_value = "bits")]
+        sort: OpenerSort,
+
+        /// Number of answers to benchmark each opener on, 0 to skip the
+        /// benchmark
+        #[arg(long, default_value_t = 100)]
+        sample: usize,
+
+        /// Maximal number of rounds of the benchmark
+        #[arg(short, long, default_value_t = MAX_GUESSES)]
+        max_rounds: usize,
+
+        /// Write the table as CSV to this file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Opening books for --book
+    Book {
+        #[command(subcommand)]
+        command: BookCommand,
+    },
+
+    /// Named snapshots of strategies for --compare
+    Strategy {
+        #[command(subcommand)]
+        command: StrategyCommand,
+    },
+
+    /// Export the decision tree of the current strategy for all answers
+    ExportTree {
+        /// The file to write the tree to
+        output: PathBuf,
+
+        #[command(flatten)]
+        cli_args: CliArgs,
+    },
+
+    /// Search the decision tree with the fewest expected guesses and write
+    /// it to a file, to follow it with --tree. Tries the --width guesses
+    /// with the highest entropy at every step.
+    BuildTree {
+        /// The file to write the tree to
+        output: PathBuf,
+
+        #[command(flatten)]
+        cli_args: CliArgs,
+    },
+
+    /// Generate a markdown strategy guide from the word list: the best
+    /// openers, the responses to the best opener and the endgame families
+    DocsGen {
+        /// The file to write the guide to. Prints to stdout if missing
+        output: Option<PathBuf>,
+
+        /// Number of openers in the opener table
+        #[arg(long, default_value_t = 10)]
+        openers: usize,
+
+        /// Number of responses to the best opener
+        #[arg(long, default_value_t = 10)]
+        patterns: usize,
+
+        /// Number of endgame families
+        #[arg(long, default_value_t = 10)]
+        families: usize,
+    },
+
+    /// Compare two versions of a word list: how the opener, the suggestions
+    /// for the most common positions and the benchmark change
+    Regression {
+        /// The old word list, in the format of --wordlist
+        old: PathBuf,
+
+        /// The new word list
+        new: PathBuf,
+
+        /// Number of responses to the old opener to compare the suggestions
+        /// for, besides the empty board
+        #[arg(long, default_value_t = 5)]
+        positions: usize,
+
+        /// Number of suggestions per position
+        #[arg(long, default_value_t = 10)]
+        suggestions: usize,
+
+        /// Skip the benchmark of both word lists
+        #[arg(long)]
+        no_benchmark: bool,
+
+        #[command(flatten)]
+        cli_args: CliArgs,
+    },
+
+    /// Print the best next guesses and the number of remaining words after
+    /// some guesses, e.g. in the middle of a game on another site
+    Suggest {
+        /// A guess in the form WORD:PATTERN or WORD=PATTERN, e.g.
+        /// slate:bybyg or slate=01012 (b or 0 = absent, y or 1 = misplaced,
+        /// g or 2 = correct). Repeat it for every guess
+        #[arg(short, long)]
+        guess: Vec<Guess>,
+
+        /// The emoji grid of a shared result instead of --guess, e.g.
+        /// "⬛🟨⬛⬛🟩", - to read it from stdin or clipboard to read it from
+        /// the clipboard. The guessed words are given with --words
+        #[arg(long, conflicts_with = "guess", requires = "words")]
+        share: Option<String>,
+
+        /// The guessed words of --share, separated by commas
+        #[arg(long, value_delimiter = ',', requires = "share")]
+        words: Vec<String>,
+
+        /// Follow a log file with one guess per line, e.g. "slate bybbg",
+        /// and print the suggestions again whenever a guess is added. A
+        /// guess after a solved one starts a new game
+        #[arg(long, conflicts_with_all = ["guess", "share"])]
+        follow: Option<PathBuf>,
+
+        /// Number of suggestions
+        #[arg(short, long, default_value_t = 10)]
+        number: usize,
+
+        /// Print JSON instead of plain text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Guess the answer of a shared result from its emoji grid alone: the
+    /// answers that fit the patterns, how many sequences of guesses give
+    /// them and how likely each answer is
+    Grid {
+        /// The emoji grid, e.g. "⬛🟨⬛⬛🟩", - to read it from stdin or
+        /// clipboard to read it from the clipboard
+        share: String,
+
+        /// The game was played in hard mode: every guess used the hints of
+        /// the guesses before it. Slower, and only a lower bound for
+        /// answers with many combinations
+        #[arg(long)]
+        hard: bool,
+
+        /// Number of answers to print
+        #[arg(short, long, default_value_t = 10)]
+        number: usize,
+    },
+
+    /// Give hints about an answer for assisted play instead of solving it,
+    /// from weak to strong: a letter at a position, a letter it contains,
+    /// the number of vowels and finally the answer
+    Hint {
+        /// The answer
+        #[arg(long)]
+        answer: String,
+
+        /// The words guessed so far, separated by commas. Hints they
+        /// already gave are left out
+        #[arg(long, value_delimiter = ',')]
+        guesses: Vec<String>,
+
+        /// Number of hints, 1 for only the weakest
+        #[arg(short, long, default_value_t = 1)]
+        level: usize,
+    },
+
+    /// Score a line of play against an answer without interaction: the bits
+    /// and the rank of every guess among the guesses of the solver, and
+    /// whether the line finds the answer
+    EvaluateLine {
+        /// The answer
+        #[arg(long)]
+        answer: String,
+
+        /// The guesses, separated by commas, e.g. "salet,courd,crane"
+        #[arg(long)]
+        line: String,
+    },
+
+    /// Drive the solver from another program: read JSON commands from
+    /// stdin, one per line, and write a JSON response for each to stdout,
+    /// see src/pipe.rs. The solver is only loaded once
+    Pipe {},
+
+    /// Aggregate a telemetry file per strategy and opener
+    TelemetryExport {
+        /// The telemetry file written with --telemetry
+        log: PathBuf,
+
+        /// The file to write the aggregate to. Prints to stdout if missing
+        output: Option<PathBuf>,
+    },
+
+    /// Compare the patterns of words with repeated letters with the corpus
+    /// of the tests, see src/corpus.rs. Fails if any pattern changed
+    Corpus {
+        /// The corpus file
+        #[arg(long, default_value = "tests/duplicate_letters.txt")]
+        fixture: PathBuf,
+
+        /// Write the patterns to the corpus file instead, after an intended
+        /// change of the patterns
+        #[arg(long)]
+        write: bool,
+    },
+
+    /// Export every pattern of a guess as CSV: the share of the remaining
+    /// words that give it, how many are left and the entropy of those, e.g.
+    /// to see where a guess leaves the most work
+    Patterns {
+        /// The guess
+        word: String,
+
+        /// A guess in the form WORD:PATTERN or WORD=PATTERN that filters
+        /// the remaining words first. Repeat it for every guess
+        #[arg(short, long)]
+        guess: Vec<Guess>,
+
+        /// The file to write the table to. Prints to stdout if missing
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Explain why a word is not a possible solution after some guesses
+    Why {
+        /// The word to explain
+        word: String,
+
+        /// The guesses in the form WORD:PATTERN or WORD=PATTERN, e.g.
+        /// slate:bybyg (b = absent, y = misplaced, g = correct)
+        guesses: Vec<Guess>,
+    },
+
+    /// Protect a streak: the guesses most likely to find the answer within
+    /// the rounds that are left, with that probability, instead of the
+    /// guesses that find it in the fewest guesses on average
+    Streak {
+        /// A guess in the form WORD:PATTERN or WORD=PATTERN, e.g.
+        /// slate:bybyg (b = absent, y = misplaced, g = correct). Repeat it
+        /// for every guess
+        #[arg(short, long)]
+        guess: Vec<Guess>,
+
+        /// The rounds that are left, including the next guess [default: 6
+        /// minus the guesses]
+        #[arg(short, long)]
+        rounds: Option<usize>,
+
+        /// Number of suggestions
+        #[arg(short, long, default_value_t = 5)]
+        number: usize,
+    },
+
+    /// The statistics of the finished games: the streaks, the number of
+    /// turns and the best and worst openers, see --history
+    Stats {},
+
+    /// Import a season log with one game per line, e.g. "2024-05-01
+    /// slate,crane bbgbg,ggggg" (the answer as a fourth column if the game
+    /// was lost), into the history. Prints the skill and luck of every week
+    /// and the guesses that spent letters on known hints
+    Import {
+        /// The season log
+        log: PathBuf,
+
+        /// Only print the analysis, do not add the games to the history
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Summarize the word list, the embedded one or that of --wordlist: the
+    /// fields of its `# key: value` header, the number of words and
+    /// answers, how the priors of the answers are distributed and a
+    /// checksum of the file
+    DataInfo {
+        /// Print JSON instead of plain text
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// The candidates searched for a guess: all allowed guesses are ranked,
+/// and with two level entropy the best of them are evaluated again
+#[derive(Clone, Copy)]
+struct Pool {
+    ranked: usize,
+    two_level: usize,
+}
+
+/// A picked guess and the candidates that were searched for it
+type Pick = (Word, Option<Pool>);
+
+/// How long picking a guess took, for the timing column of `solve`
+struct Decision {
+    elapsed: Duration,
+    // None if nothing was searched, e.g. for the opener or a guess of the
+    // decision tree
+    pool: Option<Pool>,
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "time {:>9.2?} | ", self.elapsed)?;
+        match &self.pool {
+            None => write!(f, "no search"),
+            Some(pool) if pool.two_level == 0 => write!(f, "ranked {:5}", pool.ranked),
+            Some(pool) => write!(f, "ranked {:5}, 2l {:3}", pool.ranked, pool.two_level),
+        }
+    }
+}
+
+/// How the next guess is picked
+struct Strategy<'a> {
+    start: Word,
+    two_level: bool,
+    search: Option<Search>,
+    tree: Option<&'a DecisionTree>,
+    book: Option<&'a OpeningBook>,
+    formula: Option<Formula>,
+    score: ScoreStrategy,
+    // The penalty for each round, starting with the second guess
+    penalty: Vec<f32>,
+    // Number of candidates for the two level evaluation, by the number of
+    // remaining words
+    width: Box<dyn Fn(usize) -> usize + Sync + 'a>,
+    // The picked guesses and their candidates by the sorted remaining words
+    // and the round, shared by the games of a benchmark that reach the same
+    // words
+    picks: Cache<(Box<[usize]>, usize), Pick>,
+}
+
+/// The most picks a strategy remembers, more than the decisions of a
+/// benchmark of all answers
+const PICK_CACHE_SIZE: usize = 1 << 15;
+
+impl<'a> Strategy<'a> {
+    fn new(
+        starting_word: Option<String>,
+        args: &StrategyArgs,
+        solver: &Solver,
+        tree: Option<&'a DecisionTree>,
+        book: Option<&'a OpeningBook>,
+    ) -> Result<Strategy<'a>> {
+        let two_level = args.two_level;
+        let schedule = args.width.clone();
+        let mut strategy = Strategy {
+            start: Word::new(),
+            two_level,
+            search: args.search(),
+            tree,
+            book,
+            formula: args.formula.clone(),
+            score: args.score,
+            penalty: args.penalty.clone(),
+            width: Box::new(move |n| schedule.width(n)),
+            picks: Cache::new(PICK_CACHE_SIZE),
+        };
+        // The precomputed openers are ranked like the default ranking
+        let default_ranking = args.formula.is_none() && args.score == ScoreStrategy::Entropy;
+        let opener = solver
+            .openers()
+            .first()
+            .filter(|_| args.eco && default_ranking);
+        strategy.start = match (starting_word, strategy.fixed_guess(&[]), opener) {
+            (Some(word), _, _) => solver.parse_word(&word)?,
+            (None, Some(word), _) => word,
+            (None, None, Some(&opener)) => opener,
+            (None, None, None) => strategy.pick(&[], solver, 0.0)?,
+        };
+        Ok(strategy)
+    }
+
+    /// The penalty for a round. The first guess is picked without penalty.
+    fn penalty(&self, round: usize) -> f32 {
+        if round <= 1 {
+            return 0.0;
+        }
+        let i = (round - 2).min(self.penalty.len().saturating_sub(1));
+        self.penalty.get(i).copied().unwrap_or(0.1)
+    }
+
+    /// The guess of the decision tree or the opening book, if they cover
+    /// the guesses
+    fn fixed_guess(&self, guesses: &[Guess]) -> Option<Word> {
+        match (self.tree, self.book) {
+            (Some(tree), _) => tree.next_guess(guesses),
+            (None, Some(book)) => book.next_guess(guesses),
+            (None, None) => None,
+        }
+    }
+
+    /// Name of the strategy for telemetry
+    fn name(&self) -> &'static str {
+        match (self.tree, &self.formula, self.score, self.two_level) {
+            (Some(_), _, _, _) => "tree",
+            (None, _, _, _) if self.book.is_some() => "book",
+            (None, Some(_), _, _) => "formula",
+            (None, None, _, _) if self.search.is_some() => "lookahead",
+            (None, None, ScoreStrategy::ExpectedTurns, _) => "expected-turns",
+            (None, None, ScoreStrategy::Minimax, _) => "minimax",
+            (None, None, ScoreStrategy::Entropy, true) => "two-level",
+            (None, None, ScoreStrategy::Entropy, false) => "entropy",
+        }
+    }
+
+    /// The next guess and the candidates that were searched for it. The
+    /// pick only depends on the remaining words and the round, so it is
+    /// remembered, and words that another game reached before are not
+    /// searched again. They get the guess and the candidates of that game.
+    fn next_guess(&self, guesses: &[Guess], solver: &Solver) -> Result<Pick, SolverError> {
+        if guesses.is_empty() {
+            return Ok((self.start, None));
+        }
+        if let Some(word) = self.fixed_guess(guesses) {
+            return Ok((word, None));
+        }
+        let mut remaining_words = solver.get_remaining_words_idx(guesses)?;
+        remaining_words.sort_unstable();
+        let key = (remaining_words.into_boxed_slice(), guesses.len());
+        if let Some(pick) = self.picks.get(&key) {
+            return Ok(pick);
+        }
+        let word = self.pick(guesses, solver, self.penalty(guesses.len() + 1))?;
+        let pick = (word, self.pool(key.0.len(), solver));
+        self.picks.insert(key, pick);
+        Ok(pick)
+    }
+
+    /// The candidates that [`Strategy::pick`] searches. The solver answers
+    /// right away if a single word is left.
+    fn pool(&self, n_remaining: usize, solver: &Solver) -> Option<Pool> {
+        if n_remaining <= 1 {
+            return None;
+        }
+        let two_level = match &self.formula {
+            Some(formula) => formula.uses_two_level_bits(),
+            None => self.two_level || self.search.is_some(),
+        };
+        Some(Pool {
+            ranked: solver.n_words(),
+            two_level: match two_level {
+                true => (self.width)(n_remaining),
+                false => 0,
+            },
+        })
+    }
+
+    /// Pick the next guess with the solver
+    fn pick(&self, guesses: &[Guess], solver: &Solver, penalty: f32) -> Result<Word, SolverError> {
+        Ok(self.best(1, guesses, solver, penalty)?[0])
+    }
+
+    /// The n best guesses of the solver, best first
+    fn best(
+        &self,
+        n: usize,
+        guesses: &[Guess],
+        solver: &Solver,
+        penalty: f32,
+    ) -> Result<Vec<Word>, SolverError> {
+        let remaining_words = solver.get_remaining_words_idx(guesses)?;
+        let best = match (&self.formula, self.score, self.two_level) {
+            (None, _, _) if self.search.is_some() => {
+                let width = (self.width)(remaining_words.len());
+                let search = self.search.unwrap();
+                let best = solver.guess_lookahead(n, &remaining_words, penalty, width, search)?;
+                Ok(best.iter().map(|&(word, _)| word).collect())
+            }
+            (Some(formula), _, _) => {
+                let width = (self.width)(remaining_words.len());
+                solver.guess_by_formula(n, &remaining_words, formula, penalty, width)
+            }
+            (None, ScoreStrategy::ExpectedTurns, _) => {
+                solver.guess_by_expected_turns(n, &remaining_words)
+            }
+            (None, ScoreStrategy::Minimax, _) => solver.guess_by_max_group(n, &remaining_words),
+            (None, ScoreStrategy::Entropy, true) => {
+                let width = (self.width)(remaining_words.len());
+                let evaluations = solver.guess_two_level(n, &remaining_words, penalty, width)?;
+                Ok(evaluations.iter().map(|e| e.word).collect())
+            }
+            (None, ScoreStrategy::Entropy, false) => solver.guess(n, &remaining_words, penalty),
+        };
+        Ok(best?)
+    }
+
+    /// The n best guesses, with the guess the strategy plays first: the
+    /// opener or the guess of the decision tree or the opening book
+    fn suggestions(
+        &self,
+        n: usize,
+        guesses: &[Guess],
+        solver: &Solver,
+    ) -> Result<Vec<Word>, SolverError> {
+        let mut words = self.best(n, guesses, solver, self.penalty(guesses.len() + 1))?;
+        let first = match guesses.is_empty() {
+            true => Some(self.start),
+            false => self.fixed_guess(guesses),
+        };
+        if let Some(first) = first {
+            words.retain(|w| *w != first);
+            words.insert(0, first);
+            words.truncate(n);
+        }
+        Ok(words)
+    }
+}
+
+impl Commands {
+    fn cli_args_mut(&mut self) -> Option<&mut CliArgs> {
+        match self {
+            Commands::Benchmark { cli_args, .. }
+            | Commands::Solve { cli_args, .. }
+            | Commands::Tune { cli_args, .. }
+            | Commands::ExportTree { cli_args, .. }
+            | Commands::BuildTree { cli_args, .. }
+            | Commands::Regression { cli_args, .. } => Some(cli_args),
+            _ => None,
+        }
+    }
+}
+
+/// The solver and everything else the flags load, for the subcommands
+struct Session {
+    args: Arguments,
+    pack: Option<WordPack>,
+    solver: Solver,
+    tree: Option<DecisionTree>,
+    book: Option<OpeningBook>,
+    // The snapshot of --compare and the solver of its strategy, if it needs
+    // its own ranking of the words
+    compare: Option<StrategySnapshot>,
+    compare_solver: Option<Solver>,
+    history: Option<History>,
+    telemetry: Option<Telemetry>,
+}
+
+impl Session {
+    fn load(mut args: Arguments, ranking: RankingConfig) -> Result<Session> {
+        // On stderr, so that the output of e.g. `suggest --json` can be parsed
+        eprintln!(
+            "{}",
+            "Initializing solver. This might take a while...".blue()
+        );
+        if args.pick_overlay {
+            args.overlay = platform::pick_file_or_prompt("Word list overlay")?;
+        }
+        let weighting = match args.uniform {
+            true => Weighting::Uniform,
+            false => Weighting::Priors,
+        };
+        let pack = args.pack.as_deref().map(WordPack::load).transpose()?;
+        let scoring = args
+            .scoring
+            .or(pack.as_ref().map(|pack| pack.manifest.feedback))
+            .unwrap_or_default();
+        let solver = load_solver(
+            pack.as_ref(),
+            args.wordlist.as_deref(),
+            args.overlay.as_deref(),
+            args.mapping_backend(),
+        )?
+        .with_ranking(ranking)
+        .with_prior_model(args.prior_model)
+        .with_weighting(weighting)
+        .with_pool(args.strategy.pool())
+        .with_scoring(scoring)
+        .with_cache_size(args.cache_size);
+        let tree = args
+            .tree
+            .as_ref()
+            .map(|path| load_tree(path, &solver))
+            .transpose()?;
+        let book = args
+            .book
+            .as_ref()
+            .map(|path| load_book(path, &solver))
+            .transpose()?;
+        let compare = args.compare.as_deref().map(load_snapshot).transpose()?;
+        let compare_solver = match &compare {
+            Some(snapshot) => snapshot_solver(&args, pack.as_ref(), snapshot, &solver)?,
+            None => None,
+        };
+        let history = args.history();
+        let telemetry = args.telemetry.clone().map(Telemetry::new);
+        Ok(Session {
+            args,
+            pack,
+            solver,
+            tree,
+            book,
+            compare,
+            compare_solver,
+            history,
+            telemetry,
+        })
+    }
+}
+
+fn main() -> Result<()> {
+    let matches = Arguments::command().get_matches();
+    let mut args = Arguments::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let config = match &args.config {
+        Some(path) => Config::load(path)?,
+        None => Config::load_default()?,
+    };
+    args.apply_config(config, &matches);
+    let ranking = args.strategy.ranking()?;
+    if args.strategy.eco {
+        args.strategy.apply_eco();
+    }
+    if let Some(threads) = args.max_cpu.or(args.strategy.eco.then_some(1)) {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads.max(1))
+            .build_global()
+            .context("Error limiting the worker threads")?;
+    }
+    let command = args.command.take();
+    let session = Session::load(args, ranking)?;
+
+    match command {
+        Some(Commands::Multi {
+            words, max_rounds, ..
+        }) if !words.is_empty() => run_multi(&session, words, max_rounds),
+        #[cfg(feature = "tui")]
+        command @ (Some(Commands::Tui {})
+        | Some(Commands::Play { .. })
+        | Some(Commands::Pack {})
+        | Some(Commands::Multi { .. })
+        | None) => run_tui(session, command),
+        #[cfg(not(feature = "tui"))]
+        Some(Commands::Multi { .. }) | None => {
+            anyhow::bail!("This build has no TUI, see `wordlebot help` for the other commands")
+        }
+        Some(Commands::Benchmark {
+            cli_args,
+            slowest,
+            folded,
+            progress,
+            answers,
+            sample,
+            seed,
+        }) => {
+            let answers = AnswerSet::new(&session.solver, answers.as_deref(), sample, seed)?;
+            run_benchmark(&session, cli_args, answers, slowest, folded, progress)
+        }
+        Some(Commands::Absurdle {}) => play_absurdle(&session.solver),
+        Some(Commands::Solve {
+            cli_args,
+            words,
+            absurdle,
+            noise,
+            interactive,
+        }) => run_solve(&session, cli_args, words, absurdle, noise, interactive),
+        Some(Commands::Tune {
+            from,
+            to,
+            step,
+            schedule,
+            sample,
+            output,
+            cli_args,
+        }) => tune(
+            &session.solver,
+            &penalty_schedules(from, to, step, schedule)?,
+            sample,
+            &cli_args,
+            &session.args.strategy,
+            output.as_ref(),
+        ),
+        Some(Commands::Openers {
+            top,
+            sort,
+            sample,
+            max_rounds,
+            output,
+        }) => compare_openers(
+            &session.solver,
+            top,
+            sort,
+            sample,
+            max_rounds,
+            &session.args.strategy,
+            output.as_ref(),
+        ),
+        Some(Commands::Book {
+            command:
+                BookCommand::Build {
+                    output,
+                    starting_word,
+                },
+        }) => run_build_book(&session, output, starting_word),
+        Some(Commands::Strategy {
+            command:
+                StrategyCommand::Save {
+                    name,
+                    starting_word,
+                },
+        }) => run_save_strategy(&session, name, starting_word),
+        Some(Commands::Strategy {
+            command: StrategyCommand::List {},
+        }) => list_snapshots(),
+        Some(Commands::ExportTree { output, cli_args }) => {
+            run_export_tree(&session, output, cli_args)
+        }
+        Some(Commands::BuildTree { output, cli_args }) => {
+            run_build_tree(&session, output, cli_args)
+        }
+        Some(Commands::DocsGen {
+            output,
+            openers,
+            patterns,
+            families,
+        }) => run_docs_gen(&session, output, openers, patterns, families),
+        Some(Commands::Regression {
+            old,
+            new,
+            positions,
+            suggestions,
+            no_benchmark,
+            cli_args,
+        }) => run_regression(
+            &session,
+            old,
+            new,
+            positions,
+            suggestions,
+            no_benchmark,
+            cli_args,
+        ),
+        Some(Commands::Suggest {
+            guess,
+            share,
+            words,
+            follow,
+            number,
+            json,
+        }) => {
+            let output = SuggestOutput { number, json };
+            run_suggest(&session, guess, share, words, follow, output)
+        }
+        Some(Commands::Grid {
+            share,
+            hard,
+            number,
+        }) => run_grid(&session, share, hard, number),
+        Some(Commands::Hint {
+            answer,
+            guesses,
+            level,
+        }) => run_hint(&session, answer, guesses, level),
+        Some(Commands::EvaluateLine { answer, line }) => run_evaluate_line(&session, answer, line),
+        Some(Commands::Pipe {}) => pipe(
+            &session.args.strategy,
+            &session.solver,
+            session.tree.as_ref(),
+            session.book.as_ref(),
+        ),
+        Some(Commands::TelemetryExport { log, output }) => run_telemetry_export(log, output),
+        Some(Commands::Corpus { fixture, write }) => run_corpus(fixture, write),
+        Some(Commands::Patterns {
+            word,
+            guess,
+            output,
+        }) => run_patterns(&session, word, guess, output),
+        Some(Commands::Why { word, guesses }) => run_why(&session, word, guesses),
+        Some(Commands::Streak {
+            guess,
+            rounds,
+            number,
+        }) => run_streak(&session, guess, rounds, number),
+        Some(Commands::Stats {}) => run_stats(&session),
+        Some(Commands::Import { log, dry_run }) => run_import(&session, log, dry_run),
+        Some(Commands::DataInfo { json }) => run_data_info(&session, json),
+    }
+}
+
+/// Solve several boards at once in the terminal
+fn run_multi(session: &Session, words: Vec<String>, max_rounds: Option<usize>) -> Result<()> {
+    let solver = &session.solver;
+    let answers: Vec<Word> = words
+        .iter()
+        .map(|word| solver.parse_word(word))
+        .collect::<Result<_, _>>()?;
+    let max_rounds = max_rounds.unwrap_or(answers.len() + 5);
+    solve_boards(&answers, solver, max_rounds)
+}
+
+/// Play, solve several boards or build a word pack in the TUI
+#[cfg(feature = "tui")]
+fn run_tui(session: Session, command: Option<Commands>) -> Result<()> {
+    let Session {
+        args,
+        pack,
+        solver,
+        tree,
+        book,
+        compare,
+        compare_solver,
+        history,
+        telemetry,
+    } = session;
+    // Only the TUI runs tasks in the background
+    let runtime = tokio::runtime::Runtime::new().context("Error starting the runtime")?;
+    let _runtime = runtime.enter();
+    let layout = tui::KeyboardLayout::load(&args.layout)?;
+    let theme = match &args.theme {
+        Some(name) => tui::Theme::load(name)?,
+        None => tui::Theme::default(),
+    };
+    let search = args.strategy.search();
+    let compare = match compare {
+        Some(snapshot) => {
+            let same_words =
+                check_fingerprint(&snapshot, compare_solver.as_ref().unwrap_or(&solver));
+            let strategy = snapshot_strategy_args(&snapshot)?;
+            Some(tui::CompareStrategy {
+                name: snapshot.name,
+                solver: compare_solver.map(Arc::new),
+                opener: snapshot.opener,
+                two_level: strategy.two_level,
+                formula: strategy.formula.clone().map(Arc::new),
+                score: strategy.score,
+                search: strategy.search(),
+                same_words,
+            })
+        }
+        None => None,
+    };
+    tui::initialize_panic_handler();
+    let mut terminal = tui::init()?;
+    let mut app = tui::App::init(
+        Arc::new(solver),
+        args.strategy.two_level,
+        args.strategy.formula.map(Arc::new),
+        args.strategy.score,
+        layout,
+        tree.map(Arc::new),
+        telemetry,
+    );
+    app.set_book(book.map(Arc::new));
+    app.set_history(history);
+    app.set_compare(compare);
+    app.set_check_updates(args.check_updates);
+    app.set_theme(theme);
+    app.set_eco(args.strategy.eco);
+    app.set_search(search);
+    app.set_word_pack(pack.map(|pack| pack.manifest.name));
+    if let Some(path) = args.overlay {
+        app.watch_overlay(path)?;
+    }
+    match command {
+        Some(Commands::Play { seed }) => {
+            app.set_seed(seed);
+            app.start_game()
+        }
+        Some(Commands::Pack {}) => app.start_pack(),
+        Some(Commands::Multi { boards, .. }) => app.start_boards(boards.clamp(1, 8)),
+        _ => {}
+    }
+    let app_result = runtime.block_on(app.run(&mut terminal));
+    tui::restore()?;
+    println!("{}", "Shutting down...".blue());
+    app_result?;
+    Ok(())
+}
+
+/// Benchmark the strategy, or compare it with --answers-only or a snapshot
+fn run_benchmark(
+    session: &Session,
+    cli_args: CliArgs,
+    answers: AnswerSet,
+    slowest: Option<usize>,
+    folded: Option<PathBuf>,
+    progress: ProgressMode,
+) -> Result<()> {
+    let Session {
+        args,
+        pack,
+        solver,
+        tree,
+        book,
+        compare,
+        compare_solver,
+        ..
+    } = session;
+    let strategy = Strategy::new(
+        cli_args.starting_word.clone(),
+        &args.strategy,
+        solver,
+        tree.as_ref(),
+        book.as_ref(),
+    )?;
+    // The cost of --answers-only is measured against all words
+    let timings = slowest.is_some() || folded.is_some();
+    if compare.is_none() && args.strategy.answers_only && !timings {
+        let other = all_words_solver(
+            pack.as_ref(),
+            args.wordlist.as_deref(),
+            args.overlay.as_deref(),
+            solver,
+        )?;
+        let mut other_args = args.strategy.clone();
+        other_args.answers_only = false;
+        let other_strategy = Strategy::new(
+            cli_args.starting_word,
+            &other_args,
+            &other,
+            tree.as_ref(),
+            book.as_ref(),
+        )?;
+        benchmark_compare(
+            (solver, &strategy),
+            (&other, &other_strategy),
+            cli_args.max_rounds,
+            "all words",
+            &answers,
+            progress,
+        );
+        return Ok(());
+    }
+    let Some(snapshot) = &compare else {
+        return benchmark(
+            solver,
+            &answers,
+            cli_args.max_rounds,
+            &strategy,
+            slowest,
+            folded.as_deref(),
+            progress,
+        );
+    };
+    if slowest.is_some() || folded.is_some() {
+        anyhow::bail!("--slowest and --folded do not work with --compare")
+    }
+    let other = compare_solver.as_ref().unwrap_or(solver);
+    check_fingerprint(snapshot, other);
+    let other_strategy = Strategy::new(
+        Some(snapshot.opener.to_string().to_lowercase()),
+        &snapshot_strategy_args(snapshot)?,
+        other,
+        None,
+        None,
+    )?;
+    benchmark_compare(
+        (solver, &strategy),
+        (other, &other_strategy),
+        cli_args.max_rounds,
+        &snapshot.name,
+        &answers,
+        progress,
+    );
+    Ok(())
+}
+
+/// Solve the words, an Absurdle game or a game with the patterns typed in
+fn run_solve(
+    session: &Session,
+    cli_args: CliArgs,
+    words: Vec<String>,
+    absurdle: bool,
+    noise: Option<f64>,
+    interactive: bool,
+) -> Result<()> {
+    let Session {
+        args,
+        solver,
+        tree,
+        book,
+        history,
+        telemetry,
+        ..
+    } = session;
+    let starting_word = cli_args.starting_word.clone();
+    let strategy = Strategy::new(
+        starting_word,
+        &args.strategy,
+        solver,
+        tree.as_ref(),
+        book.as_ref(),
+    )?;
+    if absurdle {
+        let guesses = try_to_solve(
+            &mut Adversarial,
+            solver,
+            cli_args.max_rounds,
+            true,
+            &strategy,
+        );
+        if let Some(telemetry) = &telemetry {
+            telemetry.record(&Outcome::today(
+                &format!("absurdle-{}", strategy.name()),
+                strategy.start,
+                solved_in(&guesses),
+            ))?;
+        }
+        return solve_absurdle(solver, &cli_args, &args.strategy.width);
+    }
+    if interactive {
+        let mut human = HumanEntered::new(std::io::stdin().lock(), std::io::stdout());
+        let guesses = try_to_solve(&mut human, solver, cli_args.max_rounds, true, &strategy);
+        // The answer of a game that was not solved is unknown
+        if let (Some(history), true) = (&history, solved_in(&guesses) > 0) {
+            let answer = guesses[guesses.len() - 1].word;
+            history.record(&GameRecord::today("solve", answer, &guesses))?;
+        }
+        return Ok(());
+    }
+    // Check every answer before the first game is played
+    let answers = solver.get_words_from_idx(&solver.get_frequent_word_idx());
+    let words: Vec<Word> = words
+        .iter()
+        .map(|w| solver.parse_word(w))
+        .collect::<Result<_, _>>()?;
+    for word in &words {
+        solver.word_id(word)?;
+        if !answers.contains(word) {
+            anyhow::bail!("{} is not a possible answer", word)
+        }
+    }
+    for word in words {
+        let now = Instant::now();
+        let mut oracle: Box<dyn FeedbackOracle> = match noise {
+            Some(rate) => Box::new(Noisy::new(word, rate, rand::random())),
+            None => Box::new(FixedAnswer(word)),
+        };
+        let guesses = try_to_solve(
+            oracle.as_mut(),
+            solver,
+            cli_args.max_rounds,
+            true,
+            &strategy,
+        );
+        if let Some(telemetry) = &telemetry {
+            telemetry.record(&Outcome::today(
+                strategy.name(),
+                strategy.start,
+                solved_in(&guesses),
+            ))?;
+        }
+        let elapsed = now.elapsed();
+        println!(" --- Elapsed: {:.2?}", elapsed);
+    }
+    Ok(())
+}
+
+/// The penalty schedules of `tune`: one constant penalty for every step
+/// from `from` to `to`, and the schedules given as lists
+fn penalty_schedules(
+    from: f32,
+    to: f32,
+    step: f32,
+    schedule: Vec<String>,
+) -> Result<Vec<Vec<f32>>> {
+    let mut schedules: Vec<Vec<f32>> = vec![];
+    let mut penalty = from;
+    while penalty <= to + f32::EPSILON && step > 0.0 {
+        schedules.push(vec![penalty]);
+        penalty += step;
+    }
+    for s in schedule {
+        let penalties = s
+            .split(',')
+            .map(|p| p.trim().parse::<f32>())
+            .collect::<Result<Vec<f32>, _>>()
+            .with_context(|| format!("Invalid penalty schedule '{}'", s))?;
+        schedules.push(penalties);
+    }
+    Ok(schedules)
+}
+
+/// Write the opening book of the strategy
+fn run_build_book(session: &Session, output: PathBuf, starting_word: Option<String>) -> Result<()> {
+    let Session { args, solver, .. } = session;
+    let strategy = Strategy::new(starting_word, &args.strategy, solver, None, None)?;
+    println!("Building the book for {}.", strategy.start);
+    let book = solver.build_book(strategy.start, |guesses| {
+        let penalty = strategy.penalty(guesses.len() + 1);
+        strategy.pick(guesses, solver, penalty).ok()
+    });
+    std::fs::write(&output, book.to_toml())
+        .with_context(|| format!("Error writing opening book {}", output.display()))?;
+    println!(
+        "Wrote the opening book to {} ({} second guesses)",
+        output.display(),
+        book.second.len()
+    );
+    Ok(())
+}
+
+/// Save the strategy as a snapshot
+fn run_save_strategy(session: &Session, name: String, starting_word: Option<String>) -> Result<()> {
+    let Session {
+        args,
+        solver,
+        tree,
+        book,
+        ..
+    } = session;
+    let strategy = Strategy::new(
+        starting_word,
+        &args.strategy,
+        solver,
+        tree.as_ref(),
+        book.as_ref(),
+    )?;
+    save_snapshot(&name, &strategy, &args.strategy, solver)
+}
+
+/// Export the games of the strategy as a decision tree
+fn run_export_tree(session: &Session, output: PathBuf, cli_args: CliArgs) -> Result<()> {
+    let Session {
+        args,
+        solver,
+        tree,
+        book,
+        ..
+    } = session;
+    let strategy = Strategy::new(
+        cli_args.starting_word,
+        &args.strategy,
+        solver,
+        tree.as_ref(),
+        book.as_ref(),
+    )?;
+    export_tree(solver, cli_args.max_rounds, &strategy, &output)
+}
+
+/// Search the decision tree with the fewest guesses and write it
+fn run_build_tree(session: &Session, output: PathBuf, cli_args: CliArgs) -> Result<()> {
+    let Session { args, solver, .. } = session;
+    let starting_word = cli_args
+        .starting_word
+        .map(|word| solver.parse_word(&word))
+        .transpose()?;
+    println!("Searching the decision tree.");
+    let tree =
+        solver.build_decision_tree(cli_args.max_rounds, starting_word, &args.strategy.width)?;
+    std::fs::write(&output, tree.to_text())
+        .with_context(|| format!("Error writing decision tree {}", output.display()))?;
+    println!(
+        "Wrote decision tree to {} (average guesses: {:.4})",
+        output.display(),
+        tree.average_guesses()
+    );
+    Ok(())
+}
+
+/// Write the strategy guide
+fn run_docs_gen(
+    session: &Session,
+    output: Option<PathBuf>,
+    openers: usize,
+    patterns: usize,
+    families: usize,
+) -> Result<()> {
+    let solver = &session.solver;
+    let guide = solver.strategy_guide(&GuideOptions {
+        openers,
+        patterns,
+        families,
+    });
+    match output {
+        Some(output) => std::fs::write(&output, guide)
+            .with_context(|| format!("Error writing {}", output.display()))?,
+        None => print!("{}", guide),
+    }
+    Ok(())
+}
+
+/// Compare the suggestions and the benchmark of two word lists
+fn run_regression(
+    session: &Session,
+    old: PathBuf,
+    new: PathBuf,
+    positions: usize,
+    suggestions: usize,
+    no_benchmark: bool,
+    cli_args: CliArgs,
+) -> Result<()> {
+    let Session { args, solver, .. } = session;
+    let ranking = *solver.ranking();
+    let prior_model = solver.prior_model();
+    let weighting = solver.weighting();
+    let pool = solver.pool();
+    let scoring = solver.scoring();
+    let load = |path: &PathBuf| -> Result<Solver> {
+        let solver = Solver::from_wordlist(path)
+            .with_context(|| format!("Error loading word list {}", path.display()))?;
+        Ok(solver
+            .with_ranking(ranking)
+            .with_prior_model(prior_model)
+            .with_weighting(weighting)
+            .with_pool(pool)
+            .with_scoring(scoring))
+    };
+    let (old, new) = (load(&old)?, load(&new)?);
+    let positions = old.common_positions(positions);
+    let mut snapshots = vec![];
+    for solver in [&old, &new] {
+        let mut snapshot = solver.snapshot(&positions, suggestions);
+        if !no_benchmark {
+            let starting_word = cli_args.starting_word.clone();
+            let strategy = Strategy::new(starting_word, &args.strategy, solver, None, None)?;
+            let games = play_all(
+                solver,
+                &snapshot.answers,
+                cli_args.max_rounds,
+                &strategy,
+                ProgressMode::Auto,
+            );
+            let steps: Vec<usize> = games.iter().map(|game| solved_in(game)).collect();
+            snapshot.benchmark = Some(BenchmarkStats::new(&snapshot.answers, &steps));
+        }
+        snapshots.push(snapshot);
+    }
+    for line in regression::diff(&snapshots[0], &snapshots[1]).lines() {
+        match line {
+            _ if line.starts_with("- ") => println!("{}", line.red()),
+            _ if line.starts_with("+ ") => println!("{}", line.green()),
+            _ if line.ends_with("(unchanged)") => println!("{}", line.dimmed()),
+            _ => println!("{}", line),
+        }
+    }
+    Ok(())
+}
+
+/// Print the suggestions for the guesses, a share string or a log
+fn run_suggest(
+    session: &Session,
+    guess: Vec<Guess>,
+    share: Option<String>,
+    words: Vec<String>,
+    follow: Option<PathBuf>,
+    output: SuggestOutput,
+) -> Result<()> {
+    let Session {
+        args,
+        solver,
+        tree,
+        book,
+        ..
+    } = session;
+    if let Some(path) = follow {
+        return follow_log(
+            &path,
+            output,
+            &args.strategy,
+            solver,
+            tree.as_ref(),
+            book.as_ref(),
+        );
+    }
+    let guess = match share {
+        Some(share) => {
+            let share = match share.as_str() {
+                "-" => std::io::read_to_string(std::io::stdin())?,
+                "clipboard" => platform::read_clipboard()?,
+                _ => share,
+            };
+            let words: Vec<Word> = words
+                .iter()
+                .map(|w| solver.parse_word(w))
+                .collect::<Result<_, _>>()?;
+            parse_share_string(&share, &words).context("Invalid share string")?
+        }
+        None => guess,
+    };
+    print_suggestions(
+        &guess,
+        output,
+        &args.strategy,
+        solver,
+        tree.as_ref(),
+        book.as_ref(),
+    )
+}
+
+/// Print the answers that fit the grid of a share string
+fn run_grid(session: &Session, share: String, hard: bool, number: usize) -> Result<()> {
+    let solver = &session.solver;
+    let share = match share.as_str() {
+        "-" => std::io::read_to_string(std::io::stdin())?,
+        "clipboard" => platform::read_clipboard()?,
+        _ => share,
+    };
+    let grid = parse_share_grid(&share).context("Invalid share string")?;
+    if grid.iter().any(|row| row.len() != solver.n_letters()) {
+        anyhow::bail!("The rows need {} tiles", solver.n_letters())
+    }
+    let rows: Vec<Pattern> = grid.iter().map(|row| encode_status(row)).collect();
+    let analysis = solver.analyze_grid(&rows, hard);
+    match analysis.solved {
+        true => println!("Solved in {}", rows.len()),
+        false => println!("Not solved in {}", rows.len()),
+    }
+    println!(
+        "{} answers fit, with {}{} sequences of guesses",
+        analysis.answers.len(),
+        match analysis.answers.iter().any(|a| a.truncated) {
+            true => "at least ",
+            false => "",
+        },
+        analysis.combinations()
+    );
+    if !analysis.answers.is_empty() {
+        println!(
+            "\n     {:<6} {:>7} {:>12}   guesses per row",
+            "answer", "chance", "sequences"
+        );
+    }
+    for (i, answer) in analysis.answers.iter().take(number).enumerate() {
+        let per_row: Vec<String> = answer
+            .guesses_per_row
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+        println!(
+            "{:>3}. {:<6} {:>6.2}% {:>12}{:1}  {}",
+            i + 1,
+            answer.answer.to_string(),
+            answer.probability * 100.0,
+            answer.combinations,
+            if answer.truncated { "+" } else { "" },
+            per_row.join(" × ")
+        );
+    }
+    Ok(())
+}
+
+/// Print the hints for the answer after the guesses
+fn run_hint(session: &Session, answer: String, guesses: Vec<String>, level: usize) -> Result<()> {
+    let solver = &session.solver;
+    let answer = solver.parse_word(&answer)?;
+    let words: Vec<Word> = guesses
+        .iter()
+        .map(|w| solver.parse_word(w))
+        .collect::<Result<_, _>>()?;
+    if let Some(word) = [answer]
+        .iter()
+        .chain(&words)
+        .find(|w| !solver.is_valid_guess(w))
+    {
+        anyhow::bail!("{} is not in the word list", word)
+    }
+    let guesses: Vec<Guess> = words
+        .iter()
+        .map(|&word| Guess {
+            word,
+            status: solver.pattern_of(&answer, &word),
+        })
+        .collect();
+    let hints = solver.hints(&answer, &guesses)?;
+    for (i, hint) in hints.iter().enumerate().take(level.max(1)) {
+        let n = solver.count_fitting(&guesses, &hints[..=i])?;
+        println!("Hint {}: {} ({} words fit)", i + 1, hint, n);
+    }
+    Ok(())
+}
+
+/// Compare every guess of a line with the guess of the solver
+fn run_evaluate_line(session: &Session, answer: String, line: String) -> Result<()> {
+    let Session { args, solver, .. } = session;
+    let answer = solver.parse_word(&answer)?;
+    let line: Vec<String> = line
+        .split(',')
+        .map(|word| word.trim().to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let Some(first) = line.first() else {
+        anyhow::bail!("The line has no guesses")
+    };
+    // The opener of the line, so that the strategy does not search one
+    let strategy = Strategy::new(Some(first.clone()), &args.strategy, solver, None, None)?;
+    let line: Vec<Word> = line
+        .iter()
+        .map(|w| solver.parse_word(w))
+        .collect::<Result<_, _>>()?;
+    let evaluation = solver.evaluate_line(&answer, &line, |round| strategy.penalty(round))?;
+    for (i, m) in evaluation.moves.iter().enumerate() {
+        let rank = format!("rank {:5}", m.rank);
+        println!(
+            " {}. {} - n before: {:4} | n after: {:4} | bits {:.2} | real bits {:.2} | {} | solver: {} {:.2} bits",
+            i + 1,
+            m.guess,
+            m.n_remaining_before,
+            m.n_remaining_after,
+            m.expected_bits,
+            m.real_bits,
+            if m.rank == 1 { rank.green() } else { rank.normal() },
+            m.best,
+            m.best_bits
+        );
+    }
+    match evaluation.outcome {
+        LineOutcome::Solved { guesses } => {
+            println!("Solved {} in {} guesses", answer, guesses)
+        }
+        LineOutcome::Unsolved { n_remaining } => {
+            println!("Not solved: {} words left for {}", n_remaining, answer)
+        }
+    }
+    Ok(())
+}
+
+/// Aggregate the telemetry log
+fn run_telemetry_export(log: PathBuf, output: Option<PathBuf>) -> Result<()> {
+    let bundle = aggregate(&Telemetry::new(log).read()?);
+    match output {
+        Some(output) => std::fs::write(&output, bundle)
+            .with_context(|| format!("Error writing {}", output.display()))?,
+        None => print!("{}", bundle),
+    }
+    Ok(())
+}
+
+/// Check the pattern corpus against the fixture, or write it
+fn run_corpus(fixture: PathBuf, write: bool) -> Result<()> {
+    let new = corpus::generate_default();
+    if write {
+        std::fs::write(&fixture, new)
+            .with_context(|| format!("Error writing {}", fixture.display()))?;
+        println!("Wrote {}", fixture.display());
+        return Ok(());
+    }
+    let old = std::fs::read_to_string(&fixture)
+        .with_context(|| format!("Error reading {}", fixture.display()))?;
+    let changes = corpus::diff(&old, &new);
+    for line in &changes {
+        println!("{}", line);
+    }
+    let problems = corpus::verify(&new);
+    for line in &problems {
+        println!("{}", line.red());
+    }
+    if !problems.is_empty() {
+        anyhow::bail!("{} patterns disagree with is_valid", problems.len())
+    }
+    if !changes.is_empty() {
+        anyhow::bail!(
+            "{} patterns changed, run with --write if that is intended",
+            changes.len()
+        )
+    }
+    let n = new.lines().filter(|line| !line.starts_with('#')).count();
+    println!("All {} patterns are unchanged", n);
+    Ok(())
+}
+
+/// Print the patterns of a word against the remaining words
+fn run_patterns(
+    session: &Session,
+    word: String,
+    guess: Vec<Guess>,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let solver = &session.solver;
+    let word = solver.parse_word(&word)?;
+    let remaining = solver.get_remaining_words_idx(&guess)?;
+    let rows = solver.pattern_table(&word, &remaining);
+    let csv = PatternRow::csv(&rows, solver.n_letters());
+    match output {
+        Some(output) => {
+            std::fs::write(&output, csv)
+                .with_context(|| format!("Error writing {}", output.display()))?;
+            println!(
+                "Wrote the {} patterns of {} over {} remaining words to {}",
+                rows.len(),
+                word,
+                remaining.len(),
+                output.display()
+            );
+        }
+        None => print!("{}", csv),
+    }
+    Ok(())
+}
+
+/// Print which guess eliminated a word
+fn run_why(session: &Session, word: String, guesses: Vec<Guess>) -> Result<()> {
+    let solver = &session.solver;
+    let word = solver.parse_word(&word)?;
+    match solver.explain_elimination(&word, &guesses) {
+        Some(elimination) => println!("{} was {}", word, elimination),
+        None => println!("{} is still possible", word),
+    }
+    Ok(())
+}
+
+/// Print the guesses most likely to solve in the rounds left
+fn run_streak(
+    session: &Session,
+    guess: Vec<Guess>,
+    rounds: Option<usize>,
+    number: usize,
+) -> Result<()> {
+    let Session { args, solver, .. } = session;
+    if let Some(g) = guess.iter().find(|g| !solver.is_valid_guess(&g.word)) {
+        anyhow::bail!("{} is not in the word list", g.word)
+    }
+    let rounds = rounds.unwrap_or(MAX_GUESSES.saturating_sub(guess.len()));
+    let remaining = solver.get_remaining_words_idx(&guess)?;
+    let width = args.strategy.width.width(remaining.len());
+    let beam = args.strategy.beam.max(1);
+    let advice = solver.streak_advice(number, &remaining, rounds, width, beam)?;
+    println!(
+        "{} words remaining, {} round{} left",
+        remaining.len(),
+        rounds,
+        if rounds == 1 { "" } else { "s" }
+    );
+    for (i, a) in advice.iter().enumerate() {
+        println!(
+            "{:3}. {} {:5.1}%{}",
+            i + 1,
+            a.word,
+            a.probability * 100.0,
+            if a.possible { " (possible)" } else { "" }
+        );
+    }
+    // What the usual suggestion risks
+    let opener = guess.first().map(|g| g.word.to_string().to_lowercase());
+    let strategy = Strategy::new(opener, &args.strategy, solver, None, None)?;
+    let suggestion = strategy.suggestions(1, &guess, solver).unwrap_or_default();
+    if let Some(best) = suggestion.first() {
+        if advice.iter().all(|a| a.word != *best) {
+            let p = solver.success_probability(best, &remaining, rounds, beam)?;
+            println!(
+                "The suggestion of the strategy, {}: {:.1}%",
+                best,
+                p * 100.0
+            );
+        }
+    }
+    if rounds > MAX_STREAK_ROUNDS {
+        println!(
+            "Only the next {} rounds are searched, so the probabilities are lower bounds",
+            MAX_STREAK_ROUNDS
+        );
+    }
+    Ok(())
+}
+
+/// Print the summary of the history
+fn run_stats(session: &Session) -> Result<()> {
+    let history = session
+        .history
+        .as_ref()
+        .context("The history is off or there is no $HOME, see --history")?;
+    let games = history.read()?;
+    if games.is_empty() {
+        println!("No game in {} yet", history.path().display());
+    } else {
+        println!("{}", history::Summary::new(&games));
+    }
+    Ok(())
+}
+
+/// Import a season log into the history and analyze it
+fn run_import(session: &Session, log: PathBuf, dry_run: bool) -> Result<()> {
+    let Session {
+        args,
+        solver,
+        history,
+        ..
+    } = session;
+    let text = std::fs::read_to_string(&log)
+        .with_context(|| format!("Error reading season log {}", log.display()))?;
+    let games = season::parse_season_log(&text)?;
+    let Some(first) = games.iter().find_map(|game| game.guesses.first()) else {
+        anyhow::bail!("{} has no games", log.display())
+    };
+    // Only the penalty of the strategy is needed, so no opener is searched
+    let opener = first.word.to_string().to_lowercase();
+    let strategy = Strategy::new(Some(opener), &args.strategy, solver, None, None)?;
+    let report = season::analyze_season(solver, &games, |round| strategy.penalty(round))?;
+    if !dry_run {
+        let history = history
+            .as_ref()
+            .context("The history is off or there is no $HOME, see --history")?;
+        let known = history.read()?;
+        let new: Vec<&GameRecord> = games.iter().filter(|g| !known.contains(g)).collect();
+        for game in &new {
+            history.record(game)?;
+        }
+        println!(
+            "Imported {} games into {}, {} were already there",
+            new.len(),
+            history.path().display(),
+            games.len() - new.len()
+        );
+    }
+    println!("{}", history::Summary::new(&games));
+    println!("{}", report);
+    Ok(())
+}
+
+/// Print the info of the word list
+fn run_data_info(session: &Session, json: bool) -> Result<()> {
+    let Session { args, pack, .. } = session;
+    let info = match (&pack, &args.wordlist) {
+        (Some(pack), _) => pack.info()?,
+        (None, Some(path)) => {
+            let data = std::fs::read(path)
+                .with_context(|| format!("Error reading word list {}", path.display()))?;
+            data::DataInfo::new(&data)?
+        }
+        (None, None) => data::embedded_info()?,
+    };
+    match json {
+        true => println!("{}", info.to_json()),
+        false => println!("{}", info),
+    }
+    Ok(())
+}
+
+/// How `suggest` prints the suggestions
+#[derive(Clone, Copy)]
+struct SuggestOutput {
+    number: usize,
+    json: bool,
+}
+
+/// Print the best next guesses after the guesses and the number of
+/// remaining words
+fn print_suggestions(
+    guesses: &[Guess],
+    output: SuggestOutput,
+    args: &StrategyArgs,
+    solver: &Solver,
+    tree: Option<&DecisionTree>,
+    book: Option<&OpeningBook>,
+) -> Result<()> {
+    if let Some(g) = guesses.iter().find(|g| !solver.is_valid_guess(&g.word)) {
+        anyhow::bail!("{} is not in the word list", g.word)
+    }
+    // The opener is only searched for the empty board
+    let opener = guesses.first().map(|g| g.word.to_string().to_lowercase());
+    let strategy = Strategy::new(opener, args, solver, tree, book)?;
+    let mut remaining = solver.get_remaining_words_idx(guesses)?;
+    remaining.sort_unstable();
+    let words = strategy
+        .suggestions(output.number, guesses, solver)
+        .unwrap_or_default();
+    let suggestions: Vec<GuessEvaluation> = words
+        .iter()
+        .map(|word| solver.evaluate_guess(word, &remaining, None, false))
+        .collect::<Result<_, _>>()?;
+    match output.json {
+        true => println!("{}", suggestions_json(remaining.len(), &suggestions)),
+        false => {
+            println!("{} words remaining", remaining.len());
+            for (i, s) in suggestions.iter().enumerate() {
+                println!(
+                    "{:3}. {} {:.2} bits{}",
+                    i + 1,
+                    s.word,
+                    s.expected_bits,
+                    if s.is_possible { " (possible)" } else { "" }
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print the suggestions for the guesses of a log file, and again whenever
+/// its guesses change, until interrupted. The file is polled, so it can be
+/// written by any program, and may not exist yet.
+fn follow_log(
+    path: &Path,
+    output: SuggestOutput,
+    args: &StrategyArgs,
+    solver: &Solver,
+    tree: Option<&DecisionTree>,
+    book: Option<&OpeningBook>,
+) -> Result<()> {
+    if !output.json {
+        println!("Following {}, stop with Ctrl+C", path.display());
+    }
+    let mut last = None;
+    loop {
+        let text = std::fs::read_to_string(path).unwrap_or_default();
+        // A line that is still being written is read once it is complete
+        let complete = &text[..text.rfind('\n').map_or(0, |i| i + 1)];
+        let guesses = parse_guess_log(complete).map_err(|e| format!("{:#}", e));
+        if last.as_ref() == Some(&guesses) {
+            std::thread::sleep(FOLLOW_INTERVAL);
+            continue;
+        }
+        match &guesses {
+            Err(e) => eprintln!(
+                "{}",
+                format!("Error reading {}: {}", path.display(), e).red()
+            ),
+            // JSON is printed for every change, one object per line
+            Ok(guesses) if output.json => {
+                if let Err(e) = print_suggestions(guesses, output, args, solver, tree, book) {
+                    eprintln!("{}", e.to_string().red());
+                }
+            }
+            Ok(guesses) => {
+                let line: Vec<String> = guesses.iter().map(|g| g.to_string()).collect();
+                match line.is_empty() {
+                    true => println!("\n{}", "New game".bold()),
+                    false => println!("\n{}", line.join(" ")),
+                }
+                let solved = solved_pattern(solver.n_letters());
+                if guesses.last().is_some_and(|g| g.status == solved) {
+                    println!("Solved in {} guesses", guesses.len());
+                } else if let Err(e) = print_suggestions(guesses, output, args, solver, tree, book)
+                {
+                    eprintln!("{}", e.to_string().red());
+                }
+            }
+        }
+        last = Some(guesses);
+    }
+}
+
+/// Answer the commands of `wordlebot pipe` until stdin is closed. The
+/// strategy, with its opener, is built once for all games.
+fn pipe(
+    args: &StrategyArgs,
+    solver: &Solver,
+    tree: Option<&DecisionTree>,
+    book: Option<&OpeningBook>,
+) -> Result<()> {
+    let strategy = Strategy::new(None, args, solver, tree, book)?;
+    let mut guesses: Vec<Guess> = vec![];
+    for line in std::io::stdin().lock().lines() {
+        let line = line.context("Error reading stdin")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let result = Request::parse(&line).and_then(|request| {
+            match request.command {
+                PipeCommand::Guess(guess) => {
+                    if !solver.is_valid_guess(&guess.word) {
+                        anyhow::bail!("{} is not in the word list", guess.word)
+                    }
+                    guesses.push(guess);
+                }
+                PipeCommand::Reset => guesses.clear(),
+                PipeCommand::Suggest { number } => {
+                    let mut remaining = solver.get_remaining_words_idx(&guesses)?;
+                    remaining.sort_unstable();
+                    let suggestions: Vec<GuessEvaluation> = strategy
+                        .suggestions(number, &guesses, solver)
+                        .map_err(|e| anyhow::anyhow!("{}", e))?
+                        .iter()
+                        .map(|word| solver.evaluate_guess(word, &remaining, None, false))
+                        .collect::<Result<_, _>>()?;
+                    return Ok(suggestions_fields(remaining.len(), &suggestions));
+                }
+            }
+            let n_remaining = solver.get_remaining_words_idx(&guesses)?.len();
+            Ok(format!(r#""remaining":{}"#, n_remaining))
+        });
+        // stdout is flushed after every line, so the response is sent right away
+        println!("{}", pipe::response(Request::id(&line).as_ref(), &result));
+    }
+    Ok(())
+}
+
+/// The suggestions as a JSON object like
+/// `{"remaining":2,"suggestions":[{"word":"crane","bits":1,"possible":true}]}`
+fn suggestions_json(n_remaining: usize, suggestions: &[GuessEvaluation]) -> String {
+    format!("{{{}}}", suggestions_fields(n_remaining, suggestions))
+}
+
+/// The fields of [`suggestions_json`] without the braces
+fn suggestions_fields(n_remaining: usize, suggestions: &[GuessEvaluation]) -> String {
+    let suggestions: Vec<String> = suggestions
+        .iter()
+        .map(|s| {
+            format!(
+                r#"{{"word":"{}","bits":{},"possible":{}}}"#,
+                s.word.to_string().to_lowercase(),
+                s.expected_bits,
+                s.is_possible
+            )
+        })
+        .collect();
+    format!(
+        r#""remaining":{},"suggestions":[{}]"#,
+        n_remaining,
+        suggestions.join(",")
+    )
 }
 
-#[derive(Args, Debug)]
-struct CliArgs {
-    /// Choose a manual starting word
-    #[arg(short, long)]
-    starting_word: Option<String>,
+/// The solver of a word list and an overlay, with the default ranking
+fn load_solver(
+    pack: Option<&WordPack>,
+    wordlist: Option<&Path>,
+    overlay: Option<&Path>,
+    backend: MappingBackend,
+) -> Result<Solver> {
+    let solver = match (pack, wordlist) {
+        (Some(pack), _) => Solver::from_pack(pack, backend),
+        (None, Some(path)) => Solver::from_wordlist_with(path, backend),
+        (None, None) => Solver::new_with(backend),
+    }
+    .context("Error initializing solver")?;
+    match overlay {
+        Some(path) => solver.with_overlay_file(path),
+        None => Ok(solver),
+    }
+}
 
-    /// Maximal number of rounds
-    #[arg(short, long, default_value_t = 6)]
-    max_rounds: usize,
+/// The directory of the saved strategy snapshots, next to the config file
+fn snapshot_dir() -> Result<PathBuf> {
+    Config::path()
+        .as_deref()
+        .and_then(std::path::Path::parent)
+        .map(|dir| dir.join("strategies"))
+        .context("No config directory, set $HOME or $XDG_CONFIG_HOME")
 }
 
-#[derive(Subcommand, Debug)]
-enum Commands {
-    /// Default. Launch with graphical interface
-    Tui {},
+/// The file of a snapshot: a path as it is, a name in the snapshot
+/// directory
+fn snapshot_path(name: &str) -> Result<PathBuf> {
+    if name.contains(std::path::MAIN_SEPARATOR) || name.ends_with(".toml") {
+        return Ok(PathBuf::from(name));
+    }
+    if name.is_empty() || name.starts_with('.') {
+        anyhow::bail!("Invalid snapshot name '{}'", name)
+    }
+    Ok(snapshot_dir()?.join(format!("{}.toml", name)))
+}
 
-    /// Benchmark against all words in file
-    Benchmark {
-        #[command(flatten)]
-        cli_args: CliArgs,
-    },
+fn load_snapshot(name: &str) -> Result<StrategySnapshot> {
+    let path = snapshot_path(name)?;
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("Error reading strategy snapshot {}", path.display()))?;
+    StrategySnapshot::parse(&text)
+        .with_context(|| format!("Error parsing strategy snapshot {}", path.display()))
+}
 
-    /// Get the best strategy to solve words
-    Solve {
-        /// The words to solve
-        words: Vec<String>,
+/// The flags of a strategy for a snapshot. The weights of the ranking are
+/// saved with the snapshot itself.
+fn snapshot_settings(args: &StrategyArgs) -> BTreeMap<String, String> {
+    let mut settings = BTreeMap::new();
+    let penalty: Vec<String> = args.penalty.iter().map(f32::to_string).collect();
+    settings.insert("two-level".to_string(), args.two_level.to_string());
+    settings.insert("width".to_string(), args.width.to_string());
+    settings.insert("penalty".to_string(), penalty.join(","));
+    settings.insert("eco".to_string(), args.eco.to_string());
+    settings.insert("answers-only".to_string(), args.answers_only.to_string());
+    if let Some(formula) = &args.formula {
+        settings.insert("formula".to_string(), formula.to_string());
+    }
+    // The score conflicts with the other rankings, even as its default
+    if args.score != ScoreStrategy::default() {
+        settings.insert("score".to_string(), args.score.to_string());
+    }
+    if let Some(depth) = args.depth {
+        settings.insert("depth".to_string(), depth.to_string());
+        settings.insert("beam".to_string(), args.beam.to_string());
+    }
+    settings
+}
 
-        #[command(flatten)]
-        cli_args: CliArgs,
-    },
+/// A solver like the solver of the flags that suggests all words, to
+/// compare --answers-only with
+fn all_words_solver(
+    pack: Option<&WordPack>,
+    wordlist: Option<&Path>,
+    overlay: Option<&Path>,
+    solver: &Solver,
+) -> Result<Solver> {
+    println!(
+        "{}",
+        "Initializing a second solver that suggests all words...".blue()
+    );
+    Ok(
+        load_solver(pack, wordlist, overlay, solver.mapping_backend())?
+            .with_ranking(*solver.ranking())
+            .with_prior_model(solver.prior_model())
+            .with_weighting(solver.weighting())
+            .with_scoring(solver.scoring())
+            .with_cache_size(solver.cache().capacity()),
+    )
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let args = Arguments::parse();
+/// The strategy flags of a snapshot
+fn snapshot_strategy_args(snapshot: &StrategySnapshot) -> Result<StrategyArgs> {
+    let args = std::iter::once("wordlebot".to_string()).chain(snapshot.args());
+    let mut strategy = SnapshotArgs::try_parse_from(args)
+        .with_context(|| format!("Error reading the settings of snapshot {}", snapshot.name))?
+        .strategy;
+    if strategy.eco {
+        strategy.apply_eco();
+    }
+    Ok(strategy)
+}
 
+/// A solver of the word list and overlay of the flags with the ranking and
+/// weighting of a snapshot, or `None` if the solver has them already
+fn snapshot_solver(
+    args: &Arguments,
+    pack: Option<&WordPack>,
+    snapshot: &StrategySnapshot,
+    solver: &Solver,
+) -> Result<Option<Solver>> {
+    let pool = snapshot_strategy_args(snapshot)?.pool();
+    if snapshot.ranking == *solver.ranking()
+        && snapshot.weighting == solver.weighting()
+        && pool == solver.pool()
+    {
+        return Ok(None);
+    }
     println!(
         "{}",
-        "Initializing solver. This might take a while...".blue()
+        format!(
+            "Initializing a second solver for snapshot {}...",
+            snapshot.name
+        )
+        .blue()
     );
-    let solver = wordlebot::solver::Solver::new().context("Error initializing solver")?;
-
-    match args.command {
-        Some(Commands::Tui {}) | None => {
-            tui::initialize_panic_handler();
-            let mut terminal = tui::init()?;
-            let app_result = tui::App::init(solver, args.two_level)
-                .run(&mut terminal)
-                .await;
-            tui::restore()?;
-            println!("{}", "Shutting down...".blue());
-            app_result?;
-            Ok(())
-        }
-        Some(Commands::Benchmark { cli_args }) => {
-            let starting_word = pick_starting_word(cli_args.starting_word, &solver, args.two_level);
-            benchmark(&solver, cli_args.max_rounds, starting_word, args.two_level);
-            Ok(())
-        }
-        Some(Commands::Solve { cli_args, words }) => {
-            use std::time::Instant;
-            let starting_word = pick_starting_word(cli_args.starting_word, &solver, args.two_level);
-            for word in words {
-                let now = Instant::now();
-                let word = create_word_from_string(&word);
-                try_to_solve(
-                    &word,
-                    &solver,
-                    cli_args.max_rounds,
-                    true,
-                    starting_word,
-                    args.two_level,
-                );
-                let elapsed = now.elapsed();
-                println!(" --- Elapsed: {:.2?}", elapsed);
-            }
-            Ok(())
-        }
+    let other = load_solver(
+        pack,
+        args.wordlist.as_deref(),
+        args.overlay.as_deref(),
+        args.mapping_backend(),
+    )?
+    .with_ranking(snapshot.ranking)
+    .with_prior_model(solver.prior_model())
+    .with_weighting(snapshot.weighting)
+    .with_pool(pool)
+    .with_scoring(solver.scoring())
+    .with_cache_size(args.cache_size);
+    Ok(Some(other))
+}
+
+/// Warn if a snapshot was saved with another word list than the solver has
+fn check_fingerprint(snapshot: &StrategySnapshot, solver: &Solver) -> bool {
+    let same = snapshot.fingerprint == solver.fingerprint();
+    if !same {
+        println!(
+            "{}",
+            format!(
+                "Snapshot {} was saved with another word list (fingerprint {:016x}, now {:016x})",
+                snapshot.name,
+                snapshot.fingerprint,
+                solver.fingerprint()
+            )
+            .yellow()
+        );
     }
+    same
 }
 
-fn pick_starting_word(word: Option<String>, solver: &Solver, two_level: bool) -> Word {
-    match word {
-        Some(word) => create_word_from_string(&word),
-        None => {
-            if two_level {
-                pick_two_level(&[], solver, 0.0)
-            } else {
-                solver.guess(1, &solver.get_frequent_word_idx(), 0.0)[0]
-            }
+/// Save the current strategy as a snapshot
+fn save_snapshot(
+    name: &str,
+    strategy: &Strategy,
+    args: &StrategyArgs,
+    solver: &Solver,
+) -> Result<()> {
+    if strategy.tree.is_some() || strategy.book.is_some() {
+        println!(
+            "{}",
+            "The decision tree and the opening book are not part of the snapshot, only the opener"
+                .yellow()
+        );
+    }
+    let snapshot = StrategySnapshot {
+        name: name.to_string(),
+        opener: strategy.start,
+        fingerprint: solver.fingerprint(),
+        weighting: solver.weighting(),
+        ranking: *solver.ranking(),
+        settings: snapshot_settings(args),
+    };
+    let path = snapshot_path(name)?;
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Error creating directory {}", dir.display()))?;
+    }
+    std::fs::write(&path, snapshot.to_toml())
+        .with_context(|| format!("Error writing strategy snapshot {}", path.display()))?;
+    println!(
+        "Saved the strategy with the opener {} as {} to {}",
+        strategy.start,
+        name,
+        path.display()
+    );
+    Ok(())
+}
+
+/// Print the saved snapshots with their opener and settings
+fn list_snapshots() -> Result<()> {
+    let dir = snapshot_dir()?;
+    let mut paths: Vec<PathBuf> = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect(),
+        Err(_) => vec![],
+    };
+    if paths.is_empty() {
+        println!("No snapshots in {}", dir.display());
+        return Ok(());
+    }
+    paths.sort();
+    for path in paths {
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        let snapshot = std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| StrategySnapshot::parse(&text));
+        match snapshot {
+            Ok(snapshot) => println!(
+                "{:<16} {} {}",
+                name,
+                snapshot.opener,
+                snapshot.args().join(" ")
+            ),
+            Err(e) => println!("{:<16} {}", name, format!("{:#}", e).red()),
         }
     }
+    Ok(())
+}
+
+fn load_book(path: &PathBuf, solver: &Solver) -> Result<OpeningBook> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Error reading opening book {}", path.display()))?;
+    let book = OpeningBook::parse(&text).context("Error parsing opening book")?;
+    if let Some(word) = book.guesses().iter().find(|w| !solver.is_valid_guess(w)) {
+        anyhow::bail!("Opening book contains the unknown word {}", word)
+    }
+    Ok(book)
 }
 
-fn pick_two_level(guesses: &[Guess], solver: &Solver, penalty: f32) -> Word {
-    let remaining_words = solver.get_remaining_words_idx(guesses);
-    let suggestions = solver.guess(10, &remaining_words, penalty);
+fn load_tree(path: &PathBuf, solver: &Solver) -> Result<DecisionTree> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Error reading decision tree {}", path.display()))?;
+    let tree = DecisionTree::parse(&text).context("Error parsing decision tree")?;
+    if let Some(word) = tree.guesses().iter().find(|w| !solver.is_valid_guess(w)) {
+        anyhow::bail!("Decision tree contains the unknown word {}", word)
+    }
+    Ok(tree)
+}
 
-    let suggestions: Vec<GuessEvaluation> = suggestions
+/// Benchmarks the current strategy and the strategy of a snapshot on the
+/// same answers and prints their results side by side
+fn benchmark_compare(
+    (solver, strategy): (&Solver, &Strategy),
+    (other, other_strategy): (&Solver, &Strategy),
+    max_rounds: usize,
+    name: &str,
+    answers: &AnswerSet,
+    progress: ProgressMode,
+) {
+    let words = &answers.words;
+    println!(
+        "Benchmarking the current strategy (A) and {} (B) on {}.",
+        name, answers.description
+    );
+    let steps: Vec<usize> = play_all(solver, words, max_rounds, strategy, progress)
+        .iter()
+        .map(|game| solved_in(game))
+        .collect();
+    let other_steps: Vec<usize> = play_all(other, words, max_rounds, other_strategy, progress)
         .iter()
-        .map(|w| solver.evalute_guess(w, &remaining_words, None, true))
+        .map(|game| solved_in(game))
         .collect();
+    let comparison = Comparison::new(words, &steps, &other_steps);
+
+    println!("{:>8} {:>8} {:>8}", "Guesses", "A", "B");
+    for n in 1..=max_rounds {
+        let count = |stats: &BenchmarkStats| stats.distribution.get(&n).copied().unwrap_or(0);
+        println!(
+            "{:>8} {:>8} {:>8}",
+            n,
+            count(&comparison.a),
+            count(&comparison.b)
+        );
+    }
+    println!(
+        "{:>8} {:>8} {:>8}",
+        "Failed",
+        comparison.a.failed.len(),
+        comparison.b.failed.len()
+    );
+    println!(
+        "{:>8} {:>8.3} {:>8.3}",
+        "Average",
+        comparison.a.average(),
+        comparison.b.average()
+    );
+    let sample = |words: &[Word]| {
+        let sample: Vec<String> = words.iter().take(10).map(Word::to_string).collect();
+        match words.len() > sample.len() {
+            true => format!("{}, ...", sample.join(", ")),
+            false => sample.join(", "),
+        }
+    };
+    println!(
+        "{} better on {} answers: {}",
+        name,
+        comparison.better.len(),
+        sample(&comparison.better).green()
+    );
+    println!(
+        "{} worse on {} answers: {}",
+        name,
+        comparison.worse.len(),
+        sample(&comparison.worse).red()
+    );
+}
 
-    let mut suggestions: Vec<(bool, GuessEvaluation)> = suggestions
+/// Plays all words and returns the guesses of each game, see [`solved_in`]
+fn play_all(
+    solver: &Solver,
+    words: &[Word],
+    max_rounds: usize,
+    strategy: &Strategy,
+    progress: ProgressMode,
+) -> Vec<Vec<Guess>> {
+    play_all_timed(solver, words, max_rounds, strategy, progress)
         .into_iter()
+        .map(|(guesses, _)| guesses)
+        .collect()
+}
+
+/// Plays all words and returns the guesses of each game with the decision
+/// of every guess
+fn play_all_timed(
+    solver: &Solver,
+    words: &[Word],
+    max_rounds: usize,
+    strategy: &Strategy,
+    progress: ProgressMode,
+) -> Vec<(Vec<Guess>, Vec<Decision>)> {
+    let progress = Progress::new(words.len(), progress);
+    let games = words
+        .par_iter()
         .map(|word| {
-            let id = solver.get_id_for_word(&word.word).unwrap();
-            let possible = remaining_words.contains(&id);
-            (possible, word)
+            let game =
+                try_to_solve_timed(&mut FixedAnswer(*word), solver, max_rounds, false, strategy);
+            progress.inc();
+            game
         })
         .collect();
-
-    suggestions.sort_by(|(p1, s1), (p2, s2)| {
-        rank_guess(s2.two_level_bits.unwrap(), s2.prior, penalty * 2., *p2)
-            .partial_cmp(&rank_guess(
-                s1.two_level_bits.unwrap(),
-                s1.prior,
-                penalty * 2.,
-                *p1,
-            ))
-            .unwrap()
-    });
-
-    // suggestions.iter().for_each(|(p, s)| {
-    //     println!(
-    //         "{} {} {}",
-    //         s.word,
-    //         rank_guess(s.two_level_bits.unwrap(), s.prior, penalty * 2., *p),
-    //         p
-    //     );
-    // });
-
-    let (_, word) = suggestions.first().unwrap();
-    word.word
+    progress.finish();
+    games
 }
 
-fn benchmark(solver: &Solver, max_rounds: usize, start: Word, two_level: bool) {
-    let words = solver.get_words_from_idx(&solver.get_frequent_word_idx());
+fn benchmark(
+    solver: &Solver,
+    answers: &AnswerSet,
+    max_rounds: usize,
+    strategy: &Strategy,
+    slowest: Option<usize>,
+    folded: Option<&Path>,
+    progress: ProgressMode,
+) -> Result<()> {
+    let words = &answers.words;
 
-    println!("Starting benchmark.");
-    let style =
-        ProgressStyle::with_template("{wide_bar} {pos:>7}/{len:7} [{eta_precise} remaining]")
-            .unwrap()
-            .progress_chars("##-");
-    let mut steps: Vec<usize> = words
-        .par_iter()
-        .progress_with_style(style)
-        .map(|word| try_to_solve(word, solver, max_rounds, false, start, two_level))
-        .collect();
+    println!("Starting benchmark on {}.", answers.description);
+    let timed = play_all_timed(solver, words, max_rounds, strategy, progress);
+    if let Some(n) = slowest {
+        print_slowest(words, &timed, n);
+    }
+    if let Some(path) = folded {
+        std::fs::write(path, folded_stacks(&timed))
+            .with_context(|| format!("Error writing {}", path.display()))?;
+        println!("Wrote the time of every decision to {}", path.display());
+    }
+    let games: Vec<Vec<Guess>> = timed.into_iter().map(|(guesses, _)| guesses).collect();
+    let mut steps: Vec<usize> = games.iter().map(|game| solved_in(game)).collect();
 
     let failed = steps.iter().filter(|&x| *x == (0_usize)).count();
     let failes_idx: Vec<usize> = steps
@@ -181,16 +2771,27 @@ fn benchmark(solver: &Solver, max_rounds: usize, start: Word, two_level: bool) {
         .filter(|(_, &x)| x == (0_usize))
         .map(|(id, _)| id)
         .collect();
-    let failed_words = solver
-        .get_words_from_idx(&failes_idx)
-        .into_iter()
-        .map(|i| format!("{}", i))
+    let failed_words = failes_idx
+        .iter()
+        .map(|&i| format!("{}", words[i]))
         .collect::<Vec<String>>()
         .join(", ");
     println!(
         "{} words could not be solved in {} guesses: {}",
         failed, max_rounds, failed_words
     );
+    if failed > 0 {
+        println!("Searching for a way to solve them in time...");
+        // The failed games themselves are searched, since the ranking of
+        // ties may differ in a replay
+        let failures: Vec<(Word, Failure)> = failes_idx
+            .par_iter()
+            .map(|&i| (words[i], classify_failure(&games[i], solver, max_rounds)))
+            .collect();
+        for (word, failure) in failures {
+            println!(" {} {}", word, failure);
+        }
+    }
 
     // Step 1: Remove all occurrences of 0 from the vector
     steps.retain(|&x| x != 0);
@@ -222,18 +2823,217 @@ fn benchmark(solver: &Solver, max_rounds: usize, start: Word, two_level: bool) {
             println!("Steps {}: Count {}", num, count);
         }
     }
+    println!("Answers: {}", answers.description);
+    Ok(())
+}
+
+/// The answers a benchmark plays, see `benchmark --answers` and `--sample`
+struct AnswerSet {
+    words: Vec<Word>,
+    // Which answers they are, for the summary
+    description: String,
+}
+
+impl AnswerSet {
+    /// All answers of the word list or those of a file, or a sample of them
+    fn new(
+        solver: &Solver,
+        path: Option<&Path>,
+        sample: Option<usize>,
+        seed: Option<u64>,
+    ) -> Result<AnswerSet> {
+        let all = solver.get_words_from_idx(&solver.get_frequent_word_idx());
+        let (mut words, mut description) = match path {
+            None => {
+                let description = format!("all {} answers of the word list", all.len());
+                (all, description)
+            }
+            Some(path) => {
+                let file = std::fs::File::open(path)
+                    .with_context(|| format!("Error opening answers {}", path.display()))?;
+                let list = data::parse_overlay(file)
+                    .with_context(|| format!("Error importing answers {}", path.display()))?;
+                let mut words: Vec<Word> = vec![];
+                for (word, _) in list {
+                    if !all.contains(&word) {
+                        anyhow::bail!(
+                            "{} in {} is not a possible answer of the word list",
+                            word,
+                            path.display()
+                        )
+                    }
+                    if !words.contains(&word) {
+                        words.push(word);
+                    }
+                }
+                if words.is_empty() {
+                    anyhow::bail!("{} has no answers", path.display())
+                }
+                let description = format!("the {} answers of {}", words.len(), path.display());
+                (words, description)
+            }
+        };
+        if let Some(n) = sample {
+            if n == 0 {
+                anyhow::bail!("The sample has no answers")
+            }
+            let seed = seed.unwrap_or_else(rand::random);
+            let mut rng = StdRng::seed_from_u64(seed);
+            // The answers keep their order, so the output reads like that
+            // of all answers
+            let mut picked =
+                rand::seq::index::sample(&mut rng, words.len(), n.min(words.len())).into_vec();
+            picked.sort_unstable();
+            words = picked.into_iter().map(|i| words[i]).collect();
+            description = format!(
+                "a sample of {} of {} (--seed {})",
+                words.len(),
+                description,
+                seed
+            );
+        }
+        Ok(AnswerSet { words, description })
+    }
+}
+
+/// Print the `n` answers whose decisions took the longest in total, with
+/// every guess and the time it took
+fn print_slowest(words: &[Word], games: &[(Vec<Guess>, Vec<Decision>)], n: usize) {
+    let total = |decisions: &[Decision]| decisions.iter().map(|d| d.elapsed).sum::<Duration>();
+    let mut order: Vec<usize> = (0..games.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(total(&games[i].1)));
+    println!(
+        "{}",
+        format!("The {} slowest answers", n.min(games.len())).bold()
+    );
+    for (rank, &i) in order.iter().take(n).enumerate() {
+        let (guesses, decisions) = &games[i];
+        let outcome = match solved_in(guesses) {
+            0 => "not solved".red().to_string(),
+            n => format!("{} guesses", n),
+        };
+        println!(
+            "{:3}. {} {:>9.2?} | {}",
+            rank + 1,
+            words[i],
+            total(decisions),
+            outcome
+        );
+        for (guess, decision) in guesses.iter().zip(decisions) {
+            println!("       {} {}", guess, decision);
+        }
+    }
+}
+
+/// The time of the decisions as folded stacks, e.g. for
+/// `inferno-flamegraph`: one line per position, with the earlier guesses
+/// and their patterns as frames, e.g. `salet;bbybb;courd`, and the time
+/// of all decisions there in microseconds
+fn folded_stacks(games: &[(Vec<Guess>, Vec<Decision>)]) -> String {
+    let mut stacks: BTreeMap<String, u128> = BTreeMap::new();
+    for (guesses, decisions) in games {
+        let mut frames: Vec<String> = vec![];
+        for (guess, decision) in guesses.iter().zip(decisions) {
+            frames.push(guess.word.to_string().to_lowercase());
+            *stacks.entry(frames.join(";")).or_default() += decision.elapsed.as_micros();
+            frames.push(pattern_to_string(guess.status, guess.word.n_letters()).to_lowercase());
+        }
+    }
+    stacks
+        .iter()
+        .map(|(stack, micros)| format!("{} {}\n", stack, micros))
+        .collect()
+}
+
+// The number of guesses the search for a way to solve a failed answer may
+// try from every position
+const FAILURE_SEARCH_BUDGET: usize = 2_000_000;
+
+/// Why the benchmark could not solve an answer in time
+enum Failure {
+    /// The answer could still be found in time after this many guesses
+    StrategyFault { after: usize, n_remaining: usize },
+    /// No strategy finds the answer in time after the opener
+    Impossible,
+    /// The search gave up. No strategy finds the answer in time after this
+    /// many guesses.
+    Undecided { impossible_after: usize },
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::StrategyFault { after, n_remaining } => write!(
+                f,
+                "{}: the {} words after guess {} can be solved in the remaining rounds",
+                "strategy fault".red(),
+                n_remaining,
+                after
+            ),
+            Failure::Impossible => write!(
+                f,
+                "{}: no strategy solves it in time after the opener",
+                "impossible within rounds".yellow()
+            ),
+            Failure::Undecided { impossible_after } => write!(
+                f,
+                "undecided: impossible within rounds after guess {}, the search gave up before",
+                impossible_after
+            ),
+        }
+    }
 }
 
-fn print_guess_evaludation(guess: &Guess, remaining_words: &[usize], solver: &Solver) {
+/// Search a failed game backwards from the end for the last position from
+/// which every remaining word can be solved in the remaining rounds. The
+/// opener is taken as given.
+fn classify_failure(guesses: &[Guess], solver: &Solver, max_rounds: usize) -> Failure {
+    for after in (1..guesses.len()).rev() {
+        // The guesses were played against an answer of the list, so they fit
+        let Ok(mut remaining) = solver.get_remaining_words_idx(&guesses[..after]) else {
+            continue;
+        };
+        remaining.sort_unstable();
+        let rounds = max_rounds - after;
+        match solver.solvable_within(&remaining, rounds, FAILURE_SEARCH_BUDGET) {
+            Some(true) => {
+                return Failure::StrategyFault {
+                    after,
+                    n_remaining: remaining.len(),
+                }
+            }
+            Some(false) => continue,
+            None => {
+                return Failure::Undecided {
+                    impossible_after: after + 1,
+                }
+            }
+        }
+    }
+    Failure::Impossible
+}
+
+fn print_guess_evaludation(
+    guess: &Guess,
+    remaining_words: &[usize],
+    solver: &Solver,
+    decision: Option<&Decision>,
+) {
     let two_level = true;
-    let res = solver.evalute_guess(
+    let res = match solver.evaluate_guess(
         &guess.word,
         remaining_words,
-        Some(decode_status(guess.status)),
+        Some(guess.get_status()),
         two_level,
-    );
+    ) {
+        Ok(res) => res,
+        Err(e) => {
+            println!(" {} - {}", guess, e.to_string().red());
+            return;
+        }
+    };
 
-    println!(
+    print!(
             " {} - n before: {:4?} | n after: {:4?} | bits {:.2} | 2l bits {:2.2} | n groups {:3} | max group {:4}",
             guess,
             res.n_remaining_before,
@@ -242,57 +3042,387 @@ fn print_guess_evaludation(guess: &Guess, remaining_words: &[usize], solver: &So
             res.two_level_bits.unwrap(),
             res.groups,
             res.max_group_size
+        );
+    match decision {
+        Some(decision) => println!(" | {}", decision),
+        None => println!(),
+    }
+}
+
+/// Prints the best openers as a table, each benchmarked on an evenly
+/// spaced sample of the answers
+fn compare_openers(
+    solver: &Solver,
+    top: usize,
+    sort: OpenerSort,
+    sample: usize,
+    max_rounds: usize,
+    strategy_args: &StrategyArgs,
+    output: Option<&PathBuf>,
+) -> Result<()> {
+    println!("Ranking the openers.");
+    let mut openers = solver.opener_stats(top);
+    if sample > 0 {
+        let answers = solver.get_words_from_idx(&solver.get_frequent_word_idx());
+        let stride = (answers.len() / sample.max(1)).max(1);
+        let words: Vec<Word> = answers.into_iter().step_by(stride).take(sample).collect();
+        for (i, opener) in openers.iter_mut().enumerate() {
+            println!("Opener {}/{}: {}", i + 1, top, opener.word);
+            let start = Some(opener.word.to_string().to_lowercase());
+            let strategy = Strategy::new(start, strategy_args, solver, None, None)?;
+            let steps: Vec<usize> =
+                play_all(solver, &words, max_rounds, &strategy, ProgressMode::Auto)
+                    .iter()
+                    .map(|game| solved_in(game))
+                    .collect();
+            let stats = BenchmarkStats::new(&words, &steps);
+            opener.mean_guesses = Some(stats.average());
+            opener.failed = Some(stats.failed.len());
+        }
+    }
+    OpenerStats::sort(&mut openers, sort);
+
+    println!(
+        "{:>4}  {:<8} {:>6} {:>8} {:>6} {:>7} {:>6} {:>6}",
+        "Rank", "Opener", "Bits", "2L bits", "Worst", "Groups", "Mean", "Failed"
+    );
+    for (i, o) in openers.iter().enumerate() {
+        println!(
+            "{:>4}  {:<8} {:>6.3} {:>8.3} {:>6} {:>7} {:>6} {:>6}",
+            i + 1,
+            o.word,
+            o.expected_bits,
+            o.two_level_bits,
+            o.max_group_size,
+            o.groups,
+            o.mean_guesses
+                .map_or("-".to_string(), |m| format!("{:.3}", m)),
+            o.failed.map_or("-".to_string(), |f| f.to_string())
+        );
+    }
+    if let Some(output) = output {
+        std::fs::write(output, OpenerStats::csv(&openers))
+            .with_context(|| format!("Error writing {}", output.display()))?;
+    }
+    Ok(())
+}
+
+/// Benchmarks each penalty schedule on an evenly spaced sample of the
+/// answers and reports the best one
+fn tune(
+    solver: &Solver,
+    schedules: &[Vec<f32>],
+    sample: usize,
+    cli_args: &CliArgs,
+    strategy_args: &StrategyArgs,
+    output: Option<&PathBuf>,
+) -> Result<()> {
+    let answers = solver.get_words_from_idx(&solver.get_frequent_word_idx());
+    let stride = (answers.len() / sample.max(1)).max(1);
+    let words: Vec<Word> = answers.into_iter().step_by(stride).take(sample).collect();
+
+    // The first guess does not depend on the penalty
+    let start = Strategy::new(
+        cli_args.starting_word.clone(),
+        strategy_args,
+        solver,
+        None,
+        None,
+    )?
+    .start
+    .to_string()
+    .to_lowercase();
+
+    let mut csv = vec!["penalty,solved,failed,mean".to_string()];
+    let mut best: Option<(usize, f64, &Vec<f32>)> = None;
+    for penalty in schedules {
+        let args = StrategyArgs {
+            penalty: penalty.clone(),
+            ..strategy_args.clone()
+        };
+        let strategy = Strategy::new(Some(start.clone()), &args, solver, None, None)?;
+
+        let penalty_name = penalty
+            .iter()
+            .map(|p| format!("{:.2}", p))
+            .collect::<Vec<_>>()
+            .join(";");
+        println!("Penalty {}", penalty_name);
+        let steps: Vec<usize> = play_all(
+            solver,
+            &words,
+            cli_args.max_rounds,
+            &strategy,
+            ProgressMode::Auto,
         )
+        .iter()
+        .map(|game| solved_in(game))
+        .collect();
+        let failed = steps.iter().filter(|&&x| x == 0).count();
+        let solved: Vec<usize> = steps.into_iter().filter(|&x| x > 0).collect();
+        let mean = solved.iter().sum::<usize>() as f64 / solved.len().max(1) as f64;
+        println!(
+            " solved {} | failed {} | mean {:.3}",
+            solved.len(),
+            failed,
+            mean
+        );
+        csv.push(format!(
+            "{},{},{},{:.4}",
+            penalty_name,
+            solved.len(),
+            failed,
+            mean
+        ));
+
+        if best.is_none_or(|(best_failed, best_mean, _)| (failed, mean) < (best_failed, best_mean))
+        {
+            best = Some((failed, mean, penalty));
+        }
+    }
+
+    if let Some((failed, mean, penalty)) = best {
+        println!(
+            "{} {:?} (failed {}, mean {:.3})",
+            "Best penalty:".green().bold(),
+            penalty,
+            failed,
+            mean
+        );
+    }
+    let csv = csv.join("\n") + "\n";
+    match output {
+        Some(output) => std::fs::write(output, csv)
+            .with_context(|| format!("Error writing {}", output.display()))?,
+        None => print!("{}", csv),
+    }
+    Ok(())
+}
+
+fn export_tree(
+    solver: &Solver,
+    max_rounds: usize,
+    strategy: &Strategy,
+    output: &PathBuf,
+) -> Result<()> {
+    let words = solver.get_words_from_idx(&solver.get_frequent_word_idx());
+
+    println!("Playing all words.");
+    let games = play_all(solver, &words, max_rounds, strategy, ProgressMode::Auto);
+    let (solved, failed): (Vec<_>, Vec<_>) =
+        games.into_iter().partition(|game| solved_in(game) > 0);
+    if !failed.is_empty() {
+        println!(
+            "{} words could not be solved in {} guesses and are not part of the tree",
+            failed.len(),
+            max_rounds
+        );
+    }
+
+    let tree = DecisionTree::from_games(&solved)?;
+    std::fs::write(output, tree.to_text())
+        .with_context(|| format!("Error writing decision tree {}", output.display()))?;
+    println!("Wrote decision tree to {}", output.display());
+    Ok(())
+}
+
+/// The number of guesses a game was solved in, or 0 if it was not solved
+fn solved_in(guesses: &[Guess]) -> usize {
+    match guesses.last() {
+        Some(guess) if guess.get_status().iter().all(|s| *s == Correct) => guesses.len(),
+        _ => 0,
+    }
 }
 
+/// Plays the game against the oracle and returns all guesses
 fn try_to_solve(
-    word: &Word,
+    oracle: &mut dyn FeedbackOracle,
     solver: &Solver,
     max_rounds: usize,
     print: bool,
-    start: Word,
-    two_level: bool,
-) -> usize {
+    strategy: &Strategy,
+) -> Vec<Guess> {
+    try_to_solve_timed(oracle, solver, max_rounds, print, strategy).0
+}
+
+/// Like [`try_to_solve`], with the decision of every guess
+fn try_to_solve_timed(
+    oracle: &mut dyn FeedbackOracle,
+    solver: &Solver,
+    max_rounds: usize,
+    print: bool,
+    strategy: &Strategy,
+) -> (Vec<Guess>, Vec<Decision>) {
     let mut guesses: Vec<Guess> = vec![];
-    let status = word.compare(&start);
-    guesses.push(Guess::from_word(start, status));
+    let mut decisions: Vec<Decision> = vec![];
     if print {
         println!(
             "{}",
             format!(
                 "Trying to solve {}",
-                format!("{}", word).bold().bright_magenta()
+                format!("{}", oracle).bold().bright_magenta()
             )
             .underline()
         );
-        print_guess_evaludation(
-            guesses.last().unwrap(),
-            &solver.get_frequent_word_idx(),
-            solver,
-        )
     };
-    if status.iter().all(|s| *s == Correct) {
-        return 1;
+
+    for _ in 1..=max_rounds {
+        let remaining_idx = match solver.get_remaining_words_idx(&guesses) {
+            Ok(remaining_idx) => remaining_idx,
+            Err(e) => {
+                if print {
+                    println!(" {}", e.to_string().red());
+                }
+                break;
+            }
+        };
+        let now = Instant::now();
+        let (next_guess, pool) = match strategy.next_guess(&guesses, solver) {
+            Ok(next) => next,
+            Err(e) => {
+                if print {
+                    println!(" {}", e.to_string().red());
+                }
+                break;
+            }
+        };
+
+        let Some(status) = oracle.respond(&next_guess, &guesses, solver) else {
+            break;
+        };
+        guesses.push(Guess {
+            word: next_guess,
+            status,
+        });
+        decisions.push(Decision {
+            elapsed: now.elapsed(),
+            pool,
+        });
+
+        if print {
+            print_guess_evaludation(
+                guesses.last().unwrap(),
+                &remaining_idx,
+                solver,
+                decisions.last(),
+            )
+        };
+        if status == solved_pattern(solver.n_letters()) {
+            break;
+        }
     }
+    (guesses, decisions)
+}
 
-    for step in 2..=max_rounds {
-        let remaining_idx = solver.get_remaining_words_idx(&guesses);
+/// Play all boards with the same guesses and print the status of every
+/// board that is not solved yet
+fn solve_boards(answers: &[Word], solver: &Solver, max_rounds: usize) -> Result<()> {
+    if let Some(word) = answers.iter().find(|word| !solver.is_valid_guess(word)) {
+        anyhow::bail!("{} is not in the word list", word)
+    }
+    let names: Vec<String> = answers.iter().map(|word| word.to_string()).collect();
+    println!(
+        "{}",
+        format!(
+            "Trying to solve {}",
+            names.join(" ").bold().bright_magenta()
+        )
+        .underline()
+    );
 
-        let penalty = 0.1;
-        let next_guess = match two_level {
-            true => pick_two_level(&guesses, solver, penalty),
-            false => solver.guess(1, &remaining_idx, penalty)[0],
+    let mut boards = MultiSolver::new(solver, &vec![vec![]; answers.len()])?;
+    let mut rounds = 0;
+    while rounds < max_rounds && !boards.is_done() {
+        let evaluation = match boards.guess(1) {
+            Ok(mut best) => best.remove(0),
+            Err(e) => {
+                println!(" {}", e.to_string().red());
+                break;
+            }
         };
+        let word = evaluation.word;
+        let statuses: Vec<Pattern> = answers
+            .iter()
+            .map(|answer| solver.pattern_of(answer, &word))
+            .collect();
+        // Solved boards stay empty
+        let cells: Vec<String> = statuses
+            .iter()
+            .enumerate()
+            .map(|(b, &status)| match boards.is_solved(b) {
+                true => " ".repeat(solver.n_letters()),
+                false => Guess { word, status }.to_string(),
+            })
+            .collect();
+        println!(
+            " {} | bits {:5.2} | solves {:.2}",
+            cells.join(" "),
+            evaluation.total_bits,
+            evaluation.solves
+        );
+        boards.play(word, &statuses);
+        rounds += 1;
+    }
+    match boards.is_done() {
+        true => println!(
+            "{}",
+            format!("Solved {} boards in {} guesses", answers.len(), rounds).green()
+        ),
+        false => println!("{}", format!("Not solved in {} guesses", max_rounds).red()),
+    }
+    Ok(())
+}
 
-        let status = word.compare(&next_guess);
-        guesses.push(Guess::from_word(next_guess, status));
+/// Search the fewest guesses that win against the adversarial host and
+/// print them
+fn solve_absurdle(solver: &Solver, cli_args: &CliArgs, width: &WidthSchedule) -> Result<()> {
+    let starting_word = cli_args
+        .starting_word
+        .as_ref()
+        .map(|word| solver.parse_word(word))
+        .transpose()?;
+    if let Some(word) = starting_word.filter(|word| !solver.is_valid_guess(word)) {
+        anyhow::bail!("{} is not in the word list", word)
+    }
+    println!("{}", "Searching the fewest guesses".underline());
+    let Some(game) = solver.solve_absurdle(starting_word, cli_args.max_rounds, width) else {
+        println!(
+            " {}",
+            format!("No win found in {} guesses", cli_args.max_rounds).red()
+        );
+        return Ok(());
+    };
+    for (i, guess) in game.iter().enumerate() {
+        let remaining_idx = solver.get_remaining_words_idx(&game[..i])?;
+        print_guess_evaludation(guess, &remaining_idx, solver, None);
+    }
+    Ok(())
+}
 
-        if print {
-            print_guess_evaludation(guesses.last().unwrap(), &remaining_idx, solver)
+/// Play against the adversarial host with guesses from stdin
+fn play_absurdle(solver: &Solver) -> Result<()> {
+    println!("Enter your guesses. The host keeps as many words as possible.");
+    let mut guesses: Vec<Guess> = vec![];
+    for line in std::io::stdin().lock().lines() {
+        let line = line?;
+        let Ok(word) = solver.parse_word(&line) else {
+            println!(" {}", format!("Enter {} letters", solver.n_letters()).red());
+            continue;
+        };
+        if !solver.is_valid_guess(&word) {
+            println!(" {}", format!("{} is not in the word list", word).red());
+            continue;
+        }
+        let remaining_idx = solver.get_remaining_words_idx(&guesses)?;
+        let Some(status) = Adversarial.respond(&word, &guesses, solver) else {
+            break;
         };
-        if status.iter().all(|s| *s == Correct) {
-            return step;
+        guesses.push(Guess { word, status });
+        print_guess_evaludation(guesses.last().unwrap(), &remaining_idx, solver, None);
+        if solved_in(&guesses) > 0 {
+            println!("{}", format!("Solved in {} guesses", guesses.len()).green());
+            break;
         }
     }
-    0
+    Ok(())
 }
@@ -0,0 +1,138 @@
+//! The protocol of `wordlebot pipe`, for programs that keep the solver
+//! running as a subprocess instead of starting it for every query.
+//!
+//! Every line on stdin is a JSON object with a command, and every command
+//! gets a JSON object as a line on stdout:
+//!
+//! - `{"cmd":"guess","guess":"slate:bybbg"}` adds a guess with its pattern
+//! - `{"cmd":"suggest","n":5}` asks for the best next guesses, 10 without `n`
+//! - `{"cmd":"reset"}` starts a new game
+//!
+//! A response has `"ok":true` and the number of `"remaining"` words, with
+//! the `"suggestions"` for `suggest`, or `"ok":false` and an `"error"`. The
+//! `"id"` of a command, if any, is sent back with its response.
+use anyhow::{bail, Result};
+
+use crate::json::{self, parse_object, Value};
+use crate::wordle::{parse_guess, Guess};
+
+/// The number of suggestions without `n`
+pub const DEFAULT_SUGGESTIONS: usize = 10;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PipeCommand {
+    Guess(Guess),
+    Suggest { number: usize },
+    Reset,
+}
+
+/// A command with the id to send back
+#[derive(Clone, Debug, PartialEq)]
+pub struct Request {
+    pub id: Option<Value>,
+    pub command: PipeCommand,
+}
+
+impl Request {
+    pub fn parse(line: &str) -> Result<Request> {
+        let mut fields = parse_object(line)?;
+        let command = match fields.remove("cmd") {
+            Some(Value::Str(cmd)) => cmd,
+            Some(_) => bail!("Expected cmd to be a string"),
+            None => bail!("Missing cmd"),
+        };
+        let command = match command.as_str() {
+            "guess" => match fields.remove("guess") {
+                Some(Value::Str(guess)) => PipeCommand::Guess(parse_guess(&guess)?),
+                _ => bail!("Expected the guess as a string like \"slate:bybbg\""),
+            },
+            "suggest" => PipeCommand::Suggest {
+                number: match fields.remove("n") {
+                    Some(Value::Number(n)) if n >= 1.0 && n.fract() == 0.0 => n as usize,
+                    None => DEFAULT_SUGGESTIONS,
+                    _ => bail!("Expected n to be a positive number"),
+                },
+            },
+            "reset" => PipeCommand::Reset,
+            _ => bail!("Unknown cmd {}, expected guess, suggest or reset", command),
+        };
+        Ok(Request {
+            id: fields.remove("id"),
+            command,
+        })
+    }
+
+    /// The id of a line, even if the command is invalid
+    pub fn id(line: &str) -> Option<Value> {
+        parse_object(line).ok()?.remove("id")
+    }
+}
+
+/// The response to a command: the fields of the result, e.g.
+/// `"remaining":12`, or the error
+pub fn response(id: Option<&Value>, result: &Result<String>) -> String {
+    let id = id.map_or(String::new(), |id| format!(r#""id":{},"#, id.to_json()));
+    match result {
+        Ok(fields) => format!(r#"{{{}"ok":true,{}}}"#, id, fields),
+        Err(e) => format!(
+            r#"{{{}"ok":false,"error":{}}}"#,
+            id,
+            json::string(&format!("{:#}", e))
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request() {
+        let request = Request::parse(r#"{"id": 7, "cmd": "guess", "guess": "slate:bybbg"}"#);
+        assert_eq!(
+            request.unwrap(),
+            Request {
+                id: Some(Value::Number(7.0)),
+                command: PipeCommand::Guess("slate:bybbg".parse().unwrap()),
+            }
+        );
+        let request = Request::parse(r#"{"cmd":"suggest"}"#).unwrap();
+        assert_eq!(
+            (request.id, request.command),
+            (
+                None,
+                PipeCommand::Suggest {
+                    number: DEFAULT_SUGGESTIONS
+                }
+            )
+        );
+
+        for (line, error) in [
+            (r#"{"cmd":"play"}"#, "Unknown cmd play"),
+            (r#"{"cmd":"suggest","n":0}"#, "positive number"),
+            (r#"{"cmd":"guess","guess":"slate"}"#, "WORD:PATTERN"),
+            (r#"{"guess":"slate"}"#, "Missing cmd"),
+        ] {
+            let e = format!("{:#}", Request::parse(line).unwrap_err());
+            assert!(e.contains(error), "{}", e);
+        }
+        assert_eq!(
+            Request::id(r#"{"id":"a","cmd":"play"}"#),
+            Some(Value::Str("a".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_response() {
+        let id = Value::Str("a".to_string());
+        assert_eq!(
+            response(Some(&id), &Ok(r#""remaining":3"#.to_string())),
+            r#"{"id":"a","ok":true,"remaining":3}"#
+        );
+        let error = Err(anyhow::anyhow!("\"XXXXX\" is not in the word list"));
+        assert_eq!(
+            response(None, &error),
+            r#"{"ok":false,"error":"\"XXXXX\" is not in the word list"}"#
+        );
+    }
+}
@@ -0,0 +1,194 @@
+//! Clipboard and file dialogs of the desktop.
+//!
+//! There is no portable API for either, so the tools of the platform are
+//! run: `pbcopy` and `osascript` on macOS, `clip.exe` and PowerShell on
+//! Windows, and `wl-copy`, `xclip`, `xsel`, `zenity` or `kdialog` on Linux,
+//! whichever is installed. Without a desktop, e.g. over SSH, there are no
+//! tools to run, so the terminal is used where it can stand in: the
+//! clipboard is written with an OSC 52 escape sequence, which most
+//! terminals forward to the clipboard of the machine they run on, and a
+//! path is typed in instead of picked. Reading the clipboard fails.
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+
+/// The commands that read the clipboard, in the order they are tried
+const READ_CLIPBOARD: &[&[&str]] = &[
+    &["pbpaste"],
+    &["powershell.exe", "-NoProfile", "-Command", "Get-Clipboard"],
+    &["wl-paste", "--no-newline"],
+    &["xclip", "-selection", "clipboard", "-out"],
+    &["xsel", "--clipboard", "--output"],
+];
+
+/// The commands that write their input to the clipboard
+const WRITE_CLIPBOARD: &[&[&str]] = &[
+    &["pbcopy"],
+    &["clip.exe"],
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard", "-in"],
+    &["xsel", "--clipboard", "--input"],
+];
+
+/// Whether there is no desktop to run the tools on. The tools of Linux need
+/// a Wayland or X11 display.
+pub fn is_headless() -> bool {
+    if cfg!(any(target_os = "macos", windows)) {
+        return false;
+    }
+    ["WAYLAND_DISPLAY", "DISPLAY"]
+        .iter()
+        .all(|var| std::env::var_os(var).is_none_or(|v| v.is_empty()))
+}
+
+/// The text of the clipboard
+pub fn read_clipboard() -> Result<String> {
+    if is_headless() {
+        bail!("There is no clipboard without a desktop")
+    }
+    for command in READ_CLIPBOARD {
+        let Ok(output) = Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+        else {
+            continue;
+        };
+        if output.status.success() {
+            return String::from_utf8(output.stdout).context("The clipboard is not text");
+        }
+    }
+    bail!("No clipboard tool found, e.g. wl-paste or xclip")
+}
+
+/// Copy the text to the clipboard. Without a clipboard tool the text is
+/// sent to the terminal with [`osc52`], which can not tell whether the
+/// terminal supports it.
+pub fn write_clipboard(text: &str) -> Result<()> {
+    if !is_headless() {
+        for command in WRITE_CLIPBOARD {
+            let Ok(mut child) = Command::new(command[0])
+                .args(&command[1..])
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+            else {
+                continue;
+            };
+            let written = child
+                .stdin
+                .take()
+                .map(|mut stdin| stdin.write_all(text.as_bytes()));
+            if child.wait().is_ok_and(|status| status.success())
+                && written.is_some_and(|w| w.is_ok())
+            {
+                return Ok(());
+            }
+        }
+    }
+    let mut stdout = std::io::stdout();
+    if !stdout.is_terminal() {
+        bail!("No clipboard tool found and the output is not a terminal")
+    }
+    write!(stdout, "{}", osc52(text))?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// The escape sequence that asks the terminal to copy the text to the
+/// clipboard
+///
+/// # Example
+///
+/// ```
+/// use wordlebot::platform::osc52;
+///
+/// assert_eq!(osc52("slate"), "\x1b]52;c;c2xhdGU=\x07");
+/// ```
+pub fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64(text.as_bytes()))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Let the user pick a file in a dialog of the desktop. Returns `None` if
+/// the dialog was cancelled, and fails if there is no dialog.
+pub fn pick_file(title: &str) -> Result<Option<PathBuf>> {
+    if is_headless() {
+        bail!("There are no file dialogs without a desktop")
+    }
+    let prompt = format!("POSIX path of (choose file with prompt \"{}\")", title);
+    let title_arg = format!("--title={}", title);
+    let commands: [&[&str]; 3] = [
+        &["osascript", "-e", &prompt],
+        &["zenity", "--file-selection", &title_arg],
+        &["kdialog", "--title", title, "--getopenfilename"],
+    ];
+    for command in commands {
+        let Ok(output) = Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+        else {
+            continue;
+        };
+        // The dialogs exit with an error when they are cancelled
+        if !output.status.success() {
+            return Ok(None);
+        }
+        let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        return Ok(Some(PathBuf::from(path)).filter(|p| !p.as_os_str().is_empty()));
+    }
+    bail!("No file dialog found, e.g. zenity or kdialog")
+}
+
+/// Pick a file in a dialog, or ask for its path in the terminal if there is
+/// no dialog. Returns `None` if the dialog was cancelled or no path was
+/// typed.
+pub fn pick_file_or_prompt(title: &str) -> Result<Option<PathBuf>> {
+    if let Ok(path) = pick_file(title) {
+        return Ok(path);
+    }
+    print!("{}: ", title);
+    std::io::stdout().flush()?;
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    let path = line.trim();
+    Ok((!path.is_empty()).then(|| PathBuf::from(path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"s"), "cw==");
+        assert_eq!(base64(b"sl"), "c2w=");
+        assert_eq!(base64(b"sla"), "c2xh");
+        assert_eq!(base64("🟩".as_bytes()), "8J+fqQ==");
+    }
+}
@@ -0,0 +1,20 @@
+//! The types most programs need, in one import
+//!
+//! ```
+//! use wordlebot::prelude::*;
+//!
+//! let solver = Solver::new().unwrap();
+//! let guess: Guess = "slate:BYBBG".parse().unwrap();
+//! let remaining = solver.get_remaining_words_idx(&[guess]).unwrap();
+//! let best = solver.guess(1, &remaining, 0.1).unwrap();
+//! assert!(solver.is_valid_guess(&best[0]));
+//! ```
+
+pub use crate::solver::multi::{MultiEvaluation, MultiSolver};
+pub use crate::solver::{
+    Conflict, Elimination, EmptyRemaining, GuessEvaluation, Solver, SolverError,
+};
+pub use crate::wordle::{
+    create_word_from_string, decode_status, encode_status, solved_pattern, Guess, LetterConstraint,
+    LetterStatus, Pattern, ScoringRule, Word, MAX_GUESSES,
+};
@@ -0,0 +1,224 @@
+//! Progress of the commands that play many games, e.g. the benchmark.
+//!
+//! In a terminal the progress is a bar. A bar is only redrawn in place by a
+//! terminal, so when the output is piped into a file or a CI log, or the
+//! terminal is too narrow for the bar, a line with the number of games and
+//! the remaining time is printed every few seconds instead.
+use anyhow::{bail, Error};
+use indicatif::{ProgressBar, ProgressStyle};
+use std::fmt;
+use std::io::IsTerminal;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The narrowest terminal that still shows the bar, the counts and the
+/// remaining time take about 40 columns
+pub const MIN_BAR_WIDTH: u16 = 50;
+
+/// The time between two plain progress lines
+pub const PLAIN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How the progress is shown
+///
+/// # Example
+///
+/// ```
+/// use wordlebot::progress::ProgressMode;
+///
+/// let mode: ProgressMode = "plain".parse().unwrap();
+/// assert_eq!(mode, ProgressMode::Plain);
+/// assert_eq!(mode.to_string(), "plain");
+/// assert_eq!(ProgressMode::Auto.resolve(false, None), ProgressMode::Plain);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProgressMode {
+    /// A bar in a terminal that is wide enough, plain lines otherwise
+    #[default]
+    Auto,
+    /// Always a bar
+    Bar,
+    /// A line every few seconds
+    Plain,
+    /// Nothing
+    None,
+}
+
+impl ProgressMode {
+    /// The mode to use for an output that is a terminal or not, with the
+    /// width of the terminal if known. Only [`ProgressMode::Auto`] depends
+    /// on the output.
+    pub fn resolve(self, is_terminal: bool, width: Option<u16>) -> ProgressMode {
+        match self {
+            ProgressMode::Auto if is_terminal && width.is_none_or(|w| w >= MIN_BAR_WIDTH) => {
+                ProgressMode::Bar
+            }
+            ProgressMode::Auto => ProgressMode::Plain,
+            mode => mode,
+        }
+    }
+}
+
+impl FromStr for ProgressMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "auto" => Ok(ProgressMode::Auto),
+            "bar" => Ok(ProgressMode::Bar),
+            "plain" => Ok(ProgressMode::Plain),
+            "none" => Ok(ProgressMode::None),
+            _ => bail!(
+                "Unknown progress '{}', expected auto, bar, plain or none",
+                s
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ProgressMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProgressMode::Auto => write!(f, "auto"),
+            ProgressMode::Bar => write!(f, "bar"),
+            ProgressMode::Plain => write!(f, "plain"),
+            ProgressMode::None => write!(f, "none"),
+        }
+    }
+}
+
+/// The progress of a number of steps, which can be advanced from several
+/// threads
+pub struct Progress {
+    bar: ProgressBar,
+    mode: ProgressMode,
+    start: Instant,
+    /// When the last plain line was printed
+    last_line: Mutex<Instant>,
+}
+
+impl Progress {
+    /// Shows the progress on stderr. Whether stdout and stderr are
+    /// terminals decides [`ProgressMode::Auto`].
+    pub fn new(len: usize, mode: ProgressMode) -> Progress {
+        let is_terminal = std::io::stdout().is_terminal() && std::io::stderr().is_terminal();
+        let width = console::Term::stderr()
+            .size_checked()
+            .map(|(_, width)| width);
+        let mode = mode.resolve(is_terminal, width);
+        let bar = match mode {
+            ProgressMode::Bar => ProgressBar::new(len as u64).with_style(
+                ProgressStyle::with_template(
+                    "{wide_bar} {pos:>7}/{len:7} [{eta_precise} remaining]",
+                )
+                .unwrap()
+                .progress_chars("##-"),
+            ),
+            _ => ProgressBar::hidden(),
+        };
+        bar.set_length(len as u64);
+        let now = Instant::now();
+        Progress {
+            bar,
+            mode,
+            start: now,
+            last_line: Mutex::new(now),
+        }
+    }
+
+    /// Counts a finished step
+    pub fn inc(&self) {
+        self.bar.inc(1);
+        if self.mode != ProgressMode::Plain {
+            return;
+        }
+        let mut last_line = self.last_line.lock().unwrap();
+        if last_line.elapsed() >= PLAIN_INTERVAL {
+            *last_line = Instant::now();
+            eprintln!("{}", self.line());
+        }
+    }
+
+    /// Ends the bar, or prints the last line
+    pub fn finish(&self) {
+        match self.mode {
+            ProgressMode::Bar => self.bar.finish(),
+            ProgressMode::Plain => eprintln!("{}", self.line()),
+            _ => {}
+        }
+    }
+
+    fn line(&self) -> String {
+        plain_line(
+            self.bar.position() as usize,
+            self.bar.length().unwrap_or_default() as usize,
+            self.start.elapsed(),
+        )
+    }
+}
+
+/// A plain progress line, with the remaining time extrapolated from the
+/// steps done so far
+pub fn plain_line(done: usize, len: usize, elapsed: Duration) -> String {
+    let percent = match len {
+        0 => 100,
+        _ => done * 100 / len,
+    };
+    let time = match done {
+        _ if done >= len => format!("done in {}", format_duration(elapsed)),
+        0 => "remaining time unknown".to_string(),
+        _ => {
+            let remaining = elapsed.mul_f64((len - done) as f64 / done as f64);
+            format!("{} remaining", format_duration(remaining))
+        }
+    };
+    format!("{:>7}/{} ({:>3}%), {}", done, len, percent, time)
+}
+
+/// A duration as `h:mm:ss`
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        for (mode, is_terminal, width, expected) in [
+            (ProgressMode::Auto, true, Some(120), ProgressMode::Bar),
+            (ProgressMode::Auto, true, None, ProgressMode::Bar),
+            (ProgressMode::Auto, true, Some(30), ProgressMode::Plain),
+            (ProgressMode::Auto, false, Some(120), ProgressMode::Plain),
+            (ProgressMode::Bar, false, None, ProgressMode::Bar),
+            (ProgressMode::None, true, Some(120), ProgressMode::None),
+        ] {
+            assert_eq!(mode.resolve(is_terminal, width), expected);
+        }
+        assert!("fancy".parse::<ProgressMode>().is_err());
+    }
+
+    #[test]
+    fn test_plain_line() {
+        let elapsed = Duration::from_secs(30);
+        assert_eq!(
+            plain_line(500, 2000, elapsed),
+            "    500/2000 ( 25%), 0:01:30 remaining"
+        );
+        assert_eq!(
+            plain_line(0, 2000, elapsed),
+            "      0/2000 (  0%), remaining time unknown"
+        );
+        assert_eq!(
+            plain_line(2000, 2000, Duration::from_secs(3725)),
+            "   2000/2000 (100%), done in 1:02:05"
+        );
+    }
+}
@@ -0,0 +1,372 @@
+//! Import a season of games from a log and analyse them together, for
+//! `wordlebot import`.
+//!
+//! A season log has one game per line: the date, the guesses and their
+//! patterns, e.g. `2024-05-01 slate,crane bbgbg,ggggg`. A lost game needs
+//! the answer as a fourth column. Empty lines and lines starting with `#`
+//! are skipped.
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::history::GameRecord;
+use crate::solver::Solver;
+use crate::telemetry::{date_from_days, days_from_date};
+use crate::wordle::*;
+
+/// The mode of the imported games in the history
+pub const IMPORT_MODE: &str = "import";
+
+/// Parse the games of a season log, see the module documentation. The
+/// patterns must fit the answer.
+pub fn parse_season_log(text: &str) -> Result<Vec<GameRecord>> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim().starts_with('#'))
+        .map(|(i, line)| parse_season_line(line).with_context(|| format!("Line {}", i + 1)))
+        .collect()
+}
+
+fn parse_season_line(line: &str) -> Result<GameRecord> {
+    let columns: Vec<&str> = line.split_whitespace().collect();
+    let (date, words, patterns, answer) = match columns[..] {
+        [date, words, patterns] => (date, words, patterns, None),
+        [date, words, patterns, answer] => (date, words, patterns, Some(answer)),
+        _ => bail!(
+            "Expected the date, the guesses, the patterns and maybe the answer, got {} columns",
+            columns.len()
+        ),
+    };
+    days_from_date(date)?;
+    let words: Vec<&str> = words.split(',').collect();
+    let patterns: Vec<&str> = patterns.split(',').collect();
+    if words.len() != patterns.len() {
+        bail!(
+            "Got {} guesses but {} patterns",
+            words.len(),
+            patterns.len()
+        )
+    }
+    let guesses = words
+        .iter()
+        .zip(&patterns)
+        .map(|(word, pattern)| parse_guess(&format!("{}:{}", word, pattern)))
+        .collect::<Result<Vec<Guess>>>()?;
+    let solved = guesses
+        .last()
+        .filter(|g| g.status == solved_pattern(g.word.n_letters()))
+        .map(|g| g.word);
+    let answer = match (solved, answer) {
+        (Some(solved), None) => solved,
+        (_, Some(answer)) => parse_word(answer)?,
+        (None, None) => bail!("The game was lost, so the answer is needed as a fourth column"),
+    };
+    if let Some(guess) = guesses.iter().find(|g| answer.pattern(&g.word) != g.status) {
+        bail!(
+            "The pattern of {} does not fit the answer {}",
+            guess,
+            answer
+        )
+    }
+    Ok(GameRecord {
+        date: date.to_string(),
+        mode: IMPORT_MODE.to_string(),
+        answer,
+        solved: solved.is_some() && guesses.len() <= MAX_GUESSES,
+        guesses,
+        session: None,
+    })
+}
+
+/// A guess that spent letters on what was already known. Only guesses that
+/// could not be the answer count, as the answer must reuse the hints.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Habit {
+    /// A letter that was green is played at its place again
+    WastedGreen,
+    /// A letter that was gray is played again
+    WastedGray,
+    /// A letter that was yellow is played at the same place again
+    RepeatedYellow,
+}
+
+impl Habit {
+    /// The habits of a guess after the earlier guesses of its game
+    pub fn of_guess(earlier: &[Guess], word: &Word) -> Vec<Habit> {
+        if earlier.iter().all(|guess| word.is_valid(guess)) {
+            return vec![];
+        }
+        let states = letter_states(earlier);
+        let mut habits = vec![];
+        let mut reused = |habit: Habit, found: bool| {
+            if found && !habits.contains(&habit) {
+                habits.push(habit)
+            }
+        };
+        for (pos, c) in word.chars().iter().enumerate() {
+            let Some(c) = c else { continue };
+            let at_pos = |status: LetterStatus| {
+                earlier
+                    .iter()
+                    .any(|g| g.word.chars()[pos] == Some(*c) && g.get_status()[pos] == status)
+            };
+            reused(Habit::WastedGreen, at_pos(LetterStatus::Correct));
+            reused(Habit::RepeatedYellow, at_pos(LetterStatus::Misplaced));
+            reused(
+                Habit::WastedGray,
+                states.get(c) == Some(&LetterStatus::Absent),
+            );
+        }
+        habits.sort();
+        habits
+    }
+}
+
+impl fmt::Display for Habit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Habit::WastedGreen => write!(f, "Played a green letter at its place again"),
+            Habit::WastedGray => write!(f, "Played a gray letter again"),
+            Habit::RepeatedYellow => write!(f, "Played a yellow letter at the same place again"),
+        }
+    }
+}
+
+/// How well a game was played
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameAnalysis {
+    pub game: GameRecord,
+    /// The bits of the guesses as a share of the bits of the solver's
+    /// guesses, on average. `None` if no guess had anything to choose from.
+    pub skill: Option<f32>,
+    /// The bits the patterns gave more than expected, negative if fewer
+    pub luck: f32,
+    /// The habits of every guess that showed one
+    pub habits: Vec<Habit>,
+}
+
+/// The games of a week, from Monday to Sunday
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeekTrend {
+    /// The Monday of the week as YYYY-MM-DD
+    pub week: String,
+    pub games: usize,
+    pub won: usize,
+    /// The average number of turns of the games won
+    pub mean_turns: Option<f32>,
+    /// The average skill of the games, see [`GameAnalysis::skill`]
+    pub skill: Option<f32>,
+    /// The average luck of the games
+    pub luck: f32,
+}
+
+/// The analysis of a season of games
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeasonReport {
+    pub games: Vec<GameAnalysis>,
+    /// The weeks with games, the oldest first
+    pub weeks: Vec<WeekTrend>,
+    /// How often each habit showed up and in how many games, the most
+    /// frequent first
+    pub habits: Vec<(Habit, usize, usize)>,
+}
+
+/// Compare every guess of the games with the guess of the solver, ranked
+/// with the penalty of each round like [`Solver::evaluate_line`]
+pub fn analyze_season(
+    solver: &Solver,
+    games: &[GameRecord],
+    penalty: impl Fn(usize) -> f32,
+) -> Result<SeasonReport> {
+    let games = games
+        .iter()
+        .map(|game| analyze_game(solver, game, &penalty))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut weeks: BTreeMap<u64, Vec<&GameAnalysis>> = BTreeMap::new();
+    for analysis in &games {
+        let days = days_from_date(&analysis.game.date)?;
+        // 1970-01-01 was a Thursday
+        weeks
+            .entry(days - (days + 3) % 7)
+            .or_default()
+            .push(analysis);
+    }
+    let weeks = weeks
+        .into_iter()
+        .map(|(monday, games)| {
+            let won: Vec<usize> = games
+                .iter()
+                .filter(|g| g.game.solved)
+                .map(|g| g.game.turns())
+                .collect();
+            let skills: Vec<f32> = games.iter().filter_map(|g| g.skill).collect();
+            WeekTrend {
+                week: date_from_days(monday),
+                games: games.len(),
+                won: won.len(),
+                mean_turns: mean(won.iter().map(|&turns| turns as f32)),
+                skill: mean(skills.into_iter()),
+                luck: games.iter().map(|g| g.luck).sum::<f32>() / games.len() as f32,
+            }
+        })
+        .collect();
+
+    let mut habits: BTreeMap<Habit, (usize, usize)> = BTreeMap::new();
+    for analysis in &games {
+        for &habit in &analysis.habits {
+            habits.entry(habit).or_default().0 += 1;
+        }
+        let mut in_game = analysis.habits.clone();
+        in_game.dedup();
+        for habit in in_game {
+            habits.entry(habit).or_default().1 += 1;
+        }
+    }
+    let mut habits: Vec<(Habit, usize, usize)> = habits
+        .into_iter()
+        .map(|(habit, (count, games))| (habit, count, games))
+        .collect();
+    habits.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    Ok(SeasonReport {
+        games,
+        weeks,
+        habits,
+    })
+}
+
+fn analyze_game(
+    solver: &Solver,
+    game: &GameRecord,
+    penalty: impl Fn(usize) -> f32,
+) -> Result<GameAnalysis> {
+    let line: Vec<Word> = game.guesses.iter().map(|g| g.word).collect();
+    let evaluation = solver
+        .evaluate_line(&game.answer, &line, penalty)
+        .with_context(|| format!("Game of {}", game.date))?;
+    // The last remaining word leaves nothing to choose
+    let moves = || evaluation.moves.iter().filter(|m| m.n_remaining_before > 1);
+    let skill = mean(
+        moves()
+            .filter(|m| m.best_bits > 0.0)
+            .map(|m| (m.expected_bits / m.best_bits).min(1.0)),
+    );
+    let luck = moves().map(|m| m.real_bits - m.expected_bits).sum();
+    let mut habits: Vec<Habit> = (0..game.guesses.len())
+        .flat_map(|i| Habit::of_guess(&game.guesses[..i], &game.guesses[i].word))
+        .collect();
+    habits.sort();
+    Ok(GameAnalysis {
+        game: game.clone(),
+        skill,
+        luck,
+        habits,
+    })
+}
+
+fn mean(values: impl Iterator<Item = f32>) -> Option<f32> {
+    let (sum, n) = values.fold((0.0, 0), |(sum, n), value| (sum + value, n + 1));
+    (n > 0).then(|| sum / n as f32)
+}
+
+impl fmt::Display for SeasonReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let percent = |skill: Option<f32>| match skill {
+            Some(skill) => format!("{:.0}%", 100.0 * skill),
+            None => "-".to_string(),
+        };
+        writeln!(f, "Week of      Games  Won  Turns  Skill   Luck")?;
+        for week in &self.weeks {
+            writeln!(
+                f,
+                "{}  {:5}  {:3}  {:>5}  {:>5}  {:+.2}",
+                week.week,
+                week.games,
+                week.won,
+                week.mean_turns
+                    .map_or("-".to_string(), |turns| format!("{:.2}", turns)),
+                percent(week.skill),
+                week.luck
+            )?;
+        }
+        writeln!(
+            f,
+            "Skill is the share of the bits of the solver's guesses, luck the bits the patterns gave more than expected"
+        )?;
+        if self.habits.is_empty() {
+            return write!(f, "No guess spent letters on known hints");
+        }
+        writeln!(f, "Worst habits:")?;
+        for (habit, count, games) in &self.habits {
+            writeln!(f, "  {}: {} times in {} games", habit, count, games)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_season_log() {
+        let log = "# May\n2024-05-01 slate,crane bbgbg,ggggg\n\n2024-05-02 slate,crane bbbbb,bybbb quirk\n";
+        let games = parse_season_log(log).unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!(
+            games[0].to_json(),
+            r#"{"date":"2024-05-01","mode":"import","answer":"crane","guesses":["slate:bbgbg","crane:ggggg"],"solved":true}"#
+        );
+        assert_eq!(
+            (games[1].answer, games[1].solved),
+            (create_word_from_string("quirk"), false)
+        );
+
+        for (line, error) in [
+            ("2024-05-01 slate,crane bybbb", "2 guesses but 1 patterns"),
+            ("2024-05-01 slate bybbb", "the answer is needed"),
+            (
+                "2024-05-01 slate,crane bbbbb,ggggg",
+                "does not fit the answer",
+            ),
+            ("May 1st slate ggggg", "YYYY-MM-DD"),
+        ] {
+            let e = format!("{:#}", parse_season_log(line).unwrap_err());
+            assert!(e.starts_with("Line 1: ") && e.contains(error), "{}", e);
+        }
+    }
+
+    #[test]
+    fn test_habits() {
+        let earlier = ["slate:bybbg".parse().unwrap()];
+        let habits = |word: &str| Habit::of_guess(&earlier, &create_word_from_string(word));
+        // LODGE fits the hints, so it does not waste anything
+        assert_eq!(habits("lodge"), []);
+        assert_eq!(habits("crone"), [Habit::WastedGreen]);
+        assert_eq!(habits("slump"), [Habit::WastedGray, Habit::RepeatedYellow]);
+        assert_eq!(habits("drink"), []);
+    }
+
+    #[test]
+    fn test_analyze_season() {
+        let words = ["bills", "fills", "hills", "slate", "crane", "crate"];
+        let words = words.map(create_word_from_string);
+        let solver = Solver::from_words(&words, &[1.0; 6]).unwrap();
+        let log = "2024-04-30 crate ggggg\n2024-05-01 slate,crate bbggg,ggggg\n2024-05-06 bills,fills,hills bgggg,bgggg,ggggg\n";
+        let report = analyze_season(&solver, &parse_season_log(log).unwrap(), |_| 0.1).unwrap();
+        let weeks: Vec<(&str, usize)> = report
+            .weeks
+            .iter()
+            .map(|w| (w.week.as_str(), w.games))
+            .collect();
+        assert_eq!(weeks, [("2024-04-29", 2), ("2024-05-06", 1)]);
+        assert_eq!(report.weeks[0].mean_turns, Some(1.5));
+        // FILLS fits the hints of BILLS, so nothing was wasted
+        assert!(report.games[2].habits.is_empty());
+        assert!(report.games[2].luck < 0.0);
+        assert!(report.games[0].luck > 0.0);
+        assert!(report.games[1].skill.unwrap() < 1.0);
+        assert!(report.habits.is_empty());
+    }
+}
@@ -0,0 +1,170 @@
+use crate::solver::parallel::*;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use crate::solver::{width::WidthSchedule, Solver};
+use crate::wordle::*;
+
+impl Solver {
+    /// The pattern an adversarial host like Absurdle answers a guess with.
+    /// Instead of fixing the answer, the host keeps the largest group of
+    /// the remaining words. Ties prefer a pattern that does not solve the
+    /// game, then the smaller pattern.
+    ///
+    /// `None` if no words remain or the guess is not in the word list.
+    pub fn adversarial_pattern(&self, guess: &Word, remaining_words: &[usize]) -> Option<Pattern> {
+        let id = self.get_id_for_word(guess)?;
+        self.largest_group(id, remaining_words)
+            .map(|(pattern, _)| pattern)
+    }
+
+    /// The pattern and size of the group the host keeps
+    fn largest_group(&self, id: usize, remaining_words: &[usize]) -> Option<(Pattern, usize)> {
+        let solved = solved_pattern(self.n_letters);
+        let mut sizes: HashMap<Pattern, usize> = HashMap::new();
+        self.mappings
+            .row(id)
+            .for_each(remaining_words, |_, pattern| {
+                *sizes.entry(pattern).or_default() += 1
+            });
+        sizes
+            .into_iter()
+            .max_by_key(|&(pattern, size)| (size, pattern != solved, Reverse(pattern)))
+    }
+
+    /// The fewest guesses that win against the adversarial host, or `None`
+    /// if there is no win within `max_rounds` guesses.
+    ///
+    /// Since the host is deterministic, every guess leads to exactly one
+    /// state. At every step the `width` guesses that leave the smallest
+    /// largest group are tried, with increasing depth, so the first win
+    /// found is the shortest among them.
+    pub fn solve_absurdle(
+        &self,
+        starting_word: Option<Word>,
+        max_rounds: usize,
+        width: &WidthSchedule,
+    ) -> Option<Vec<Guess>> {
+        let answers = self.get_frequent_word_idx();
+        let start = match starting_word {
+            Some(word) => Some(self.get_id_for_word(&word)?),
+            None => None,
+        };
+        (1..=max_rounds).find_map(|depth| {
+            let mut guesses = vec![];
+            self.search_absurdle(&answers, start, depth, width, &mut guesses)
+                .then_some(guesses)
+        })
+    }
+
+    /// Depth first search for a win in at most `depth` guesses. The guesses
+    /// of the win are pushed to `guesses`.
+    fn search_absurdle(
+        &self,
+        remaining_words: &[usize],
+        start: Option<usize>,
+        depth: usize,
+        width: &WidthSchedule,
+        guesses: &mut Vec<Guess>,
+    ) -> bool {
+        if depth == 0 || remaining_words.is_empty() {
+            return false;
+        }
+        // Only a single remaining word can be guessed for sure
+        if depth == 1 && remaining_words.len() > 1 {
+            return false;
+        }
+        let candidates = match start {
+            Some(id) => vec![id],
+            None => self.absurdle_candidates(remaining_words, width.width(remaining_words.len())),
+        };
+        for id in candidates {
+            let Some((pattern, _)) = self.largest_group(id, remaining_words) else {
+                continue;
+            };
+            guesses.push(Guess {
+                word: self.words[id],
+                status: pattern,
+            });
+            if pattern == solved_pattern(self.n_letters) {
+                return true;
+            }
+            let mut next = vec![];
+            self.mappings.row(id).for_each(remaining_words, |i, p| {
+                if p == pattern {
+                    next.push(remaining_words[i])
+                }
+            });
+            if next.len() < remaining_words.len()
+                && self.search_absurdle(&next, None, depth - 1, width, guesses)
+            {
+                return true;
+            }
+            guesses.pop();
+        }
+        false
+    }
+
+    /// The guesses that leave the smallest largest group. Ties prefer
+    /// possible answers, since they might win right away.
+    fn absurdle_candidates(&self, remaining_words: &[usize], n: usize) -> Vec<usize> {
+        let mut ranked: Vec<(usize, bool, usize)> = (0..self.words.len())
+            .into_par_iter()
+            .filter_map(|id| {
+                let (_, size) = self.largest_group(id, remaining_words)?;
+                Some((size, remaining_words.binary_search(&id).is_err(), id))
+            })
+            .collect();
+        ranked.sort_unstable();
+        ranked.iter().take(n).map(|&(_, _, id)| id).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LetterStatus::*;
+    use super::*;
+    use crate::solver::tests::solver_for;
+
+    #[test]
+    fn test_adversarial_pattern() {
+        let solver = solver_for(&["bills", "fills", "hills", "slate"]);
+        let remaining = solver.get_frequent_word_idx();
+        // The host keeps the three words that end with -ills
+        let pattern = solver
+            .adversarial_pattern(&create_word_from_string("slate"), &remaining)
+            .unwrap();
+        assert_eq!(
+            decode_status(pattern, 5),
+            vec![Misplaced, Misplaced, Absent, Absent, Absent]
+        );
+
+        // The host only gives up if nothing else is left
+        let bills = create_word_from_string("bills");
+        assert_ne!(
+            solver.adversarial_pattern(&bills, &[0, 1]),
+            Some(solved_pattern(5))
+        );
+        assert_eq!(
+            solver.adversarial_pattern(&bills, &[0]),
+            Some(solved_pattern(5))
+        );
+        assert_eq!(solver.adversarial_pattern(&bills, &[]), None);
+    }
+
+    #[test]
+    fn test_solve_absurdle() {
+        let solver = solver_for(&["bills", "fills", "hills", "fjord"]);
+        let width = WidthSchedule::constant(10);
+        let guesses = solver.solve_absurdle(None, 6, &width).unwrap();
+        // The host always avoids the guessed -ills word, so every guess
+        // removes only one of them
+        assert_eq!(guesses.len(), 3);
+        assert_eq!(guesses.last().unwrap().status, solved_pattern(5));
+        assert!(solver.solve_absurdle(None, 2, &width).is_none());
+
+        let start = create_word_from_string("fjord");
+        let guesses = solver.solve_absurdle(Some(start), 6, &width).unwrap();
+        assert_eq!(guesses[0].word, start);
+    }
+}
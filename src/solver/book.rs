@@ -0,0 +1,221 @@
+//! A fixed opening book: the first guess, and the second guess for every
+//! pattern of it.
+//!
+//! The book is a small TOML file, e.g.
+//!
+//! ```toml
+//! # Opening book of wordlebot
+//! opener = "salet"
+//!
+//! [second]
+//! bbbbb = "courd"
+//! bbbby = "dough"
+//! ```
+//!
+//! Patterns use `b` for absent, `y` for misplaced and `g` for correct
+//! letters, like `--guess` (`0`, `1` and `2` work too). Patterns without a
+//! second guess are left to the solver, like every later guess.
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+
+use crate::solver::parallel::*;
+use crate::solver::Solver;
+use crate::wordle::*;
+
+/// The first two guesses of a game, see the [module](self)
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpeningBook {
+    pub opener: Word,
+    /// The second guess by the pattern of the opener
+    pub second: BTreeMap<Pattern, Word>,
+}
+
+impl OpeningBook {
+    /// Parse a book from its TOML text
+    pub fn parse(text: &str) -> Result<OpeningBook> {
+        let mut opener = None;
+        let mut second = BTreeMap::new();
+        let mut in_second = false;
+        for (i, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let context = || format!("Line {}", i + 1);
+            if line.starts_with('[') {
+                match line {
+                    "[second]" => in_second = true,
+                    _ => bail!("{}: unknown table {}", context(), line),
+                }
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                bail!("{}: expected key = \"word\"", context())
+            };
+            let (key, value) = (key.trim(), value.trim());
+            let Some(word) = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+            else {
+                bail!("{}: the word {} must be quoted", context(), value)
+            };
+            let word = create_word_from_string(&word.to_lowercase());
+            match (in_second, key) {
+                (false, "opener") => opener = Some(word),
+                (false, _) => bail!("{}: unknown key '{}'", context(), key),
+                (true, _) => {
+                    let pattern = parse_pattern(key).with_context(context)?;
+                    if second.insert(pattern, word).is_some() {
+                        bail!("{}: the pattern {} is in the book twice", context(), key)
+                    }
+                }
+            }
+        }
+        let Some(opener) = opener else {
+            bail!("The book has no opener")
+        };
+        Ok(OpeningBook { opener, second })
+    }
+
+    /// The TOML text of the book
+    pub fn to_toml(&self) -> String {
+        let n_letters = self.opener.n_letters();
+        let mut text = format!(
+            "# Opening book of wordlebot, see src/solver/book.rs\nopener = \"{}\"\n\n[second]\n",
+            self.opener.to_string().to_lowercase()
+        );
+        for (&pattern, word) in &self.second {
+            text += &format!(
+                "{} = \"{}\"\n",
+                pattern_to_string(pattern, n_letters).to_lowercase(),
+                word.to_string().to_lowercase()
+            );
+        }
+        text
+    }
+
+    /// The guess of the book after the given guesses, or `None` if the
+    /// book does not cover them
+    pub fn next_guess(&self, guesses: &[Guess]) -> Option<Word> {
+        match guesses {
+            [] => Some(self.opener),
+            [first] if first.word == self.opener => self.second.get(&first.status).copied(),
+            _ => None,
+        }
+    }
+
+    /// All words of the book
+    pub fn guesses(&self) -> Vec<Word> {
+        let mut words = vec![self.opener];
+        words.extend(self.second.values());
+        words
+    }
+}
+
+impl Solver {
+    /// The book for an opener, with the second guess that `pick` chooses
+    /// after every pattern the opener can get from an answer
+    pub fn build_book(
+        &self,
+        opener: Word,
+        pick: impl Fn(&[Guess]) -> Option<Word> + Sync,
+    ) -> OpeningBook {
+        let answers = self.get_frequent_word_idx();
+        let mut patterns: Vec<Pattern> = self
+            .get_words_from_idx(&answers)
+            .iter()
+            .map(|answer| self.pattern_of(answer, &opener))
+            .filter(|&pattern| pattern != solved_pattern(self.n_letters))
+            .collect();
+        patterns.sort_unstable();
+        patterns.dedup();
+        let second = patterns
+            .par_iter()
+            .filter_map(|&status| {
+                let guess = Guess {
+                    word: opener,
+                    status,
+                };
+                Some((status, pick(&[guess])?))
+            })
+            .collect();
+        OpeningBook { opener, second }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::{solver_for, SMALL_WORDS};
+
+    const BOOK: &str = "\
+# A book
+opener = \"salet\" # the opener
+
+[second]
+bbbbb = \"courd\"
+YBBBG = \"those\"
+";
+
+    #[test]
+    fn test_parse_and_serialize() {
+        let book = OpeningBook::parse(BOOK).unwrap();
+        assert_eq!(book.opener, create_word_from_string("salet"));
+        assert_eq!(book.second.len(), 2);
+        assert_eq!(OpeningBook::parse(&book.to_toml()).unwrap(), book);
+        assert!(book.to_toml().contains("ybbbg = \"those\"\n"));
+
+        for text in [
+            "",
+            "opener = salet",
+            "opener = \"salet\"\nsecond = \"courd\"",
+            "opener = \"salet\"\n[third]",
+            "opener = \"salet\"\n[second]\nbbbxb = \"courd\"",
+            "opener = \"salet\"\n[second]\nbbbbb = \"courd\"\n00000 = \"those\"",
+        ] {
+            assert!(OpeningBook::parse(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_next_guess() {
+        let book = OpeningBook::parse(BOOK).unwrap();
+        let salet = create_word_from_string("salet");
+        assert_eq!(book.next_guess(&[]), Some(salet));
+        let guess = |word: &str, pattern: &str| Guess {
+            word: create_word_from_string(word),
+            status: parse_pattern(pattern).unwrap(),
+        };
+        assert_eq!(
+            book.next_guess(&[guess("salet", "bbbbb")]),
+            Some(create_word_from_string("courd"))
+        );
+        // Not in the book
+        assert_eq!(book.next_guess(&[guess("salet", "gbbbb")]), None);
+        assert_eq!(book.next_guess(&[guess("crane", "bbbbb")]), None);
+        let two = [guess("salet", "bbbbb"), guess("courd", "bbbbb")];
+        assert_eq!(book.next_guess(&two), None);
+    }
+
+    #[test]
+    fn test_build_book() {
+        let solver = solver_for(&SMALL_WORDS);
+        let words = solver.words.clone();
+        let book = solver.build_book(words[5], |guesses| {
+            let remaining = solver.get_remaining_words_idx(guesses).ok()?;
+            Some(solver.guess(1, &remaining, 0.0).ok()?[0])
+        });
+        assert_eq!(book.opener, words[5]);
+        // BILLS solves itself and gets one more pattern for SLATE, the
+        // others share theirs
+        assert_eq!(book.second.len(), 2);
+        for (&status, &second) in &book.second {
+            let guesses = [Guess {
+                word: words[5],
+                status,
+            }];
+            let remaining = solver.get_remaining_words_idx(&guesses).unwrap();
+            assert_eq!(second, solver.guess(1, &remaining, 0.0).unwrap()[0]);
+        }
+    }
+}
@@ -0,0 +1,323 @@
+//! Caches of results of the solver by the remaining words, with how often
+//! they were hit.
+//!
+//! A cache is locked only to look up and store a value, never while the
+//! value is computed, so it can be shared by the threads of the solver and
+//! the tasks of the TUI. When it is full, the least recently used value is
+//! evicted. The values are kept in a list from the most to the least
+//! recently used one, so a lookup, an insert and an eviction take constant
+//! time.
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::solver::mappings::fnv1a;
+use crate::solver::GuessEvaluation;
+
+/// The number of values each cache of a solver keeps by default
+pub const DEFAULT_CACHE_SIZE: usize = 4096;
+
+/// A bounded map from keys to values, see the [module](self)
+pub struct Cache<K, V> {
+    capacity: usize,
+    entries: Mutex<Entries<K, V>>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+/// The end of the list of entries
+const NONE: usize = usize::MAX;
+
+/// The values of a cache in a doubly linked list over the slots, from the
+/// most recently used one at the head to the least recently used one at
+/// the tail
+struct Entries<K, V> {
+    // The slot of every key
+    slots: HashMap<K, usize>,
+    nodes: Vec<Node<K, V>>,
+    head: usize,
+    tail: usize,
+}
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    prev: usize,
+    next: usize,
+}
+
+impl<K: Hash + Eq + Clone, V> Entries<K, V> {
+    fn new() -> Entries<K, V> {
+        Entries {
+            slots: HashMap::new(),
+            nodes: vec![],
+            head: NONE,
+            tail: NONE,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// The value of the key, which becomes the most recently used one
+    fn get(&mut self, key: &K) -> Option<&V> {
+        let slot = *self.slots.get(key)?;
+        self.unlink(slot);
+        self.push_front(slot);
+        Some(&self.nodes[slot].value)
+    }
+
+    /// Store the value as the most recently used one. If there are
+    /// `capacity` values already, the least recently used one is replaced.
+    fn insert(&mut self, key: K, value: V, capacity: usize) {
+        if let Some(&slot) = self.slots.get(&key) {
+            self.nodes[slot].value = value;
+            self.unlink(slot);
+            self.push_front(slot);
+            return;
+        }
+        let slot = match self.len() >= capacity {
+            true => {
+                let slot = self.tail;
+                self.unlink(slot);
+                self.slots.remove(&self.nodes[slot].key);
+                self.nodes[slot].key = key.clone();
+                self.nodes[slot].value = value;
+                slot
+            }
+            false => {
+                self.nodes.push(Node {
+                    key: key.clone(),
+                    value,
+                    prev: NONE,
+                    next: NONE,
+                });
+                self.nodes.len() - 1
+            }
+        };
+        self.slots.insert(key, slot);
+        self.push_front(slot);
+    }
+
+    fn clear(&mut self) {
+        *self = Entries::new();
+    }
+
+    fn unlink(&mut self, slot: usize) {
+        let (prev, next) = (self.nodes[slot].prev, self.nodes[slot].next);
+        match prev {
+            NONE => self.head = next,
+            prev => self.nodes[prev].next = next,
+        }
+        match next {
+            NONE => self.tail = prev,
+            next => self.nodes[next].prev = prev,
+        }
+    }
+
+    fn push_front(&mut self, slot: usize) {
+        self.nodes[slot].prev = NONE;
+        self.nodes[slot].next = self.head;
+        match self.head {
+            NONE => self.tail = slot,
+            head => self.nodes[head].prev = slot,
+        }
+        self.head = slot;
+    }
+}
+
+/// How a cache was used
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+    /// The number of values in the cache
+    pub len: usize,
+    pub capacity: usize,
+}
+
+impl CacheStats {
+    /// The share of the lookups that found a value, 0 without lookups
+    pub fn hit_rate(&self) -> f32 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            lookups => self.hits as f32 / lookups as f32,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Cache<K, V> {
+    /// A cache of at most `capacity` values. A capacity of 0 keeps nothing.
+    pub fn new(capacity: usize) -> Cache<K, V> {
+        Cache {
+            capacity,
+            entries: Mutex::new(Entries::new()),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The value of the key, if it is cached
+    pub fn get(&self, key: &K) -> Option<V> {
+        match self.entries.lock().unwrap().get(key) {
+            Some(value) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some(value.clone())
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Cache the value, and evict the least recently used one if the cache
+    /// is full
+    pub fn insert(&self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key, value, self.capacity);
+    }
+
+    /// The cached value of the key, or the value of `f`, which is cached.
+    /// Two threads that miss at the same time both compute the value.
+    pub fn get_or_insert_with(&self, key: K, f: impl FnOnce() -> V) -> V {
+        if let Some(value) = self.get(&key) {
+            return value;
+        }
+        let value = f();
+        self.insert(key, value.clone());
+        value
+    }
+
+    /// Remove all values, e.g. when the results they were computed with
+    /// change. The counts of the hits and misses are kept.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            len: self.entries.lock().unwrap().len(),
+            capacity: self.capacity,
+        }
+    }
+}
+
+/// The caches of a solver. The keys start with [`remaining_key`] of the
+/// remaining words.
+pub struct SolverCache {
+    /// The evaluations of guesses without their two level bits, by the
+    /// index of the guess
+    pub evaluations: Cache<(u64, usize), GuessEvaluation>,
+    /// The two level bits of guesses, by the index of the guess
+    pub two_level: Cache<(u64, usize), f32>,
+    /// The bits of the best guess, for the words left by a guess of the two
+    /// level bits. Different guesses and games leave the same words.
+    pub next_level: Cache<u64, f32>,
+    /// Suggestions by a key of the caller, which includes everything that
+    /// changes them, like the ranking and the filter
+    pub suggestions: Cache<u64, Vec<GuessEvaluation>>,
+}
+
+impl SolverCache {
+    pub fn new(capacity: usize) -> SolverCache {
+        SolverCache {
+            evaluations: Cache::new(capacity),
+            two_level: Cache::new(capacity),
+            next_level: Cache::new(capacity),
+            suggestions: Cache::new(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.evaluations.capacity()
+    }
+
+    pub fn clear(&self) {
+        self.evaluations.clear();
+        self.two_level.clear();
+        self.next_level.clear();
+        self.suggestions.clear();
+    }
+
+    /// The stats of every cache with its name
+    pub fn stats(&self) -> Vec<(&'static str, CacheStats)> {
+        vec![
+            ("suggestions", self.suggestions.stats()),
+            ("evaluations", self.evaluations.stats()),
+            ("two level", self.two_level.stats()),
+            ("next level", self.next_level.stats()),
+        ]
+    }
+}
+
+/// A key of the remaining words. The order of the words does not matter.
+pub fn remaining_key(remaining_words: &[usize]) -> u64 {
+    let mut words = remaining_words.to_vec();
+    words.sort_unstable();
+    fnv1a(words.iter().map(|&word| word as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eviction() {
+        let cache = Cache::new(2);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+        // 1 is used more recently than 2 now
+        assert_eq!(cache.get(&1), Some("one"));
+        cache.insert(3, "three");
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&3), Some("three"));
+        assert_eq!(cache.get_or_insert_with(4, || "four"), "four");
+        assert_eq!(cache.get(&1), None);
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.len), (2, 3, 2));
+        assert_eq!(stats.hit_rate(), 0.4);
+
+        // Storing a key again replaces its value and uses it
+        let cache = Cache::new(3);
+        for i in 0..3 {
+            cache.insert(i, i);
+        }
+        cache.insert(0, 10);
+        cache.insert(3, 3);
+        cache.insert(4, 4);
+        assert_eq!(cache.get(&0), Some(10));
+        assert_eq!((cache.get(&1), cache.get(&2)), (None, None));
+        cache.insert(5, 5);
+        assert_eq!(cache.get(&3), None);
+        assert_eq!(cache.stats().len, 3);
+        cache.clear();
+        cache.insert(6, 6);
+        assert_eq!((cache.get(&6), cache.stats().len), (Some(6), 1));
+
+        // Nothing is kept without a capacity
+        let cache = Cache::new(0);
+        assert_eq!(cache.get_or_insert_with(1, || 1), 1);
+        assert_eq!(cache.get(&1), None);
+    }
+
+    #[test]
+    fn test_remaining_key() {
+        assert_eq!(remaining_key(&[3, 1, 2]), remaining_key(&[1, 2, 3]));
+        assert_ne!(remaining_key(&[1, 2]), remaining_key(&[1, 2, 3]));
+    }
+}
@@ -0,0 +1,165 @@
+use anyhow::{bail, Error};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::wordle::*;
+
+/// What is known about the answer without a guess, e.g. from another
+/// source. The remaining words of the guesses must also meet it.
+///
+/// The constraints are written as space separated parts: `+LETTERS` for
+/// letters the answer contains, `-LETTERS` for letters it does not contain,
+/// `R@3` for a letter at a position and `R!3` for a letter not at a
+/// position. Positions count from 1.
+///
+/// # Example
+///
+/// ```
+/// use wordlebot::solver::constraint::ManualConstraints;
+/// use wordlebot::wordle::*;
+///
+/// let known: ManualConstraints = "r!3 -s".parse().unwrap();
+/// assert!(known.matches(&create_word_from_string("crane")));
+/// assert!(!known.matches(&create_word_from_string("barge")));
+/// assert!(!known.matches(&create_word_from_string("crest")));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ManualConstraints {
+    pub constraints: Vec<LetterConstraint>,
+}
+
+impl ManualConstraints {
+    pub fn is_empty(&self) -> bool {
+        self.constraints.is_empty()
+    }
+
+    /// Whether the word meets every constraint
+    pub fn matches(&self, word: &Word) -> bool {
+        self.constraints.iter().all(|c| word.meets(c))
+    }
+
+    /// The first position that is not a position of words with `n_letters`
+    pub fn invalid_position(&self, n_letters: usize) -> Option<usize> {
+        self.constraints.iter().find_map(|c| match *c {
+            LetterConstraint::At(_, pos) | LetterConstraint::NotAt(_, pos) if pos >= n_letters => {
+                Some(pos + 1)
+            }
+            _ => None,
+        })
+    }
+}
+
+impl FromStr for ManualConstraints {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut constraints = vec![];
+        for part in s.split_whitespace() {
+            let part = part.to_lowercase();
+            let positional = part
+                .split_once('@')
+                .map(|(c, pos)| (c, pos, true))
+                .or_else(|| part.split_once('!').map(|(c, pos)| (c, pos, false)));
+            let letters = match (positional, part.chars().next()) {
+                (Some((letter, pos, at)), _) => {
+                    let mut chars = letter.chars();
+                    let (Some(c), None) = (chars.next(), chars.next()) else {
+                        bail!("Expected a single letter before the position in '{}'", part)
+                    };
+                    if !c.is_alphabetic() {
+                        bail!("Invalid letter '{}' in '{}'", c, part)
+                    }
+                    let pos = match pos.parse::<usize>() {
+                        Ok(pos) if pos >= 1 => pos - 1,
+                        _ => bail!("Expected a position from 1 in '{}'", part),
+                    };
+                    constraints.push(match at {
+                        true => LetterConstraint::At(c, pos),
+                        false => LetterConstraint::NotAt(c, pos),
+                    });
+                    continue;
+                }
+                (None, Some('+' | '-')) => &part[1..],
+                _ => bail!("Expected +LETTERS, -LETTERS, R@3 or R!3, got '{}'", part),
+            };
+            if letters.is_empty() {
+                bail!("No letters after '{}'", part)
+            }
+            for c in letters.chars() {
+                if !c.is_alphabetic() {
+                    bail!("Invalid letter '{}' in '{}'", c, part)
+                }
+                let constraint = match part.starts_with('+') {
+                    true => LetterConstraint::AtLeast(c, 1),
+                    false => LetterConstraint::Absent(c),
+                };
+                if !constraints.contains(&constraint) {
+                    constraints.push(constraint);
+                }
+            }
+        }
+        let contains = |c: char| {
+            constraints.iter().any(|constraint| {
+                matches!(*constraint,
+                    LetterConstraint::AtLeast(l, _) | LetterConstraint::At(l, _) if l == c)
+            })
+        };
+        let contradiction = constraints.iter().find_map(|constraint| match *constraint {
+            LetterConstraint::Absent(c) if contains(c) => Some(c),
+            _ => None,
+        });
+        if let Some(c) = contradiction {
+            bail!("The letter '{}' is both in the answer and not", c)
+        }
+        Ok(ManualConstraints { constraints })
+    }
+}
+
+impl fmt::Display for ManualConstraints {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self
+            .constraints
+            .iter()
+            .map(|constraint| match *constraint {
+                LetterConstraint::Absent(c) => format!("-{}", c),
+                LetterConstraint::AtLeast(c, _) => format!("+{}", c),
+                LetterConstraint::At(c, pos) => format!("{}@{}", c, pos + 1),
+                LetterConstraint::NotAt(c, pos) => format!("{}!{}", c, pos + 1),
+                LetterConstraint::AtMost(c, n) => format!("{}<={}", c, n),
+            })
+            .collect();
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let known: ManualConstraints = " R!3 +ae -s  e@5 +a ".parse().unwrap();
+        assert_eq!(
+            known.constraints,
+            vec![
+                LetterConstraint::NotAt('r', 2),
+                LetterConstraint::AtLeast('a', 1),
+                LetterConstraint::AtLeast('e', 1),
+                LetterConstraint::Absent('s'),
+                LetterConstraint::At('e', 4),
+            ]
+        );
+        assert_eq!(known.to_string(), "r!3 +a +e -s e@5");
+        assert_eq!(
+            known.to_string().parse::<ManualConstraints>().unwrap(),
+            known
+        );
+        assert_eq!(known.invalid_position(5), None);
+        assert_eq!(known.invalid_position(4), Some(5));
+
+        assert!("".parse::<ManualConstraints>().unwrap().is_empty());
+        for invalid in ["r3", "r!0", "rs!3", "r@x", "+", "+r1", "+r -r", "-e e@5"] {
+            assert!(invalid.parse::<ManualConstraints>().is_err(), "{}", invalid);
+        }
+    }
+}
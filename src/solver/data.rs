@@ -1,24 +1,466 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Error, Result};
+use std::fmt;
 use std::io::{prelude::*, BufReader};
+use std::str::FromStr;
 
-use crate::wordle::{create_word_from_string, Word};
-
-pub const N_LINES: usize = 14855;
+use crate::json::string;
+use crate::solver::mappings::fnv1a;
+use crate::wordle::{create_word_from_string, Word, MAX_LETTERS, MIN_LETTERS};
 
 const DATA: &[u8] = include_bytes!("../../data/words.csv");
+const OPENERS: &str = include_str!("../../data/openers.txt");
+
+/// Import the embedded word list
+pub fn import() -> Result<(Vec<Word>, Vec<f32>)> {
+    parse(DATA)
+}
+
+/// A summary of the embedded word list, see [`DataInfo`]
+pub fn embedded_info() -> Result<DataInfo> {
+    DataInfo::new(DATA)
+}
 
-pub fn import() -> Result<([Word; N_LINES], [f32; N_LINES])> {
-    let mut words = [Word::new(); N_LINES];
-    let mut priors: [f32; N_LINES] = [0.0; N_LINES];
+/// The best first guesses for the embedded word list, in the order of the
+/// default ranking. They are precomputed, since ranking all words for the
+/// first guess is the slowest step of the solver.
+pub fn openers() -> Vec<Word> {
+    OPENERS
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| create_word_from_string(line.trim()))
+        .collect()
+}
+
+/// Parse a word list.
+///
+/// Every line holds a word and optionally its prior, separated by a tab.
+/// Words without a prior get a prior of 1, so a plain list of words is a
+/// valid word list. Words with a prior of 0 are allowed guesses, but not
+/// possible answers. A header line `word<TAB>prior` and comment lines
+/// starting with `#` are skipped; the comments before the first word may
+/// describe the list, see [`parse_metadata`].
+///
+/// All words must have the same length, between `MIN_LETTERS` and
+/// `MAX_LETTERS` letters.
+pub fn parse<R: Read>(data: R) -> Result<(Vec<Word>, Vec<f32>)> {
+    let (words, priors) = parse_lines(data)?;
+    if !priors.iter().any(|&p| p > 0.0) {
+        bail!("The word list does not contain any possible answer")
+    }
+    Ok((words, priors))
+}
+
+/// Parse a word list overlay, the words that are added to a word list. The
+/// format is the same as for [`parse`], but an overlay may be empty or only
+/// hold allowed guesses.
+pub fn parse_overlay<R: Read>(data: R) -> Result<Vec<(Word, f32)>> {
+    let (words, priors) = parse_lines(data)?;
+    Ok(words.into_iter().zip(priors).collect())
+}
+
+/// How the priors of a word list become the weights of the answers.
+///
+/// The priors of the embedded word list are a sigmoid over how often the
+/// words are used, so a temperature can make common words count more or
+/// less without the frequencies themselves. Written as `csv`, `flat`,
+/// `sigmoid` or `sigmoid:TEMPERATURE`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PriorModel {
+    /// The priors as they are in the word list
+    #[default]
+    Csv,
+    /// Every possible answer is equally likely, like a flat prior over an
+    /// official answer list
+    Flat,
+    /// The priors are taken back to the scale of the sigmoid and divided by
+    /// the temperature. Below 1 common words are favored more, above 1 less.
+    Sigmoid { temperature: f32 },
+}
+
+impl PriorModel {
+    /// Transform the priors. A prior of 0 stays 0, since the word is no
+    /// possible answer.
+    pub fn apply(&self, priors: &mut [f32]) {
+        match *self {
+            PriorModel::Csv => {}
+            PriorModel::Flat => priors
+                .iter_mut()
+                .filter(|p| **p > 0.0)
+                .for_each(|p| *p = 1.0),
+            PriorModel::Sigmoid { temperature } => {
+                for p in priors.iter_mut().filter(|p| **p > 0.0) {
+                    // Priors of 1 would be infinitely far up the sigmoid
+                    let clamped = p.clamp(1e-6, 1.0 - 1e-6);
+                    let logit = (clamped / (1.0 - clamped)).ln();
+                    *p = 1.0 / (1.0 + (-logit / temperature).exp());
+                }
+            }
+        }
+    }
+}
 
-    let reader = BufReader::new(DATA);
-    for (i, line) in reader.lines().skip(1).enumerate() {
+impl FromStr for PriorModel {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, temperature) = match s.trim().split_once(':') {
+            Some((name, temperature)) => (name, Some(temperature)),
+            None => (s.trim(), None),
+        };
+        match (name, temperature) {
+            ("csv", None) => Ok(PriorModel::Csv),
+            ("flat", None) => Ok(PriorModel::Flat),
+            ("sigmoid", None) => Ok(PriorModel::Sigmoid { temperature: 1.0 }),
+            ("sigmoid", Some(temperature)) => {
+                let temperature = temperature
+                    .trim()
+                    .parse::<f32>()
+                    .with_context(|| format!("Invalid temperature in '{}'", s))?;
+                if !(temperature.is_finite() && temperature > 0.0) {
+                    bail!("The temperature in '{}' must be positive", s)
+                }
+                Ok(PriorModel::Sigmoid { temperature })
+            }
+            _ => bail!(
+                "Unknown prior model '{}', expected csv, flat or sigmoid:TEMPERATURE",
+                s
+            ),
+        }
+    }
+}
+
+impl fmt::Display for PriorModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PriorModel::Csv => write!(f, "csv"),
+            PriorModel::Flat => write!(f, "flat"),
+            PriorModel::Sigmoid { temperature } => write!(f, "sigmoid:{}", temperature),
+        }
+    }
+}
+
+/// The description of a word list, the `# key: value` comment lines before
+/// its first word or header, e.g. `# source: https://example.com/words`.
+/// Keys are lowercase, and comments that are no such field are skipped.
+pub fn parse_metadata<R: Read>(data: R) -> Result<Vec<(String, String)>> {
+    let mut fields = vec![];
+    let reader = BufReader::new(data);
+    for line in reader.lines() {
         let line = line.context("Error reading line")?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some(comment) = line.strip_prefix('#') else {
+            break;
+        };
+        if let Some((key, value)) = comment.split_once(':') {
+            let key = key.trim().to_lowercase();
+            if !key.is_empty() && !key.contains(char::is_whitespace) {
+                fields.push((key, value.trim().to_string()));
+            }
+        }
+    }
+    Ok(fields)
+}
+
+/// The letters of an alphabet field, e.g. `# alphabet: abcdefghijklmnopqrstuvwxyzäöüß`
+/// in the header of a German word list. Spaces and commas between the
+/// letters are skipped. The letters are lowercase and sorted.
+///
+/// # Example
+///
+/// ```
+/// use wordlebot::solver::data::parse_alphabet;
+///
+/// assert_eq!(parse_alphabet("n, A, ñ").unwrap(), ['a', 'n', 'ñ']);
+/// assert!(parse_alphabet("ab1").is_err());
+/// assert!(parse_alphabet("aba").is_err());
+/// ```
+pub fn parse_alphabet(s: &str) -> Result<Vec<char>> {
+    let mut letters: Vec<char> = s
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ',')
+        .flat_map(char::to_lowercase)
+        .collect();
+    if let Some(c) = letters.iter().find(|c| !c.is_alphabetic()) {
+        bail!("Invalid letter '{}' in the alphabet", c)
+    }
+    letters.sort_unstable();
+    if let Some(pair) = letters.windows(2).find(|pair| pair[0] == pair[1]) {
+        bail!("The letter '{}' is in the alphabet twice", pair[0])
+    }
+    if letters.is_empty() {
+        bail!("The alphabet is empty")
+    }
+    Ok(letters)
+}
 
+/// The priors of the answers are summarized at these quantiles
+pub const PRIOR_QUANTILES: [f32; 5] = [0.1, 0.25, 0.5, 0.75, 0.9];
+
+/// A summary of a word list, to check which data a solver uses and to
+/// compare word lists
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataInfo {
+    /// The fields of the comments, see [`parse_metadata`]
+    pub metadata: Vec<(String, String)>,
+    /// The number of allowed guesses
+    pub n_words: usize,
+    /// The number of words with a prior above 0
+    pub n_answers: usize,
+    pub n_letters: usize,
+    /// The smallest and the largest prior of the answers
+    pub prior_min: f32,
+    pub prior_max: f32,
+    pub prior_mean: f32,
+    /// The priors of the answers at the [`PRIOR_QUANTILES`]
+    pub prior_quantiles: Vec<(f32, f32)>,
+    /// The FNV-1a hash of the bytes of the file
+    pub checksum: u64,
+}
+
+impl DataInfo {
+    /// Summarize a word list in the format of [`parse`]
+    pub fn new(data: &[u8]) -> Result<DataInfo> {
+        let (words, priors) = parse(data)?;
+        let mut answers: Vec<f32> = priors.into_iter().filter(|&p| p > 0.0).collect();
+        answers.sort_by(f32::total_cmp);
+        // The nearest rank, answers is not empty after `parse`
+        let quantile = |q: f32| answers[((q * answers.len() as f32).ceil() as usize).max(1) - 1];
+        Ok(DataInfo {
+            metadata: parse_metadata(data)?,
+            n_words: words.len(),
+            n_answers: answers.len(),
+            n_letters: words[0].n_letters(),
+            prior_min: answers[0],
+            prior_max: answers[answers.len() - 1],
+            prior_mean: answers.iter().sum::<f32>() / answers.len() as f32,
+            prior_quantiles: PRIOR_QUANTILES.iter().map(|&q| (q, quantile(q))).collect(),
+            checksum: fnv1a(data.iter().map(|&byte| byte as u64)),
+        })
+    }
+
+    /// The summary as a JSON object like
+    /// `{"metadata":{"name":"wordlebot"},"words":14855,...}`
+    pub fn to_json(&self) -> String {
+        let metadata: Vec<String> = self
+            .metadata
+            .iter()
+            .map(|(key, value)| format!("{}:{}", string(key), string(value)))
+            .collect();
+        let quantiles: Vec<String> = self
+            .prior_quantiles
+            .iter()
+            .map(|(q, prior)| format!(r#""p{}":{}"#, q * 100.0, prior))
+            .collect();
+        format!(
+            r#"{{"metadata":{{{}}},"words":{},"answers":{},"letters":{},"priors":{{"min":{},"max":{},"mean":{},{}}},"checksum":"{:016x}"}}"#,
+            metadata.join(","),
+            self.n_words,
+            self.n_answers,
+            self.n_letters,
+            self.prior_min,
+            self.prior_max,
+            self.prior_mean,
+            quantiles.join(","),
+            self.checksum
+        )
+    }
+}
+
+impl fmt::Display for DataInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in &self.metadata {
+            writeln!(f, "{}: {}", key, value)?;
+        }
+        writeln!(f, "words: {}", self.n_words)?;
+        writeln!(f, "answers: {}", self.n_answers)?;
+        writeln!(f, "letters: {}", self.n_letters)?;
+        writeln!(
+            f,
+            "priors of the answers: min {}, max {}, mean {:.4}",
+            self.prior_min, self.prior_max, self.prior_mean
+        )?;
+        for (q, prior) in &self.prior_quantiles {
+            writeln!(f, "  p{}: {}", q * 100.0, prior)?;
+        }
+        write!(f, "checksum: {:016x}", self.checksum)
+    }
+}
+
+fn parse_lines<R: Read>(data: R) -> Result<(Vec<Word>, Vec<f32>)> {
+    let mut words = vec![];
+    let mut priors = vec![];
+
+    let reader = BufReader::new(data);
+    let mut header = true;
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.context("Error reading line")?;
+        let line = line.trim();
         let cells: Vec<&str> = line.split('\t').collect();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // The header is the first line that is no comment
+        if std::mem::take(&mut header) && cells[0] == "word" {
+            continue;
+        }
+
+        let word = cells[0].trim().to_lowercase();
+        let n_letters = word.chars().count();
+        if !(MIN_LETTERS..=MAX_LETTERS).contains(&n_letters)
+            || !word.chars().all(|c| c.is_alphabetic())
+        {
+            bail!("Invalid word '{}' in line {}", cells[0], i + 1)
+        }
+        if let Some(first) = words.first().map(Word::n_letters) {
+            if n_letters != first {
+                bail!(
+                    "Word '{}' in line {} has {} letters, but the first word has {}",
+                    cells[0],
+                    i + 1,
+                    n_letters,
+                    first
+                )
+            }
+        }
+        let prior = match cells.get(1) {
+            Some(prior) => prior
+                .trim()
+                .parse::<f32>()
+                .with_context(|| format!("Parsing prior in line {}", i + 1))?,
+            None => 1.0,
+        };
         // Add the word to the vector
-        words[i] = create_word_from_string(cells[0]);
-        priors[i] = cells[1].parse::<f32>().context("Parsing prior")?;
+        words.push(create_word_from_string(&word));
+        priors.push(prior);
     }
     Ok((words, priors))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import() {
+        let (words, priors) = import().unwrap();
+        assert_eq!(words.len(), 14855);
+        assert_eq!(priors.len(), 14855);
+        assert_eq!(words[0], create_word_from_string("raise"));
+    }
+
+    #[test]
+    fn test_parse() {
+        let (words, priors) =
+            parse("word\tprior\nslate\t1\nwater\t0.5\nGOOSE\n".as_bytes()).unwrap();
+        assert_eq!(
+            words,
+            vec![
+                create_word_from_string("slate"),
+                create_word_from_string("water"),
+                create_word_from_string("goose")
+            ]
+        );
+        assert_eq!(priors, vec![1.0, 0.5, 1.0]);
+
+        let (words, _) = parse("words\nslate\n".as_bytes()).unwrap();
+        assert_eq!(words.len(), 2);
+
+        assert!(parse("slate\t1\nwat\t1\n".as_bytes()).is_err());
+        assert!(parse("slate\tone\n".as_bytes()).is_err());
+        assert!(parse("slate\t0\n".as_bytes()).is_err());
+
+        let (words, _) = parse("wasp\nswap\n".as_bytes()).unwrap();
+        assert_eq!(words[1].n_letters(), 4);
+        assert!(parse("slate\nsalted\n".as_bytes()).is_err());
+        assert!(parse("abracadabra\n".as_bytes()).is_err());
+
+        // Comments are skipped, and the header may follow them
+        let (words, _) =
+            parse("# name: test\nword\tprior\nslate\n# more\nwater\n".as_bytes()).unwrap();
+        assert_eq!(words.len(), 2);
+    }
+
+    #[test]
+    fn test_metadata() {
+        let data = "# Name: test list\n# a plain comment\n# source: https://example.com\n\
+                    word\tprior\nslate\t1\n# version: 2\nwater\t0.5\ngoose\t0\n";
+        assert_eq!(
+            parse_metadata(data.as_bytes()).unwrap(),
+            vec![
+                ("name".to_string(), "test list".to_string()),
+                ("source".to_string(), "https://example.com".to_string())
+            ]
+        );
+
+        let info = DataInfo::new(data.as_bytes()).unwrap();
+        assert_eq!((info.n_words, info.n_answers, info.n_letters), (3, 2, 5));
+        assert_eq!(
+            (info.prior_min, info.prior_max, info.prior_mean),
+            (0.5, 1.0, 0.75)
+        );
+        assert_eq!(info.prior_quantiles[2], (0.5, 0.5));
+        assert_eq!(info.prior_quantiles[4], (0.9, 1.0));
+        assert_ne!(
+            info.checksum,
+            DataInfo::new(format!("{}\n", data).as_bytes())
+                .unwrap()
+                .checksum
+        );
+        assert!(info
+            .to_json()
+            .starts_with(r#"{"metadata":{"name":"test list","source":"https://example.com"},"words":3,"answers":2"#));
+
+        let embedded = embedded_info().unwrap();
+        assert_eq!(embedded.n_words, 14855);
+        assert!(embedded.metadata.iter().any(|(key, _)| key == "name"));
+    }
+
+    #[test]
+    fn test_parse_overlay() {
+        let overlay = parse_overlay("slate\t0\nquirk\n".as_bytes()).unwrap();
+        assert_eq!(
+            overlay,
+            vec![
+                (create_word_from_string("slate"), 0.0),
+                (create_word_from_string("quirk"), 1.0)
+            ]
+        );
+        assert!(parse_overlay("".as_bytes()).unwrap().is_empty());
+        assert!(parse_overlay("slate\nwat\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_prior_model() {
+        let priors = [1.0, 0.5, 0.1, 0.0];
+        let apply = |model: &str| {
+            let mut priors = priors;
+            model.parse::<PriorModel>().unwrap().apply(&mut priors);
+            priors
+        };
+        assert_eq!(apply("csv"), priors);
+        assert_eq!(apply("flat"), [1.0, 1.0, 1.0, 0.0]);
+        let sigmoid = apply("sigmoid");
+        assert!(sigmoid
+            .iter()
+            .zip(&priors)
+            .all(|(a, b)| (a - b).abs() < 1e-5));
+
+        // A low temperature favors common words more, a high one less
+        let sharp = apply("sigmoid:0.5");
+        assert!(sharp[2] < 0.02 && sharp[1] == 0.5 && sharp[3] == 0.0);
+        let soft = apply("sigmoid:4");
+        assert!(soft[2] > 0.3 && soft[0] < 0.99);
+
+        assert_eq!(
+            "sigmoid:0.5".parse::<PriorModel>().unwrap().to_string(),
+            "sigmoid:0.5"
+        );
+        assert!("sigmoid:0".parse::<PriorModel>().is_err());
+        assert!("sigmoid:hot".parse::<PriorModel>().is_err());
+        assert!("flat:2".parse::<PriorModel>().is_err());
+        assert!("zipf".parse::<PriorModel>().is_err());
+    }
+}
@@ -0,0 +1,327 @@
+//! The exact best guesses when few words remain.
+//!
+//! The entropy looks one guess ahead, which misleads it in families of
+//! answers that only differ in one letter, like BIGHT, FIGHT, LIGHT and
+//! MIGHT: guessing them one by one may take as many guesses as there are
+//! answers, while a word that tests several of the letters at once finds
+//! any of them in a few. Once at most [`ENDGAME_SIZE`] words remain, all
+//! guesses are searched to the end instead, for the fewest guesses in the
+//! worst case and then the fewest expected guesses, with every later guess
+//! picked the same way. Every remaining word is equally likely, like in
+//! [`Solver::build_decision_tree`].
+//!
+//! Guesses that split the words into the same groups are the same for the
+//! search, so each split is searched once, and the best cost of every group
+//! is remembered. Splits whose lower bound can not beat the best split so
+//! far are skipped.
+use std::collections::HashMap;
+
+use crate::solver::Solver;
+use crate::wordle::*;
+
+/// The most remaining words that are searched exactly
+pub const ENDGAME_SIZE: usize = 20;
+
+/// The cost of a guess with the best guesses after it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExactCost {
+    /// The most guesses needed to find any of the remaining words,
+    /// including this guess
+    pub worst: usize,
+    /// The expected number of guesses to find the answer, including this
+    /// guess
+    pub expected: f32,
+}
+
+/// The groups of the words that are not solved by a guess, see
+/// [`Solver::splits`]
+type Split = Vec<Vec<usize>>;
+
+/// The cost of a strategy for some answers, fewer guesses in the worst
+/// case first and then fewer guesses in total
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Cost {
+    worst: usize,
+    /// The guesses needed for all answers together
+    total: usize,
+}
+
+/// The best cost of the answers that were searched
+type Memo = HashMap<Vec<usize>, Cost>;
+
+impl Solver {
+    /// The n best guesses for the remaining words with their exact cost,
+    /// see the [module](self). `None` if more than [`ENDGAME_SIZE`] words
+    /// remain.
+    pub fn endgame(&self, n: usize, remaining_words: &[usize]) -> Option<Vec<(Word, ExactCost)>> {
+        self.endgame_where(n, remaining_words, |_| true)
+    }
+
+    /// Like [`Solver::endgame`], among the words that `keep` accepts. Only
+    /// the first guess has to be accepted, the guesses after it may be any
+    /// word. Ties prefer possible answers and then higher priors.
+    pub fn endgame_where(
+        &self,
+        n: usize,
+        remaining_words: &[usize],
+        keep: impl Fn(&Word) -> bool,
+    ) -> Option<Vec<(Word, ExactCost)>> {
+        if remaining_words.is_empty() || remaining_words.len() > ENDGAME_SIZE {
+            return None;
+        }
+        let mut answers = remaining_words.to_vec();
+        answers.sort_unstable();
+        let mut memo = HashMap::new();
+
+        // Every guess with the same split costs the same
+        let (splits, split_of) = self.splits(&answers);
+        let mut n_kept = vec![0; splits.len()];
+        let split_of: Vec<Option<usize>> = split_of
+            .into_iter()
+            .enumerate()
+            .map(|(id, split)| {
+                split.filter(|_| keep(&self.words[id]) && self.in_pool(&answers, id))
+            })
+            .collect();
+        for split in split_of.iter().flatten() {
+            n_kept[*split] += 1;
+        }
+
+        // The splits are searched from the lowest bound, until n guesses
+        // are found that no split left can beat
+        let bounds: Vec<Cost> = splits
+            .iter()
+            .map(|groups| lower_bound(&answers, groups))
+            .collect();
+        let mut order: Vec<usize> = (0..splits.len()).filter(|&i| n_kept[i] > 0).collect();
+        order.sort_by_key(|&i| bounds[i]);
+        let mut costs: Vec<Option<Cost>> = vec![None; splits.len()];
+        let wanted = n.max(1);
+        let mut found: Vec<Cost> = vec![];
+        for i in order {
+            if found.len() >= wanted && found[wanted - 1] < bounds[i] {
+                break;
+            }
+            let cost = self.split_cost(&answers, &splits[i], &mut memo);
+            costs[i] = Some(cost);
+            found.extend(std::iter::repeat_n(cost, n_kept[i].min(wanted)));
+            found.sort();
+            found.truncate(wanted);
+        }
+
+        let mut ranked: Vec<(usize, Cost)> = split_of
+            .into_iter()
+            .enumerate()
+            .filter_map(|(id, split)| Some((id, costs[split?]?)))
+            .collect();
+        ranked.sort_by(|(a, x), (b, y)| {
+            x.cmp(y)
+                .then(
+                    answers
+                        .binary_search(b)
+                        .is_ok()
+                        .cmp(&answers.binary_search(a).is_ok()),
+                )
+                .then(self.priors[*b].total_cmp(&self.priors[*a]))
+                .then(a.cmp(b))
+        });
+        Some(
+            ranked
+                .into_iter()
+                .take(n)
+                .map(|(id, cost)| {
+                    let expected = cost.total as f32 / answers.len() as f32;
+                    let cost = ExactCost {
+                        worst: cost.worst,
+                        expected,
+                    };
+                    (self.words[id], cost)
+                })
+                .collect(),
+        )
+    }
+
+    /// The different splits of the answers by the guesses, and the index of
+    /// the split of every word. Words that do not tell the answers apart
+    /// have none.
+    fn splits(&self, answers: &[usize]) -> (Vec<Split>, Vec<Option<usize>>) {
+        let solved = solved_pattern(self.n_letters);
+        let mut index: HashMap<Vec<Pattern>, usize> = HashMap::new();
+        let mut splits = vec![];
+        let mut split_of = vec![None; self.words.len()];
+        let mut patterns = Vec::with_capacity(answers.len());
+        let mut numbers: Vec<Pattern> = vec![];
+        let mut key: Vec<Pattern> = vec![];
+        for (guess, split_of) in split_of.iter_mut().enumerate() {
+            patterns.clear();
+            self.mappings
+                .row(guess)
+                .for_each(answers, |_, pattern| patterns.push(pattern));
+            if patterns.iter().all(|&p| p == patterns[0] && p != solved) {
+                continue;
+            }
+            // The patterns numbered in the order they first appear, with the
+            // solved pattern kept apart, are the same for the same split
+            numbers.clear();
+            key.clear();
+            for &p in &patterns {
+                key.push(match numbers.iter().position(|&q| q == p) {
+                    _ if p == solved => Pattern::MAX,
+                    Some(i) => i as Pattern,
+                    None => {
+                        numbers.push(p);
+                        (numbers.len() - 1) as Pattern
+                    }
+                });
+            }
+            let split = match index.get(key.as_slice()) {
+                Some(&split) => split,
+                None => {
+                    let mut groups = vec![vec![]; numbers.len()];
+                    for (&n, &answer) in key.iter().zip(answers) {
+                        if n != Pattern::MAX {
+                            groups[n as usize].push(answer);
+                        }
+                    }
+                    splits.push(groups);
+                    index.insert(key.clone(), splits.len() - 1);
+                    splits.len() - 1
+                }
+            };
+            *split_of = Some(split);
+        }
+        (splits, split_of)
+    }
+
+    /// The best cost of the answers, with the best first guess for them
+    fn exact_cost(&self, answers: &[usize], memo: &mut Memo) -> Cost {
+        if answers.len() == 1 {
+            return Cost { worst: 1, total: 1 };
+        }
+        if let Some(&cost) = memo.get(answers) {
+            return cost;
+        }
+
+        let (splits, _) = self.splits(answers);
+        let bounds: Vec<Cost> = splits
+            .iter()
+            .map(|groups| lower_bound(answers, groups))
+            .collect();
+        let mut order: Vec<usize> = (0..splits.len()).collect();
+        order.sort_by_key(|&i| bounds[i]);
+        let mut best: Option<Cost> = None;
+        for i in order {
+            if best.is_some_and(|best| bounds[i] >= best) {
+                break;
+            }
+            let cost = self.split_cost(answers, &splits[i], memo);
+            if best.is_none_or(|best| cost < best) {
+                best = Some(cost);
+            }
+        }
+        // Every guess that is one of the answers splits them
+        let best = best.unwrap();
+        memo.insert(answers.to_vec(), best);
+        best
+    }
+
+    /// The cost of a split with the best guesses for its groups
+    fn split_cost(&self, answers: &[usize], groups: &[Vec<usize>], memo: &mut Memo) -> Cost {
+        let mut cost = Cost {
+            worst: 1,
+            total: answers.len(),
+        };
+        for group in groups {
+            let group_cost = self.exact_cost(group, memo);
+            cost.worst = cost.worst.max(1 + group_cost.worst);
+            cost.total += group_cost.total;
+        }
+        cost
+    }
+}
+
+/// The lowest cost a split can have: one word of every group can be found
+/// with the next guess, all others need at least two more
+fn lower_bound(answers: &[usize], groups: &[Vec<usize>]) -> Cost {
+    Cost {
+        worst: 1 + groups
+            .iter()
+            .map(|group| group.len().min(2))
+            .max()
+            .unwrap_or(0),
+        total: answers.len()
+            + groups
+                .iter()
+                .map(|group| 2 * group.len() - 1)
+                .sum::<usize>(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::solver_with;
+    use approx::*;
+
+    const FAMILY: [&str; 9] = [
+        "bight", "fight", "light", "might", "night", "right", "sight", "tight", "wight",
+    ];
+
+    /// The IGHT family, and two guesses that test four of its first letters
+    /// each
+    fn solver() -> Solver {
+        let words = [&FAMILY[..], &["blown", "frump", "stamp"]].concat();
+        let mut priors = vec![1.0; FAMILY.len()];
+        priors.extend([0.0; 3]);
+        solver_with(&words, &priors)
+    }
+
+    #[test]
+    fn test_trap_family() {
+        let solver = solver();
+        let remaining: Vec<usize> = (0..FAMILY.len()).collect();
+        let ranked = solver.endgame(12, &remaining).unwrap();
+        assert_eq!(ranked.len(), 12);
+
+        // BLOWN finds BIGHT, LIGHT, NIGHT and WIGHT, FRUMP then FIGHT,
+        // RIGHT and MIGHT, which leaves SIGHT and TIGHT
+        let (best, cost) = ranked[0];
+        assert_eq!(best, create_word_from_string("blown"));
+        assert_eq!(cost.worst, 4);
+        assert_relative_eq!(cost.expected, 1.0 + 4.0 / 9.0 + 5.0 / 9.0 * 2.2);
+
+        // LIGHT leaves the other eight for BLOWN, and no answer first is
+        // as good
+        let light = create_word_from_string("light");
+        let (_, light_cost) = ranked.iter().find(|(word, _)| *word == light).unwrap();
+        assert_eq!(light_cost.worst, 5);
+        let answers = solver.get_words_from_idx(&remaining);
+        let only_answers = solver
+            .endgame_where(1, &remaining, |word| answers.contains(word))
+            .unwrap();
+        assert!(only_answers[0].1.expected > cost.expected);
+
+        // The exact search replaces the entropy in guess
+        assert_eq!(solver.guess(1, &remaining, 0.1).unwrap(), [best]);
+    }
+
+    #[test]
+    fn test_small_endgames() {
+        let solver = solver();
+        // One of two words is found with the first guess half of the time
+        let (word, cost) = solver.endgame(1, &[0, 1]).unwrap()[0];
+        assert!(word == solver.words[0] || word == solver.words[1]);
+        assert_eq!(cost.worst, 2);
+        assert_relative_eq!(cost.expected, 1.5);
+        assert_eq!(
+            solver.endgame(1, &[3]).unwrap()[0].1,
+            ExactCost {
+                worst: 1,
+                expected: 1.0
+            }
+        );
+        assert!(solver.endgame(1, &[]).is_none());
+        let many: Vec<usize> = (0..=ENDGAME_SIZE).map(|i| i % 12).collect();
+        assert!(solver.endgame(1, &many).is_none());
+    }
+}
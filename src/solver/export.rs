@@ -0,0 +1,241 @@
+//! The remaining words and the patterns of a guess as tables, to continue
+//! the analysis in a spreadsheet or a script.
+use std::path::Path;
+
+use crate::solver::parallel::*;
+use crate::solver::{Solver, SolverError};
+use crate::wordle::*;
+
+/// A remaining word with its prior and the bits it gives as the next guess
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RemainingWord {
+    pub word: Word,
+    pub prior: f32,
+    /// The entropy of the patterns of the word over the remaining words
+    pub expected_bits: f32,
+}
+
+/// The file format of an export
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// A header `word,prior,expected_bits` and one line per word
+    Csv,
+    /// An array of objects like
+    /// `{"word":"crane","prior":1,"expected_bits":2.5}`
+    Json,
+}
+
+impl ExportFormat {
+    /// JSON for a file name ending in `.json`, CSV for any other
+    pub fn from_path(path: &Path) -> ExportFormat {
+        match path.extension() {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => ExportFormat::Json,
+            _ => ExportFormat::Csv,
+        }
+    }
+
+    /// The words in the format
+    pub fn format(&self, words: &[RemainingWord]) -> String {
+        match self {
+            ExportFormat::Csv => {
+                let mut lines = vec!["word,prior,expected_bits".to_string()];
+                lines.extend(words.iter().map(|w| {
+                    format!(
+                        "{},{},{}",
+                        w.word.to_string().to_lowercase(),
+                        w.prior,
+                        w.expected_bits
+                    )
+                }));
+                lines.join("\n") + "\n"
+            }
+            ExportFormat::Json => {
+                let objects: Vec<String> = words
+                    .iter()
+                    .map(|w| {
+                        format!(
+                            r#"{{"word":"{}","prior":{},"expected_bits":{}}}"#,
+                            w.word.to_string().to_lowercase(),
+                            w.prior,
+                            w.expected_bits
+                        )
+                    })
+                    .collect();
+                format!("[{}]\n", objects.join(","))
+            }
+        }
+    }
+}
+
+/// A pattern of a guess over the remaining words
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PatternRow {
+    pub pattern: Pattern,
+    /// The share of the prior of the remaining words that give the pattern
+    pub probability: f64,
+    /// The number of remaining words that give the pattern
+    pub remaining: usize,
+    /// The entropy of the priors of the words that are left after the
+    /// pattern, in bits
+    pub entropy: f64,
+}
+
+impl PatternRow {
+    /// The patterns as CSV with a header line. The bits are the information
+    /// of the pattern, `-log2(probability)`, and empty for patterns that
+    /// cannot occur.
+    pub fn csv(rows: &[PatternRow], n_letters: usize) -> String {
+        let mut lines = vec!["pattern,probability,remaining,bits,entropy".to_string()];
+        lines.extend(rows.iter().map(|row| {
+            let bits = match row.probability > 0.0 {
+                true => format!("{:.6}", -row.probability.log2()),
+                false => String::new(),
+            };
+            format!(
+                "{},{:.6},{},{},{:.6}",
+                pattern_to_string(row.pattern, n_letters).to_lowercase(),
+                row.probability,
+                row.remaining,
+                bits,
+                row.entropy
+            )
+        }));
+        lines.join("\n") + "\n"
+    }
+}
+
+impl Solver {
+    /// Every pattern a guess can get in the order of their encoding, with
+    /// the remaining words that give it, see [`PatternRow`]. Patterns that
+    /// no remaining word gives have a probability of 0.
+    pub fn pattern_table(&self, guess: &Word, remaining_words: &[usize]) -> Vec<PatternRow> {
+        let n_patterns = solved_pattern(self.n_letters) as usize + 1;
+        let mut priors: Vec<Vec<f64>> = vec![vec![]; n_patterns];
+        for &i in remaining_words {
+            let pattern = self.words[i].pattern(guess) as usize;
+            priors[pattern].push(self.priors[i] as f64);
+        }
+        let total: f64 = priors.iter().flatten().sum();
+        priors
+            .iter()
+            .enumerate()
+            .map(|(pattern, priors)| {
+                // Folds, as the sum of no floats is -0
+                let sum = priors.iter().fold(0.0, |sum, p| sum + p);
+                let entropy = priors
+                    .iter()
+                    .filter(|&&p| p > 0.0)
+                    .fold(0.0, |entropy, p| entropy - (p / sum) * (p / sum).log2());
+                PatternRow {
+                    pattern: pattern as Pattern,
+                    probability: if total > 0.0 { sum / total } else { 0.0 },
+                    remaining: priors.len(),
+                    entropy,
+                }
+            })
+            .collect()
+    }
+
+    /// The remaining words after the guesses in word list order, each with
+    /// the bits it gives as the next guess
+    pub fn remaining_words(&self, guesses: &[Guess]) -> Result<Vec<RemainingWord>, SolverError> {
+        let mut remaining = self.get_remaining_words_idx(guesses)?;
+        remaining.sort_unstable();
+        Ok(remaining
+            .par_iter()
+            .map(|&i| RemainingWord {
+                word: self.words[i],
+                prior: self.priors[i],
+                expected_bits: self.evaluate_id(i, &remaining, None, false).expected_bits,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::{solver_with, SMALL_WORDS};
+
+    #[test]
+    fn test_remaining_words() {
+        let solver = solver_with(&SMALL_WORDS, &[1.0, 1.0, 0.5, 1.0, 1.0, 0.0]);
+        let words = solver.words.clone();
+        // BILLS is not a possible answer
+        assert_eq!(solver.remaining_words(&[]).unwrap().len(), 5);
+        let bills = Guess {
+            word: words[5],
+            status: words[1].pattern(&words[5]),
+        };
+        let remaining = solver.remaining_words(&[bills]).unwrap();
+        let found: Vec<Word> = remaining.iter().map(|w| w.word).collect();
+        assert_eq!(found, &words[1..5]);
+        assert_eq!(remaining[1].prior, 0.5);
+        let ids = [1, 2, 3, 4];
+        let crane = solver.evaluate_guess(&words[1], &ids, None, false).unwrap();
+        assert_eq!(remaining[0].expected_bits, crane.expected_bits);
+
+        let crane = [RemainingWord {
+            word: words[1],
+            prior: 1.0,
+            expected_bits: 1.5,
+        }];
+        assert_eq!(
+            ExportFormat::Csv.format(&crane),
+            "word,prior,expected_bits\ncrane,1,1.5\n"
+        );
+        assert_eq!(
+            ExportFormat::Json.format(&crane),
+            "[{\"word\":\"crane\",\"prior\":1,\"expected_bits\":1.5}]\n"
+        );
+        assert_eq!(ExportFormat::Json.format(&[]), "[]\n");
+        assert_eq!(
+            ExportFormat::from_path(Path::new("words.JSON")),
+            ExportFormat::Json
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("words.csv")),
+            ExportFormat::Csv
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("words")),
+            ExportFormat::Csv
+        );
+    }
+
+    #[test]
+    fn test_pattern_table() {
+        let solver = solver_with(&SMALL_WORDS, &[1.0, 1.0, 1.0, 2.0, 1.0, 0.0]);
+        let words = solver.words.clone();
+        let remaining = solver.get_frequent_word_idx();
+        let rows = solver.pattern_table(&words[5], &remaining);
+        assert_eq!(rows.len(), 243);
+        assert!(rows
+            .iter()
+            .enumerate()
+            .all(|(i, row)| row.pattern as usize == i));
+        assert_eq!(rows.iter().map(|row| row.remaining).sum::<usize>(), 5);
+        let total: f64 = rows.iter().map(|row| row.probability).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+
+        // CRANE, CRATE, GRATE and PRONE share a pattern of BILLS, and
+        // GRATE is twice as likely as the others
+        let shared = rows[words[1].pattern(&words[5]) as usize];
+        assert_eq!(shared.remaining, 4);
+        assert!((shared.probability - 5.0 / 6.0).abs() < 1e-9);
+        let entropy = -(0.4 * 0.4f64.log2()) - 3.0 * 0.2 * 0.2f64.log2();
+        assert!((shared.entropy - entropy).abs() < 1e-9);
+        let slate = rows[words[0].pattern(&words[5]) as usize];
+        assert_eq!((slate.remaining, slate.entropy), (1, 0.0));
+
+        let csv = PatternRow::csv(&rows, 5);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 244);
+        assert_eq!(
+            lines[1],
+            format!("bbbbb,0.833333,4,0.263034,{:.6}", entropy)
+        );
+        // BILLS has no prior, so no answer solves it
+        assert_eq!(lines[243], "ggggg,0.000000,0,,0.000000");
+    }
+}
@@ -0,0 +1,157 @@
+use anyhow::{bail, Error};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::wordle::*;
+
+/// Letters the next guess has to test or avoid.
+///
+/// A filter is written as space separated parts: `+LETTERS` for letters the
+/// guess must contain, `-LETTERS` for letters it must not contain and `!`
+/// to not put a letter where it is already known to be correct.
+///
+/// # Example
+///
+/// ```
+/// use wordlebot::solver::filter::GuessFilter;
+/// use wordlebot::wordle::*;
+/// use wordlebot::wordle::LetterStatus::*;
+///
+/// let filter: GuessFilter = "+rd -s !".parse().unwrap();
+/// let guesses = [Guess::new("crane", [Absent, Absent, Absent, Absent, Correct])];
+/// assert!(filter.matches(&create_word_from_string("dirty"), &guesses));
+/// assert!(!filter.matches(&create_word_from_string("rides"), &guesses));
+/// assert!(!filter.matches(&create_word_from_string("drove"), &guesses));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GuessFilter {
+    /// Letters the guess must contain
+    pub include: Vec<char>,
+    /// Letters the guess must not contain
+    pub exclude: Vec<char>,
+    /// Do not reuse a known correct letter at its position
+    pub avoid_greens: bool,
+}
+
+impl GuessFilter {
+    /// Whether the filter keeps every guess
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty() && !self.avoid_greens
+    }
+
+    /// Whether the word passes the filter after the guesses
+    pub fn matches(&self, word: &Word, guesses: &[Guess]) -> bool {
+        self.predicate(guesses)(word)
+    }
+
+    /// The filter as a predicate, with the correct letters of the guesses
+    /// looked up once
+    pub fn predicate(&self, guesses: &[Guess]) -> impl Fn(&Word) -> bool + Sync + '_ {
+        let mut greens: Vec<(usize, char)> = vec![];
+        for guess in guesses.iter().filter(|_| self.avoid_greens) {
+            for (i, (status, letter)) in guess
+                .get_status()
+                .iter()
+                .zip(guess.word.chars())
+                .enumerate()
+            {
+                if let (LetterStatus::Correct, Some(c)) = (status, letter) {
+                    greens.push((i, *c));
+                }
+            }
+        }
+        move |word: &Word| {
+            let chars = word.chars();
+            self.include.iter().all(|c| chars.contains(&Some(*c)))
+                && !self.exclude.iter().any(|c| chars.contains(&Some(*c)))
+                && !greens.iter().any(|&(i, c)| chars.get(i) == Some(&Some(c)))
+        }
+    }
+}
+
+impl FromStr for GuessFilter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut filter = GuessFilter::default();
+        for part in s.split_whitespace() {
+            let (letters, target) = match part.chars().next() {
+                Some('!') if part == "!" => {
+                    filter.avoid_greens = true;
+                    continue;
+                }
+                Some('+') => (&part[1..], &mut filter.include),
+                Some('-') => (&part[1..], &mut filter.exclude),
+                _ => bail!("Expected +LETTERS, -LETTERS or !, got '{}'", part),
+            };
+            if letters.is_empty() {
+                bail!("No letters after '{}'", part)
+            }
+            for c in letters.chars() {
+                if !c.is_alphabetic() {
+                    bail!("Invalid letter '{}' in '{}'", c, part)
+                }
+                let c = c.to_lowercase().next().unwrap_or(c);
+                if !target.contains(&c) {
+                    target.push(c);
+                }
+            }
+        }
+        if let Some(c) = filter.include.iter().find(|c| filter.exclude.contains(c)) {
+            bail!("The letter '{}' is both included and excluded", c)
+        }
+        Ok(filter)
+    }
+}
+
+impl fmt::Display for GuessFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = vec![];
+        if !self.include.is_empty() {
+            parts.push(format!("+{}", self.include.iter().collect::<String>()));
+        }
+        if !self.exclude.is_empty() {
+            parts.push(format!("-{}", self.exclude.iter().collect::<String>()));
+        }
+        if self.avoid_greens {
+            parts.push("!".to_string());
+        }
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wordle::LetterStatus::*;
+
+    #[test]
+    fn test_parse() {
+        let filter: GuessFilter = " +RD  -st +r ! ".parse().unwrap();
+        assert_eq!(filter.include, vec!['r', 'd']);
+        assert_eq!(filter.exclude, vec!['s', 't']);
+        assert!(filter.avoid_greens);
+        assert_eq!(filter.to_string(), "+rd -st !");
+        assert_eq!(filter.to_string().parse::<GuessFilter>().unwrap(), filter);
+
+        assert!("".parse::<GuessFilter>().unwrap().is_empty());
+        assert!("rd".parse::<GuessFilter>().is_err());
+        assert!("+".parse::<GuessFilter>().is_err());
+        assert!("+r1".parse::<GuessFilter>().is_err());
+        assert!("+r -r".parse::<GuessFilter>().is_err());
+    }
+
+    #[test]
+    fn test_avoid_greens() {
+        let guesses = [Guess::new(
+            "slate",
+            [Correct, Absent, Absent, Absent, Absent],
+        )];
+        let filter: GuessFilter = "!".parse().unwrap();
+        assert!(!filter.matches(&create_word_from_string("sound"), &guesses));
+        // The letter may be tested elsewhere
+        assert!(filter.matches(&create_word_from_string("mossy"), &guesses));
+        // Without guesses nothing is known
+        assert!(filter.matches(&create_word_from_string("sound"), &[]));
+    }
+}
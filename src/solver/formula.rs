@@ -0,0 +1,437 @@
+use anyhow::{bail, Error, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// A custom formula to rank guesses. Higher values are better.
+///
+/// A formula is an arithmetic expression over the variables
+///
+/// - `entropy`: the expected information of the guess in bits
+/// - `two_level_bits`: the expected information of the guess and the best
+///   next guess in bits
+/// - `prior`: the prior of the word
+/// - `max_group`: the number of words in the largest group of patterns
+/// - `groups`: the number of different patterns
+/// - `is_possible`: 1 if the word is a possible solution, 0 otherwise
+/// - `penalty`: the prior penalty of the round
+/// - `remaining`: the number of remaining words
+///
+/// with numbers, `+ - * / ^`, parentheses and the functions `log2`, `ln`,
+/// `sqrt`, `abs`, `min` and `max`. The formula can only compute a number,
+/// nothing else.
+///
+/// # Example
+///
+/// ```
+/// use wordlebot::solver::formula::{Formula, Variables};
+/// let formula: Formula = "entropy + is_possible * prior / 20 * penalty".parse().unwrap();
+/// let vars = Variables {
+///     entropy: 5.0,
+///     prior: 1.0,
+///     is_possible: true,
+///     penalty: 2.0,
+///     ..Default::default()
+/// };
+/// assert_eq!(formula.eval(&vars), 5.1);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Formula {
+    text: String,
+    expr: Expr,
+}
+
+/// The values of the variables of a formula for one guess
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Variables {
+    pub entropy: f32,
+    pub two_level_bits: f32,
+    pub prior: f32,
+    pub max_group: usize,
+    pub groups: usize,
+    pub is_possible: bool,
+    pub penalty: f32,
+    pub remaining: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Var {
+    Entropy,
+    TwoLevelBits,
+    Prior,
+    MaxGroup,
+    Groups,
+    IsPossible,
+    Penalty,
+    Remaining,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Func {
+    Log2,
+    Ln,
+    Sqrt,
+    Abs,
+    Min,
+    Max,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Expr {
+    Num(f32),
+    Var(Var),
+    Neg(Box<Expr>),
+    Bin(char, Box<Expr>, Box<Expr>),
+    Call(Func, Vec<Expr>),
+}
+
+// Limit the nesting and the length of a formula, so parsing and evaluating
+// it can not overflow the stack
+const MAX_DEPTH: usize = 64;
+const MAX_TOKENS: usize = 1000;
+
+impl Formula {
+    /// Evaluate the formula. A result that is not a number ranks below all
+    /// other guesses.
+    pub fn eval(&self, vars: &Variables) -> f32 {
+        let value = self.expr.eval(vars);
+        if value.is_nan() {
+            f32::NEG_INFINITY
+        } else {
+            value
+        }
+    }
+
+    /// Whether the formula needs the two level bits, which are expensive to
+    /// compute
+    pub fn uses_two_level_bits(&self) -> bool {
+        self.expr.uses(Var::TwoLevelBits)
+    }
+}
+
+impl Expr {
+    fn eval(&self, vars: &Variables) -> f32 {
+        match self {
+            Expr::Num(x) => *x,
+            Expr::Var(var) => match var {
+                Var::Entropy => vars.entropy,
+                Var::TwoLevelBits => vars.two_level_bits,
+                Var::Prior => vars.prior,
+                Var::MaxGroup => vars.max_group as f32,
+                Var::Groups => vars.groups as f32,
+                Var::IsPossible => vars.is_possible as u8 as f32,
+                Var::Penalty => vars.penalty,
+                Var::Remaining => vars.remaining as f32,
+            },
+            Expr::Neg(x) => -x.eval(vars),
+            Expr::Bin(op, a, b) => {
+                let (a, b) = (a.eval(vars), b.eval(vars));
+                match op {
+                    '+' => a + b,
+                    '-' => a - b,
+                    '*' => a * b,
+                    '/' => a / b,
+                    _ => a.powf(b),
+                }
+            }
+            Expr::Call(func, args) => {
+                let args: Vec<f32> = args.iter().map(|x| x.eval(vars)).collect();
+                match func {
+                    Func::Log2 => args[0].log2(),
+                    Func::Ln => args[0].ln(),
+                    Func::Sqrt => args[0].sqrt(),
+                    Func::Abs => args[0].abs(),
+                    Func::Min => args[0].min(args[1]),
+                    Func::Max => args[0].max(args[1]),
+                }
+            }
+        }
+    }
+
+    fn uses(&self, var: Var) -> bool {
+        match self {
+            Expr::Num(_) => false,
+            Expr::Var(x) => *x == var,
+            Expr::Neg(x) => x.uses(var),
+            Expr::Bin(_, a, b) => a.uses(var) || b.uses(var),
+            Expr::Call(_, args) => args.iter().any(|x| x.uses(var)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Num(f32),
+    Name(String),
+    Op(char),
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut number = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit() || **c == '.') {
+                number.push(c);
+                chars.next();
+            }
+            match number.parse() {
+                Ok(x) => tokens.push(Token::Num(x)),
+                Err(_) => bail!("Invalid number '{}'", number),
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut name = String::new();
+            while let Some(&c) = chars
+                .peek()
+                .filter(|c| c.is_ascii_alphanumeric() || **c == '_')
+            {
+                name.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Name(name));
+        } else if "+-*/^(),".contains(c) {
+            tokens.push(Token::Op(c));
+            chars.next();
+        } else {
+            bail!("Unexpected character '{}'", c)
+        }
+    }
+    Ok(tokens)
+}
+
+/// A recursive descent parser with the usual precedence: `+ -` before
+/// `* /` before unary minus before `^`, which is right associative.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, op: char) -> Result<()> {
+        match self.next() {
+            Some(Token::Op(c)) if c == op => Ok(()),
+            _ => bail!("Expected '{}'", op),
+        }
+    }
+
+    fn descend(&mut self) -> Result<()> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            bail!("Formula is nested too deeply")
+        }
+        Ok(())
+    }
+
+    fn sum(&mut self) -> Result<Expr> {
+        self.descend()?;
+        let mut expr = self.product()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek().cloned() {
+            self.next();
+            expr = Expr::Bin(op, Box::new(expr), Box::new(self.product()?));
+        }
+        self.depth -= 1;
+        Ok(expr)
+    }
+
+    fn product(&mut self) -> Result<Expr> {
+        let mut expr = self.unary()?;
+        while let Some(Token::Op(op @ ('*' | '/'))) = self.peek().cloned() {
+            self.next();
+            expr = Expr::Bin(op, Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.peek() == Some(&Token::Op('-')) {
+            self.next();
+            self.descend()?;
+            let expr = Expr::Neg(Box::new(self.unary()?));
+            self.depth -= 1;
+            return Ok(expr);
+        }
+        self.power()
+    }
+
+    fn power(&mut self) -> Result<Expr> {
+        let base = self.atom()?;
+        if self.peek() == Some(&Token::Op('^')) {
+            self.next();
+            self.descend()?;
+            let expr = Expr::Bin('^', Box::new(base), Box::new(self.unary()?));
+            self.depth -= 1;
+            return Ok(expr);
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<Expr> {
+        match self.next() {
+            Some(Token::Num(x)) => Ok(Expr::Num(x)),
+            Some(Token::Op('(')) => {
+                let expr = self.sum()?;
+                self.expect(')')?;
+                Ok(expr)
+            }
+            Some(Token::Name(name)) => {
+                if let Some(var) = variable(&name) {
+                    return Ok(Expr::Var(var));
+                }
+                let Some((func, n_args)) = function(&name) else {
+                    bail!("Unknown variable or function '{}'", name)
+                };
+                self.expect('(')?;
+                let mut args = vec![self.sum()?];
+                while self.peek() == Some(&Token::Op(',')) {
+                    self.next();
+                    args.push(self.sum()?);
+                }
+                self.expect(')')?;
+                if args.len() != n_args {
+                    bail!("{} expects {} argument(s)", name, n_args)
+                }
+                Ok(Expr::Call(func, args))
+            }
+            Some(Token::Op(c)) => bail!("Unexpected '{}'", c),
+            None => bail!("Unexpected end of formula"),
+        }
+    }
+}
+
+fn variable(name: &str) -> Option<Var> {
+    Some(match name {
+        "entropy" => Var::Entropy,
+        "two_level_bits" => Var::TwoLevelBits,
+        "prior" => Var::Prior,
+        "max_group" => Var::MaxGroup,
+        "groups" => Var::Groups,
+        "is_possible" => Var::IsPossible,
+        "penalty" => Var::Penalty,
+        "remaining" => Var::Remaining,
+        _ => return None,
+    })
+}
+
+fn function(name: &str) -> Option<(Func, usize)> {
+    Some(match name {
+        "log2" => (Func::Log2, 1),
+        "ln" => (Func::Ln, 1),
+        "sqrt" => (Func::Sqrt, 1),
+        "abs" => (Func::Abs, 1),
+        "min" => (Func::Min, 2),
+        "max" => (Func::Max, 2),
+        _ => return None,
+    })
+}
+
+impl FromStr for Formula {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(s)?;
+        if tokens.len() > MAX_TOKENS {
+            bail!("Formula is longer than {} tokens", MAX_TOKENS)
+        }
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            depth: 0,
+        };
+        let expr = parser.sum()?;
+        if let Some(token) = parser.peek() {
+            bail!("Unexpected {:?} after the end of the formula", token)
+        }
+        Ok(Formula {
+            text: s.trim().to_string(),
+            expr,
+        })
+    }
+}
+
+impl fmt::Display for Formula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::*;
+
+    fn eval(formula: &str, vars: &Variables) -> f32 {
+        formula.parse::<Formula>().unwrap().eval(vars)
+    }
+
+    #[test]
+    fn test_eval() {
+        let vars = Variables {
+            entropy: 5.5,
+            two_level_bits: 9.0,
+            prior: 0.5,
+            max_group: 300,
+            groups: 150,
+            is_possible: false,
+            penalty: 0.1,
+            remaining: 3189,
+        };
+        assert_eq!(eval("1 + 2 * 3", &vars), 7.0);
+        assert_eq!(eval("(1 + 2) * 3", &vars), 9.0);
+        assert_eq!(eval("2 ^ 3 ^ 2", &vars), 512.0);
+        assert_eq!(eval("-2 ^ 2", &vars), -4.0);
+        assert_eq!(eval("10 - 4 - 3", &vars), 3.0);
+        assert_eq!(eval("entropy - max_group / groups", &vars), 3.5);
+        assert_eq!(eval("is_possible + two_level_bits", &vars), 9.0);
+        assert_eq!(eval("max(prior, penalty) + min(1, 2)", &vars), 1.5);
+        assert_relative_eq!(
+            eval("log2(remaining) - entropy", &vars),
+            3189f32.log2() - 5.5
+        );
+        assert_eq!(eval("sqrt(abs(-16)) + ln(1)", &vars), 4.0);
+
+        // Invalid results rank last
+        assert_eq!(eval("sqrt(-1)", &vars), f32::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("".parse::<Formula>().is_err());
+        assert!("entropy +".parse::<Formula>().is_err());
+        assert!("(entropy".parse::<Formula>().is_err());
+        assert!("entropy)".parse::<Formula>().is_err());
+        assert!("bits".parse::<Formula>().is_err());
+        assert!("max(1)".parse::<Formula>().is_err());
+        assert!("1..2".parse::<Formula>().is_err());
+        assert!("entropy; rm".parse::<Formula>().is_err());
+        assert!(format!("{}1{}", "(".repeat(100), ")".repeat(100))
+            .parse::<Formula>()
+            .is_err());
+        assert!(format!("{}1", "-".repeat(100_000))
+            .parse::<Formula>()
+            .is_err());
+        assert!("2^".repeat(100_000).parse::<Formula>().is_err());
+        assert!("1+".repeat(100_000).parse::<Formula>().is_err());
+    }
+
+    #[test]
+    fn test_uses_two_level_bits() {
+        let formula: Formula = "entropy + prior".parse().unwrap();
+        assert!(!formula.uses_two_level_bits());
+        let formula: Formula = "max(entropy, two_level_bits / 2)".parse().unwrap();
+        assert!(formula.uses_two_level_bits());
+        assert_eq!(formula.to_string(), "max(entropy, two_level_bits / 2)");
+    }
+}
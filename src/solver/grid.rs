@@ -0,0 +1,287 @@
+//! What the emoji grid of a shared result gives away without its words: the
+//! answers and guesses that could have produced its patterns.
+//!
+//! For every possible answer the guesses of a row are the words that give
+//! the pattern of the row for it. Without hard mode the rows only depend on
+//! the answer, so the combinations are the product of the guesses per row.
+//! In hard mode every guess must also follow the hints of the guesses
+//! before it, which needs a search over the sequences of guesses.
+use crate::solver::parallel::*;
+use crate::solver::Solver;
+use crate::wordle::*;
+
+/// The guesses tried in the hard mode search, split evenly between the
+/// answers that fit the rows, but at least [`MIN_HARD_MODE_BUDGET`] and at
+/// most [`MAX_HARD_MODE_BUDGET`] per answer. Answers with more combinations
+/// get a lower bound, see [`GridAnswer::truncated`]
+const HARD_MODE_BUDGET: usize = 100_000_000;
+const MIN_HARD_MODE_BUDGET: usize = 2_000;
+const MAX_HARD_MODE_BUDGET: usize = 200_000;
+
+/// An answer that fits the grid
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridAnswer {
+    pub answer: Word,
+    /// The number of words that give the pattern of each row for the
+    /// answer, without the solved row
+    pub guesses_per_row: Vec<usize>,
+    /// The number of sequences of different guesses that give the rows
+    pub combinations: u128,
+    /// Whether the hard mode search stopped early, so that `combinations`
+    /// is a lower bound
+    pub truncated: bool,
+    /// How likely the answer is given the grid, see [`Solver::analyze_grid`]
+    pub probability: f64,
+}
+
+/// The answers that fit a grid, see [`Solver::analyze_grid`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridAnalysis {
+    /// Whether the last row solved the game
+    pub solved: bool,
+    /// The answers with at least one combination, the most likely first
+    pub answers: Vec<GridAnswer>,
+}
+
+impl GridAnalysis {
+    /// The number of combinations of guesses and answer that give the grid
+    pub fn combinations(&self) -> u128 {
+        self.answers
+            .iter()
+            .fold(0, |total: u128, a| total.saturating_add(a.combinations))
+    }
+}
+
+impl Solver {
+    /// The answers that fit the patterns of a grid, with the number of
+    /// guess sequences that give it for each. A solved last row is the
+    /// answer itself, the other rows are different guesses. With `hard`
+    /// every guess follows the hard mode rules for the guesses before it.
+    ///
+    /// The likelihood of an answer assumes that any word is as likely to be
+    /// guessed as any other: it is proportional to the prior of the answer
+    /// times its number of combinations.
+    pub fn analyze_grid(&self, rows: &[Pattern], hard: bool) -> GridAnalysis {
+        let solved_pattern = solved_pattern(self.n_letters);
+        let solved = rows.last() == Some(&solved_pattern);
+        let rows = match solved {
+            true => &rows[..rows.len() - 1],
+            false => rows,
+        };
+        let answers = self.get_frequent_word_idx();
+
+        // The guesses per distinct pattern and answer, in one pass over the
+        // rows of the mappings
+        let mut patterns = rows.to_vec();
+        patterns.sort_unstable();
+        patterns.dedup();
+        let mut counts = vec![vec![0; answers.len()]; patterns.len()];
+        for guess in 0..self.words.len() {
+            self.mappings
+                .row(guess)
+                .for_each(&answers, |position, pattern| {
+                    if let Ok(k) = patterns.binary_search(&pattern) {
+                        counts[k][position] += 1;
+                    }
+                });
+        }
+
+        let candidates: Vec<(usize, Vec<usize>)> = answers
+            .iter()
+            .enumerate()
+            .map(|(position, &answer)| {
+                let guesses_per_row = rows
+                    .iter()
+                    .map(|pattern| {
+                        let k = patterns.binary_search(pattern).expect("pattern of a row");
+                        counts[k][position]
+                    })
+                    .collect();
+                (answer, guesses_per_row)
+            })
+            .filter(|(_, guesses_per_row): &(usize, Vec<usize>)| !guesses_per_row.contains(&0))
+            .collect();
+        let budget = (HARD_MODE_BUDGET / candidates.len().max(1))
+            .clamp(MIN_HARD_MODE_BUDGET, MAX_HARD_MODE_BUDGET);
+
+        let mut fitting: Vec<GridAnswer> = candidates
+            .into_par_iter()
+            .filter_map(|(answer, guesses_per_row)| {
+                let (combinations, truncated) = match hard {
+                    true => self.count_hard_mode_guesses(answer, rows, budget),
+                    false => (distinct_combinations(rows, &guesses_per_row), false),
+                };
+                (combinations > 0).then(|| GridAnswer {
+                    answer: self.words[answer],
+                    guesses_per_row,
+                    combinations,
+                    truncated,
+                    probability: self.priors[answer] as f64 * combinations as f64,
+                })
+            })
+            .collect();
+
+        let total = fitting.iter().fold(0.0, |total, a| total + a.probability);
+        for answer in &mut fitting {
+            answer.probability = match total > 0.0 {
+                true => answer.probability / total,
+                false => 0.0,
+            };
+        }
+        fitting.sort_by(|a, b| {
+            b.probability
+                .total_cmp(&a.probability)
+                .then(b.combinations.cmp(&a.combinations))
+        });
+        GridAnalysis {
+            solved,
+            answers: fitting,
+        }
+    }
+
+    /// The number of sequences of different guesses that give the rows for
+    /// the answer by the hard mode rules, and whether the search stopped
+    /// after `budget` guesses
+    fn count_hard_mode_guesses(
+        &self,
+        answer: usize,
+        rows: &[Pattern],
+        mut budget: usize,
+    ) -> (u128, bool) {
+        let candidates: Vec<Vec<Word>> = rows
+            .iter()
+            .map(|&pattern| {
+                (0..self.words.len())
+                    .filter(|&guess| self.mappings.get(guess, answer) == pattern)
+                    .map(|guess| self.words[guess])
+                    .collect()
+            })
+            .collect();
+        let count = count_sequences(&candidates, rows, &mut vec![], &mut budget);
+        (count, budget == 0)
+    }
+}
+
+/// The sequences of guesses from the candidates of each row that follow
+/// the hard mode rules for the played guesses
+fn count_sequences(
+    candidates: &[Vec<Word>],
+    rows: &[Pattern],
+    played: &mut Vec<Guess>,
+    budget: &mut usize,
+) -> u128 {
+    let depth = played.len();
+    if depth == rows.len() {
+        return 1;
+    }
+    let mut count: u128 = 0;
+    for &word in &candidates[depth] {
+        if *budget == 0 {
+            break;
+        }
+        *budget -= 1;
+        let allowed = played
+            .iter()
+            .all(|guess| guess.word != word && word.hard_mode_violation(guess).is_none());
+        if allowed {
+            played.push(Guess {
+                word,
+                status: rows[depth],
+            });
+            count = count.saturating_add(count_sequences(candidates, rows, played, budget));
+            played.pop();
+        }
+    }
+    count
+}
+
+/// The sequences of different guesses for the rows, given the number of
+/// guesses for each: rows with the same pattern pick from the same words
+fn distinct_combinations(rows: &[Pattern], guesses_per_row: &[usize]) -> u128 {
+    let mut combinations: u128 = 1;
+    for (i, (pattern, &n)) in rows.iter().zip(guesses_per_row).enumerate() {
+        let used = rows[..i].iter().filter(|&p| p == pattern).count();
+        combinations = combinations.saturating_mul(n.saturating_sub(used) as u128);
+    }
+    combinations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::{solver_with, SMALL_WORDS};
+
+    /// The small solver, and TRACE as an allowed guess
+    fn solver() -> Solver {
+        let words = [&SMALL_WORDS[..], &["trace"]].concat();
+        solver_with(&words, &[1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 0.0])
+    }
+
+    #[test]
+    fn test_analyze_grid() {
+        let solver = solver();
+        let crate_ = create_word_from_string("crate");
+        let crane = create_word_from_string("crane");
+        let rows = [crate_.pattern(&crane), solved_pattern(5)];
+        let analysis = solver.analyze_grid(&rows, false);
+        assert!(analysis.solved);
+
+        // Brute force: the guesses that give the first row for each answer
+        let words = solver.get_words_from_idx(&(0..7).collect::<Vec<_>>());
+        for answer in &analysis.answers {
+            let n = words
+                .iter()
+                .filter(|w| answer.answer.pattern(w) == rows[0])
+                .count();
+            assert_eq!(answer.guesses_per_row, [n]);
+            assert_eq!(answer.combinations, n as u128);
+        }
+        assert!(analysis.answers.iter().any(|a| a.answer == crate_));
+        // TRACE is only a guess
+        let trace = create_word_from_string("trace");
+        assert!(analysis.answers.iter().all(|a| a.answer != trace));
+        let total: f64 = analysis.answers.iter().map(|a| a.probability).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert!(analysis
+            .answers
+            .windows(2)
+            .all(|w| w[0].probability >= w[1].probability));
+        assert_eq!(
+            analysis.combinations(),
+            analysis
+                .answers
+                .iter()
+                .map(|a| a.combinations)
+                .sum::<u128>()
+        );
+
+        // The solved row is the answer itself and rules out no answer
+        let unsolved = solver.analyze_grid(&rows[..1], false);
+        assert!(!unsolved.solved);
+        assert_eq!(unsolved.answers, analysis.answers);
+    }
+
+    #[test]
+    fn test_repeated_rows_and_hard_mode() {
+        // The same pattern twice needs two different guesses
+        assert_eq!(distinct_combinations(&[3, 3, 5], &[4, 4, 2]), 4 * 3 * 2);
+        assert_eq!(distinct_combinations(&[3, 3], &[1, 1]), 0);
+
+        let solver = solver();
+        let crate_ = create_word_from_string("crate");
+        let rows = [
+            crate_.pattern(&create_word_from_string("prone")),
+            crate_.pattern(&create_word_from_string("crane")),
+            solved_pattern(5),
+        ];
+        let easy = solver.analyze_grid(&rows, false);
+        let hard = solver.analyze_grid(&rows, true);
+        // CRANE keeps the R and the E of PRONE
+        assert!(hard.answers.iter().any(|a| a.answer == crate_));
+        for answer in &hard.answers {
+            assert!(!answer.truncated);
+            let easy = easy.answers.iter().find(|a| a.answer == answer.answer);
+            assert!(answer.combinations <= easy.unwrap().combinations);
+        }
+    }
+}
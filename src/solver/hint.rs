@@ -0,0 +1,164 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::iter::zip;
+
+use crate::solver::{Solver, SolverError};
+use crate::wordle::*;
+
+const VOWELS: [char; 5] = ['a', 'e', 'i', 'o', 'u'];
+
+/// A hint about a known answer, see [`Solver::hints`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Hint {
+    /// The letter at a position that no guess found yet, counted from 0
+    Letter { position: usize, letter: char },
+    /// A letter of the answer that no guess revealed yet
+    Contains(char),
+    /// The number of vowels of the answer
+    Vowels(usize),
+    /// The answer itself
+    Answer(Word),
+}
+
+impl Hint {
+    /// Whether a word agrees with the hint
+    pub fn fits(&self, word: &Word) -> bool {
+        match *self {
+            Hint::Letter { position, letter } => word.chars()[position] == Some(letter),
+            Hint::Contains(letter) => word.count_char(&letter) > 0,
+            Hint::Vowels(n) => count_vowels(word) == n,
+            Hint::Answer(answer) => *word == answer,
+        }
+    }
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Hint::Letter {
+                position: 0,
+                letter,
+            } => write!(f, "The answer starts with {}", letter.to_uppercase()),
+            Hint::Letter { position, letter } => write!(
+                f,
+                "Letter {} of the answer is {}",
+                position + 1,
+                letter.to_uppercase()
+            ),
+            Hint::Contains(letter) => write!(f, "The answer contains {}", letter.to_uppercase()),
+            Hint::Vowels(1) => write!(f, "The answer has 1 vowel"),
+            Hint::Vowels(n) => write!(f, "The answer has {} vowels", n),
+            Hint::Answer(answer) => write!(f, "The answer is {}", answer),
+        }
+    }
+}
+
+fn count_vowels(word: &Word) -> usize {
+    VOWELS.iter().map(|vowel| word.count_char(vowel)).sum()
+}
+
+impl Solver {
+    /// Hints about the answer after the guesses, from the weakest to the
+    /// strongest: a letter at the first position that is not found yet, a
+    /// letter it contains, the number of vowels and finally the answer.
+    /// Hints that the guesses already gave are left out.
+    ///
+    /// The contained letter is the unrevealed letter of the answer that
+    /// splits the remaining words most evenly, so that it helps without
+    /// giving the answer away.
+    pub fn hints(&self, answer: &Word, guesses: &[Guess]) -> Result<Vec<Hint>, SolverError> {
+        let n_letters = answer.n_letters();
+        let mut found = vec![false; n_letters];
+        let mut revealed = BTreeSet::new();
+        for guess in guesses {
+            for (i, (status, letter)) in zip(guess.get_status(), guess.word.chars()).enumerate() {
+                match status {
+                    LetterStatus::Correct => found[i] = true,
+                    LetterStatus::Misplaced => {}
+                    LetterStatus::Absent => continue,
+                }
+                revealed.extend(*letter);
+            }
+        }
+
+        let mut hints = vec![];
+        let letter = (0..n_letters).find(|&i| !found[i]).and_then(|position| {
+            let letter = answer.chars()[position]?;
+            Some(Hint::Letter { position, letter })
+        });
+        hints.extend(letter);
+
+        let remaining = self.get_words_from_idx(&self.get_remaining_words_idx(guesses)?);
+        let letters: BTreeSet<char> = answer
+            .chars()
+            .iter()
+            .flatten()
+            .filter(|c| !revealed.contains(*c))
+            .filter(|&&c| !matches!(letter, Some(Hint::Letter { letter, .. }) if letter == c))
+            .copied()
+            .collect();
+        // The letter that about half of the remaining words contain
+        let contains = letters.into_iter().min_by_key(|&c| {
+            let n = remaining.iter().filter(|w| w.count_char(&c) > 0).count();
+            (2 * n).abs_diff(remaining.len())
+        });
+        hints.extend(contains.map(Hint::Contains));
+
+        hints.push(Hint::Vowels(count_vowels(answer)));
+        hints.push(Hint::Answer(*answer));
+        Ok(hints)
+    }
+
+    /// The number of remaining words after the guesses that agree with all
+    /// hints
+    pub fn count_fitting(&self, guesses: &[Guess], hints: &[Hint]) -> Result<usize, SolverError> {
+        Ok(self
+            .get_words_from_idx(&self.get_remaining_words_idx(guesses)?)
+            .iter()
+            .filter(|word| hints.iter().all(|hint| hint.fits(word)))
+            .count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::{solver_for, SMALL_WORDS};
+
+    #[test]
+    fn test_hints() {
+        let solver = solver_for(&SMALL_WORDS);
+        let words = solver.words.clone();
+        let crate_ = create_word_from_string("crate");
+
+        let hints = solver.hints(&crate_, &[]).unwrap();
+        assert_eq!(
+            hints[0],
+            Hint::Letter {
+                position: 0,
+                letter: 'c'
+            }
+        );
+        assert_eq!(hints[0].to_string(), "The answer starts with C");
+        assert!(matches!(hints[1], Hint::Contains(c) if c != 'c'));
+        assert_eq!(&hints[2..], [Hint::Vowels(2), Hint::Answer(crate_)]);
+        assert_eq!(solver.count_fitting(&[], &hints[..1]), Ok(2));
+        assert_eq!(solver.count_fitting(&[], &hints), Ok(1));
+
+        // CRANE finds C, R, A and E, so only T is left to hint at
+        let crane = Guess {
+            word: words[1],
+            status: crate_.pattern(&words[1]),
+        };
+        let hints = solver.hints(&crate_, &[crane]).unwrap();
+        assert_eq!(
+            hints[0],
+            Hint::Letter {
+                position: 3,
+                letter: 't'
+            }
+        );
+        assert_eq!(hints[0].to_string(), "Letter 4 of the answer is T");
+        assert_eq!(hints[1], Hint::Vowels(2));
+    }
+}
@@ -0,0 +1,167 @@
+use anyhow::{bail, Result};
+
+use crate::solver::Solver;
+use crate::wordle::*;
+
+/// One move of a line of play, see [`Solver::evaluate_line`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoveEvaluation {
+    /// The guess with the pattern it got
+    pub guess: Guess,
+    pub n_remaining_before: usize,
+    pub n_remaining_after: usize,
+    /// The entropy of the patterns of the guess over the remaining words
+    pub expected_bits: f32,
+    /// The bits the pattern actually gave
+    pub real_bits: f32,
+    /// The rank of the guess among all allowed guesses, 1 for the guess of
+    /// the solver
+    pub rank: usize,
+    /// The guess of the solver at this move
+    pub best: Word,
+    pub best_bits: f32,
+}
+
+/// How a line of play ended
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineOutcome {
+    /// The answer was found with this many guesses
+    Solved { guesses: usize },
+    /// The line ended before the answer was found
+    Unsolved { n_remaining: usize },
+}
+
+/// The evaluation of a line of play against an answer
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineEvaluation {
+    pub answer: Word,
+    pub moves: Vec<MoveEvaluation>,
+    pub outcome: LineOutcome,
+}
+
+impl Solver {
+    /// Play the guesses of a line against the answer and compare every move
+    /// with the guess of the solver. The guesses are ranked like
+    /// [`Solver::guess`], with the penalty for each round, starting at 1.
+    ///
+    /// Fails if a guess is not in the word list, the answer is not a
+    /// possible answer or the line goes on after the answer is found.
+    pub fn evaluate_line(
+        &self,
+        answer: &Word,
+        line: &[Word],
+        penalty: impl Fn(usize) -> f32,
+    ) -> Result<LineEvaluation> {
+        let answers = self.get_words_from_idx(&self.get_frequent_word_idx());
+        if !answers.contains(answer) {
+            bail!("{} is not a possible answer", answer)
+        }
+        if let Some(word) = line.iter().find(|w| !self.is_valid_guess(w)) {
+            bail!("{} is not in the word list", word)
+        }
+
+        let mut guesses: Vec<Guess> = vec![];
+        let mut moves = vec![];
+        for (i, &word) in line.iter().enumerate() {
+            if guesses.last().is_some_and(|g: &Guess| g.word == *answer) {
+                bail!("The line goes on after {} is found", answer)
+            }
+            let mut remaining = self.get_remaining_words_idx(&guesses)?;
+            remaining.sort_unstable();
+            let guess = Guess {
+                word,
+                status: self.pattern_of(answer, &word),
+            };
+            let evaluation =
+                self.evaluate_guess(&word, &remaining, Some(guess.get_status()), false)?;
+
+            let mut ranked = self.ranked_guesses(&remaining, penalty(i + 1), |_| true);
+            // The last remaining word is always the guess of the solver, see
+            // `guess_where`
+            if let &[last] = remaining.as_slice() {
+                ranked.retain(|&id| id != last);
+                ranked.insert(0, last);
+            }
+            let word_id = self.get_id_for_word(&word).unwrap();
+            let best = self.words[ranked[0]];
+            moves.push(MoveEvaluation {
+                guess,
+                n_remaining_before: remaining.len(),
+                n_remaining_after: evaluation.n_remaining_after.unwrap_or(0),
+                expected_bits: evaluation.expected_bits,
+                real_bits: evaluation.real_bits.unwrap_or(0.0),
+                rank: ranked.iter().position(|&id| id == word_id).unwrap() + 1,
+                best,
+                best_bits: self
+                    .evaluate_id(ranked[0], &remaining, None, false)
+                    .expected_bits,
+            });
+            guesses.push(guess);
+        }
+
+        let outcome = match guesses.last() {
+            Some(guess) if guess.word == *answer => LineOutcome::Solved {
+                guesses: guesses.len(),
+            },
+            _ => LineOutcome::Unsolved {
+                n_remaining: self.get_remaining_words_idx(&guesses)?.len(),
+            },
+        };
+        Ok(LineEvaluation {
+            answer: *answer,
+            moves,
+            outcome,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<Word> {
+        words.iter().map(|w| create_word_from_string(w)).collect()
+    }
+
+    #[test]
+    fn test_evaluate_line() {
+        let list = words(&["bills", "fills", "hills", "slate", "crane", "crate"]);
+        let solver = Solver::from_words(&list, &[1.0; 6]).unwrap();
+        let answer = create_word_from_string("crate");
+
+        let line = words(&["slate", "crate"]);
+        let evaluation = solver.evaluate_line(&answer, &line, |_| 0.1).unwrap();
+        assert_eq!(evaluation.outcome, LineOutcome::Solved { guesses: 2 });
+        let first = &evaluation.moves[0];
+        assert_eq!((first.n_remaining_before, first.n_remaining_after), (6, 1));
+        assert_eq!(first.real_bits, 6f32.log2());
+        // BILLS splits the words better
+        assert!(first.rank > 1);
+        assert!(first.best_bits > first.expected_bits);
+        // Only CRATE is left for the last guess
+        let last = &evaluation.moves[1];
+        assert_eq!((last.rank, last.best), (1, answer));
+
+        let evaluation = solver.evaluate_line(&answer, &line[..1], |_| 0.1).unwrap();
+        assert_eq!(evaluation.outcome, LineOutcome::Unsolved { n_remaining: 1 });
+        assert_eq!(
+            solver.evaluate_line(&answer, &[], |_| 0.1).unwrap().outcome,
+            LineOutcome::Unsolved { n_remaining: 6 }
+        );
+    }
+
+    #[test]
+    fn test_invalid_line() {
+        let list = words(&["bills", "fills", "hills", "slate", "crane", "crate"]);
+        let solver = Solver::from_words(&list, &[1.0, 1.0, 1.0, 1.0, 1.0, 0.0]).unwrap();
+        let crane = create_word_from_string("crane");
+        let quirk = create_word_from_string("quirk");
+        assert!(solver.evaluate_line(&crane, &[quirk], |_| 0.1).is_err());
+        assert!(solver
+            .evaluate_line(&crane, &[crane, crane], |_| 0.1)
+            .is_err());
+        // CRATE is allowed as a guess, but is not a possible answer
+        let crate_ = create_word_from_string("crate");
+        assert!(solver.evaluate_line(&crate_, &[crane], |_| 0.1).is_err());
+    }
+}
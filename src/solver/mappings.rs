@@ -0,0 +1,638 @@
+use anyhow::{bail, Context, Result};
+use ndarray::{Array, Ix2, Zip};
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::solver::cache::{Cache, CacheStats};
+use crate::solver::packed::{pack_words, PackedWord};
+use crate::wordle::*;
+
+// Identifies the file format of the cache. Bump the version when the
+// format or the pattern encoding changes.
+const MAGIC: &[u8; 8] = b"WSMAP001";
+
+/// The patterns between all words. Row and column indices are the indices
+/// of the guess and the answer in the word list.
+///
+/// The matrix is by far the largest part of the solver, so patterns are
+/// stored in a byte as long as they fit, which is the case for words with
+/// up to five letters.
+pub enum Mappings {
+    Narrow(Array<u8, Ix2>),
+    Wide(Array<u16, Ix2>),
+    // Rows are computed when they are needed
+    Lazy(LazyMappings),
+}
+
+/// How the solver stores the patterns between all words
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MappingBackend {
+    /// All patterns in memory, read from the cache or computed at start
+    #[default]
+    Dense,
+    /// The patterns are computed when they are needed, only for the
+    /// remaining answers when a guess is ranked. The whole rows of the
+    /// `rows` guesses that most recently filtered the answers are kept.
+    /// Starts instantly and needs little memory, but ranking is slower.
+    Lazy { rows: usize },
+}
+
+/// The patterns of one guess against all answers
+#[derive(Clone)]
+pub enum Row<'a> {
+    Narrow(&'a [u8]),
+    Wide(&'a [u16]),
+    // A row of lazy mappings, shared with their cache
+    SharedNarrow(Arc<[u8]>),
+    SharedWide(Arc<[u16]>),
+    // A guess of lazy mappings whose row is not cached. Only the patterns
+    // of the answers that are asked for are computed.
+    Lazy(&'a LazyMappings, usize),
+}
+
+/// The words of lazy mappings and their most recently used rows
+pub struct LazyMappings {
+    words: Vec<Word>,
+    packed: Option<Vec<PackedWord>>,
+    narrow: bool,
+    scoring: ScoringRule,
+    // The rows by guess
+    cache: Cache<usize, Row<'static>>,
+}
+
+/// A value type of the pattern matrix
+trait Cell: Copy + Default + Send + Sync + Into<Pattern> {
+    fn from_pattern(pattern: Pattern) -> Self;
+}
+
+impl Cell for u8 {
+    fn from_pattern(pattern: Pattern) -> Self {
+        pattern as u8
+    }
+}
+
+impl Cell for u16 {
+    fn from_pattern(pattern: Pattern) -> Self {
+        pattern
+    }
+}
+
+/// A matrix with the value of every guess and answer index
+fn fill<T: Cell>(n: usize, value: impl Fn(usize, usize) -> T + Sync + Send) -> Array<T, Ix2> {
+    let mut mappings: Array<T, Ix2> = Array::default((n, n));
+    #[cfg(feature = "parallel")]
+    Zip::indexed(&mut mappings).par_for_each(|(i, j), val| *val = value(i, j));
+    #[cfg(not(feature = "parallel"))]
+    Zip::indexed(&mut mappings).for_each(|(i, j), val| *val = value(i, j));
+    mappings
+}
+
+/// The pattern of the answer `j` for the guess `i`, with the packed words
+/// if all words could be packed. The packed words only color by the rules
+/// of the New York Times.
+fn pattern(
+    words: &[Word],
+    packed: Option<&[PackedWord]>,
+    scoring: ScoringRule,
+    i: usize,
+    j: usize,
+) -> Pattern {
+    match (packed, scoring) {
+        (Some(packed), ScoringRule::NytStandard) => packed[j].pattern(&packed[i]),
+        _ => words[j].pattern_by(&words[i], scoring),
+    }
+}
+
+fn create<T: Cell>(words: &[Word], scoring: ScoringRule) -> Array<T, Ix2> {
+    let packed = pack_words(words);
+    fill(words.len(), |i, j| {
+        T::from_pattern(pattern(words, packed.as_deref(), scoring, i, j))
+    })
+}
+
+/// Like [`create`], but copies the patterns of words that were already in
+/// the old matrix. `previous[i]` is the old index of the word `i`.
+fn extend<T: Cell>(
+    old: &Array<T, Ix2>,
+    words: &[Word],
+    previous: &[Option<usize>],
+    scoring: ScoringRule,
+) -> Array<T, Ix2> {
+    let packed = pack_words(words);
+    fill(words.len(), |i, j| match (previous[i], previous[j]) {
+        (Some(guess), Some(answer)) => old[[guess, answer]],
+        _ => T::from_pattern(pattern(words, packed.as_deref(), scoring, i, j)),
+    })
+}
+
+impl LazyMappings {
+    fn new(words: &[Word], capacity: usize, scoring: ScoringRule) -> LazyMappings {
+        LazyMappings {
+            words: words.to_vec(),
+            packed: pack_words(words),
+            narrow: is_narrow(words),
+            scoring,
+            cache: Cache::new(capacity.max(1)),
+        }
+    }
+
+    fn get(&self, guess: usize, answer: usize) -> Pattern {
+        pattern(
+            &self.words,
+            self.packed.as_deref(),
+            self.scoring,
+            guess,
+            answer,
+        )
+    }
+
+    fn compute(&self, guess: usize) -> Row<'static> {
+        let patterns = (0..self.words.len()).map(|answer| self.get(guess, answer));
+        match self.narrow {
+            true => Row::SharedNarrow(patterns.map(u8::from_pattern).collect()),
+            false => Row::SharedWide(patterns.collect()),
+        }
+    }
+
+    /// The row of the guess if it is cached, or the guess to compute its
+    /// patterns on demand
+    fn row(&self, guess: usize) -> Row<'_> {
+        self.cache.get(&guess).unwrap_or(Row::Lazy(self, guess))
+    }
+
+    /// The whole row of a guess that was not cached, which is cached now
+    fn cached_row(&self, guess: usize) -> Row<'static> {
+        let row = self.compute(guess);
+        self.cache.insert(guess, row.clone());
+        row
+    }
+}
+
+/// Whether the patterns of the words fit into a byte
+fn is_narrow(words: &[Word]) -> bool {
+    let n_letters = words.first().map_or(NLETTER, Word::n_letters);
+    solved_pattern(n_letters) <= u8::MAX as Pattern
+}
+
+/// A fingerprint of the word list, to detect a cache that was written for
+/// other words. FNV-1a is used since, unlike the std hasher, it does not
+/// change between Rust versions.
+fn fingerprint(words: &[Word]) -> u64 {
+    fnv1a(words.iter().flat_map(|word| {
+        let chars = word.chars().iter().map(|c| c.unwrap_or('_'));
+        chars.chain(['\n']).map(|c| c as u64)
+    }))
+}
+
+/// The FNV-1a hash of the values
+pub(crate) fn fnv1a(values: impl IntoIterator<Item = u64>) -> u64 {
+    values.into_iter().fold(0xcbf29ce484222325, |hash, value| {
+        (hash ^ value).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// The default location of the cache, `$XDG_CACHE_HOME/wordle-solver/mappings.bin`
+/// or `~/.cache/wordle-solver/mappings.bin`
+pub fn cache_path() -> Option<PathBuf> {
+    let dir = match env::var_os("XDG_CACHE_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("HOME")?).join(".cache"),
+    };
+    Some(dir.join("wordle-solver").join("mappings.bin"))
+}
+
+impl Mappings {
+    /// Compute the patterns between all words, which must all have the same
+    /// length
+    pub fn new(words: &[Word]) -> Mappings {
+        Mappings::with_scoring(words, MappingBackend::Dense, ScoringRule::NytStandard)
+    }
+
+    /// Patterns for the words that are stored by the backend. Lazy
+    /// mappings compute nothing yet.
+    pub fn with_backend(words: &[Word], backend: MappingBackend, cache: Option<&Path>) -> Mappings {
+        match (backend, cache) {
+            (MappingBackend::Dense, Some(path)) => Mappings::cached(words, path),
+            _ => Mappings::with_scoring(words, backend, ScoringRule::NytStandard),
+        }
+    }
+
+    /// Patterns colored by a scoring rule that are stored by the backend.
+    /// They are never cached, the cache only holds the patterns of
+    /// [`ScoringRule::NytStandard`].
+    pub fn with_scoring(words: &[Word], backend: MappingBackend, scoring: ScoringRule) -> Mappings {
+        match backend {
+            MappingBackend::Lazy { rows } => {
+                Mappings::Lazy(LazyMappings::new(words, rows, scoring))
+            }
+            MappingBackend::Dense if is_narrow(words) => Mappings::Narrow(create(words, scoring)),
+            MappingBackend::Dense => Mappings::Wide(create(words, scoring)),
+        }
+    }
+
+    /// How the patterns are stored
+    pub fn backend(&self) -> MappingBackend {
+        match self {
+            Mappings::Lazy(lazy) => MappingBackend::Lazy {
+                rows: lazy.cache.capacity(),
+            },
+            _ => MappingBackend::Dense,
+        }
+    }
+
+    /// How the rows of lazy mappings were cached
+    pub fn stats(&self) -> Option<CacheStats> {
+        match self {
+            Mappings::Lazy(lazy) => Some(lazy.cache.stats()),
+            _ => None,
+        }
+    }
+
+    /// The patterns for a changed word list of the same word length. Only
+    /// the rows and columns of new words are computed, the others are copied
+    /// from these mappings. `previous[i]` is the index of the word `i` in the
+    /// old word list, if it was in it. New patterns are colored by
+    /// `scoring`, which must be the rule of these mappings.
+    pub fn update(
+        &self,
+        words: &[Word],
+        previous: &[Option<usize>],
+        scoring: ScoringRule,
+    ) -> Mappings {
+        match self {
+            Mappings::Narrow(m) => Mappings::Narrow(extend(m, words, previous, scoring)),
+            Mappings::Wide(m) => Mappings::Wide(extend(m, words, previous, scoring)),
+            Mappings::Lazy(lazy) => {
+                Mappings::Lazy(LazyMappings::new(words, lazy.cache.capacity(), scoring))
+            }
+        }
+    }
+
+    /// Load the patterns from the cache file if it was written for the same
+    /// words. Otherwise compute them and replace the cache. A broken cache
+    /// only costs the time to compute the patterns, it is never an error.
+    pub fn cached(words: &[Word], path: &Path) -> Mappings {
+        if let Ok(mappings) = Mappings::read(words, path) {
+            return mappings;
+        }
+        let mappings = Mappings::new(words);
+        mappings.write(words, path).ok();
+        mappings
+    }
+
+    fn read(words: &[Word], path: &Path) -> Result<Mappings> {
+        let file = BufReader::new(File::open(path)?);
+        Mappings::read_from(words, file).with_context(|| format!("Cache {}", path.display()))
+    }
+
+    /// Read the patterns in the format of the cache, e.g. from a
+    /// precomputed asset. The patterns must have been written for the same
+    /// words.
+    pub fn read_from<R: Read>(words: &[Word], mut file: R) -> Result<Mappings> {
+        let mut magic = [0; 8];
+        let mut width = [0; 1];
+        let mut n_words = [0; 8];
+        let mut hash = [0; 8];
+        file.read_exact(&mut magic)?;
+        file.read_exact(&mut width)?;
+        file.read_exact(&mut n_words)?;
+        file.read_exact(&mut hash)?;
+
+        let n = words.len();
+        let expected_width = if is_narrow(words) { 1 } else { 2 };
+        if &magic != MAGIC
+            || width[0] != expected_width
+            || u64::from_le_bytes(n_words) != n as u64
+            || u64::from_le_bytes(hash) != fingerprint(words)
+        {
+            bail!("The patterns are outdated")
+        }
+
+        let mut data = vec![0; n * n * expected_width as usize];
+        file.read_exact(&mut data)?;
+        if file.read(&mut [0])? != 0 {
+            bail!("The patterns are too long")
+        }
+        Ok(match expected_width {
+            1 => Mappings::Narrow(Array::from_shape_vec((n, n), data)?),
+            _ => {
+                let data = data
+                    .chunks_exact(2)
+                    .map(|x| u16::from_le_bytes([x[0], x[1]]))
+                    .collect();
+                Mappings::Wide(Array::from_shape_vec((n, n), data)?)
+            }
+        })
+    }
+
+    /// Write the patterns to the cache. The file is written under a
+    /// temporary name first, so other processes never read half a cache.
+    fn write(&self, words: &[Word], path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Error creating {}", dir.display()))?;
+        }
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+        let mut file = BufWriter::new(File::create(&tmp)?);
+        self.write_to(words, &mut file)?;
+        file.flush()?;
+        drop(file);
+        fs::rename(&tmp, path).with_context(|| format!("Error writing {}", path.display()))
+    }
+
+    /// Write the patterns in the format of the cache
+    pub fn write_to<W: Write>(&self, words: &[Word], mut file: W) -> Result<()> {
+        file.write_all(MAGIC)?;
+        match is_narrow(words) {
+            true => file.write_all(&[1])?,
+            false => file.write_all(&[2])?,
+        }
+        file.write_all(&(words.len() as u64).to_le_bytes())?;
+        file.write_all(&fingerprint(words).to_le_bytes())?;
+        match self {
+            Mappings::Narrow(m) => {
+                file.write_all(m.as_slice().expect("mappings are contiguous"))?
+            }
+            Mappings::Wide(m) => {
+                for &x in m.iter() {
+                    file.write_all(&x.to_le_bytes())?
+                }
+            }
+            // Every row is computed once, without filling the cache
+            Mappings::Lazy(lazy) => {
+                for guess in 0..words.len() {
+                    match lazy.compute(guess) {
+                        Row::SharedNarrow(patterns) => file.write_all(&patterns)?,
+                        row => {
+                            for answer in 0..words.len() {
+                                file.write_all(&row.get(answer).to_le_bytes())?
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The pattern of a guess for an answer
+    pub fn get(&self, guess: usize, answer: usize) -> Pattern {
+        match self {
+            Mappings::Narrow(m) => m[[guess, answer]].into(),
+            Mappings::Wide(m) => m[[guess, answer]],
+            Mappings::Lazy(lazy) => lazy.get(guess, answer),
+        }
+    }
+
+    /// The patterns of a guess for all answers
+    pub fn row(&self, guess: usize) -> Row<'_> {
+        match self {
+            Mappings::Narrow(m) => Row::Narrow(
+                m.row(guess)
+                    .to_slice()
+                    .expect("mapping rows are contiguous"),
+            ),
+            Mappings::Wide(m) => Row::Wide(
+                m.row(guess)
+                    .to_slice()
+                    .expect("mapping rows are contiguous"),
+            ),
+            Mappings::Lazy(lazy) => lazy.row(guess),
+        }
+    }
+}
+
+impl Row<'_> {
+    /// Calls `f` with the position in `answers` and the pattern of every
+    /// answer. The loop is generated for each storage type, so reading the
+    /// patterns does not branch.
+    pub fn for_each(&self, answers: &[usize], mut f: impl FnMut(usize, Pattern)) {
+        match self {
+            Row::Narrow(patterns) => visit(patterns, answers, f),
+            Row::Wide(patterns) => visit(patterns, answers, f),
+            Row::SharedNarrow(patterns) => visit(patterns, answers, f),
+            Row::SharedWide(patterns) => visit(patterns, answers, f),
+            Row::Lazy(lazy, guess) => answers
+                .iter()
+                .enumerate()
+                .for_each(|(id, &answer)| f(id, lazy.get(*guess, answer))),
+        }
+    }
+
+    /// The pattern of an answer
+    pub fn get(&self, answer: usize) -> Pattern {
+        match self {
+            Row::Narrow(patterns) => patterns[answer].into(),
+            Row::Wide(patterns) => patterns[answer],
+            Row::SharedNarrow(patterns) => patterns[answer].into(),
+            Row::SharedWide(patterns) => patterns[answer],
+            Row::Lazy(lazy, guess) => lazy.get(*guess, answer),
+        }
+    }
+
+    /// The indices of all answers with the pattern
+    pub fn matching(&self, pattern: Pattern) -> Vec<usize> {
+        match self {
+            Row::Narrow(patterns) => positions(patterns, pattern),
+            Row::Wide(patterns) => positions(patterns, pattern),
+            Row::SharedNarrow(patterns) => positions(patterns, pattern),
+            Row::SharedWide(patterns) => positions(patterns, pattern),
+            // Filtering by a guess needs all of its patterns, and is likely
+            // repeated for the next guesses
+            Row::Lazy(lazy, guess) => lazy.cached_row(*guess).matching(pattern),
+        }
+    }
+}
+
+fn visit<T: Cell>(patterns: &[T], answers: &[usize], mut f: impl FnMut(usize, Pattern)) {
+    answers
+        .iter()
+        .enumerate()
+        .for_each(|(id, &answer)| f(id, patterns[answer].into()));
+}
+
+fn positions<T: Cell>(patterns: &[T], pattern: Pattern) -> Vec<usize> {
+    patterns
+        .iter()
+        .enumerate()
+        .filter(|(_, &x)| x.into() == pattern)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage() {
+        let words = vec![
+            create_word_from_string("slate"),
+            create_word_from_string("water"),
+        ];
+        let mappings = Mappings::new(&words);
+        assert!(matches!(mappings, Mappings::Narrow(_)));
+        assert_eq!(mappings.get(0, 1), 117);
+        assert_eq!(mappings.row(1).matching(solved_pattern(5)), vec![1]);
+
+        let words = vec![
+            create_word_from_string("salted"),
+            create_word_from_string("slated"),
+            create_word_from_string("wasted"),
+        ];
+        let mappings = Mappings::new(&words);
+        assert!(matches!(mappings, Mappings::Wide(_)));
+        assert_eq!(mappings.get(2, 2), solved_pattern(6));
+        let expected = encode_status(&words[1].compare(&words[0]));
+        assert_eq!(mappings.get(0, 1), expected);
+
+        let mut patterns = vec![];
+        mappings
+            .row(0)
+            .for_each(&[2, 0], |id, pattern| patterns.push((id, pattern)));
+        assert_eq!(
+            patterns,
+            vec![(0, mappings.get(0, 2)), (1, solved_pattern(6))]
+        );
+    }
+
+    #[test]
+    fn test_update() {
+        let words: Vec<Word> = ["slate", "water", "goose"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let mappings = Mappings::new(&words);
+
+        // Drop water, keep the others and add a new word in between
+        let other: Vec<Word> = ["slate", "quirk", "goose"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let updated = mappings.update(&other, &[Some(0), None, Some(2)], ScoringRule::NytStandard);
+        assert!(same(&updated, &Mappings::new(&other), 3));
+    }
+
+    #[test]
+    fn test_lazy() {
+        let words: Vec<Word> = ["slate", "water", "goose"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let dense = Mappings::new(&words);
+        let lazy = Mappings::with_backend(&words, MappingBackend::Lazy { rows: 2 }, None);
+        assert_eq!(lazy.backend(), MappingBackend::Lazy { rows: 2 });
+        assert!(same(&lazy, &dense, 3));
+        for guess in [0, 1, 2, 0] {
+            let row = lazy.row(guess);
+            assert!((0..3).all(|answer| row.get(answer) == dense.get(guess, answer)));
+            let solved = solved_pattern(5);
+            assert_eq!(row.matching(solved), dense.row(guess).matching(solved));
+        }
+        // Only the recently used rows are kept
+        assert_eq!(lazy.stats().unwrap().len, 2);
+
+        // The same format as the dense patterns
+        let (mut a, mut b) = (vec![], vec![]);
+        lazy.write_to(&words, &mut a).unwrap();
+        dense.write_to(&words, &mut b).unwrap();
+        assert_eq!(a, b);
+
+        let other: Vec<Word> = ["salted", "slated", "wasted"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let updated = lazy.update(&other, &[None, None, None], ScoringRule::NytStandard);
+        assert_eq!(updated.row(0).matching(solved_pattern(6)), vec![0]);
+        assert!(matches!(updated.row(0), Row::SharedWide(_)));
+        assert!(same(&updated, &Mappings::new(&other), 3));
+    }
+
+    #[test]
+    fn test_scoring() {
+        let words: Vec<Word> = ["crane", "eerie", "speed", "abide"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let scoring = ScoringRule::GreedyYellow;
+        let dense = Mappings::with_scoring(&words, MappingBackend::Dense, scoring);
+        let lazy = Mappings::with_scoring(&words, MappingBackend::Lazy { rows: 2 }, scoring);
+        for (i, guess) in words.iter().enumerate() {
+            for (j, answer) in words.iter().enumerate() {
+                assert_eq!(dense.get(i, j), answer.pattern_by(guess, scoring));
+            }
+        }
+        assert!(same(&lazy, &dense, 4));
+        // EERIE against CRANE differs from the standard patterns
+        assert_ne!(dense.get(1, 0), Mappings::new(&words).get(1, 0));
+
+        let other: Vec<Word> = ["crane", "geese", "speed"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let updated = dense.update(&other, &[Some(0), None, Some(2)], scoring);
+        assert!(same(
+            &updated,
+            &Mappings::with_scoring(&other, MappingBackend::Dense, scoring),
+            3
+        ));
+    }
+
+    fn same(a: &Mappings, b: &Mappings, n: usize) -> bool {
+        (0..n).all(|i| (0..n).all(|j| a.get(i, j) == b.get(i, j)))
+    }
+
+    #[test]
+    fn test_cache() {
+        let dir = env::temp_dir().join(format!("wordle-solver-test-{}", std::process::id()));
+        let path = dir.join("mappings.bin");
+        let words: Vec<Word> = ["slate", "water", "goose"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+
+        let mappings = Mappings::cached(&words, &path);
+        assert!(path.exists());
+        let cached = Mappings::read(&words, &path).unwrap();
+        assert!(same(&mappings, &cached, 3));
+
+        // Other words invalidate the cache
+        let mut other = words.clone();
+        other[2] = create_word_from_string("moose");
+        assert!(Mappings::read(&other, &path).is_err());
+        let mappings = Mappings::cached(&other, &path);
+        assert!(same(&mappings, &Mappings::new(&other), 3));
+        assert!(Mappings::read(&other, &path).is_ok());
+
+        // Patterns of six letter words are stored in two bytes
+        let words: Vec<Word> = ["salted", "slated"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        Mappings::cached(&words, &path);
+        let cached = Mappings::read(&words, &path).unwrap();
+        assert!(matches!(cached, Mappings::Wide(_)));
+        assert!(same(&cached, &Mappings::new(&words), 2));
+
+        // A truncated cache is recomputed
+        let data = fs::read(&path).unwrap();
+        fs::write(&path, &data[..data.len() - 1]).unwrap();
+        assert!(Mappings::read(&words, &path).is_err());
+        assert!(same(&Mappings::cached(&words, &path), &cached, 2));
+
+        // The same format in memory
+        let mut asset = vec![];
+        cached.write_to(&words, &mut asset).unwrap();
+        assert_eq!(asset, fs::read(&path).unwrap());
+        assert!(same(
+            &Mappings::read_from(&words, &asset[..]).unwrap(),
+            &cached,
+            2
+        ));
+        assert!(Mappings::read_from(&words, &asset[..asset.len() - 1]).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
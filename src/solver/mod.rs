@@ -1,16 +1,61 @@
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use std::cmp::Reverse;
 use std::collections::{HashMap, HashSet};
 use std::fmt;
-
-use crate::solver::data::import;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::solver::cache::{remaining_key, CacheStats, SolverCache, DEFAULT_CACHE_SIZE};
+pub use crate::solver::data::PriorModel;
+use crate::solver::data::{import, openers, parse, parse_alphabet, parse_metadata, parse_overlay};
+use crate::solver::formula::{Formula, Variables};
+pub use crate::solver::mappings::MappingBackend;
+use crate::solver::mappings::{cache_path, fnv1a, Mappings};
+use crate::solver::parallel::{IntoParallelRefIterator, ParallelIterator};
+use crate::solver::ranking::RankingConfig;
 use crate::wordle::*;
-use anyhow::{Context, Result};
-use ndarray::{prelude::*, Zip};
-
+use anyhow::{bail, Context, Result};
+use ndarray::prelude::*;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+pub mod absurdle;
+pub mod book;
+pub mod cache;
+pub mod constraint;
 pub mod data;
-
-#[derive(Clone)]
+pub mod endgame;
+pub mod export;
+pub mod filter;
+pub mod formula;
+pub mod grid;
+pub mod hint;
+pub mod line;
+mod mappings;
+pub mod multi;
+pub mod ngrams;
+pub mod opening;
+pub mod optimal;
+pub mod oracle;
+pub mod pack;
+pub mod packed;
+mod parallel;
+pub mod ranking;
+pub mod regression;
+pub mod report;
+pub mod score;
+pub mod search;
+pub mod simulate;
+pub mod snapshot;
+pub mod soft;
+pub mod streak;
+pub mod tree;
+pub mod width;
+
+/// The solver holds the ~200MB pattern matrix, unless it computes the
+/// patterns when they are needed, see [`MappingBackend`]. It is deliberately not
+/// `Clone`; share it between tasks and threads with an `Arc<Solver>`.
 pub struct Solver {
     // An array of words
     words: Vec<Word>,
@@ -21,16 +66,63 @@ pub struct Solver {
 
     // The mappings between all words
     // row and column inidces are the indices for words
-    // the values in the u8 encoded pattern
-    mappings: Array<u8, Ix2>,
+    // the values in the encoded pattern
+    mappings: Mappings,
+
+    // The length of all words
+    n_letters: usize,
+
+    // The precomputed best first guesses, only known for the embedded
+    // word list
+    openers: Vec<Word>,
+
+    // The priors of the word list without an overlay. The words of the
+    // word list come first, the words added by the overlay after them.
+    base_priors: Vec<f32>,
+
+    // The weights of the default ranking
+    ranking: RankingConfig,
+
+    // How the priors of the word list are transformed
+    prior_model: PriorModel,
+
+    // How likely the words are as answers
+    weighting: Weighting,
+
+    // Which words may be guessed
+    pool: CandidatePool,
+
+    // How the host colors repeated letters, which the mappings follow
+    scoring: ScoringRule,
+
+    // The letters the words are spelled with, sorted
+    alphabet: Vec<char>,
+
+    // Results by the remaining words, see `cache`
+    cache: SolverCache,
 }
 
-fn create_mappings(words: &[Word]) -> Array<u8, Ix2> {
-    let mut mappings: Array<u8, Ix2> = Array::zeros((words.len(), words.len()));
-    Zip::indexed(&mut mappings)
-        .par_for_each(|(i, j), val| *val = encode_status(&words[j].compare(&words[i])));
+/// How likely the words are as answers
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Weighting {
+    /// By their priors, and words with a prior of 0 are not answers
+    #[default]
+    Priors,
+    /// All words are possible answers and equally likely, like in the
+    /// analyses of the original Wordle that ignore how common words are
+    Uniform,
+}
 
-    mappings
+/// Which words the solver suggests as guesses
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CandidatePool {
+    /// All words of the word list, including probes that cannot be the
+    /// answer but split the remaining words better
+    #[default]
+    AllWords,
+    /// Only the remaining possible answers, like players who never guess a
+    /// word that cannot win
+    RemainingAnswers,
 }
 
 fn entropy(x: &ArrayView<f32, Ix1>) -> f32 {
@@ -46,71 +138,626 @@ fn entropy(x: &ArrayView<f32, Ix1>) -> f32 {
         .sum()
 }
 
+/// The entropy of the weights of the patterns, which add up to `sum`. The
+/// weights are reset to 0, so the buffer can be used for the next guess.
+fn drain_entropy(weights: &mut [f32], sum: f32) -> f32 {
+    weights
+        .iter_mut()
+        .map(|v| {
+            let v = std::mem::take(v);
+            if v == 0.0 {
+                return 0.0;
+            }
+            let p = v / sum;
+            -p * f32::log2(p)
+        })
+        .sum()
+}
+
+/// The expected turns of a guess from the weights and sizes of its groups,
+/// like [`drain_entropy`]. Without weights all remaining words are equally
+/// likely.
+fn drain_expected_turns(
+    weights: &mut [f32],
+    counts: &mut [usize],
+    sum: f32,
+    n_remaining: usize,
+) -> f32 {
+    let solved = weights.len() - 1;
+    let groups = weights
+        .iter_mut()
+        .zip(counts.iter_mut())
+        .enumerate()
+        .filter_map(|(pattern, (weight, count))| {
+            let (weight, count) = (std::mem::take(weight), std::mem::take(count));
+            let p = match sum {
+                0.0 => count as f32 / n_remaining as f32,
+                _ => weight / sum,
+            };
+            (count > 0).then_some((pattern == solved, count, p))
+        });
+    score::expected_turns(groups)
+}
+
+/// The letters of the words added to an alphabet, sorted
+fn letters_of(words: &[Word], mut alphabet: Vec<char>) -> Vec<char> {
+    alphabet.extend(words.iter().flat_map(|w| w.chars().iter().flatten()));
+    alphabet.sort_unstable();
+    alphabet.dedup();
+    alphabet
+}
+
+/// The rank of a guess with the default weights, see [`RankingConfig::rank`]
 pub fn rank_guess(entropy: f32, prior: f32, penalty: f32, possible: bool) -> f32 {
-    if !possible {
-        return entropy;
-    }
-    entropy + prior / 20. * penalty
+    RankingConfig::default().rank(entropy, prior, penalty, possible)
 }
 
 impl Solver {
+    /// Create a solver for the embedded word list
+    ///
+    /// The pattern matrix is read from the cache in the user's cache
+    /// directory, and only computed if the cache is missing or outdated.
     pub fn new() -> Result<Solver> {
+        Solver::new_with(MappingBackend::Dense)
+    }
+
+    /// Like [`Solver::new`], with the patterns stored by the backend. The
+    /// lazy backend neither reads nor writes the cache.
+    pub fn new_with(backend: MappingBackend) -> Result<Solver> {
         let (words, priors) = import().context("Error importing data")?;
-        let mappings = create_mappings(&words);
-        Ok(Solver {
-            words: words.into(),
-            priors: priors.into(),
+        let mut solver = Solver::build(words, priors, backend, cache_path().as_deref());
+        solver.openers = openers();
+        Ok(solver)
+    }
+
+    /// Create a solver for the embedded word list with the pattern matrix
+    /// from a precomputed asset in the format of the cache, see
+    /// [`Solver::write_mappings`]. Nothing is computed or written, e.g. for
+    /// WebAssembly, where computing the matrix takes long.
+    pub fn from_mappings(asset: &[u8]) -> Result<Solver> {
+        let (words, priors) = import().context("Error importing data")?;
+        let mappings =
+            Mappings::read_from(&words, asset).context("Error reading the pattern asset")?;
+        let mut solver = Solver::build_with(words, priors, mappings);
+        solver.openers = openers();
+        Ok(solver)
+    }
+
+    /// Write the pattern matrix as an asset for [`Solver::from_mappings`]
+    pub fn write_mappings<W: Write>(&self, writer: W) -> Result<()> {
+        self.mappings.write_to(&self.words, writer)
+    }
+
+    /// Create a solver for a word list file. See [`data::parse`] for the
+    /// format. The pattern matrix is cached like for [`Solver::new`].
+    ///
+    /// An `# alphabet:` field in the header of the list sets the alphabet,
+    /// see [`Solver::with_alphabet`].
+    pub fn from_wordlist<P: AsRef<Path>>(path: P) -> Result<Solver> {
+        Solver::from_wordlist_with(path, MappingBackend::Dense)
+    }
+
+    /// Like [`Solver::from_wordlist`], with the patterns stored by the
+    /// backend
+    pub fn from_wordlist_with<P: AsRef<Path>>(path: P, backend: MappingBackend) -> Result<Solver> {
+        let path = path.as_ref();
+        let data = std::fs::read(path)
+            .with_context(|| format!("Error opening word list {}", path.display()))?;
+        let (words, priors) = parse(&data[..])
+            .with_context(|| format!("Error importing word list {}", path.display()))?;
+        let solver = Solver::build(words, priors, backend, cache_path().as_deref());
+        let alphabet = parse_metadata(&data[..])?
+            .into_iter()
+            .find(|(key, _)| key == "alphabet");
+        match alphabet {
+            Some((_, letters)) => parse_alphabet(&letters)
+                .and_then(|alphabet| solver.with_alphabet(&alphabet))
+                .with_context(|| format!("Error importing word list {}", path.display())),
+            None => Ok(solver),
+        }
+    }
+
+    /// Create a solver for a custom word list. Every word has a prior at
+    /// the same index; words with a prior of 0 are allowed guesses, but not
+    /// possible answers. The pattern matrix is computed and not cached.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wordlebot::prelude::*;
+    /// let words: Vec<Word> = ["slate", "crane", "goose"]
+    ///     .iter()
+    ///     .map(|w| create_word_from_string(w))
+    ///     .collect();
+    /// let solver = Solver::from_words(&words, &[1.0, 1.0, 0.0]).unwrap();
+    /// assert_eq!(solver.get_frequent_word_idx().len(), 2);
+    /// assert!(Solver::from_words(&words, &[1.0]).is_err());
+    /// ```
+    pub fn from_words(words: &[Word], priors: &[f32]) -> Result<Solver> {
+        Solver::check_words(words, priors)?;
+        Ok(Solver::build(
+            words.to_vec(),
+            priors.to_vec(),
+            MappingBackend::Dense,
+            None,
+        ))
+    }
+
+    /// Create a solver for the words of a pack, colored by its feedback
+    /// rule. The pattern matrix of the standard rule is cached like for
+    /// [`Solver::new`].
+    pub fn from_pack(pack: &pack::WordPack, backend: MappingBackend) -> Result<Solver> {
+        Solver::check_words(&pack.words, &pack.priors)
+            .with_context(|| format!("Error loading pack {}", pack.name()))?;
+        let cache = match pack.manifest.feedback {
+            ScoringRule::NytStandard => cache_path(),
+            _ => None,
+        };
+        let mut solver = Solver::build(
+            pack.words.clone(),
+            pack.priors.clone(),
+            backend,
+            cache.as_deref(),
+        );
+        if let Some(alphabet) = &pack.manifest.alphabet {
+            solver = solver
+                .with_alphabet(alphabet)
+                .with_context(|| format!("Error loading pack {}", pack.name()))?;
+        }
+        Ok(solver.with_scoring(pack.manifest.feedback))
+    }
+
+    /// Check the words and priors of a word list, see [`Solver::from_words`]
+    fn check_words(words: &[Word], priors: &[f32]) -> Result<()> {
+        if words.len() != priors.len() {
+            bail!("{} words, but {} priors", words.len(), priors.len())
+        }
+        let Some(first) = words.first() else {
+            bail!("The word list is empty")
+        };
+        let mut seen = HashSet::new();
+        for word in words {
+            if word.chars().iter().any(|c| c.is_none()) {
+                bail!("The word {} is incomplete", word)
+            }
+            if word.n_letters() != first.n_letters() {
+                bail!(
+                    "The word {} has {} letters, but the first word has {}",
+                    word,
+                    word.n_letters(),
+                    first.n_letters()
+                )
+            }
+            if !seen.insert(word) {
+                bail!("The word {} is in the word list twice", word)
+            }
+        }
+        if let Some(prior) = priors.iter().find(|p| !p.is_finite() || **p < 0.0) {
+            bail!("Invalid prior {}", prior)
+        }
+        if !priors.iter().any(|&p| p > 0.0) {
+            bail!("The word list does not contain any possible answer")
+        }
+        Ok(())
+    }
+
+    fn build(
+        words: Vec<Word>,
+        priors: Vec<f32>,
+        backend: MappingBackend,
+        cache: Option<&Path>,
+    ) -> Solver {
+        let mappings = Mappings::with_backend(&words, backend, cache);
+        Solver::build_with(words, priors, mappings)
+    }
+
+    /// How the patterns between all words are stored
+    pub fn mapping_backend(&self) -> MappingBackend {
+        self.mappings.backend()
+    }
+
+    fn build_with(words: Vec<Word>, priors: Vec<f32>, mappings: Mappings) -> Solver {
+        let n_letters = words.first().map_or(NLETTER, Word::n_letters);
+        let alphabet = letters_of(&words, vec![]);
+        Solver {
+            words,
+            base_priors: priors.clone(),
+            priors,
             mappings,
+            n_letters,
+            openers: vec![],
+            ranking: RankingConfig::default(),
+            prior_model: PriorModel::Csv,
+            weighting: Weighting::Priors,
+            pool: CandidatePool::AllWords,
+            scoring: ScoringRule::NytStandard,
+            alphabet,
+            cache: SolverCache::new(DEFAULT_CACHE_SIZE),
+        }
+    }
+
+    /// The solver with other weights for the default ranking
+    pub fn with_ranking(mut self, ranking: RankingConfig) -> Solver {
+        // A bonus for possible answers changes the ranking of the first
+        // guess, so the opening table no longer applies
+        if ranking.possible_bonus != 0.0 || ranking.entropy_weight <= 0.0 {
+            self.openers.clear();
+        }
+        self.ranking = ranking;
+        // The two level bits are ranked with the next best guess
+        self.cache.clear();
+        self
+    }
+
+    /// The weights of the default ranking
+    pub fn ranking(&self) -> &RankingConfig {
+        &self.ranking
+    }
+
+    /// The solver with another weighting of the answers. With
+    /// [`Weighting::Uniform`] every word gets a prior of 1, so the pattern
+    /// distributions, the rankings and the shown priors all treat the words
+    /// alike. The priors are not restored by switching back.
+    pub fn with_weighting(mut self, weighting: Weighting) -> Solver {
+        if weighting == Weighting::Uniform {
+            self.priors.fill(1.0);
+            // The opening table is ranked with the priors
+            self.openers.clear();
+        }
+        self.weighting = weighting;
+        self.cache.clear();
+        self
+    }
+
+    /// Suggest the words of the pool only
+    pub fn with_pool(mut self, pool: CandidatePool) -> Solver {
+        if pool == CandidatePool::RemainingAnswers {
+            // The opening table has words that are no answers
+            self.openers.clear();
+        }
+        self.pool = pool;
+        self.cache.clear();
+        self
+    }
+
+    /// Which words the solver suggests
+    pub fn pool(&self) -> CandidatePool {
+        self.pool
+    }
+
+    /// The solver for a host that colors repeated letters by another rule.
+    /// The patterns of all words are computed again, and never cached.
+    pub fn with_scoring(mut self, scoring: ScoringRule) -> Solver {
+        if scoring != self.scoring {
+            self.mappings = Mappings::with_scoring(&self.words, self.mappings.backend(), scoring);
+            // The opening table was ranked with the standard patterns
+            self.openers.clear();
+            self.cache.clear();
+        }
+        self.scoring = scoring;
+        self
+    }
+
+    /// How the host colors repeated letters
+    pub fn scoring(&self) -> ScoringRule {
+        self.scoring
+    }
+
+    /// The solver with an alphabet, e.g. the 30 letters of German with
+    /// `äöüß`, so the keyboard shows letters that none of the words use.
+    /// Every letter of the words must be in the alphabet. By default the
+    /// alphabet holds the letters of the words.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wordlebot::prelude::*;
+    /// let words: Vec<Word> = ["möwen", "bären"].iter().map(|w| create_word_from_string(w)).collect();
+    /// let solver = Solver::from_words(&words, &[1.0, 1.0]).unwrap();
+    /// assert_eq!(solver.alphabet(), ['b', 'e', 'm', 'n', 'r', 'w', 'ä', 'ö']);
+    /// let german: Vec<char> = "abcdefghijklmnopqrstuvwxyzäöüß".chars().collect();
+    /// assert_eq!(solver.with_alphabet(&german).unwrap().alphabet().len(), 30);
+    /// ```
+    pub fn with_alphabet(mut self, letters: &[char]) -> Result<Solver> {
+        let mut alphabet = letters.to_vec();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+        if let Some(c) = self
+            .alphabet
+            .iter()
+            .find(|c| alphabet.binary_search(c).is_err())
+        {
+            bail!("The letter '{}' of the words is not in the alphabet", c)
+        }
+        self.alphabet = alphabet;
+        Ok(self)
+    }
+
+    /// The letters the words are spelled with, sorted
+    pub fn alphabet(&self) -> &[char] {
+        &self.alphabet
+    }
+
+    /// The pattern of a guess for an answer by the scoring rule of the
+    /// solver. Unlike the patterns of the mappings, the words do not have
+    /// to be in the word list.
+    pub fn pattern_of(&self, answer: &Word, guess: &Word) -> Pattern {
+        answer.pattern_by(guess, self.scoring)
+    }
+
+    /// Keep at most `size` results in each cache of the solver, see
+    /// [`cache`]. A size of 0 disables the caches.
+    pub fn with_cache_size(mut self, size: usize) -> Solver {
+        self.cache = SolverCache::new(size);
+        self
+    }
+
+    /// The caches of the solver, e.g. for suggestions of the caller
+    pub fn cache(&self) -> &SolverCache {
+        &self.cache
+    }
+
+    /// The stats of every cache with its name, including the rows of the
+    /// patterns that are computed lazily
+    pub fn cache_stats(&self) -> Vec<(&'static str, CacheStats)> {
+        let mut stats = self.cache.stats();
+        if let Some(rows) = self.mappings.stats() {
+            stats.push(("pattern rows", rows));
+        }
+        stats
+    }
+
+    /// How likely the words are as answers
+    pub fn weighting(&self) -> Weighting {
+        self.weighting
+    }
+
+    /// The solver with the priors transformed by a [`PriorModel`], which
+    /// is applied to the words of an overlay too. The model transforms the
+    /// current priors, so set it once.
+    pub fn with_prior_model(mut self, model: PriorModel) -> Solver {
+        if model != PriorModel::Csv {
+            model.apply(&mut self.priors);
+            // The opening table is ranked with the priors of the word list
+            self.openers.clear();
+        }
+        self.prior_model = model;
+        self.cache.clear();
+        self
+    }
+
+    pub fn prior_model(&self) -> PriorModel {
+        self.prior_model
+    }
+
+    /// A solver with the overlay from a file. See [`data::parse_overlay`]
+    /// for the format and [`Solver::with_overlay`] for how it is applied.
+    pub fn with_overlay_file<P: AsRef<Path>>(&self, path: P) -> Result<Solver> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .with_context(|| format!("Error opening overlay {}", path.display()))?;
+        let overlay = parse_overlay(file)
+            .with_context(|| format!("Error importing overlay {}", path.display()))?;
+        self.with_overlay(&overlay)
+    }
+
+    /// A solver for the word list with the words of an overlay on top. Words
+    /// that are already in the word list only get the prior of the overlay,
+    /// the others are added. An earlier overlay of this solver is replaced.
+    ///
+    /// The patterns are copied from this solver, so only the patterns of
+    /// words that are new to it are computed.
+    pub fn with_overlay(&self, overlay: &[(Word, f32)]) -> Result<Solver> {
+        let n_base = self.base_priors.len();
+        let mut words = self.words[..n_base].to_vec();
+        let mut priors = self.base_priors.clone();
+        let mut index: HashMap<Word, usize> =
+            words.iter().enumerate().map(|(i, &w)| (w, i)).collect();
+        for &(word, prior) in overlay {
+            if word.n_letters() != self.n_letters {
+                bail!(
+                    "The overlay word {} does not have {} letters",
+                    word,
+                    self.n_letters
+                )
+            }
+            match index.get(&word) {
+                Some(&i) => priors[i] = prior,
+                None => {
+                    index.insert(word, words.len());
+                    words.push(word);
+                    priors.push(prior);
+                }
+            }
+        }
+        if !priors.iter().any(|&p| p > 0.0) {
+            bail!("The overlay leaves no possible answer")
+        }
+        self.prior_model.apply(&mut priors);
+        if self.weighting == Weighting::Uniform {
+            priors.fill(1.0);
+        }
+
+        let old: HashMap<Word, usize> = self
+            .words
+            .iter()
+            .enumerate()
+            .map(|(i, &w)| (w, i))
+            .collect();
+        let previous: Vec<Option<usize>> = words.iter().map(|w| old.get(w).copied()).collect();
+        let alphabet = letters_of(&words, self.alphabet.clone());
+        Ok(Solver {
+            mappings: self.mappings.update(&words, &previous, self.scoring),
+            words,
+            priors,
+            n_letters: self.n_letters,
+            // The opening table was ranked for the words and priors without
+            // the overlay, so it no longer applies
+            openers: vec![],
+            base_priors: self.base_priors.clone(),
+            ranking: self.ranking,
+            prior_model: self.prior_model,
+            weighting: self.weighting,
+            pool: self.pool,
+            scoring: self.scoring,
+            alphabet,
+            cache: SolverCache::new(self.cache.capacity()),
         })
     }
 
+    /// The number of letters of the words
+    pub fn n_letters(&self) -> usize {
+        self.n_letters
+    }
+
+    /// The number of allowed guesses
+    pub fn n_words(&self) -> usize {
+        self.words.len()
+    }
+
+    /// A fingerprint of the words and their priors, which changes with
+    /// every update of the word list that can change the suggestions
+    pub fn fingerprint(&self) -> u64 {
+        fnv1a(
+            self.words
+                .iter()
+                .zip(&self.priors)
+                .flat_map(|(word, prior)| {
+                    let chars = word.chars().iter().map(|c| c.unwrap_or('_') as u64);
+                    chars.chain([prior.to_bits() as u64, '\n' as u64])
+                }),
+        )
+    }
+
+    /// The best first guesses from the embedded opening table, without
+    /// ranking all words. Empty for other word lists.
+    pub fn openers(&self) -> &[Word] {
+        &self.openers
+    }
+
     /// Allowed words are the allowed guesses, eg, 14000 words
+    ///
+    /// The rows of the mapping matrix are read in place through the index
+    /// lists, so no sub-matrix has to be copied for every call.
     fn get_mapping_distribution(
         &self,
         allowed_words: &[usize],
         remaining_words: &[usize],
     ) -> Array<f32, Ix2> {
-        let pattern_matrix = self
-            .mappings
-            .select(Axis(1), remaining_words)
-            .select(Axis(0), allowed_words);
         let n = allowed_words.len();
-        let mut distributions: Array<f32, Ix2> = Array::zeros((n, 3_usize.pow(5)));
-        let n_range: Vec<usize> = (0..n).collect::<Vec<usize>>();
-        pattern_matrix
-            .axis_iter(Axis(1))
-            .enumerate()
-            .for_each(|(id, column)| {
-                column
-                    .iter()
-                    .zip(&n_range)
-                    .for_each(|(&j, i)| distributions[[*i, j as usize]] += self.priors[id]);
+        let n_patterns = solved_pattern(self.n_letters) as usize + 1;
+        let mut distributions: Array<f32, Ix2> = Array::zeros((n, n_patterns));
+        distributions
+            .axis_iter_mut(Axis(0))
+            .zip(allowed_words)
+            .for_each(|(mut distribution, &allowed)| {
+                self.mappings
+                    .row(allowed)
+                    .for_each(remaining_words, |i, pattern| {
+                        distribution[pattern as usize] += self.priors[remaining_words[i]]
+                    });
             });
         distributions
     }
 
-    pub fn get_remaining_words_idx(&self, guesses: &[Guess]) -> Vec<usize> {
+    /// The entropy of the patterns of each allowed word over the remaining
+    /// words, like the rows of [`Solver::get_mapping_distribution`]. Every
+    /// thread counts into its own buffer, so no distribution matrix is
+    /// built.
+    fn entropies(&self, allowed_words: &[usize], remaining_words: &[usize]) -> Vec<f32> {
+        let n_patterns = solved_pattern(self.n_letters) as usize + 1;
+        // Every guess splits the same weights into groups
+        let sum: f32 = remaining_words.iter().map(|&id| self.priors[id]).sum();
+        allowed_words
+            .par_iter()
+            .map_init(
+                || vec![0.0; n_patterns],
+                |weights, &allowed| {
+                    self.mappings
+                        .row(allowed)
+                        .for_each(remaining_words, |i, pattern| {
+                            weights[pattern as usize] += self.priors[remaining_words[i]]
+                        });
+                    drain_entropy(weights, sum)
+                },
+            )
+            .collect()
+    }
+
+    /// The expected bits of guessing each of the remaining words, in their
+    /// order, e.g. to see which possible answer is also the best probe
+    pub fn remaining_bits(&self, remaining_words: &[usize]) -> Vec<f32> {
+        self.entropies(remaining_words, remaining_words)
+    }
+
+    /// The expected number of turns to find the answer with each allowed
+    /// word as the next guess, see [`score::expected_turns`]. Lower is
+    /// better.
+    fn expected_turns(&self, allowed_words: &[usize], remaining_words: &[usize]) -> Vec<f32> {
+        let n_patterns = solved_pattern(self.n_letters) as usize + 1;
+        let sum: f32 = remaining_words.iter().map(|&id| self.priors[id]).sum();
+        allowed_words
+            .par_iter()
+            .map_init(
+                || (vec![0.0; n_patterns], vec![0; n_patterns]),
+                |(weights, counts), &allowed| {
+                    self.mappings
+                        .row(allowed)
+                        .for_each(remaining_words, |i, pattern| {
+                            weights[pattern as usize] += self.priors[remaining_words[i]];
+                            counts[pattern as usize] += 1;
+                        });
+                    drain_expected_turns(weights, counts, sum, remaining_words.len())
+                },
+            )
+            .collect()
+    }
+
+    /// The ids of the possible answers that fit the guesses. Fails if a
+    /// guess is not in the word list or the same word was guessed with two
+    /// patterns. Guesses that no answer fits leave no words.
+    pub fn get_remaining_words_idx(&self, guesses: &[Guess]) -> Result<Vec<usize>, SolverError> {
+        let ids = guesses
+            .iter()
+            .map(|g| Ok((self.word_id(&g.word)?, g.status)))
+            .collect::<Result<Vec<_>, SolverError>>()?;
+        for (i, first) in guesses.iter().enumerate() {
+            let other = guesses[i + 1..]
+                .iter()
+                .find(|g| g.word == first.word && g.status != first.status);
+            if let Some(&second) = other {
+                return Err(SolverError::InconsistentGuesses {
+                    first: *first,
+                    second,
+                });
+            }
+        }
+        Ok(self.remaining_for_ids(&ids))
+    }
+
+    /// The remaining words that also meet constraints that were not found by
+    /// a guess, see [`constraint::ManualConstraints`]
+    pub fn apply_constraints(
+        &self,
+        remaining_words: &[usize],
+        constraints: &constraint::ManualConstraints,
+    ) -> Vec<usize> {
+        remaining_words
+            .iter()
+            .copied()
+            .filter(|&i| constraints.matches(&self.words[i]))
+            .collect()
+    }
+
+    /// The remaining words after guesses of words of the list, by their ids
+    fn remaining_for_ids(&self, guesses: &[(usize, Pattern)]) -> Vec<usize> {
         let frequent_words = self.get_frequent_word_idx();
         if guesses.is_empty() {
             return frequent_words;
         }
         let res: Vec<usize> = guesses
             .iter()
-            .map(|g| {
-                let id = self
-                    .words
-                    .iter()
-                    .position(|&r| r == g.word)
-                    .expect("Not a valid guess");
-
-                self.mappings
-                    .row(id)
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, &x)| x == g.status)
-                    .map(|(i, _)| i)
-                    .collect::<Vec<usize>>()
-            })
+            .map(|&(id, status)| self.mappings.row(id).matching(status))
             .map(HashSet::from_iter)
             .reduce(|a: HashSet<usize>, b| a.intersection(&b).cloned().collect())
             .unwrap()
@@ -124,23 +771,131 @@ impl Solver {
         idx.iter().map(|&i| self.words[i]).collect()
     }
 
+    /// How likely each of the words is the answer: its share of the prior
+    /// of all of them. All are 0 if none of them has a prior.
+    pub fn get_likelihoods(&self, idx: &[usize]) -> Vec<f32> {
+        let total: f32 = idx.iter().map(|&i| self.priors[i]).sum();
+        idx.iter()
+            .map(|&i| match total > 0.0 {
+                true => self.priors[i] / total,
+                false => 0.0,
+            })
+            .collect()
+    }
+
     pub fn get_id_for_word(&self, word: &Word) -> Option<usize> {
         self.words.iter().position(|w| word == w)
     }
 
+    /// The id of a word, or why it is not in the word list
+    pub fn word_id(&self, word: &Word) -> Result<usize, SolverError> {
+        if word.n_letters() != self.n_letters {
+            return Err(SolverError::WrongLength {
+                word: word.to_string(),
+                n_letters: self.n_letters,
+            });
+        }
+        self.get_id_for_word(word)
+            .ok_or(SolverError::UnknownWord(*word))
+    }
+
+    /// The word of some user input, lowercased. Fails if it has not as many
+    /// letters as the words of the list or a letter that is not in the
+    /// alphabet, instead of panicking like [`create_word_from_string`] for
+    /// too long words. It does not have to be in the word list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wordlebot::prelude::*;
+    /// let solver = Solver::new().unwrap();
+    /// assert_eq!(solver.parse_word(" Slate").unwrap().to_string(), "SLATE");
+    /// assert!(solver.parse_word("abcdefghijk").is_err());
+    /// assert!(solver.parse_word("sl4te").is_err());
+    /// ```
+    pub fn parse_word(&self, s: &str) -> Result<Word, SolverError> {
+        let s = s.trim().to_lowercase();
+        if s.chars().count() != self.n_letters {
+            return Err(SolverError::WrongLength {
+                word: s,
+                n_letters: self.n_letters,
+            });
+        }
+        let word = create_word_from_string(&s);
+        if !s.chars().all(|c| self.alphabet.binary_search(&c).is_ok()) {
+            return Err(SolverError::UnknownWord(word));
+        }
+        Ok(word)
+    }
+
+    #[deprecated(note = "renamed to `evaluate_guess`")]
     pub fn evalute_guess(
         &self,
         word: &Word,
         remaining_words: &[usize],
-        status: Option<[LetterStatus; 5]>,
+        status: Option<Vec<LetterStatus>>,
+        two_level_bit: bool,
+    ) -> Result<GuessEvaluation, SolverError> {
+        self.evaluate_guess(word, remaining_words, status, two_level_bit)
+    }
+
+    /// Evaluate a guess for the remaining words. With the status of the
+    /// guess, the bits it actually gave are computed too. The two level
+    /// bits add the expected bits of the best next guess and are slow.
+    ///
+    /// Fails if the word is not in the word list.
+    pub fn evaluate_guess(
+        &self,
+        word: &Word,
+        remaining_words: &[usize],
+        status: Option<Vec<LetterStatus>>,
+        two_level_bit: bool,
+    ) -> Result<GuessEvaluation, SolverError> {
+        let word_id = self.word_id(word)?;
+        Ok(self.evaluate_id(word_id, remaining_words, status, two_level_bit))
+    }
+
+    /// [`Solver::evaluate_guess`] for a word of the list, by its id
+    fn evaluate_id(
+        &self,
+        word_id: usize,
+        remaining_words: &[usize],
+        status: Option<Vec<LetterStatus>>,
         two_level_bit: bool,
     ) -> GuessEvaluation {
-        let word_id = self
-            .words
-            .iter()
-            .position(|w| word == w)
-            .expect("Not a valid guess");
+        // The groups and the two level bits only depend on the remaining
+        // words, so they are cached
+        let key = (remaining_key(remaining_words), word_id);
+        let mut evaluation = self
+            .cache
+            .evaluations
+            .get_or_insert_with(key, || self.evaluate_groups(word_id, remaining_words));
+        if two_level_bit {
+            let bits = self.cache.two_level.get_or_insert_with(key, || {
+                self.avg_entropy_next_level(
+                    word_id,
+                    &evaluation.group_probabilities,
+                    remaining_words,
+                ) + evaluation.expected_bits
+            });
+            evaluation.two_level_bits = Some(bits);
+        }
+
+        let n_after = status
+            .as_ref()
+            .map(|status| self.get_n_solutions_after_guess(word_id, remaining_words, status));
+
+        // There is no information content if the guess leaves no words
+        evaluation.real_bits = n_after
+            .filter(|&x| x > 0)
+            .map(|x| f32::log2(remaining_words.len() as f32 / x as f32));
+        evaluation.status = status;
+        evaluation.n_remaining_after = n_after;
+        evaluation
+    }
 
+    /// The evaluation of a guess without its status and two level bits
+    fn evaluate_groups(&self, word_id: usize, remaining_words: &[usize]) -> GuessEvaluation {
         let distributions = self.get_mapping_distribution(&[word_id], remaining_words);
 
         let entropies: Vec<f32> = distributions
@@ -149,68 +904,83 @@ impl Solver {
             .copied()
             .collect();
 
-        let distributions: Vec<(u8, f32)> = distributions
-            .row(0)
-            .into_iter()
-            .enumerate()
-            .map(|(status, &prop)| (status as u8, prop))
-            .collect();
+        let distribution = distributions.row(0);
+        let total_sum: f32 = distribution.sum();
 
-        let total_sum: f32 = distributions.iter().map(|(_, prop)| *prop).sum();
+        let group_sizes = self.get_group_sizes(word_id, remaining_words);
+        let max_group_size = &group_sizes.iter().map(|(_, v)| *v).max().unwrap_or(0);
 
-        let group_probabilities: Vec<(u8, f32)> = distributions
-            .into_iter()
-            .map(|(i, prop)| (i, prop / total_sum))
+        // The same groups as the group sizes, weighted by the priors
+        let group_probabilities: Vec<(Pattern, f32)> = group_sizes
+            .iter()
+            .map(|&(pattern, _)| match total_sum {
+                0.0 => (pattern, 0.0),
+                _ => (pattern, distribution[pattern as usize] / total_sum),
+            })
             .collect();
 
-        let avg_entropy_next_level = if two_level_bit {
-            Some(
-                self.avg_entropy_next_level(word, &group_probabilities, remaining_words)
-                    + entropies[0],
-            )
-        } else {
-            None
-        };
-
-        let n_after =
-            status.map(|status| self.get_n_solutions_after_guess(word_id, remaining_words, status));
-
-        let real_bits = n_after.map(|x| f32::log2(remaining_words.len() as f32 / x as f32));
-
-        let group_sizes = self.get_group_sizes(word_id, remaining_words);
-        let max_group_size = &group_sizes.iter().map(|(_, v)| *v).max().unwrap_or(0);
+        let solved = solved_pattern(self.n_letters);
+        let expected_turns =
+            score::expected_turns(group_sizes.iter().zip(&group_probabilities).map(
+                |(&(pattern, size), &(_, p))| match total_sum {
+                    0.0 => (
+                        pattern == solved,
+                        size,
+                        size as f32 / remaining_words.len() as f32,
+                    ),
+                    _ => (pattern == solved, size, p),
+                },
+            ));
 
         GuessEvaluation {
-            word: *word,
-            status,
+            word: self.words[word_id],
+            status: None,
             expected_bits: entropies[0],
-            real_bits,
-            two_level_bits: avg_entropy_next_level,
+            real_bits: None,
+            two_level_bits: None,
+            lookahead_bits: None,
+            exact: None,
             groups: group_sizes.len(),
             group_sizes,
             group_probabilities,
             max_group_size: *max_group_size,
+            expected_turns,
             n_remaining_before: remaining_words.len(),
-            n_remaining_after: n_after,
+            n_remaining_after: None,
             is_possible: remaining_words.contains(&word_id),
             prior: self.priors[word_id],
         }
     }
 
+    /// The remaining words that give the guess the pattern, in the order
+    /// of the remaining words
+    pub fn get_group_words(
+        &self,
+        guess: &Word,
+        remaining_words: &[usize],
+        pattern: Pattern,
+    ) -> Vec<Word> {
+        let Some(word_id) = self.get_id_for_word(guess) else {
+            return vec![];
+        };
+        let mut words = vec![];
+        self.mappings
+            .row(word_id)
+            .for_each(remaining_words, |position, p| {
+                if p == pattern {
+                    words.push(self.words[remaining_words[position]]);
+                }
+            });
+        words
+    }
+
     fn get_n_solutions_after_guess(
         &self,
         word_id: usize,
         remaining_words: &[usize],
-        status: [LetterStatus; 5],
+        status: &[LetterStatus],
     ) -> usize {
-        let possible_word_ids = self
-            .mappings
-            .row(word_id)
-            .iter()
-            .enumerate()
-            .filter(|(_, &x)| x == encode_status(&status))
-            .map(|(i, _)| i)
-            .collect::<Vec<usize>>();
+        let possible_word_ids = self.mappings.row(word_id).matching(encode_status(status));
         // Convert vectors into sets
         let set1: HashSet<_> = remaining_words.iter().collect();
         let set2: HashSet<_> = possible_word_ids.iter().collect();
@@ -220,58 +990,449 @@ impl Solver {
         intersection.len()
     }
 
-    fn get_group_sizes(&self, word_id: usize, remaining_words: &[usize]) -> Vec<(u8, usize)> {
-        let pattern_matrix = self.mappings.row(word_id).select(Axis(0), remaining_words);
+    fn get_group_sizes(&self, word_id: usize, remaining_words: &[usize]) -> Vec<(Pattern, usize)> {
         let mut frequency_map = HashMap::new();
 
-        pattern_matrix.iter().for_each(|num| {
-            *frequency_map.entry(*num).or_insert(0) += 1;
-        });
+        self.mappings
+            .row(word_id)
+            .for_each(remaining_words, |_, pattern| {
+                *frequency_map.entry(pattern).or_insert(0) += 1;
+            });
 
         // Convert the hashmap into a vector of key-value pairs
         let mut sorted_vec: Vec<_> = frequency_map.into_iter().collect();
 
-        // Sort the vector by keys
-        sorted_vec.sort_by_key(|&(_, key)| Reverse(key));
+        // Largest groups first, groups of the same size by pattern
+        sorted_vec.sort_by_key(|&(pattern, size)| (Reverse(size), pattern));
         sorted_vec
     }
 
-    pub fn guess(&self, n: usize, remaining_words: &[usize], penalty: f32) -> Vec<Word> {
+    /// The n best guesses for the remaining words
+    pub fn guess(
+        &self,
+        n: usize,
+        remaining_words: &[usize],
+        penalty: f32,
+    ) -> Result<Vec<Word>, EmptyRemaining> {
+        self.guess_where(n, remaining_words, penalty, |_| true)
+    }
+
+    /// The n best guesses for the remaining words among the words that
+    /// `keep` accepts, e.g. a [`filter::GuessFilter`]. There may be fewer
+    /// than n. The last remaining word is returned even if it is not
+    /// accepted, since it is the answer.
+    pub fn guess_where(
+        &self,
+        n: usize,
+        remaining_words: &[usize],
+        penalty: f32,
+        keep: impl Fn(&Word) -> bool,
+    ) -> Result<Vec<Word>, EmptyRemaining> {
+        if remaining_words.is_empty() {
+            return Err(EmptyRemaining);
+        }
         if remaining_words.len() == 1 {
-            return remaining_words.iter().map(|&i| self.words[i]).collect();
+            return Ok(remaining_words.iter().map(|&i| self.words[i]).collect());
+        }
+        if let Some(best) = self.endgame_where(n, remaining_words, &keep) {
+            return Ok(best.into_iter().map(|(word, _)| word).collect());
         }
+        Ok(self
+            .ranked_guesses(remaining_words, penalty, keep)
+            .iter()
+            .take(n)
+            .map(|&i| self.words[i])
+            .collect())
+    }
 
-        let is_in_remaining: Vec<bool> = (0..self.words.len())
-            .map(|x| remaining_words.contains(&x))
+    /// The n words with the most expected bits for the remaining words among
+    /// the words that only test untried letters: no letter is repeated or in
+    /// `used_letters`. They are rarely the answer, but such a "burn" guess
+    /// tells many similar words apart at once. There may be fewer than n.
+    pub fn best_probe_words(
+        &self,
+        remaining_words: &[usize],
+        used_letters: &[char],
+        n: usize,
+    ) -> Result<Vec<Word>, EmptyRemaining> {
+        if remaining_words.is_empty() {
+            return Err(EmptyRemaining);
+        }
+        let candidates: Vec<usize> = (0..self.words.len())
+            .filter(|&i| {
+                let chars = self.words[i].chars();
+                chars.iter().enumerate().all(|(pos, c)| match c {
+                    Some(c) => !used_letters.contains(c) && !chars[..pos].contains(&Some(*c)),
+                    None => false,
+                })
+            })
             .collect();
+        let bits = self.entropies(&candidates, remaining_words);
 
-        let distributions = self.get_mapping_distribution(
-            &(0..self.words.len()).collect::<Vec<usize>>(),
-            remaining_words,
-        );
+        // The sort is stable, so ties keep the order of the word list
+        let mut order: Vec<usize> = (0..candidates.len()).collect();
+        order.sort_by(|&a, &b| bits[b].total_cmp(&bits[a]));
+        Ok(order
+            .iter()
+            .take(n)
+            .map(|&i| self.words[candidates[i]])
+            .collect())
+    }
 
-        let entropies: Vec<f32> = distributions
-            .map_axis(Axis(1), |x| entropy(&x))
+    /// How many words fit a guess that is still being typed, whose untyped
+    /// letters are `None`: words of the list with the typed letters at
+    /// their positions, and remaining answers if all typed letters turned
+    /// out gray or green.
+    pub fn partial_counts(&self, remaining_words: &[usize], partial: &Word) -> PartialCounts {
+        let typed: Vec<(usize, char)> = partial
+            .chars()
             .iter()
-            .copied()
+            .enumerate()
+            .filter_map(|(pos, c)| c.map(|c| (pos, c)))
             .collect();
+        let all_green = |word: &Word| typed.iter().all(|&(pos, c)| word.chars()[pos] == Some(c));
+        let all_gray = |word: &Word| typed.iter().all(|&(_, c)| !word.chars().contains(&Some(c)));
+        PartialCounts {
+            words: self.words.iter().filter(|word| all_green(word)).count(),
+            all_gray: remaining_words
+                .iter()
+                .filter(|&&i| all_gray(&self.words[i]))
+                .count(),
+            all_green: remaining_words
+                .iter()
+                .filter(|&&i| all_green(&self.words[i]))
+                .count(),
+        }
+    }
 
-        let mut indices: Vec<usize> = (0..self.words.len()).collect();
-        // indices.sort_by_cached_key(|i| (Reverse(entropies[*i])));
-        indices.sort_by(|&a, &b| {
-            rank_guess(entropies[b], self.priors[b], penalty, is_in_remaining[b])
-                .partial_cmp(&rank_guess(
-                    entropies[a],
-                    self.priors[a],
-                    penalty,
-                    is_in_remaining[a],
-                ))
-                .unwrap()
+    /// The ids of the words of the pool that `keep` accepts, in the order of
+    /// the word list
+    fn candidates(&self, remaining_words: &[usize], keep: impl Fn(&Word) -> bool) -> Vec<usize> {
+        let mut candidates = match self.pool {
+            CandidatePool::AllWords => (0..self.words.len()).collect(),
+            CandidatePool::RemainingAnswers => {
+                let mut ids = remaining_words.to_vec();
+                ids.sort_unstable();
+                ids
+            }
+        };
+        candidates.retain(|&i| keep(&self.words[i]));
+        candidates
+    }
+
+    /// Whether the word may be guessed for the remaining words, which are
+    /// sorted
+    fn in_pool(&self, sorted_remaining: &[usize], id: usize) -> bool {
+        self.pool == CandidatePool::AllWords || sorted_remaining.binary_search(&id).is_ok()
+    }
+
+    /// The ids of the words that `keep` accepts, best guess first
+    fn ranked_guesses(
+        &self,
+        remaining_words: &[usize],
+        penalty: f32,
+        keep: impl Fn(&Word) -> bool,
+    ) -> Vec<usize> {
+        let mut is_in_remaining = vec![false; self.words.len()];
+        for &i in remaining_words {
+            is_in_remaining[i] = true;
+        }
+
+        let candidates = self.candidates(remaining_words, keep);
+        let ranks: Vec<f32> = self
+            .entropies(&candidates, remaining_words)
+            .into_iter()
+            .zip(&candidates)
+            .map(|(e, &i)| {
+                self.ranking
+                    .rank(e, self.priors[i], penalty, is_in_remaining[i])
+            })
+            .collect();
+
+        // The sort is stable, so ties keep the order of the word list
+        let mut order: Vec<usize> = (0..candidates.len()).collect();
+        order.sort_by(|&a, &b| ranks[b].total_cmp(&ranks[a]));
+        order.iter().map(|&i| candidates[i]).collect()
+    }
+
+    /// The n best guesses for the remaining words, ranked by the expected
+    /// number of turns to find the answer instead of [`rank_guess`]
+    pub fn guess_by_expected_turns(
+        &self,
+        n: usize,
+        remaining_words: &[usize],
+    ) -> Result<Vec<Word>, EmptyRemaining> {
+        self.guess_by_expected_turns_where(n, remaining_words, |_| true)
+    }
+
+    /// Like [`Solver::guess_by_expected_turns`], among the words that `keep`
+    /// accepts. See [`Solver::guess_where`].
+    pub fn guess_by_expected_turns_where(
+        &self,
+        n: usize,
+        remaining_words: &[usize],
+        keep: impl Fn(&Word) -> bool,
+    ) -> Result<Vec<Word>, EmptyRemaining> {
+        if remaining_words.len() <= 1 {
+            return self.guess_where(n, remaining_words, 0.0, keep);
+        }
+
+        let candidates = self.candidates(remaining_words, keep);
+        let turns = self.expected_turns(&candidates, remaining_words);
+
+        // The sort is stable, so ties keep the order of the word list
+        let mut order: Vec<usize> = (0..candidates.len()).collect();
+        order.sort_by(|&a, &b| turns[a].total_cmp(&turns[b]));
+        Ok(order
+            .iter()
+            .take(n)
+            .map(|&i| self.words[candidates[i]])
+            .collect())
+    }
+
+    /// The n best guesses for the remaining words by the worst case: the
+    /// smallest largest group of remaining words they leave, then the most
+    /// groups, then the possible answers
+    pub fn guess_by_max_group(
+        &self,
+        n: usize,
+        remaining_words: &[usize],
+    ) -> Result<Vec<Word>, EmptyRemaining> {
+        self.guess_by_max_group_where(n, remaining_words, |_| true)
+    }
+
+    /// Like [`Solver::guess_by_max_group`], among the words that `keep`
+    /// accepts. See [`Solver::guess_where`].
+    pub fn guess_by_max_group_where(
+        &self,
+        n: usize,
+        remaining_words: &[usize],
+        keep: impl Fn(&Word) -> bool,
+    ) -> Result<Vec<Word>, EmptyRemaining> {
+        if remaining_words.len() <= 1 {
+            return self.guess_where(n, remaining_words, 0.0, keep);
+        }
+
+        let candidates = self.candidates(remaining_words, keep);
+        let groups = self.group_sizes(&candidates, remaining_words);
+        let possible: HashSet<usize> = remaining_words.iter().copied().collect();
+        let key = |i: usize| {
+            let (max_group, n_groups) = groups[i];
+            (
+                max_group,
+                Reverse(n_groups),
+                !possible.contains(&candidates[i]),
+            )
+        };
+
+        // The sort is stable, so ties keep the order of the word list
+        let mut order: Vec<usize> = (0..candidates.len()).collect();
+        order.sort_by_key(|&i| key(i));
+        Ok(order
+            .iter()
+            .take(n)
+            .map(|&i| self.words[candidates[i]])
+            .collect())
+    }
+
+    /// The largest group and the number of groups of the remaining words
+    /// that each allowed word leaves
+    fn group_sizes(
+        &self,
+        allowed_words: &[usize],
+        remaining_words: &[usize],
+    ) -> Vec<(usize, usize)> {
+        let n_patterns = solved_pattern(self.n_letters) as usize + 1;
+        allowed_words
+            .par_iter()
+            .map_init(
+                || vec![0; n_patterns],
+                |counts, &allowed| {
+                    self.mappings
+                        .row(allowed)
+                        .for_each(remaining_words, |_, pattern| counts[pattern as usize] += 1);
+                    let mut max_group = 0;
+                    let mut n_groups = 0;
+                    for count in counts.iter_mut().filter(|count| **count > 0) {
+                        max_group = max_group.max(*count);
+                        n_groups += 1;
+                        *count = 0;
+                    }
+                    (max_group, n_groups)
+                },
+            )
+            .collect()
+    }
+
+    /// The n best guesses for the remaining words with their evaluations,
+    /// ranked by their two level bits, see [`Solver::two_level_score`].
+    ///
+    /// The two level bits are too expensive to compute for all words, so
+    /// only the `width` best guesses of the default ranking are evaluated,
+    /// but at least n.
+    pub fn guess_two_level(
+        &self,
+        n: usize,
+        remaining_words: &[usize],
+        penalty: f32,
+        width: usize,
+    ) -> Result<Vec<GuessEvaluation>, EmptyRemaining> {
+        self.guess_two_level_where(n, remaining_words, penalty, width, |_| true)
+    }
+
+    /// Like [`Solver::guess_two_level`], among the words that `keep`
+    /// accepts. See [`Solver::guess_where`].
+    pub fn guess_two_level_where(
+        &self,
+        n: usize,
+        remaining_words: &[usize],
+        penalty: f32,
+        width: usize,
+        keep: impl Fn(&Word) -> bool,
+    ) -> Result<Vec<GuessEvaluation>, EmptyRemaining> {
+        self.guess_two_level_progress(n, remaining_words, penalty, width, keep, |_, _| true)
+    }
+
+    /// Like [`Solver::guess_two_level_where`], and calls `progress` with
+    /// the number of evaluated candidates and the number of all candidates
+    /// after each one. Once `progress` returns false, e.g. when the user
+    /// cancels, the candidates that are left are skipped and only the
+    /// evaluated ones are ranked.
+    pub fn guess_two_level_progress(
+        &self,
+        n: usize,
+        remaining_words: &[usize],
+        penalty: f32,
+        width: usize,
+        keep: impl Fn(&Word) -> bool,
+        progress: impl Fn(usize, usize) -> bool + Sync,
+    ) -> Result<Vec<GuessEvaluation>, EmptyRemaining> {
+        let candidates = self.guess_where(width.max(n), remaining_words, penalty, keep)?;
+        let evaluated = AtomicUsize::new(0);
+        let stopped = AtomicBool::new(false);
+        let mut evaluations: Vec<GuessEvaluation> = candidates
+            .par_iter()
+            .filter_map(|word| {
+                if stopped.load(Ordering::Relaxed) {
+                    return None;
+                }
+                // The candidates are words of the list
+                let evaluation = self
+                    .evaluate_guess(word, remaining_words, None, true)
+                    .ok()?;
+                let done = evaluated.fetch_add(1, Ordering::Relaxed) + 1;
+                if !progress(done, candidates.len()) {
+                    stopped.store(true, Ordering::Relaxed);
+                }
+                Some(evaluation)
+            })
+            .collect();
+        // The sort is stable, so ties keep the order of the default ranking
+        evaluations.sort_by(|a, b| {
+            self.two_level_score(b, penalty)
+                .total_cmp(&self.two_level_score(a, penalty))
         });
+        evaluations.truncate(n);
+        Ok(evaluations)
+    }
+
+    /// The rank of an evaluated guess by its two level bits, see
+    /// [`RankingConfig::rank_two_level`]. Higher is better, and a guess
+    /// that was evaluated without two level bits has 0.
+    pub fn two_level_score(&self, evaluation: &GuessEvaluation, penalty: f32) -> f32 {
+        self.ranking.rank_two_level(
+            evaluation.two_level_bits.unwrap_or(0.0),
+            evaluation.prior,
+            penalty,
+            evaluation.is_possible,
+        )
+    }
+
+    /// The n best guesses for the remaining words, ranked by a custom
+    /// formula instead of [`rank_guess`].
+    ///
+    /// The two level bits are too expensive to compute for all words. If the
+    /// formula uses them, only the `width` best guesses of the default
+    /// ranking are ranked by the formula.
+    pub fn guess_by_formula(
+        &self,
+        n: usize,
+        remaining_words: &[usize],
+        formula: &Formula,
+        penalty: f32,
+        width: usize,
+    ) -> Result<Vec<Word>, EmptyRemaining> {
+        self.guess_by_formula_where(n, remaining_words, formula, penalty, width, |_| true)
+    }
+
+    /// Like [`Solver::guess_by_formula`], among the words that `keep`
+    /// accepts. See [`Solver::guess_where`].
+    pub fn guess_by_formula_where(
+        &self,
+        n: usize,
+        remaining_words: &[usize],
+        formula: &Formula,
+        penalty: f32,
+        width: usize,
+        keep: impl Fn(&Word) -> bool,
+    ) -> Result<Vec<Word>, EmptyRemaining> {
+        if remaining_words.len() <= 1 {
+            return self.guess_where(n, remaining_words, penalty, keep);
+        }
 
-        let highest_indices: Vec<usize> = indices.iter().take(n).cloned().collect();
+        let two_level = formula.uses_two_level_bits();
+        let candidates: Vec<usize> = if two_level {
+            self.guess_where(width.max(n), remaining_words, penalty, keep)?
+                .iter()
+                .filter_map(|w| self.get_id_for_word(w))
+                .collect()
+        } else {
+            self.candidates(remaining_words, keep)
+        };
+        let is_in_remaining: HashSet<usize> = remaining_words.iter().copied().collect();
+        let n_patterns = solved_pattern(self.n_letters) as usize + 1;
 
-        highest_indices.iter().map(|&i| self.words[i]).collect()
+        let mut ranked: Vec<(f32, usize)> = candidates
+            .par_iter()
+            .map(|&id| {
+                // Weighted like get_mapping_distribution for the entropy, and
+                // unweighted for the group sizes
+                let mut weights = vec![0.0; n_patterns];
+                let mut counts = vec![0; n_patterns];
+                self.mappings
+                    .row(id)
+                    .for_each(remaining_words, |i, pattern| {
+                        weights[pattern as usize] += self.priors[remaining_words[i]];
+                        counts[pattern as usize] += 1;
+                    });
+                let two_level_bits = match two_level {
+                    true => self
+                        .evaluate_id(id, remaining_words, None, true)
+                        .two_level_bits
+                        .unwrap_or(0.0),
+                    false => 0.0,
+                };
+                let vars = Variables {
+                    entropy: entropy(&ArrayView1::from(&weights)),
+                    two_level_bits,
+                    prior: self.priors[id],
+                    max_group: counts.iter().copied().max().unwrap_or(0),
+                    groups: counts.iter().filter(|&&c| c > 0).count(),
+                    is_possible: is_in_remaining.contains(&id),
+                    penalty,
+                    remaining: remaining_words.len(),
+                };
+                (formula.eval(&vars), id)
+            })
+            .collect();
+        // The sort is stable, so ties keep the order of the word list
+        ranked.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+        Ok(ranked
+            .iter()
+            .take(n)
+            .map(|&(_, id)| self.words[id])
+            .collect())
     }
 
     pub fn get_frequent_word_idx(&self) -> Vec<usize> {
@@ -283,56 +1444,382 @@ impl Solver {
             .collect()
     }
 
+    /// A random answer, picked with a probability proportional to its prior.
+    /// `None` if no word is a possible answer.
+    pub fn random_answer<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Word> {
+        let distribution = WeightedIndex::new(&self.priors).ok()?;
+        Some(self.words[distribution.sample(rng)])
+    }
+
+    /// The answer of a daily puzzle, the same for everyone with the same
+    /// word list. `puzzle` tells several puzzles of the same day apart.
+    pub fn daily_answer(&self, day: u64, puzzle: u64) -> Option<Word> {
+        let mut rng = StdRng::seed_from_u64(day.wrapping_mul(1000).wrapping_add(puzzle));
+        self.random_answer(&mut rng)
+    }
+
+    /// Counts for every letter in how many of the words it appears
+    pub fn get_letter_frequencies(&self, idx: &[usize]) -> HashMap<char, usize> {
+        let mut frequencies = HashMap::new();
+        for &i in idx {
+            let mut chars: Vec<char> = self.words[i].chars().iter().flatten().copied().collect();
+            chars.sort();
+            chars.dedup();
+            for c in chars {
+                *frequencies.entry(c).or_insert(0) += 1;
+            }
+        }
+        frequencies
+    }
+
+    /// Counts for every position how many of the words have each letter
+    /// there, in the order of [`Solver::alphabet`]
+    pub fn positional_letter_frequencies(&self, remaining: &[usize]) -> Vec<Vec<usize>> {
+        let mut frequencies = vec![vec![0; self.alphabet.len()]; self.n_letters];
+        for &i in remaining {
+            for (position, c) in self.words[i].chars().iter().enumerate() {
+                if let Some(letter) = c.and_then(|c| self.alphabet.binary_search(&c).ok()) {
+                    frequencies[position][letter] += 1;
+                }
+            }
+        }
+        frequencies
+    }
+
     pub fn is_valid_guess(&self, word: &Word) -> bool {
         self.words.contains(word)
     }
 
+    /// Explains why a word is not among the remaining words after the
+    /// guesses. Returns `None` if the word is still a possible solution.
+    pub fn explain_elimination(&self, word: &Word, guesses: &[Guess]) -> Option<Elimination> {
+        let Some(word_id) = self.get_id_for_word(word) else {
+            return Some(Elimination::NotInWordList);
+        };
+        if self.priors[word_id] <= 0.0 {
+            return Some(Elimination::NotAnAnswer);
+        }
+        guesses.iter().enumerate().find_map(|(i, guess)| {
+            let guess_id = self.get_id_for_word(&guess.word)?;
+            if self.mappings.get(guess_id, word_id) == guess.status {
+                return None;
+            }
+            Some(Elimination::Guess {
+                guess_number: i + 1,
+                guess: *guess,
+                constraint: word.violated_constraint(guess),
+            })
+        })
+    }
+
+    /// Finds guesses that no answer fits together, e.g. after a mistyped
+    /// pattern. Returns `None` if some answer fits all guesses. Otherwise
+    /// the conflict is a minimal subset: every guess of it is needed, any
+    /// of them left out leaves an answer.
+    pub fn find_conflicts(&self, guesses: &[Guess]) -> Result<Option<Conflict>, SolverError> {
+        let ids = guesses
+            .iter()
+            .map(|g| Ok((self.word_id(&g.word)?, g.status)))
+            .collect::<Result<Vec<_>, SolverError>>()?;
+        if !self.remaining_for_ids(&ids).is_empty() {
+            return Ok(None);
+        }
+        // Leave out every guess that the others conflict without
+        let mut kept: Vec<usize> = (0..guesses.len()).collect();
+        for i in (0..guesses.len()).rev() {
+            let others: Vec<(usize, Pattern)> =
+                kept.iter().filter(|&&k| k != i).map(|&k| ids[k]).collect();
+            if !others.is_empty() && self.remaining_for_ids(&others).is_empty() {
+                kept.retain(|&k| k != i);
+            }
+        }
+        let green = kept.iter().enumerate().find_map(|(n, &later)| {
+            kept[..n]
+                .iter()
+                .find_map(|&earlier| contradicted_green(&guesses[earlier], &guesses[later]))
+        });
+        Ok(Some(Conflict {
+            guesses: kept,
+            green,
+        }))
+    }
+
     /// This function calculates the avg bits of information
     /// for all next guesses of a guess
     fn avg_entropy_next_level(
         &self,
-        word: &Word,
-        group_probabilities: &[(u8, f32)],
+        word_id: usize,
+        group_probabilities: &[(Pattern, f32)],
         remaining_words: &[usize],
     ) -> f32 {
         let hm: HashSet<&usize> = HashSet::from_iter(remaining_words);
         let avg_bits: f32 = group_probabilities
             .par_iter()
+            .filter(|(_, prop)| *prop > 0.0)
             .map(|(status, prop)| {
-                let guess = Guess::from_word(*word, decode_status(*status));
-                let new_remaining_words = self.get_remaining_words_idx(&[guess]);
+                let new_remaining_words = self.remaining_for_ids(&[(word_id, *status)]);
                 let new_remaining_words: HashSet<&usize> = HashSet::from_iter(&new_remaining_words);
                 let remaining_words: Vec<_> = hm
                     .intersection(&new_remaining_words)
                     .copied()
                     .copied()
                     .collect();
-                let next = self.guess(1, &remaining_words, 0.1)[0];
-                let next_eval = self.evalute_guess(&next, &remaining_words, None, false);
-                *prop * next_eval.expected_bits
+                let key = remaining_key(&remaining_words);
+                let bits = self.cache.next_level.get_or_insert_with(key, || {
+                    let Ok(next) = self.guess(1, &remaining_words, 0.1) else {
+                        return 0.0;
+                    };
+                    self.evaluate_guess(&next[0], &remaining_words, None, false)
+                        .map_or(0.0, |evaluation| evaluation.expected_bits)
+                });
+                *prop * bits
             })
             .sum();
         avg_bits
     }
 }
 
+/// No word matches the guesses, so there is nothing to suggest
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EmptyRemaining;
+
+impl fmt::Display for EmptyRemaining {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "No words match the guesses")
+    }
+}
+
+impl std::error::Error for EmptyRemaining {}
+
+/// Why the solver could not handle its input
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SolverError {
+    /// The word is not in the word list
+    UnknownWord(Word),
+    /// The word has a different number of letters than the word list. It is
+    /// kept as text, since it may be too long for a [`Word`].
+    WrongLength { word: String, n_letters: usize },
+    /// The same word was guessed with two different patterns
+    InconsistentGuesses { first: Guess, second: Guess },
+    /// No words match the guesses, see [`EmptyRemaining`]
+    EmptyRemainingSet,
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolverError::UnknownWord(word) => write!(f, "{} is not in the word list", word),
+            SolverError::WrongLength { word, n_letters } => write!(
+                f,
+                "{} has {} letters, the words of the list have {}",
+                word.to_uppercase(),
+                word.chars().count(),
+                n_letters
+            ),
+            SolverError::InconsistentGuesses { first, second } => {
+                let n_letters = first.word.n_letters();
+                write!(
+                    f,
+                    "{} was guessed with the patterns {} and {}",
+                    first.word,
+                    pattern_to_string(first.status, n_letters),
+                    pattern_to_string(second.status, n_letters)
+                )
+            }
+            SolverError::EmptyRemainingSet => write!(f, "{}", EmptyRemaining),
+        }
+    }
+}
+
+impl std::error::Error for SolverError {}
+
+impl From<EmptyRemaining> for SolverError {
+    fn from(_: EmptyRemaining) -> SolverError {
+        SolverError::EmptyRemainingSet
+    }
+}
+
+/// The reason why a word is not a possible solution
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Elimination {
+    /// The word is not in the word list at all
+    NotInWordList,
+    /// The word is an allowed guess, but not a possible answer
+    NotAnAnswer,
+    /// The word does not match the pattern of a guess
+    Guess {
+        guess_number: usize,
+        guess: Guess,
+        constraint: Option<LetterConstraint>,
+    },
+}
+
+impl fmt::Display for Elimination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Elimination::NotInWordList => write!(f, "not in the word list"),
+            Elimination::NotAnAnswer => write!(f, "not a possible answer"),
+            Elimination::Guess {
+                guess_number,
+                guess,
+                constraint,
+            } => {
+                write!(f, "eliminated by guess {} ({})", guess_number, guess.word)?;
+                match constraint {
+                    Some(constraint) => write!(f, ": {}", constraint),
+                    None => write!(f, ": pattern does not match"),
+                }
+            }
+        }
+    }
+}
+
+/// Guesses that no answer fits together, see [`Solver::find_conflicts`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conflict {
+    /// The positions of the guesses, ascending
+    pub guesses: Vec<usize>,
+    /// A green letter of one of the guesses that a later one contradicts
+    pub green: Option<LetterConstraint>,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let numbers: Vec<String> = self.guesses.iter().map(|i| (i + 1).to_string()).collect();
+        match numbers.split_last() {
+            Some((last, [])) => write!(f, "No answer fits guess {}", last)?,
+            Some((last, rest)) => {
+                write!(f, "No answer fits guesses {} and {}", rest.join(", "), last)?
+            }
+            None => write!(f, "No answer fits the guesses")?,
+        }
+        match self.green {
+            Some(green) => write!(f, ": {}", green),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The words that fit a guess that is still being typed, see
+/// [`Solver::partial_counts`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PartialCounts {
+    /// The words of the list with the typed letters
+    pub words: usize,
+    /// The remaining answers without any of the typed letters
+    pub all_gray: usize,
+    /// The remaining answers with the typed letters at their positions
+    pub all_green: usize,
+}
+
+/// The green letter of the earlier guess if the later guess contradicts it:
+/// it has another green letter at that position, or the same letter
+/// without green
+fn contradicted_green(earlier: &Guess, later: &Guess) -> Option<LetterConstraint> {
+    let (status, later_status) = (earlier.get_status(), later.get_status());
+    let (chars, later_chars) = (earlier.word.chars(), later.word.chars());
+    (0..status.len()).find_map(|pos| {
+        let c = chars[pos]?;
+        let same = later_chars[pos] == Some(c);
+        let green = later_status[pos] == LetterStatus::Correct;
+        (status[pos] == LetterStatus::Correct && same != green)
+            .then_some(LetterConstraint::At(c, pos))
+    })
+}
+
+/// The evaluation of a guess, see [`Solver::evaluate_guess`]
 #[derive(Clone)]
 pub struct GuessEvaluation {
     pub word: Word,
-    pub status: Option<[LetterStatus; 5]>,
+    /// The status of the guess, if it was played
+    pub status: Option<Vec<LetterStatus>>,
+    /// The entropy of the patterns over the remaining words
     pub expected_bits: f32,
+    /// The bits the status actually gave
     pub real_bits: Option<f32>,
+    /// The expected bits including the best next guess
     pub two_level_bits: Option<f32>,
+    /// The expected bits including the best guesses of a deeper search,
+    /// see [`search`]
+    pub lookahead_bits: Option<f32>,
+    /// The exact cost with the best guesses after it, if few words remain,
+    /// see [`endgame`]
+    pub exact: Option<endgame::ExactCost>,
+    /// The number of non-empty groups
     pub groups: usize,
-    pub group_sizes: Vec<(u8, usize)>,
-    pub group_probabilities: Vec<(u8, f32)>,
+    /// The number of remaining words per pattern, largest group first
+    pub group_sizes: Vec<(Pattern, usize)>,
+    /// The probability of each group in `group_sizes`, in the same order,
+    /// weighted by the priors
+    pub group_probabilities: Vec<(Pattern, f32)>,
     pub max_group_size: usize,
+    /// The expected number of turns to find the answer, including this
+    /// guess, see [`score::expected_turns`]
+    pub expected_turns: f32,
     pub n_remaining_before: usize,
+    /// The number of remaining words after the status
     pub n_remaining_after: Option<usize>,
+    /// Whether the guess is one of the remaining words
     pub is_possible: bool,
     pub prior: f32,
 }
 
+/// How many bits a played guess gave compared with the expected bits, see
+/// [`GuessEvaluation::luck`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Luck {
+    /// At least [`LUCK_BITS`] more than expected
+    Lucky,
+    /// Within [`LUCK_BITS`] of the expected bits
+    Even,
+    /// At least [`LUCK_BITS`] fewer than expected
+    Unlucky,
+}
+
+/// How far the real bits of a guess have to be from the expected bits to
+/// count as luck. One bit is twice or half as many remaining words as
+/// expected.
+pub const LUCK_BITS: f32 = 1.0;
+
+impl GuessEvaluation {
+    /// The groups of the guess, largest first: the pattern, the number of
+    /// remaining words and the probability of each
+    pub fn groups_with_probabilities(&self) -> impl Iterator<Item = (Pattern, usize, f32)> + '_ {
+        self.group_sizes
+            .iter()
+            .zip(&self.group_probabilities)
+            .map(|(&(pattern, size), &(_, probability))| (pattern, size, probability))
+    }
+
+    /// The probability that the guess is the answer, 0 if it is not one of
+    /// the remaining words
+    pub fn answer_probability(&self) -> f32 {
+        let solved = solved_pattern(self.word.n_letters());
+        self.group_probabilities
+            .iter()
+            .find(|&&(pattern, _)| pattern == solved)
+            .map_or(0.0, |&(_, probability)| probability)
+    }
+
+    /// The bits the status gave more than expected, negative if it gave
+    /// fewer. `None` if the guess was not played.
+    pub fn luck_bits(&self) -> Option<f32> {
+        Some(self.real_bits? - self.expected_bits)
+    }
+
+    /// Whether the status of the guess was lucky, see [`LUCK_BITS`]
+    pub fn luck(&self) -> Option<Luck> {
+        let bits = self.luck_bits()?;
+        Some(if bits >= LUCK_BITS {
+            Luck::Lucky
+        } else if bits <= -LUCK_BITS {
+            Luck::Unlucky
+        } else {
+            Luck::Even
+        })
+    }
+}
+
 impl fmt::Display for GuessEvaluation {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -358,7 +1845,7 @@ mod tests {
         // The diagonal of the matrix need to be 242 (perfect fit) for
         // all values, since the index and hence the words for x and y is the
         // same
-        assert!(solver.mappings.diag().iter().all(|x| *x == 242u8));
+        assert!((0..solver.words.len()).all(|i| solver.mappings.get(i, i) == 242));
     }
 
     #[test]
@@ -369,15 +1856,37 @@ mod tests {
             [Misplaced, Correct, Absent, Correct, Absent],
         )];
 
-        let remaining = solver.get_remaining_words_idx(&guesses);
+        let remaining = solver.get_remaining_words_idx(&guesses).unwrap();
         assert_eq!(remaining.len(), 12);
 
         guesses.push(Guess::new(
             "dempt",
             [Absent, Misplaced, Absent, Absent, Correct],
         ));
-        let remaining = solver.get_remaining_words_idx(&guesses);
+        let remaining = solver.get_remaining_words_idx(&guesses).unwrap();
         assert_eq!(remaining.len(), 2);
+
+        // Which of the two is known from elsewhere
+        for (known, n) in [("", 2), ("f@1", 1), ("v!1", 1), ("-fv", 0)] {
+            let constraints = known.parse().unwrap();
+            assert_eq!(solver.apply_constraints(&remaining, &constraints).len(), n);
+        }
+    }
+
+    #[test]
+    fn test_openers() {
+        // The table has to be regenerated when the word list or the ranking
+        // changes
+        let solver = Solver::new().unwrap();
+        let openers = solver.openers();
+        assert!(!openers.is_empty());
+        let ranked = solver
+            .guess(openers.len(), &solver.get_frequent_word_idx(), 0.0)
+            .unwrap();
+        assert_eq!(openers, ranked);
+        assert!(test_solver().openers().is_empty());
+        let overlay = [(create_word_from_string("quirk"), 1.0)];
+        assert!(solver.with_overlay(&overlay).unwrap().openers().is_empty());
     }
 
     fn test_solver() -> Solver {
@@ -386,26 +1895,250 @@ mod tests {
             create_word_from_string("water"),
             create_word_from_string("goose"),
         ];
-        let mappings = create_mappings(&words);
-        Solver {
-            words,
-            priors: vec![1., 1., 1.],
-            mappings,
+        Solver::from_words(&words, &[1., 1., 1.]).unwrap()
+    }
+
+    /// The words of the small solver of the tests of the submodules
+    pub(crate) const SMALL_WORDS: [&str; 6] =
+        ["slate", "crane", "crate", "grate", "prone", "bills"];
+
+    /// A solver of the words with their priors
+    pub(crate) fn solver_with(words: &[&str], priors: &[f32]) -> Solver {
+        let words: Vec<Word> = words.iter().map(|w| create_word_from_string(w)).collect();
+        Solver::from_words(&words, priors).unwrap()
+    }
+
+    /// A solver of the words, all equally likely
+    pub(crate) fn solver_for(words: &[&str]) -> Solver {
+        solver_with(words, &vec![1.0; words.len()])
+    }
+
+    #[test]
+    fn test_prior_weights() {
+        // BILLS is much more likely than CRANE and CRATE
+        let solver = solver_with(&SMALL_WORDS, &[1.0, 1.0, 1.0, 1.0, 1.0, 100.0]);
+        let remaining = [5, 1, 2];
+        let expected: f32 = [100.0f32 / 102.0, 1.0 / 102.0, 1.0 / 102.0]
+            .iter()
+            .map(|p| -p * p.log2())
+            .sum();
+        let slate = create_word_from_string("slate");
+        let evaluation = solver
+            .evaluate_guess(&slate, &remaining, None, false)
+            .unwrap();
+        assert_relative_eq!(evaluation.expected_bits, expected, epsilon = 1e-5);
+
+        // The rankings weight the words the same way
+        let evaluations: Vec<GuessEvaluation> = solver
+            .words
+            .iter()
+            .map(|w| solver.evaluate_guess(w, &remaining, None, false).unwrap())
+            .collect();
+        let bits = solver.remaining_bits(&remaining);
+        for (&id, bits) in remaining.iter().zip(bits) {
+            assert_relative_eq!(bits, evaluations[id].expected_bits, epsilon = 1e-5);
         }
+        let most_bits = evaluations
+            .iter()
+            .map(|e| e.expected_bits)
+            .fold(0.0, f32::max);
+        let formula: Formula = "entropy".parse().unwrap();
+        let best = solver
+            .guess_by_formula(1, &remaining, &formula, 0.1, 6)
+            .unwrap()[0];
+        let best = &evaluations[solver.get_id_for_word(&best).unwrap()];
+        assert_relative_eq!(best.expected_bits, most_bits, epsilon = 1e-5);
+        let fewest_turns = evaluations
+            .iter()
+            .map(|e| e.expected_turns)
+            .fold(f32::INFINITY, f32::min);
+        let best = solver.guess_by_expected_turns(1, &remaining).unwrap()[0];
+        let best = &evaluations[solver.get_id_for_word(&best).unwrap()];
+        assert_relative_eq!(best.expected_turns, fewest_turns, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn test_evaluation_cache() {
+        let solver = test_solver();
+        let slate = create_word_from_string("slate");
+        let first = solver
+            .evaluate_guess(&slate, &[0, 1, 2], None, true)
+            .unwrap();
+        // The same evaluation from the cache, with or without a status
+        let again = solver
+            .evaluate_guess(&slate, &[2, 1, 0], None, true)
+            .unwrap();
+        assert_eq!(again.two_level_bits, first.two_level_bits);
+        assert_eq!(again.group_sizes, first.group_sizes);
+        let played = solver
+            .evaluate_guess(&slate, &[0, 1, 2], Some(slate.compare(&slate)), false)
+            .unwrap();
+        assert_eq!(played.two_level_bits, None);
+        assert_eq!(played.n_remaining_after, Some(1));
+        // The two level bits evaluate the next guesses, which are cached too
+        assert!(solver.cache().evaluations.stats().hits >= 2);
+        assert_eq!(solver.cache().two_level.stats().hits, 1);
+        // And the bits of the best next guess by the words left
+        let water = create_word_from_string("water");
+        let misses = solver.cache().next_level.stats().misses;
+        solver
+            .evaluate_guess(&water, &[0, 1, 2], None, true)
+            .unwrap();
+        let stats = solver.cache().next_level.stats();
+        assert_eq!(stats.misses, misses);
+        assert!(stats.hits > 0);
+
+        // Without caches the evaluations are the same
+        let uncached = test_solver().with_cache_size(0);
+        let evaluation = uncached
+            .evaluate_guess(&slate, &[0, 1, 2], None, true)
+            .unwrap();
+        assert_eq!(evaluation.two_level_bits, first.two_level_bits);
+        assert_eq!(uncached.cache().evaluations.stats().len, 0);
+    }
+
+    #[test]
+    fn test_guess_two_level() {
+        let solver = solver_for(&SMALL_WORDS);
+        let words = solver.words.clone();
+        let remaining: Vec<usize> = (0..words.len()).collect();
+        let score = |e: &GuessEvaluation| solver.two_level_score(e, 0.1);
+
+        // With all words as candidates, the best ones of all words
+        let best = solver.guess_two_level(3, &remaining, 0.1, 6).unwrap();
+        let mut all: Vec<f32> = words
+            .iter()
+            .map(|w| score(&solver.evaluate_guess(w, &remaining, None, true).unwrap()))
+            .collect();
+        all.sort_by(|a, b| b.total_cmp(a));
+        assert_eq!(best.iter().map(score).collect::<Vec<_>>(), all[..3]);
+        assert!(best.iter().all(|e| e.two_level_bits.is_some()));
+
+        // At least n candidates are evaluated
+        assert_eq!(
+            solver.guess_two_level(2, &remaining, 0.1, 0).unwrap().len(),
+            2
+        );
+        assert_eq!(
+            solver.guess_two_level(1, &remaining[..1], 0.1, 3).unwrap()[0].word,
+            words[0]
+        );
+        assert!(solver.guess_two_level(1, &[], 0.1, 3).is_err());
+
+        // The progress counts the candidates, and stopping skips the ones
+        // that are not started yet
+        let calls = AtomicUsize::new(0);
+        let progress = |done: usize, total: usize| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            assert!(done <= total && total == 4);
+            done < 2
+        };
+        let best = solver
+            .guess_two_level_progress(4, &remaining, 0.1, 4, |_| true, progress)
+            .unwrap();
+        assert!(best.len() >= 2);
+        assert_eq!(calls.load(Ordering::Relaxed), best.len());
+    }
+
+    #[test]
+    fn test_overlay() {
+        let solver = test_solver();
+        let quirk = create_word_from_string("quirk");
+        let overlay = vec![(create_word_from_string("water"), 0.0), (quirk, 2.0)];
+        let updated = solver.with_overlay(&overlay).unwrap();
+        assert_eq!(updated.words.len(), 4);
+        assert_eq!(updated.priors, vec![1., 0., 1., 2.]);
+        assert_eq!(updated.get_frequent_word_idx(), vec![0, 2, 3]);
+        assert!((0..4).all(|i| updated.mappings.get(i, i) == 242));
+        assert_eq!(
+            updated.mappings.get(3, 0),
+            encode_status(&create_word_from_string("slate").compare(&quirk))
+        );
+
+        // A new overlay replaces the old one
+        let reverted = updated.with_overlay(&[]).unwrap();
+        assert_eq!(reverted.words, solver.words);
+        assert_eq!(reverted.priors, solver.priors);
+
+        assert!(solver
+            .with_overlay(&[(create_word_from_string("quirks"), 1.0)])
+            .is_err());
+        let none: Vec<(Word, f32)> = solver.words.iter().map(|&w| (w, 0.0)).collect();
+        assert!(solver.with_overlay(&none).is_err());
+    }
+
+    #[test]
+    fn test_uniform() {
+        let words: Vec<Word> = ["slate", "crane", "crate", "bills"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let solver = Solver::from_words(&words, &[1.0, 0.5, 0.2, 0.0]).unwrap();
+        assert_eq!(solver.weighting(), Weighting::Priors);
+        assert_eq!(
+            solver.get_likelihoods(&[0, 1, 3]),
+            [1.0 / 1.5, 0.5 / 1.5, 0.0]
+        );
+        assert_eq!(solver.get_likelihoods(&[3]), [0.0]);
+        let uniform = solver.with_weighting(Weighting::Uniform);
+        assert_eq!(uniform.weighting(), Weighting::Uniform);
+
+        // BILLS is an answer, and all answers weigh the same
+        let equal = Solver::from_words(&words, &[1.0; 4]).unwrap();
+        let remaining = uniform.get_frequent_word_idx();
+        assert_eq!(remaining, [0, 1, 2, 3]);
+        for word in &words {
+            let a = uniform
+                .evaluate_guess(word, &remaining, None, true)
+                .unwrap();
+            let b = equal.evaluate_guess(word, &remaining, None, true).unwrap();
+            assert_eq!(a.expected_bits, b.expected_bits);
+            assert_eq!(a.two_level_bits, b.two_level_bits);
+            assert_eq!((a.prior, a.is_possible), (1.0, true));
+        }
+
+        assert_eq!(uniform.get_likelihoods(&[0, 1, 3]), [1.0 / 3.0; 3]);
+
+        // An overlay does not bring the priors back
+        let quirk = create_word_from_string("quirk");
+        let updated = uniform.with_overlay(&[(quirk, 0.3)]).unwrap();
+        assert_eq!(updated.priors, vec![1.0; 5]);
+        assert_eq!(updated.weighting(), Weighting::Uniform);
+    }
+
+    #[test]
+    fn test_prior_model() {
+        let words: Vec<Word> = ["slate", "crane", "crate", "bills"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let solver = Solver::from_words(&words, &[1.0, 0.5, 0.2, 0.0])
+            .unwrap()
+            .with_prior_model(PriorModel::Flat);
+        assert_eq!(solver.prior_model(), PriorModel::Flat);
+        // BILLS is still no answer
+        assert_eq!(solver.priors, vec![1.0, 1.0, 1.0, 0.0]);
+        assert_eq!(solver.get_frequent_word_idx(), [0, 1, 2]);
+
+        // The words of an overlay are transformed too
+        let quirk = create_word_from_string("quirk");
+        let updated = solver.with_overlay(&[(quirk, 0.3)]).unwrap();
+        assert_eq!(updated.priors, vec![1.0, 1.0, 1.0, 0.0, 1.0]);
     }
 
     #[test]
     fn test_mappings_2() {
         let solver = test_solver();
         let expected = array![[242, 117, 163], [39, 242, 27], [189, 81, 242]];
-        assert_eq!(solver.mappings, expected)
+        let mappings = Array::from_shape_fn((3, 3), |(i, j)| solver.mappings.get(i, j));
+        assert_eq!(mappings, expected)
     }
 
     #[test]
     fn test_get_mapping_distribution() {
         let solver = test_solver();
 
-        let dist = solver.get_mapping_distribution(&vec![0], &vec![0, 1, 2]);
+        let dist = solver.get_mapping_distribution(&[0], &[0, 1, 2]);
         let expected = array![
             0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0.,
             0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0.,
@@ -424,7 +2157,7 @@ mod tests {
         assert_eq!(dist.shape(), [1, 243]);
         assert_eq!(dist, expected);
 
-        let dist = solver.get_mapping_distribution(&vec![0, 1], &vec![0, 1, 2]);
+        let dist = solver.get_mapping_distribution(&[0, 1], &[0, 1, 2]);
         assert_eq!(dist.shape(), [2, 243]);
         assert_eq!(dist.index_axis(Axis(0), 0), expected.index_axis(Axis(0), 0));
 
@@ -450,7 +2183,7 @@ mod tests {
         let mut solver = test_solver();
         solver.priors = vec![1., 2., 3.];
 
-        let dist = solver.get_mapping_distribution(&vec![0], &vec![0, 1, 2]);
+        let dist = solver.get_mapping_distribution(&[0], &[0, 1, 2]);
         let expected = array![
             0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0.,
             0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0.,
@@ -476,35 +2209,48 @@ mod tests {
         assert_relative_eq!(entropy(&x.view()), 1.4591479);
 
         let solver = test_solver();
-        let dist = solver.get_mapping_distribution(&vec![0, 1], &vec![0, 1, 2]);
+        let dist = solver.get_mapping_distribution(&[0, 1], &[0, 1, 2]);
         let entropies: Vec<f32> = dist
             .map_axis(Axis(1), |x| entropy(&x))
             .iter()
-            .map(|x| *x)
+            .copied()
             .collect();
 
-        assert_eq!(entropies, vec![1.5849626, 1.5849626])
+        assert_eq!(entropies, vec![1.5849626, 1.5849626]);
+        // Without the distribution matrix
+        assert_eq!(solver.entropies(&[0, 1], &[0, 1, 2]), entropies);
+        assert_eq!(solver.entropies(&[0, 1], &[]), vec![0.0, 0.0]);
     }
 
     #[test]
     fn test_step_penalty() {
         let solver = Solver::new().unwrap();
 
-        let guess = solver.guess(1, &solver.get_frequent_word_idx(), 0.0)[0];
+        let guess = solver
+            .guess(1, &solver.get_frequent_word_idx(), 0.0)
+            .unwrap()[0];
         assert_eq!(guess, create_word_from_string("tarse"));
 
-        let guess = solver.guess(1, &solver.get_frequent_word_idx(), 10.0)[0];
+        let guess = solver
+            .guess(1, &solver.get_frequent_word_idx(), 10.0)
+            .unwrap()[0];
         assert_eq!(guess, create_word_from_string("raise"));
+
+        // The NaN ranks of a NaN penalty are sorted instead of panicking
+        let guesses = solver
+            .guess(3, &solver.get_frequent_word_idx(), f32::NAN)
+            .unwrap();
+        assert_eq!(guesses.len(), 3);
     }
 
     #[test]
     fn test_mapping_subset() {
         let solver = Solver::new().unwrap();
         let dist =
-            solver.get_mapping_distribution(&vec![10], &solver.get_remaining_words_idx(&vec![]));
+            solver.get_mapping_distribution(&[10], &solver.get_remaining_words_idx(&[]).unwrap());
         let dist2 = solver.get_mapping_distribution(
             &(0..solver.words.len()).collect::<Vec<usize>>(),
-            &solver.get_remaining_words_idx(&vec![]),
+            &solver.get_remaining_words_idx(&[]).unwrap(),
         );
         assert_eq!(dist.row(0), dist2.row(10));
     }
@@ -514,12 +2260,14 @@ mod tests {
         let solver = Solver::new().unwrap();
         let guess = create_word_from_string("slate");
 
-        let res = solver.evalute_guess(
-            &guess,
-            &solver.get_frequent_word_idx(),
-            Some([Misplaced, Absent, Misplaced, Absent, Correct]),
-            false,
-        );
+        let res = solver
+            .evaluate_guess(
+                &guess,
+                &solver.get_frequent_word_idx(),
+                Some(vec![Misplaced, Absent, Misplaced, Absent, Correct]),
+                false,
+            )
+            .unwrap();
 
         assert_eq!(res.groups, 154);
         assert_eq!(res.max_group_size, 328);
@@ -527,5 +2275,504 @@ mod tests {
         assert_eq!(res.n_remaining_after, Some(13));
         assert_relative_eq!(res.expected_bits, 5.789861);
         assert_eq!(res.real_bits, Some(7.938449));
+        // 13 words are left instead of the 3189 / 2^5.79 = 58 expected
+        assert_eq!(res.luck(), Some(Luck::Lucky));
+
+        let res = solver
+            .evaluate_guess(&guess, &solver.get_frequent_word_idx(), None, false)
+            .unwrap();
+        assert_eq!(res.luck(), None);
+        assert!(res.answer_probability() > 0.0);
+        let groups: Vec<_> = res.groups_with_probabilities().collect();
+        assert_eq!(groups.len(), res.groups);
+        assert_eq!(groups[0].1, res.max_group_size);
+        let total: f32 = groups.iter().map(|&(_, _, p)| p).sum();
+        assert_relative_eq!(total, 1.0, epsilon = 1e-4);
+
+        // The words of the largest group
+        let remaining = solver.get_frequent_word_idx();
+        let words = solver.get_group_words(&guess, &remaining, groups[0].0);
+        assert_eq!(words.len(), res.max_group_size);
+        assert!(words.iter().all(|w| w.pattern(&guess) == groups[0].0));
+    }
+
+    #[test]
+    fn test_concurrent_suggestions() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Solver>();
+
+        let solver = std::sync::Arc::new(test_solver());
+        let expected = solver.guess(3, &[0, 1, 2], 0.1).unwrap();
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let solver = std::sync::Arc::clone(&solver);
+                std::thread::spawn(move || {
+                    let guess = solver.guess(3, &[0, 1, 2], 0.1).unwrap();
+                    let eval = solver
+                        .evaluate_guess(&guess[0], &[0, 1, 2], None, true)
+                        .unwrap();
+                    (guess, eval.expected_bits)
+                })
+            })
+            .collect();
+        for handle in handles {
+            let (guess, bits) = handle.join().unwrap();
+            assert_eq!(guess, expected);
+            assert_relative_eq!(bits, 1.5849626);
+        }
+    }
+
+    #[test]
+    fn test_guess_where() {
+        let solver = test_solver();
+        let goose = create_word_from_string("goose");
+        let has_g = |w: &Word| w.chars().contains(&Some('g'));
+        assert_eq!(
+            solver.guess_where(3, &[0, 1, 2], 0.0, has_g),
+            Ok(vec![goose])
+        );
+        assert_eq!(
+            solver.guess_where(3, &[0, 1, 2], 0.0, |_| false),
+            Ok(vec![])
+        );
+        // The answer is suggested even if it does not pass the filter
+        assert_eq!(solver.guess_where(3, &[2], 0.0, |_| false), Ok(vec![goose]));
+    }
+
+    #[test]
+    fn test_expected_turns() {
+        let solver = test_solver();
+        // Every word splits the others, so guessing a possible answer
+        // saves a turn a third of the time
+        let slate = create_word_from_string("slate");
+        let evaluation = solver
+            .evaluate_guess(&slate, &[0, 1, 2], None, false)
+            .unwrap();
+        assert_relative_eq!(evaluation.expected_turns, 1.0 / 3.0 + 2.0 / 3.0 * 2.0);
+        assert_relative_eq!(
+            solver.expected_turns(&[0], &[0, 1, 2])[0],
+            evaluation.expected_turns
+        );
+        // The answer is found with the guess
+        let solved = solver.evaluate_guess(&slate, &[0], None, false).unwrap();
+        assert_eq!(solved.expected_turns, 1.0);
+        assert_eq!(
+            solver.guess_by_expected_turns(1, &[1]),
+            Ok(vec![solver.words[1]])
+        );
+        assert_eq!(
+            solver.guess_by_expected_turns(3, &[0, 1, 2]).unwrap().len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_guess_by_max_group() {
+        let words: Vec<Word> = ["bight", "fight", "light", "might", "sight", "flams"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let solver = Solver::from_words(&words, &[1.0, 1.0, 1.0, 1.0, 1.0, 0.0]).unwrap();
+        let remaining = solver.get_frequent_word_idx();
+        // FLAMS tells all answers apart, every answer leaves the other four
+        // in one group
+        assert_eq!(solver.group_sizes(&[0, 5], &remaining), [(4, 2), (1, 5)]);
+        let best = solver.guess_by_max_group(2, &remaining).unwrap();
+        assert_eq!(best, [words[5], words[0]]);
+        assert_eq!(
+            solver.guess_by_max_group_where(1, &remaining, |w| *w != words[5]),
+            Ok(vec![words[0]])
+        );
+        assert_eq!(solver.guess_by_max_group(1, &[2]), Ok(vec![words[2]]));
+    }
+
+    #[test]
+    fn test_remaining_bits() {
+        let solver = Solver::from_words(
+            &["bight", "fight", "light", "flams"].map(create_word_from_string),
+            &[1.0, 1.0, 1.0, 0.0],
+        )
+        .unwrap();
+        let remaining = solver.get_frequent_word_idx();
+        let bits = solver.remaining_bits(&remaining);
+        assert_eq!(bits.len(), 3);
+        for (&id, bits) in remaining.iter().zip(bits) {
+            let word = solver.words[id];
+            let evaluation = solver
+                .evaluate_guess(&word, &remaining, None, false)
+                .unwrap();
+            assert_abs_diff_eq!(bits, evaluation.expected_bits, epsilon = 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_remaining_answers_pool() {
+        let words: Vec<Word> = ["bight", "fight", "light", "might", "sight", "flams"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let solver = Solver::from_words(&words, &[1.0, 1.0, 1.0, 1.0, 1.0, 0.0])
+            .unwrap()
+            .with_pool(CandidatePool::RemainingAnswers);
+        assert_eq!(solver.pool(), CandidatePool::RemainingAnswers);
+        // FLAMS is the best guess, but no answer is left that it could be
+        let remaining = solver.get_frequent_word_idx();
+        let guesses = solver.guess(6, &remaining, 0.0).unwrap();
+        assert_eq!(guesses.len(), 5);
+        assert!(!guesses.contains(&words[5]));
+        let best = solver.guess_by_max_group(1, &[1, 2, 3]).unwrap();
+        assert!(words[1..4].contains(&best[0]));
+    }
+
+    #[test]
+    fn test_from_words() {
+        let words: Vec<Word> = ["slate", "crane", "slated"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        assert!(Solver::from_words(&words[..2], &[1., 0.]).is_ok());
+        assert!(Solver::from_words(&words[..2], &[0., 0.]).is_err());
+        assert!(Solver::from_words(&words[..2], &[1., f32::NAN]).is_err());
+        assert!(Solver::from_words(&words, &[1., 1., 1.]).is_err());
+        assert!(Solver::from_words(&[words[0], words[0]], &[1., 1.]).is_err());
+        assert!(Solver::from_words(&[Word::new()], &[1.]).is_err());
+        assert!(Solver::from_words(&[], &[]).is_err());
+    }
+
+    #[test]
+    fn test_empty_remaining() {
+        let solver = test_solver();
+        assert_eq!(solver.guess(3, &[], 0.1), Err(EmptyRemaining));
+
+        let slate = create_word_from_string("slate");
+        let res = solver.evaluate_guess(&slate, &[], None, true).unwrap();
+        assert_eq!(res.expected_bits, 0.0);
+        assert_eq!(res.two_level_bits, Some(0.0));
+        assert_eq!(res.groups, 0);
+        assert!(res.group_probabilities.is_empty());
+
+        // A pattern that matches none of the remaining words
+        let res = solver
+            .evaluate_guess(
+                &slate,
+                &[0, 1, 2],
+                Some(vec![Correct, Correct, Correct, Correct, Absent]),
+                false,
+            )
+            .unwrap();
+        assert_eq!(res.n_remaining_after, Some(0));
+        assert_eq!(res.real_bits, None);
+    }
+
+    #[test]
+    fn test_alphabet() {
+        let solver = test_solver();
+        assert_eq!(
+            solver.alphabet(),
+            ['a', 'e', 'g', 'l', 'o', 'r', 's', 't', 'w']
+        );
+        assert!(test_solver().with_alphabet(&['a', 'e']).is_err());
+
+        // Overlay words add their letters, also to a set alphabet
+        let spanish: Vec<char> = "abcdefghijklmnñopqrstuvwxyz".chars().collect();
+        let solver = solver.with_alphabet(&spanish).unwrap();
+        assert_eq!(solver.alphabet().len(), 27);
+        let overlay = [(create_word_from_string("façon"), 1.0)];
+        let solver = solver.with_overlay(&overlay).unwrap();
+        assert_eq!(solver.alphabet().len(), 28);
+        assert!(solver.alphabet().contains(&'ç'));
+
+        // Non-ASCII letters are counted and compared like any other
+        let words: Vec<Word> = ["niños", "señal", "nadie"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let solver = Solver::from_words(&words, &[1.0, 1.0, 1.0]).unwrap();
+        let frequencies = solver.positional_letter_frequencies(&[0, 1, 2]);
+        let ñ = solver.alphabet().binary_search(&'ñ').unwrap();
+        assert_eq!(frequencies[2][ñ], 2);
+        let pattern = solver.pattern_of(&words[1], &words[0]);
+        assert_eq!(pattern_to_string(pattern, 5), "BBGBY");
+
+        // The alphabet of a word list is read from its header
+        let path = std::env::temp_dir().join(format!("wordlebot-abc-{}.txt", std::process::id()));
+        std::fs::write(
+            &path,
+            "# alphabet: a b c d e ñ
+abcde
+ñadea
+",
+        )
+        .unwrap();
+        let solver = Solver::from_wordlist_with(&path, MappingBackend::Lazy { rows: 2 }).unwrap();
+        assert_eq!(solver.alphabet(), ['a', 'b', 'c', 'd', 'e', 'ñ']);
+        std::fs::write(
+            &path,
+            "# alphabet: abcde
+abcde
+ñadea
+",
+        )
+        .unwrap();
+        assert!(Solver::from_wordlist_with(&path, MappingBackend::Lazy { rows: 2 }).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_solver_error() {
+        let solver = test_solver();
+        let xxxxx = create_word_from_string("xxxxx");
+        let err = solver.word_id(&xxxxx).unwrap_err();
+        assert_eq!(err, SolverError::UnknownWord(xxxxx));
+        assert_eq!(err.to_string(), "XXXXX is not in the word list");
+
+        let guess = Guess::new("slates", [Absent; 6]);
+        let err = solver.get_remaining_words_idx(&[guess]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "SLATES has 6 letters, the words of the list have 5"
+        );
+        let res = solver.evaluate_guess(&xxxxx, &[0, 1, 2], None, false);
+        assert_eq!(res.err(), Some(SolverError::UnknownWord(xxxxx)));
+
+        let first = Guess::new("slate", [Misplaced, Absent, Absent, Absent, Correct]);
+        let second = Guess::new("slate", [Correct, Absent, Absent, Absent, Absent]);
+        let err = solver
+            .get_remaining_words_idx(&[first, second])
+            .unwrap_err();
+        assert_eq!(err, SolverError::InconsistentGuesses { first, second });
+        assert_eq!(
+            err.to_string(),
+            "SLATE was guessed with the patterns YBBBG and GBBBB"
+        );
+
+        // The same guess twice is fine
+        let guess = Guess::new("slate", [Absent; 5]);
+        assert!(solver.get_remaining_words_idx(&[guess, guess]).is_ok());
+    }
+
+    #[test]
+    fn test_best_probe_words() {
+        let words: Vec<Word> = [
+            "crane", "crank", "crate", "craze", "might", "tempo", "blitz", "kudzu",
+        ]
+        .iter()
+        .map(|w| create_word_from_string(w))
+        .collect();
+        let solver = Solver::from_words(&words, &[1.; 8]).unwrap();
+        let used: Vec<char> = "crane".chars().collect();
+        // BLITZ tells all three apart, MIGHT only CRATE. TEMPO has the E of
+        // CRANE and KUDZU a repeated U.
+        let probes = solver.best_probe_words(&[1, 2, 3], &used, 5).unwrap();
+        assert_eq!(probes, vec![words[6], words[4]]);
+        assert_eq!(
+            solver.best_probe_words(&[1, 2, 3], &used, 1).unwrap(),
+            vec![words[6]]
+        );
+        assert_eq!(solver.best_probe_words(&[], &used, 5), Err(EmptyRemaining));
+    }
+
+    #[test]
+    fn test_partial_counts() {
+        let words: Vec<Word> = ["crane", "crank", "crate", "might", "tempo", "blitz"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let solver = Solver::from_words(&words, &[1.; 6]).unwrap();
+        let mut partial = Word::with_length(5);
+        partial.set_letter(Some('c'), 0);
+        partial.set_letter(Some('r'), 1);
+        assert_eq!(
+            solver.partial_counts(&[1, 2, 3, 5], &partial),
+            PartialCounts {
+                words: 3,
+                all_gray: 2,
+                all_green: 2,
+            }
+        );
+        // Only the typed letters count, not the empty ones before them
+        let mut partial = Word::with_length(5);
+        partial.set_letter(Some('t'), 4);
+        assert_eq!(
+            solver.partial_counts(&[1, 2, 3, 5], &partial),
+            PartialCounts {
+                words: 1,
+                all_gray: 1,
+                all_green: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_find_conflicts() {
+        let solver = Solver::new().unwrap();
+        let mut guesses = vec![
+            Guess::new("tares", [Misplaced, Correct, Absent, Correct, Absent]),
+            Guess::new("dempt", [Absent, Misplaced, Absent, Absent, Correct]),
+        ];
+        assert_eq!(solver.find_conflicts(&guesses).unwrap(), None);
+
+        // The A of TARES is green, so it cannot be gray in CANAL
+        guesses.push(Guess::new("canal", [Absent; 5]));
+        let conflict = solver.find_conflicts(&guesses).unwrap().unwrap();
+        assert_eq!(
+            conflict,
+            Conflict {
+                guesses: vec![0, 2],
+                green: Some(LetterConstraint::At('a', 1)),
+            }
+        );
+        assert_eq!(
+            conflict.to_string(),
+            "No answer fits guesses 1 and 3: A must be at position 2"
+        );
+
+        let guesses = [Guess::new("xxxxx", [Absent; 5])];
+        assert!(solver.find_conflicts(&guesses).is_err());
+    }
+
+    #[test]
+    fn test_other_word_length() {
+        let words: Vec<Word> = ["salted", "slated", "wasted", "tasted", "lasted"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let solver = Solver::from_words(&words, &[1.; 5]).unwrap();
+        assert_eq!(solver.n_letters(), 6);
+
+        let guess = Guess::new(
+            "wasted",
+            [Absent, Correct, Correct, Correct, Correct, Correct],
+        );
+        let mut remaining = solver.get_remaining_words_idx(&[guess]).unwrap();
+        remaining.sort();
+        assert_eq!(remaining, vec![3, 4]);
+
+        let res = solver
+            .evaluate_guess(&create_word_from_string("tasted"), &remaining, None, true)
+            .unwrap();
+        assert_eq!(res.groups, 2);
+        assert_relative_eq!(res.expected_bits, 1.0);
+        assert!(solver.guess(1, &remaining, 0.1).is_ok());
+    }
+
+    #[test]
+    fn test_guess_by_formula() {
+        let solver = Solver::new().unwrap();
+        let remaining = solver.get_frequent_word_idx();
+
+        // The default ranking as a formula
+        let formula: Formula = "entropy + is_possible * prior / 20 * penalty"
+            .parse()
+            .unwrap();
+        for penalty in [0.0, 10.0] {
+            assert_eq!(
+                solver.guess_by_formula(5, &remaining, &formula, penalty, 10),
+                solver.guess(5, &remaining, penalty)
+            );
+        }
+
+        // Minimize the largest group
+        let formula: Formula = "-max_group".parse().unwrap();
+        let guess = solver
+            .guess_by_formula(1, &remaining, &formula, 0.1, 10)
+            .unwrap()[0];
+        let best = solver
+            .evaluate_guess(&guess, &remaining, None, false)
+            .unwrap();
+        let tarse = create_word_from_string("tarse");
+        let tarse = solver
+            .evaluate_guess(&tarse, &remaining, None, false)
+            .unwrap();
+        assert!(best.max_group_size <= tarse.max_group_size);
+
+        // Two level bits only rank the candidates of the default ranking
+        let solver = test_solver();
+        let formula: Formula = "two_level_bits".parse().unwrap();
+        let guess = solver
+            .guess_by_formula(1, &[0, 1, 2], &formula, 0.1, 2)
+            .unwrap();
+        assert!(solver
+            .guess(2, &[0, 1, 2], 0.1)
+            .unwrap()
+            .contains(&guess[0]));
+        assert_eq!(
+            solver.guess_by_formula(1, &[], &formula, 0.1, 2),
+            Err(EmptyRemaining)
+        );
+    }
+
+    #[test]
+    fn test_random_answer() {
+        let mut rng = StdRng::seed_from_u64(7);
+
+        // Words with a prior of 0 are never picked
+        let mut solver = test_solver();
+        solver.priors = vec![0., 2., 0.];
+        for _ in 0..10 {
+            assert_eq!(
+                solver.random_answer(&mut rng),
+                Some(create_word_from_string("water"))
+            );
+        }
+        solver.priors = vec![0.; 3];
+        assert_eq!(solver.random_answer(&mut rng), None);
+
+        // The daily answer only depends on the day and the puzzle
+        let solver = Solver::new().unwrap();
+        let daily = solver.daily_answer(20000, 0).unwrap();
+        assert_eq!(solver.daily_answer(20000, 0), Some(daily));
+        let answers = solver.get_words_from_idx(&solver.get_frequent_word_idx());
+        assert!(answers.contains(&daily));
+        assert!((0..10).any(|day| solver.daily_answer(day, 0) != Some(daily)));
+    }
+
+    #[test]
+    fn test_letter_frequencies() {
+        let solver = test_solver();
+        let frequencies = solver.get_letter_frequencies(&[0, 1, 2]);
+        assert_eq!(frequencies[&'e'], 3);
+        assert_eq!(frequencies[&'s'], 2);
+        assert_eq!(frequencies[&'o'], 1);
+        assert_eq!(frequencies.get(&'z'), None);
+
+        // SLATE, WATER and GOOSE
+        let frequencies = solver.positional_letter_frequencies(&[0, 1, 2]);
+        assert_eq!(frequencies.len(), 5);
+        let index = |c: char| solver.alphabet().binary_search(&c).unwrap();
+        assert_eq!(frequencies[4][index('e')], 2);
+        assert_eq!(frequencies[4][index('r')], 1);
+        assert_eq!(frequencies[1][index('o')], 1);
+        assert!(frequencies.iter().all(|f| f.iter().sum::<usize>() == 3));
+        let empty = vec![vec![0; solver.alphabet().len()]; 5];
+        assert_eq!(solver.positional_letter_frequencies(&[]), empty);
+    }
+
+    #[test]
+    fn test_explain_elimination() {
+        let solver = test_solver();
+        let guesses = vec![Guess::new(
+            "slate",
+            [Misplaced, Absent, Absent, Absent, Correct],
+        )];
+
+        let goose = create_word_from_string("goose");
+        assert_eq!(solver.explain_elimination(&goose, &guesses), None);
+
+        let water = create_word_from_string("water");
+        assert_eq!(
+            solver.explain_elimination(&water, &guesses),
+            Some(Elimination::Guess {
+                guess_number: 1,
+                guess: guesses[0],
+                constraint: Some(LetterConstraint::AtLeast('s', 1)),
+            })
+        );
+
+        let weary = create_word_from_string("weary");
+        assert_eq!(
+            solver.explain_elimination(&weary, &guesses),
+            Some(Elimination::NotInWordList)
+        );
     }
 }
@@ -0,0 +1,270 @@
+use crate::solver::{entropy, EmptyRemaining, Solver, SolverError};
+use crate::wordle::*;
+
+/// One of the boards of a multi board game
+struct Board {
+    guesses: Vec<Guess>,
+    remaining: Vec<usize>,
+    solved: bool,
+}
+
+/// Several boards that are played with the same guesses, like Quordle (4
+/// boards) or Octordle (8 boards). Every board has its own answer, so every
+/// guess gets a status per board.
+pub struct MultiSolver<'a> {
+    solver: &'a Solver,
+    boards: Vec<Board>,
+}
+
+/// The evaluation of a guess for all boards
+#[derive(Clone, Debug)]
+pub struct MultiEvaluation {
+    pub word: Word,
+    /// The expected bits for each board, `None` for solved boards
+    pub bits: Vec<Option<f32>>,
+    /// The sum of the expected bits of all boards
+    pub total_bits: f32,
+    /// The expected number of boards the guess solves
+    pub solves: f32,
+    /// The boards the word is a possible answer for
+    pub possible: Vec<usize>,
+}
+
+impl MultiEvaluation {
+    /// The combined score of the guess. The answers of the boards are
+    /// independent, so the bits of the boards add up. Solving a board is
+    /// worth another bit, since it does not have to be guessed later.
+    pub fn score(&self) -> f32 {
+        self.total_bits + self.solves
+    }
+}
+
+impl<'a> MultiSolver<'a> {
+    /// The boards after their guesses. A board is solved with its first
+    /// guess that has only correct letters; later guesses are ignored.
+    pub fn new(solver: &'a Solver, boards: &[Vec<Guess>]) -> Result<MultiSolver<'a>, SolverError> {
+        let solved = solved_pattern(solver.n_letters);
+        let boards = boards
+            .iter()
+            .map(|guesses| {
+                let n = guesses
+                    .iter()
+                    .position(|g| g.status == solved)
+                    .map_or(guesses.len(), |i| i + 1);
+                let guesses = guesses[..n].to_vec();
+                let mut remaining = solver.get_remaining_words_idx(&guesses)?;
+                remaining.sort_unstable();
+                Ok(Board {
+                    remaining,
+                    solved: n > 0 && guesses[n - 1].status == solved,
+                    guesses,
+                })
+            })
+            .collect::<Result<_, SolverError>>()?;
+        Ok(MultiSolver { solver, boards })
+    }
+
+    pub fn n_boards(&self) -> usize {
+        self.boards.len()
+    }
+
+    /// The guesses that count for the board
+    pub fn guesses(&self, board: usize) -> &[Guess] {
+        &self.boards[board].guesses
+    }
+
+    pub fn remaining(&self, board: usize) -> &[usize] {
+        &self.boards[board].remaining
+    }
+
+    pub fn is_solved(&self, board: usize) -> bool {
+        self.boards[board].solved
+    }
+
+    /// Whether all boards are solved
+    pub fn is_done(&self) -> bool {
+        self.boards.iter().all(|board| board.solved)
+    }
+
+    /// Play a guess with the status of each board. Solved boards ignore it.
+    pub fn play(&mut self, word: Word, statuses: &[Pattern]) {
+        let solved = solved_pattern(self.solver.n_letters);
+        for (board, &status) in self.boards.iter_mut().zip(statuses) {
+            if board.solved {
+                continue;
+            }
+            let guess = Guess { word, status };
+            board
+                .remaining
+                .retain(|&i| self.solver.pattern_of(&self.solver.words[i], &word) == status);
+            board.guesses.push(guess);
+            board.solved = status == solved;
+        }
+    }
+
+    /// The n best guesses for all boards, ranked by [`MultiEvaluation::score`].
+    /// A word that is the last remaining word of a board comes first, since
+    /// it has to be guessed anyway and still tells something about the
+    /// other boards.
+    pub fn guess(&self, n: usize) -> Result<Vec<MultiEvaluation>, EmptyRemaining> {
+        let open: Vec<usize> = (0..self.boards.len())
+            .filter(|&b| !self.boards[b].solved)
+            .collect();
+        if open.iter().any(|&b| self.boards[b].remaining.is_empty()) {
+            return Err(EmptyRemaining);
+        }
+
+        let all_words: Vec<usize> = (0..self.solver.words.len()).collect();
+        // Boards with the same remaining words, e.g. before the first guess,
+        // have the same entropies
+        let mut entropies: Vec<(&[usize], Vec<f32>)> = vec![];
+        for &b in &open {
+            let remaining = &self.boards[b].remaining[..];
+            if entropies.iter().any(|(r, _)| *r == remaining) {
+                continue;
+            }
+            let bits = self.solver.entropies(&all_words, remaining);
+            entropies.push((remaining, bits));
+        }
+        let bits_of = |b: usize| {
+            let remaining = &self.boards[b].remaining[..];
+            &entropies.iter().find(|(r, _)| *r == remaining).unwrap().1
+        };
+
+        let mut ranked: Vec<(bool, f32, usize)> = all_words
+            .iter()
+            .map(|&id| {
+                let evaluation = self.evaluate_id(id, |b| bits_of(b)[id]);
+                let forced = open.iter().any(|&b| self.boards[b].remaining == [id]);
+                (forced, evaluation.score(), id)
+            })
+            .collect();
+        // The sort is stable, so ties keep the order of the word list
+        ranked.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.total_cmp(&a.1)));
+        Ok(ranked
+            .iter()
+            .take(n)
+            .map(|&(_, _, id)| self.evaluate_id(id, |b| bits_of(b)[id]))
+            .collect())
+    }
+
+    /// Evaluate a guess for all boards
+    pub fn evaluate(&self, word: &Word) -> Option<MultiEvaluation> {
+        let id = self.solver.get_id_for_word(word)?;
+        Some(self.evaluate_id(id, |b| {
+            let distribution = self
+                .solver
+                .get_mapping_distribution(&[id], &self.boards[b].remaining);
+            entropy(&distribution.row(0))
+        }))
+    }
+
+    fn evaluate_id(&self, id: usize, bits: impl Fn(usize) -> f32) -> MultiEvaluation {
+        let mut evaluation = MultiEvaluation {
+            word: self.solver.words[id],
+            bits: vec![None; self.boards.len()],
+            total_bits: 0.0,
+            solves: 0.0,
+            possible: vec![],
+        };
+        for (b, board) in self.boards.iter().enumerate() {
+            if board.solved {
+                continue;
+            }
+            let board_bits = bits(b);
+            evaluation.bits[b] = Some(board_bits);
+            evaluation.total_bits += board_bits;
+            if board.remaining.binary_search(&id).is_ok() {
+                let total: f32 = board.remaining.iter().map(|&i| self.solver.priors[i]).sum();
+                evaluation.solves += self.solver.priors[id] / total;
+                evaluation.possible.push(b);
+            }
+        }
+        evaluation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::solver_for;
+
+    fn play(guess: &str, answers: &[&str]) -> (Word, Vec<Pattern>) {
+        let guess = create_word_from_string(guess);
+        let statuses = answers
+            .iter()
+            .map(|answer| create_word_from_string(answer).pattern(&guess))
+            .collect();
+        (guess, statuses)
+    }
+
+    #[test]
+    fn test_boards() {
+        let solver = solver_for(&["bills", "fills", "hills", "slate", "crane", "crate"]);
+        let mut multi = MultiSolver::new(&solver, &[vec![], vec![]]).unwrap();
+        assert_eq!(multi.n_boards(), 2);
+        assert_eq!(multi.remaining(0).len(), 6);
+
+        let (word, statuses) = play("crate", &["crate", "hills"]);
+        multi.play(word, &statuses);
+        assert!(multi.is_solved(0));
+        assert!(!multi.is_done());
+        assert_eq!(multi.remaining(1), &[0, 1, 2]);
+
+        // The solved board ignores later guesses
+        let (word, statuses) = play("hills", &["crate", "hills"]);
+        multi.play(word, &statuses);
+        assert!(multi.is_done());
+        assert_eq!(multi.guesses(0).len(), 1);
+        assert_eq!(multi.guesses(1).len(), 2);
+
+        // The same state from the guesses of each board
+        let boards: Vec<Vec<Guess>> = (0..2).map(|b| multi.guesses(b).to_vec()).collect();
+        let rebuilt = MultiSolver::new(&solver, &boards).unwrap();
+        assert!(rebuilt.is_done());
+    }
+
+    #[test]
+    fn test_combined_guess() {
+        let solver = solver_for(&["bills", "fills", "hills", "slate", "crane", "crate"]);
+        let boards = MultiSolver::new(&solver, &[vec![], vec![]]).unwrap();
+        let best = boards.guess(6).unwrap();
+        // Both boards are the same, so the bits add up
+        let single = boards.evaluate(&best[0].word).unwrap();
+        assert_eq!(best[0].bits[0], best[0].bits[1]);
+        assert_eq!(best[0].total_bits, single.total_bits);
+        assert!(best.windows(2).all(|w| w[0].score() >= w[1].score()));
+
+        // A board with a single word left is finished first
+        let (word, status) = play("crane", &["crate"]);
+        let boards = MultiSolver::new(
+            &solver,
+            &[
+                vec![],
+                vec![Guess {
+                    word,
+                    status: status[0],
+                }],
+            ],
+        )
+        .unwrap();
+        let best = boards.guess(1).unwrap();
+        assert_eq!(best[0].word, create_word_from_string("crate"));
+        assert_eq!(best[0].possible, vec![0, 1]);
+
+        // No word matches the guesses of the second board
+        let (word, status) = play("bills", &["billy"]);
+        let boards = MultiSolver::new(
+            &solver,
+            &[
+                vec![],
+                vec![Guess {
+                    word,
+                    status: status[0],
+                }],
+            ],
+        )
+        .unwrap();
+        assert_eq!(boards.guess(1).err(), Some(EmptyRemaining));
+    }
+}
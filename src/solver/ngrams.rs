@@ -0,0 +1,109 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use super::Solver;
+use crate::wordle::Word;
+
+/// A sequence of adjacent letters with the number of words it appears in
+/// and a guess that tests it
+#[derive(Clone, Debug, PartialEq)]
+pub struct NgramCount {
+    pub ngram: String,
+    pub count: usize,
+    pub probe: Option<Word>,
+}
+
+/// All distinct sequences of n adjacent letters of a word
+fn ngrams(word: &Word, n: usize) -> Vec<String> {
+    let chars: Vec<char> = word.chars().iter().flatten().copied().collect();
+    let mut res: Vec<String> = chars
+        .windows(n)
+        .map(|window| window.iter().collect())
+        .collect();
+    res.sort();
+    res.dedup();
+    res
+}
+
+impl Solver {
+    /// The `top` most common sequences of n adjacent letters in the words,
+    /// counted once per word. Every n-gram comes with the allowed guess
+    /// that contains it and as many of the other top n-grams as possible.
+    pub fn get_ngram_frequencies(&self, idx: &[usize], n: usize, top: usize) -> Vec<NgramCount> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for &i in idx {
+            for ngram in ngrams(&self.words[i], n) {
+                *counts.entry(ngram).or_insert(0) += 1;
+            }
+        }
+        let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+        counts.sort_by_key(|(ngram, count)| (Reverse(*count), ngram.clone()));
+        counts.truncate(top);
+
+        let top_ngrams: Vec<&String> = counts.iter().map(|(ngram, _)| ngram).collect();
+        let covered: Vec<Vec<String>> = self
+            .words
+            .iter()
+            .map(|word| {
+                ngrams(word, n)
+                    .into_iter()
+                    .filter(|ngram| top_ngrams.contains(&ngram))
+                    .collect()
+            })
+            .collect();
+
+        counts
+            .iter()
+            .map(|(ngram, count)| {
+                let probe = covered
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, c)| c.contains(ngram))
+                    .max_by_key(|(i, c)| (c.len(), Reverse(*i)))
+                    .map(|(i, _)| self.words[i]);
+                NgramCount {
+                    ngram: ngram.clone(),
+                    count: *count,
+                    probe,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wordle::create_word_from_string;
+
+    #[test]
+    fn test_ngrams() {
+        let word = create_word_from_string("eerie");
+        assert_eq!(ngrams(&word, 2), vec!["ee", "er", "ie", "ri"]);
+        assert_eq!(ngrams(&word, 3), vec!["eer", "eri", "rie"]);
+    }
+
+    #[test]
+    fn test_ngram_frequencies() {
+        let words: Vec<Word> = ["shine", "shore", "chore", "thorn"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let solver = Solver::from_words(&words, &[1., 1., 1., 0.]).unwrap();
+
+        let bigrams = solver.get_ngram_frequencies(&[0, 1, 2], 2, 3);
+        assert_eq!(
+            bigrams
+                .iter()
+                .map(|c| (c.ngram.as_str(), c.count))
+                .collect::<Vec<_>>(),
+            vec![("ho", 2), ("or", 2), ("re", 2)]
+        );
+        // SHORE and CHORE test all three bigrams, SHORE comes first
+        assert_eq!(bigrams[0].probe, Some(create_word_from_string("shore")));
+
+        let trigrams = solver.get_ngram_frequencies(&[0, 1, 2], 3, 1);
+        assert_eq!(trigrams[0].ngram, "hor");
+        assert_eq!(trigrams[0].count, 2);
+    }
+}
@@ -0,0 +1,195 @@
+//! Compare opening words side by side.
+//!
+//! The candidates are the best first guesses of the default ranking, each
+//! with its expected bits, two level bits and largest group over all
+//! answers. How well a strategy does with an opener is only known from
+//! playing it, so the benchmark columns are filled in by the caller.
+use anyhow::{bail, Error};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::solver::{GuessEvaluation, Solver};
+use crate::wordle::*;
+
+/// The numbers of an opener
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpenerStats {
+    pub word: Word,
+    pub expected_bits: f32,
+    pub two_level_bits: f32,
+    /// The number of answers in the largest group the opener leaves
+    pub max_group_size: usize,
+    pub groups: usize,
+    /// The average number of guesses of the solved answers of a benchmark
+    pub mean_guesses: Option<f64>,
+    /// The answers the benchmark did not solve
+    pub failed: Option<usize>,
+}
+
+/// The column the openers are sorted by, the best first
+///
+/// # Example
+///
+/// ```
+/// use wordlebot::solver::opening::OpenerSort;
+/// let sort: OpenerSort = "two-level".parse().unwrap();
+/// assert_eq!(sort, OpenerSort::TwoLevel);
+/// assert_eq!(sort.to_string(), "two-level");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OpenerSort {
+    /// The most expected bits
+    #[default]
+    Bits,
+    /// The most two level bits
+    TwoLevel,
+    /// The smallest largest group
+    Worst,
+    /// The fewest failures and then the fewest guesses of the benchmark
+    Mean,
+}
+
+impl FromStr for OpenerSort {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "bits" => Ok(OpenerSort::Bits),
+            "two-level" => Ok(OpenerSort::TwoLevel),
+            "worst" => Ok(OpenerSort::Worst),
+            "mean" => Ok(OpenerSort::Mean),
+            _ => bail!(
+                "Unknown column '{}', expected bits, two-level, worst or mean",
+                s
+            ),
+        }
+    }
+}
+
+impl fmt::Display for OpenerSort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpenerSort::Bits => write!(f, "bits"),
+            OpenerSort::TwoLevel => write!(f, "two-level"),
+            OpenerSort::Worst => write!(f, "worst"),
+            OpenerSort::Mean => write!(f, "mean"),
+        }
+    }
+}
+
+impl OpenerStats {
+    fn from_evaluation(evaluation: &GuessEvaluation) -> OpenerStats {
+        OpenerStats {
+            word: evaluation.word,
+            expected_bits: evaluation.expected_bits,
+            two_level_bits: evaluation
+                .two_level_bits
+                .unwrap_or(evaluation.expected_bits),
+            max_group_size: evaluation.max_group_size,
+            groups: evaluation.groups,
+            mean_guesses: None,
+            failed: None,
+        }
+    }
+
+    /// Sort the openers by a column, ties by expected bits. Openers without
+    /// a benchmark come last for [`OpenerSort::Mean`].
+    pub fn sort(openers: &mut [OpenerStats], by: OpenerSort) {
+        openers.sort_by(|a, b| {
+            let order = match by {
+                OpenerSort::Bits => std::cmp::Ordering::Equal,
+                OpenerSort::TwoLevel => b.two_level_bits.total_cmp(&a.two_level_bits),
+                OpenerSort::Worst => a.max_group_size.cmp(&b.max_group_size),
+                OpenerSort::Mean => {
+                    let failed = |o: &OpenerStats| (o.failed.is_none(), o.failed);
+                    let mean = |o: &OpenerStats| o.mean_guesses.unwrap_or(0.0);
+                    failed(a).cmp(&failed(b)).then(mean(a).total_cmp(&mean(b)))
+                }
+            };
+            order.then(b.expected_bits.total_cmp(&a.expected_bits))
+        });
+    }
+
+    /// The openers as CSV with a header line. The benchmark columns are
+    /// empty without a benchmark.
+    pub fn csv(openers: &[OpenerStats]) -> String {
+        let mut lines =
+            vec!["opener,expected_bits,two_level_bits,max_group,groups,mean,failed".to_string()];
+        for o in openers {
+            lines.push(format!(
+                "{},{:.4},{:.4},{},{},{},{}",
+                o.word.to_string().to_lowercase(),
+                o.expected_bits,
+                o.two_level_bits,
+                o.max_group_size,
+                o.groups,
+                o.mean_guesses
+                    .map_or(String::new(), |m| format!("{:.4}", m)),
+                o.failed.map_or(String::new(), |f| f.to_string())
+            ));
+        }
+        lines.join("\n") + "\n"
+    }
+}
+
+impl Solver {
+    /// The `n` best openers of the default ranking over all answers, see
+    /// the [module](self)
+    pub fn opener_stats(&self, n: usize) -> Vec<OpenerStats> {
+        let answers = self.get_frequent_word_idx();
+        let Ok(openers) = self.guess(n, &answers, 0.0) else {
+            return vec![];
+        };
+        // The openers are words of the list
+        openers
+            .iter()
+            .filter_map(|word| self.evaluate_guess(word, &answers, None, true).ok())
+            .map(|evaluation| OpenerStats::from_evaluation(&evaluation))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::{solver_for, SMALL_WORDS};
+
+    #[test]
+    fn test_opener_stats() {
+        let solver = solver_for(&SMALL_WORDS);
+        let mut openers = solver.opener_stats(3);
+        assert_eq!(openers.len(), 3);
+        let answers = solver.get_frequent_word_idx();
+        let ranked = solver.guess(3, &answers, 0.0).unwrap();
+        let words: Vec<Word> = openers.iter().map(|o| o.word).collect();
+        assert_eq!(words, ranked);
+        let best = solver
+            .evaluate_guess(&openers[0].word, &answers, None, true)
+            .unwrap();
+        assert_eq!(openers[0].max_group_size, best.max_group_size);
+        assert_eq!(Some(openers[0].two_level_bits), best.two_level_bits);
+
+        OpenerStats::sort(&mut openers, OpenerSort::Worst);
+        assert!(openers
+            .windows(2)
+            .all(|w| w[0].max_group_size <= w[1].max_group_size));
+
+        // Benchmarked openers come first, the fewest failures before the
+        // fewest guesses
+        openers[0].failed = Some(1);
+        openers[0].mean_guesses = Some(2.0);
+        openers[1].failed = Some(0);
+        openers[1].mean_guesses = Some(3.0);
+        let (a, b, c) = (openers[0].word, openers[1].word, openers[2].word);
+        OpenerStats::sort(&mut openers, OpenerSort::Mean);
+        let order: Vec<Word> = openers.iter().map(|o| o.word).collect();
+        assert_eq!(order, [b, a, c]);
+
+        let csv = OpenerStats::csv(&openers);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].ends_with(",3.0000,0"));
+        assert!(lines[3].ends_with(",,"));
+        assert!("best".parse::<OpenerSort>().is_err());
+    }
+}
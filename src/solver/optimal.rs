@@ -0,0 +1,383 @@
+use crate::solver::parallel::*;
+use anyhow::{bail, Context, Result};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use crate::solver::{tree::DecisionTree, width::WidthSchedule, Solver};
+use crate::wordle::*;
+
+/// A solved part of the search. The cost is the total number of guesses
+/// needed for all answers of the subtree.
+struct Subtree {
+    cost: usize,
+    guess: usize,
+    // The guess is one of the answers
+    solves: bool,
+    children: Vec<(Pattern, Subtree)>,
+}
+
+/// A depth first search over the guesses with the highest entropy
+struct Search<'a> {
+    solver: &'a Solver,
+    width: &'a WidthSchedule,
+}
+
+impl Solver {
+    /// Build the decision tree with the fewest expected guesses, where every
+    /// answer is equally likely. No answer takes more than `depth` guesses.
+    ///
+    /// At every step the `width` guesses with the highest entropy are tried
+    /// and the search within them is exhaustive. A guess is abandoned as soon
+    /// as it can not beat the best guess so far, since every answer of a
+    /// group of `k` answers except one needs at least two more guesses.
+    pub fn build_decision_tree(
+        &self,
+        depth: usize,
+        starting_word: Option<Word>,
+        width: &WidthSchedule,
+    ) -> Result<DecisionTree> {
+        let answers = self.get_frequent_word_idx();
+        let search = Search {
+            solver: self,
+            width,
+        };
+        let candidates = match starting_word {
+            Some(word) => vec![self
+                .get_id_for_word(&word)
+                .with_context(|| format!("{} is not in the word list", word))?],
+            None => search.candidates(&answers),
+        };
+        let best = candidates
+            .iter()
+            .filter_map(|&guess| search.split_parallel(guess, &answers, depth))
+            .min_by_key(|tree| tree.cost);
+        let Some(best) = best else {
+            bail!("No decision tree solves all answers in {} guesses", depth)
+        };
+
+        let mut games = vec![];
+        self.collect_games(&best, &mut vec![], &mut games);
+        DecisionTree::from_games(&games)
+    }
+
+    /// Whether some strategy finds every answer within `rounds` guesses.
+    /// The answers are word ids in word list order.
+    ///
+    /// The search tries every guess, so it is only feasible for few answers
+    /// or rounds. It gives up with `None` after `budget` guesses are tried.
+    pub fn solvable_within(&self, answers: &[usize], rounds: usize, budget: usize) -> Option<bool> {
+        let all = WidthSchedule::constant(self.words.len());
+        let search = Search {
+            solver: self,
+            width: &all,
+        };
+        let mut budget = budget;
+        search.solvable(answers, rounds, &mut budget)
+    }
+
+    /// The paths to all answers of the subtree
+    fn collect_games(&self, tree: &Subtree, path: &mut Vec<Guess>, games: &mut Vec<Vec<Guess>>) {
+        let word = self.words[tree.guess];
+        if tree.solves {
+            let mut game = path.clone();
+            game.push(Guess {
+                word,
+                status: solved_pattern(self.n_letters),
+            });
+            games.push(game);
+        }
+        for (pattern, child) in &tree.children {
+            path.push(Guess {
+                word,
+                status: *pattern,
+            });
+            self.collect_games(child, path, games);
+            path.pop();
+        }
+    }
+}
+
+impl Search<'_> {
+    /// The best subtree for the answers that costs less than `bound`
+    fn search(&self, answers: &[usize], depth: usize, bound: usize) -> Option<Subtree> {
+        let n = answers.len();
+        if depth == 0 || lower_bound(n) >= bound {
+            return None;
+        }
+        if n == 1 {
+            return Some(Subtree {
+                cost: 1,
+                guess: answers[0],
+                solves: true,
+                children: vec![],
+            });
+        }
+
+        let mut best: Option<Subtree> = None;
+        let mut bound = bound;
+        for guess in self.candidates(answers) {
+            if let Some(tree) = self.split(guess, answers, depth, bound) {
+                bound = tree.cost;
+                best = Some(tree);
+                // Nothing can beat a guess that reaches the lower bound
+                if bound == lower_bound(n) {
+                    break;
+                }
+            }
+        }
+        best
+    }
+
+    /// The subtree for a guess that costs less than `bound`
+    fn split(
+        &self,
+        guess: usize,
+        answers: &[usize],
+        depth: usize,
+        bound: usize,
+    ) -> Option<Subtree> {
+        let solved = solved_pattern(self.solver.n_letters);
+        let mut groups = self.groups(guess, answers)?;
+
+        // The lower bound of the groups that are not searched yet
+        let mut pending: usize = groups
+            .iter()
+            .filter(|(pattern, _)| *pattern != solved)
+            .map(|(_, group)| lower_bound(group.len()))
+            .sum();
+        let mut cost = answers.len();
+        if cost + pending >= bound {
+            return None;
+        }
+
+        // Large groups are the most likely to exceed the bound
+        groups.sort_by_key(|(_, group)| Reverse(group.len()));
+        let mut solves = false;
+        let mut children = vec![];
+        for (pattern, group) in groups {
+            if pattern == solved {
+                solves = true;
+                continue;
+            }
+            pending -= lower_bound(group.len());
+            let child = self.search(&group, depth - 1, bound - cost - pending)?;
+            cost += child.cost;
+            children.push((pattern, child));
+        }
+        Some(Subtree {
+            cost,
+            guess,
+            solves,
+            children,
+        })
+    }
+
+    /// See [`Solver::solvable_within`]
+    fn solvable(&self, answers: &[usize], rounds: usize, budget: &mut usize) -> Option<bool> {
+        match (answers.len(), rounds) {
+            (0, _) => return Some(true),
+            (_, 0) => return Some(false),
+            (1, _) => return Some(true),
+            (_, 1) => return Some(false),
+            // Guessing one of two answers tells which one it is
+            (2, _) => return Some(true),
+            _ => {}
+        }
+        let solved = solved_pattern(self.solver.n_letters);
+        if rounds == 2 {
+            // Every answer needs its own pattern. The answers are the most
+            // likely to separate the others, so they are tried first.
+            for guess in answers.iter().copied().chain(0..self.solver.words.len()) {
+                *budget = budget.checked_sub(1)?;
+                let mut patterns = Vec::with_capacity(answers.len());
+                self.solver
+                    .mappings
+                    .row(guess)
+                    .for_each(answers, |_, pattern| patterns.push(pattern));
+                patterns.sort_unstable();
+                patterns.dedup();
+                if patterns.len() == answers.len() {
+                    return Some(true);
+                }
+            }
+            return Some(false);
+        }
+
+        'guesses: for guess in self.candidates(answers) {
+            *budget = budget.checked_sub(1)?;
+            let Some(mut groups) = self.groups(guess, answers) else {
+                continue;
+            };
+            groups.retain(|(pattern, _)| *pattern != solved);
+            // Large groups are the most likely to fail
+            groups.sort_by_key(|(_, group)| Reverse(group.len()));
+            for (_, group) in groups {
+                if !self.solvable(&group, rounds - 1, budget)? {
+                    continue 'guesses;
+                }
+            }
+            return Some(true);
+        }
+        Some(false)
+    }
+
+    /// Like [`Search::split`] without a bound, but searches the groups in
+    /// parallel
+    fn split_parallel(&self, guess: usize, answers: &[usize], depth: usize) -> Option<Subtree> {
+        if depth == 0 {
+            return None;
+        }
+        let solved = solved_pattern(self.solver.n_letters);
+        let groups = self.groups(guess, answers)?;
+        let solves = groups.iter().any(|(pattern, _)| *pattern == solved);
+        let children = groups
+            .into_par_iter()
+            .filter(|(pattern, _)| *pattern != solved)
+            .map(|(pattern, group)| Some((pattern, self.search(&group, depth - 1, usize::MAX)?)))
+            .collect::<Option<Vec<_>>>()?;
+        Some(Subtree {
+            cost: answers.len() + children.iter().map(|(_, child)| child.cost).sum::<usize>(),
+            guess,
+            solves,
+            children,
+        })
+    }
+
+    /// The answers grouped by their pattern for the guess, sorted by the
+    /// pattern. `None` if the guess does not tell the answers apart.
+    fn groups(&self, guess: usize, answers: &[usize]) -> Option<Vec<(Pattern, Vec<usize>)>> {
+        let mut groups: BTreeMap<Pattern, Vec<usize>> = BTreeMap::new();
+        self.solver
+            .mappings
+            .row(guess)
+            .for_each(answers, |i, pattern| {
+                groups.entry(pattern).or_default().push(answers[i])
+            });
+        let solved = solved_pattern(self.solver.n_letters);
+        if groups.len() == 1 && !groups.contains_key(&solved) {
+            return None;
+        }
+        Some(groups.into_iter().collect())
+    }
+
+    /// The guesses with the highest entropy for the answers, which are all
+    /// equally likely. Ties prefer possible answers.
+    fn candidates(&self, answers: &[usize]) -> Vec<usize> {
+        let n = answers.len();
+        let mut ranked: Vec<(f32, bool, usize)> = (0..self.solver.words.len())
+            .into_par_iter()
+            .map(|id| {
+                let mut patterns = Vec::with_capacity(n);
+                self.solver
+                    .mappings
+                    .row(id)
+                    .for_each(answers, |_, pattern| patterns.push(pattern));
+                patterns.sort_unstable();
+                let entropy: f32 = patterns
+                    .chunk_by(|a, b| a == b)
+                    .map(|group| {
+                        let p = group.len() as f32 / n as f32;
+                        -p * p.log2()
+                    })
+                    .sum();
+                // The answers are sorted, since they are in word list order
+                (entropy, answers.binary_search(&id).is_ok(), id)
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0).then(b.1.cmp(&a.1)));
+        ranked
+            .iter()
+            .take(self.width.width(n))
+            .map(|&(_, _, id)| id)
+            .collect()
+    }
+}
+
+/// The fewest guesses needed for `n` answers: one answer can be solved with
+/// the first guess, all others need at least two.
+fn lower_bound(n: usize) -> usize {
+    2 * n - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::solver_for;
+
+    #[test]
+    fn test_small_trees() {
+        let solver = solver_for(&["slate"]);
+        let tree = solver
+            .build_decision_tree(6, None, &WidthSchedule::constant(5))
+            .unwrap();
+        assert_eq!(tree.to_text(), "slate GGGGG1\n");
+
+        // No word tells all others apart, so two words are left after the
+        // first guess
+        let solver = solver_for(&["bears", "beers", "hears", "tears"]);
+        let tree = solver
+            .build_decision_tree(6, None, &WidthSchedule::constant(5))
+            .unwrap();
+        assert_eq!(tree.average_guesses(), 2.0);
+    }
+
+    #[test]
+    fn test_depth() {
+        // Only one letter differs, so every guess leaves the other words
+        let solver = solver_for(&["bills", "fills", "hills", "kills", "mills"]);
+        let width = WidthSchedule::constant(20);
+        assert!(solver.build_decision_tree(2, None, &width).is_err());
+        let tree = solver.build_decision_tree(5, None, &width).unwrap();
+        assert_eq!(tree.average_guesses(), 3.0);
+
+        let start = create_word_from_string("mills");
+        let tree = solver.build_decision_tree(5, Some(start), &width).unwrap();
+        assert_eq!(tree.next_guess(&[]), Some(start));
+        assert!(solver
+            .build_decision_tree(5, Some(create_word_from_string("crane")), &width)
+            .is_err());
+    }
+
+    #[test]
+    fn test_solvable_within() {
+        // Every guess only tells one word apart
+        let solver = solver_for(&["bills", "fills", "hills", "kills", "mills"]);
+        let answers = solver.get_frequent_word_idx();
+        assert_eq!(solver.solvable_within(&answers, 4, 1000), Some(false));
+        assert_eq!(solver.solvable_within(&answers, 5, 1000), Some(true));
+        assert_eq!(solver.solvable_within(&answers[..3], 2, 1000), Some(false));
+        assert_eq!(solver.solvable_within(&answers[..2], 2, 1000), Some(true));
+        // The search gives up
+        assert_eq!(solver.solvable_within(&answers, 4, 3), None);
+
+        let solver = solver_for(&["bills", "fills", "hills", "fight"]);
+        let answers = &solver.get_frequent_word_idx()[..3];
+        assert_eq!(solver.solvable_within(answers, 2, 1000), Some(true));
+    }
+
+    #[test]
+    fn test_optimal_tree() {
+        let solver = solver_for(&[
+            "cigar", "rebut", "sissy", "humph", "awake", "blush", "focal", "evade", "naval",
+            "serve", "heath", "dwarf", "model", "karma", "stink", "grade", "quiet", "bench",
+        ]);
+        let width = WidthSchedule::constant(18);
+        let tree = solver.build_decision_tree(6, None, &width).unwrap();
+        // The tree solves every answer
+        for word in &solver.words {
+            let mut guesses = vec![];
+            loop {
+                let guess = tree.next_guess(&guesses).unwrap();
+                guesses.push(Guess::from_word(guess, &word.compare(&guess)));
+                if guess == *word {
+                    break;
+                }
+            }
+        }
+        // No greedy tree is better than the searched one
+        let greedy = solver
+            .build_decision_tree(6, None, &WidthSchedule::constant(1))
+            .unwrap();
+        assert!(tree.average_guesses() <= greedy.average_guesses());
+    }
+}
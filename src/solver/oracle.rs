@@ -0,0 +1,233 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::fmt;
+use std::io::{BufRead, Write};
+
+use crate::solver::Solver;
+use crate::wordle::*;
+
+/// Colors the guesses of a game. Everything that plays a game, like the
+/// benchmark or the play mode of the TUI, asks an oracle for the patterns
+/// instead of comparing with a known answer, so a new kind of host only has
+/// to implement this trait.
+///
+/// # Example
+///
+/// ```no_run
+/// use wordlebot::solver::oracle::{FeedbackOracle, FixedAnswer};
+/// use wordlebot::solver::Solver;
+/// use wordlebot::wordle::*;
+///
+/// let solver = Solver::new().unwrap();
+/// let mut oracle = FixedAnswer(create_word_from_string("goose"));
+/// let guess = create_word_from_string("slate");
+/// let pattern = oracle.respond(&guess, &[], &solver).unwrap();
+/// assert_eq!(decode_status(pattern, 5)[0], LetterStatus::Misplaced);
+/// ```
+pub trait FeedbackOracle: fmt::Display {
+    /// The pattern of a guess after the earlier guesses, or `None` if the
+    /// game can not go on, e.g. because no word is left
+    fn respond(&mut self, guess: &Word, guesses: &[Guess], solver: &Solver) -> Option<Pattern>;
+
+    /// The answer, if the oracle knows it
+    fn answer(&self) -> Option<Word> {
+        None
+    }
+}
+
+/// A host with a fixed answer, which colors the guesses by the scoring rule
+/// of the solver
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedAnswer(pub Word);
+
+impl FeedbackOracle for FixedAnswer {
+    fn respond(&mut self, guess: &Word, _: &[Guess], solver: &Solver) -> Option<Pattern> {
+        Some(solver.pattern_of(&self.0, guess))
+    }
+
+    fn answer(&self) -> Option<Word> {
+        Some(self.0)
+    }
+}
+
+impl fmt::Display for FixedAnswer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A host like Absurdle that keeps the largest group of the remaining
+/// words, see [`Solver::adversarial_pattern`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Adversarial;
+
+impl FeedbackOracle for Adversarial {
+    fn respond(&mut self, guess: &Word, guesses: &[Guess], solver: &Solver) -> Option<Pattern> {
+        let remaining = solver.get_remaining_words_idx(guesses).ok()?;
+        solver.adversarial_pattern(guess, &remaining)
+    }
+}
+
+impl fmt::Display for Adversarial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Absurdle")
+    }
+}
+
+/// A host with a fixed answer that makes mistakes: with the probability
+/// `error_rate` one letter of a guess gets a wrong status. A guess that
+/// solves the game is always colored correctly, so the game can end.
+#[derive(Clone, Debug)]
+pub struct Noisy {
+    answer: Word,
+    error_rate: f64,
+    rng: StdRng,
+}
+
+impl Noisy {
+    /// A noisy host whose mistakes are the same for the same seed
+    pub fn new(answer: Word, error_rate: f64, seed: u64) -> Noisy {
+        Noisy {
+            answer,
+            error_rate: error_rate.clamp(0.0, 1.0),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl FeedbackOracle for Noisy {
+    fn respond(&mut self, guess: &Word, _: &[Guess], solver: &Solver) -> Option<Pattern> {
+        let mut status = decode_status(solver.pattern_of(&self.answer, guess), guess.n_letters());
+        if *guess == self.answer || !self.rng.gen_bool(self.error_rate) {
+            return Some(encode_status(&status));
+        }
+        let i = self.rng.gen_range(0..status.len());
+        status[i] = match (status[i], self.rng.gen_bool(0.5)) {
+            (LetterStatus::Absent, true) | (LetterStatus::Correct, false) => {
+                LetterStatus::Misplaced
+            }
+            (LetterStatus::Absent, false) | (LetterStatus::Misplaced, true) => {
+                LetterStatus::Correct
+            }
+            (LetterStatus::Misplaced, false) | (LetterStatus::Correct, true) => {
+                LetterStatus::Absent
+            }
+        };
+        Some(encode_status(&status))
+    }
+
+    fn answer(&self) -> Option<Word> {
+        Some(self.answer)
+    }
+}
+
+impl fmt::Display for Noisy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}% noise)", self.answer, self.error_rate * 100.0)
+    }
+}
+
+/// A person who enters the patterns, e.g. of a game on another site. The
+/// guess is written to `output` and the pattern read from `input` in the
+/// form `bybyg` (b = absent, y = misplaced, g = correct). An empty input
+/// ends the game.
+pub struct HumanEntered<R, W> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> HumanEntered<R, W> {
+    pub fn new(input: R, output: W) -> HumanEntered<R, W> {
+        HumanEntered { input, output }
+    }
+}
+
+impl<R: BufRead, W: Write> FeedbackOracle for HumanEntered<R, W> {
+    fn respond(&mut self, guess: &Word, _: &[Guess], _: &Solver) -> Option<Pattern> {
+        loop {
+            write!(self.output, "Pattern of {} (b/y/g): ", guess).ok()?;
+            self.output.flush().ok()?;
+            let mut line = String::new();
+            if self.input.read_line(&mut line).ok()? == 0 || line.trim().is_empty() {
+                return None;
+            }
+            match format!("{}:{}", guess, line.trim()).parse::<Guess>() {
+                Ok(parsed) => return Some(parsed.status),
+                Err(e) => writeln!(self.output, "{}", e).ok()?,
+            }
+        }
+    }
+}
+
+impl<R, W> fmt::Display for HumanEntered<R, W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "your game")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_solver() -> Solver {
+        let words: Vec<Word> = ["slate", "water", "goose", "crane"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        Solver::from_words(&words, &[1., 1., 1., 0.]).unwrap()
+    }
+
+    #[test]
+    fn test_noisy() {
+        let solver = test_solver();
+        let goose = create_word_from_string("goose");
+        let crane = create_word_from_string("crane");
+        let mut exact = Noisy::new(goose, 0.0, 1);
+        assert_eq!(
+            exact.respond(&crane, &[], &solver),
+            Some(goose.pattern(&crane))
+        );
+        // Always wrong, but the answer stays recognizable
+        let mut noisy = Noisy::new(goose, 1.0, 1);
+        for _ in 0..20 {
+            let pattern = noisy.respond(&crane, &[], &solver).unwrap();
+            assert_ne!(pattern, goose.pattern(&crane));
+            let wrong = decode_status(pattern, 5)
+                .iter()
+                .zip(goose.compare(&crane))
+                .filter(|(a, b)| **a != *b)
+                .count();
+            assert_eq!(wrong, 1);
+        }
+        assert_eq!(noisy.respond(&goose, &[], &solver), Some(solved_pattern(5)));
+    }
+
+    #[test]
+    fn test_human_entered() {
+        let solver = test_solver();
+        let crane = create_word_from_string("crane");
+        let mut output = vec![];
+        let mut human = HumanEntered::new("bgx\nbyyby\n\n".as_bytes(), &mut output);
+        let pattern = human.respond(&crane, &[], &solver);
+        assert_eq!(
+            pattern,
+            Some(create_word_from_string("water").pattern(&crane))
+        );
+        assert_eq!(human.respond(&crane, &[], &solver), None);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Pattern of CRANE"));
+        assert!(output.contains("same length"));
+    }
+
+    #[test]
+    fn test_adversarial() {
+        let solver = test_solver();
+        let crane = create_word_from_string("crane");
+        // Every answer gives another pattern, so the host keeps one
+        let pattern = Adversarial.respond(&crane, &[], &solver).unwrap();
+        let guesses = [Guess {
+            word: crane,
+            status: pattern,
+        }];
+        assert_eq!(solver.get_remaining_words_idx(&guesses).unwrap().len(), 1);
+    }
+}
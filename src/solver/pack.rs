@@ -0,0 +1,248 @@
+//! Word packs: the word list of a Wordle variant in a directory, e.g. a
+//! community list for Lewdle or Taylordle, so a variant needs no code.
+//!
+//! The directory holds a manifest `pack.toml` next to the lists it names:
+//!
+//! ```toml
+//! name = "Taylordle"
+//! description = "Words from the songs of Taylor Swift"
+//! words = "words.txt"
+//! answers = "answers.txt"
+//! feedback = "nyt"
+//! alphabet = "abcdefghijklmnopqrstuvwxyzäöüß"
+//! ```
+//!
+//! `words` is a word list in the format of [`data::parse`]. The optional
+//! `answers` list is in the same format: its words are the possible answers
+//! with its priors, and the other words become allowed guesses only.
+//! Answers that are not in `words` are added. Like in the embedded list,
+//! the words are ordered by their priors. `feedback` is the
+//! [`ScoringRule`] of the variant, `nyt` if it is left out. The optional
+//! `alphabet` lists the letters of the language, see
+//! [`data::parse_alphabet`]. Paths are relative to the directory.
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use crate::config::{parse_table, Value};
+use crate::solver::data::{self, parse_overlay};
+use crate::wordle::*;
+
+/// The file name of the manifest in the directory of a pack
+pub const MANIFEST: &str = "pack.toml";
+
+/// The manifest of a pack, before its lists are read
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Manifest {
+    pub name: String,
+    pub description: Option<String>,
+    /// The word list, relative to the directory
+    pub words: PathBuf,
+    /// The answer list, relative to the directory
+    pub answers: Option<PathBuf>,
+    pub feedback: ScoringRule,
+    /// The letters of the language, sorted
+    pub alphabet: Option<Vec<char>>,
+}
+
+/// A pack with its lists read and checked
+#[derive(Clone, Debug, PartialEq)]
+pub struct WordPack {
+    pub manifest: Manifest,
+    /// The directory of the pack
+    pub dir: PathBuf,
+    /// The allowed guesses and their priors, 0 for the words that are not
+    /// possible answers
+    pub words: Vec<Word>,
+    pub priors: Vec<f32>,
+}
+
+impl std::str::FromStr for Manifest {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mut name, mut words) = (None, None);
+        let mut manifest = Manifest::default();
+        parse_table(s, |key, value| {
+            match (key, value) {
+                ("name", Value::Str(text)) if !text.trim().is_empty() => name = Some(text),
+                ("description", Value::Str(text)) => manifest.description = Some(text),
+                ("words", Value::Str(path)) => words = Some(PathBuf::from(path)),
+                ("answers", Value::Str(path)) => manifest.answers = Some(PathBuf::from(path)),
+                ("feedback", Value::Str(rule)) => manifest.feedback = rule.parse()?,
+                ("alphabet", Value::Str(letters)) => {
+                    manifest.alphabet = Some(data::parse_alphabet(&letters)?)
+                }
+                ("name" | "description" | "words" | "answers" | "feedback" | "alphabet", value) => {
+                    bail!("Invalid value {} for {}", value, key)
+                }
+                _ => bail!("Unknown key '{}'", key),
+            }
+            Ok(())
+        })?;
+        let Some(name) = name else {
+            bail!("The pack has no name")
+        };
+        let Some(words) = words else {
+            bail!("The pack has no word list, set words = \"FILE\"")
+        };
+        Ok(Manifest {
+            name,
+            words,
+            ..manifest
+        })
+    }
+}
+
+impl WordPack {
+    /// Read the pack in a directory
+    pub fn load<P: AsRef<Path>>(dir: P) -> Result<WordPack> {
+        let dir = dir.as_ref();
+        let path = dir.join(MANIFEST);
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("Error reading the pack manifest {}", path.display()))?;
+        let manifest: Manifest = text
+            .parse()
+            .with_context(|| format!("Error parsing the pack manifest {}", path.display()))?;
+        let (words, priors) = WordPack::read_lists(dir, &manifest)
+            .with_context(|| format!("Error loading pack {}", manifest.name))?;
+        Ok(WordPack {
+            manifest,
+            dir: dir.to_path_buf(),
+            words,
+            priors,
+        })
+    }
+
+    fn read_lists(dir: &Path, manifest: &Manifest) -> Result<(Vec<Word>, Vec<f32>)> {
+        let read = |path: &Path| -> Result<Vec<(Word, f32)>> {
+            let path = dir.join(path);
+            let file = File::open(&path)
+                .with_context(|| format!("Error opening word list {}", path.display()))?;
+            parse_overlay(file)
+                .with_context(|| format!("Error importing word list {}", path.display()))
+        };
+        let mut list = read(&manifest.words)?;
+        if let Some(answers) = &manifest.answers {
+            let answers = read(answers)?;
+            if let (Some(&(word, _)), Some(&(answer, _))) = (list.first(), answers.first()) {
+                if word.n_letters() != answer.n_letters() {
+                    bail!(
+                        "The answers have {} letters, the words {}",
+                        answer.n_letters(),
+                        word.n_letters()
+                    )
+                }
+            }
+            for (_, prior) in list.iter_mut() {
+                *prior = 0.0;
+            }
+            let mut index: HashMap<Word, usize> =
+                list.iter().enumerate().map(|(i, &(w, _))| (w, i)).collect();
+            for (answer, prior) in answers {
+                match index.get(&answer) {
+                    Some(&i) => list[i].1 = prior,
+                    None => {
+                        index.insert(answer, list.len());
+                        list.push((answer, prior));
+                    }
+                }
+            }
+        }
+        if !list.iter().any(|&(_, prior)| prior > 0.0) {
+            bail!("The pack does not contain any possible answer")
+        }
+        // Like the embedded list, the most likely answers come first and
+        // the allowed guesses last
+        list.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Ok(list.into_iter().unzip())
+    }
+
+    /// The name of the pack
+    pub fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    /// A summary of the word list of the pack, see [`data::DataInfo`]
+    pub fn info(&self) -> Result<data::DataInfo> {
+        let path = self.dir.join(&self.manifest.words);
+        let data = std::fs::read(&path)
+            .with_context(|| format!("Error reading word list {}", path.display()))?;
+        data::DataInfo::new(&data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest() {
+        let text = r#"
+name = "Taylordle" # the variant
+description = "Swift words"
+words = "words.txt"
+answers = "answers.txt"
+feedback = "greedy-yellow"
+alphabet = "a b c ñ"
+"#;
+        let manifest: Manifest = text.parse().unwrap();
+        assert_eq!(
+            manifest,
+            Manifest {
+                name: "Taylordle".to_string(),
+                description: Some("Swift words".to_string()),
+                words: PathBuf::from("words.txt"),
+                answers: Some(PathBuf::from("answers.txt")),
+                feedback: ScoringRule::GreedyYellow,
+                alphabet: Some(vec!['a', 'b', 'c', 'ñ']),
+            }
+        );
+        let manifest: Manifest = "name = \"Lewdle\"\nwords = \"w.txt\"".parse().unwrap();
+        assert_eq!(manifest.feedback, ScoringRule::NytStandard);
+
+        for invalid in [
+            "words = \"w.txt\"",
+            "name = \"\"\nwords = \"w.txt\"",
+            "name = \"Lewdle\"",
+            "name = \"Lewdle\"\nwords = \"w.txt\"\nfeedback = \"fancy\"",
+            "name = \"Lewdle\"\nwords = 5",
+            "name = \"Lewdle\"\nwords = \"w.txt\"\nalphabet = \"aa\"",
+            "name = \"Lewdle\"\nwords = \"w.txt\"\nlang = \"en\"",
+        ] {
+            assert!(invalid.parse::<Manifest>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_load() {
+        let dir = std::env::temp_dir().join(format!("wordlebot-pack-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join(MANIFEST),
+            "name = \"Test\"\nwords = \"words.txt\"\nanswers = \"answers.txt\"\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("words.txt"), "slate\ncrane\t0.5\ngoose\n").unwrap();
+        std::fs::write(dir.join("answers.txt"), "crane\t0.8\nwater\n").unwrap();
+
+        let pack = WordPack::load(&dir).unwrap();
+        assert_eq!(pack.name(), "Test");
+        let words: Vec<String> = pack.words.iter().map(|w| w.to_string()).collect();
+        assert_eq!(words, ["WATER", "CRANE", "SLATE", "GOOSE"]);
+        assert_eq!(pack.priors, [1.0, 0.8, 0.0, 0.0]);
+
+        // The answers must be as long as the words
+        std::fs::write(dir.join("answers.txt"), "waters\nplanet\n").unwrap();
+        assert!(WordPack::load(&dir).is_err());
+        std::fs::write(dir.join("answers.txt"), "crane\t0\n").unwrap();
+        let error = WordPack::load(&dir).unwrap_err();
+        assert_eq!(
+            format!("{:#}", error),
+            "Error loading pack Test: The pack does not contain any possible answer"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(WordPack::load(&dir).is_err());
+    }
+}
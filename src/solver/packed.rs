@@ -0,0 +1,209 @@
+//! Words packed into the bytes of a `u64`, one small letter code per byte,
+//! for computing the patterns between all words of a word list.
+//!
+//! The letters of a word list are numbered in alphabetical order, so `a` to
+//! `z` are `0` to `25` for an English list. Comparing all letters of two
+//! words is then a few operations on the whole `u64` (SIMD within a
+//! register): the green tiles are the bytes that are equal, and the yellow
+//! tile of a guess letter is the first unused byte of the answer with the
+//! same code. This gives the same patterns as [`Word::pattern`], which
+//! stays the reference for words outside of a word list.
+use crate::wordle::*;
+
+/// The lowest bit of every byte
+const LOW_BITS: u64 = 0x0101_0101_0101_0101;
+/// The highest bit of every byte
+const HIGH_BITS: u64 = 0x8080_8080_8080_8080;
+
+/// The pattern with a 1 for every set bit of the index, e.g. the yellow
+/// tiles of a mask of the letters
+const DIGITS: [Pattern; 256] = digits();
+
+const fn digits() -> [Pattern; 256] {
+    let mut digits = [0; 256];
+    let mut mask = 0;
+    while mask < 256 {
+        let mut i = 0;
+        while i < MAX_LETTERS {
+            if mask & (1 << i) != 0 {
+                digits[mask] += (3 as Pattern).pow(i as u32);
+            }
+            i += 1;
+        }
+        mask += 1;
+    }
+    digits
+}
+
+/// The letters of a word list, in alphabetical order. The code of a letter
+/// is its index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alphabet {
+    letters: Vec<char>,
+}
+
+/// A complete word as the codes of its letters, see the [module](self)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PackedWord {
+    bytes: u64,
+    n_letters: u8,
+}
+
+impl Alphabet {
+    /// The letters of the words, or `None` if there are more than fit into
+    /// a byte
+    pub fn new(words: &[Word]) -> Option<Alphabet> {
+        let mut letters: Vec<char> = words
+            .iter()
+            .flat_map(|w| w.chars())
+            .flatten()
+            .copied()
+            .collect();
+        letters.sort_unstable();
+        letters.dedup();
+        (letters.len() <= u8::MAX as usize + 1).then_some(Alphabet { letters })
+    }
+
+    /// The codes of the letters of the word, or `None` if a letter is
+    /// missing or not in the alphabet
+    pub fn pack(&self, word: &Word) -> Option<PackedWord> {
+        let mut bytes = 0;
+        for (i, letter) in word.chars().iter().enumerate() {
+            let code = self.letters.binary_search(&(*letter)?).ok()?;
+            bytes |= (code as u64) << (8 * i);
+        }
+        Some(PackedWord {
+            bytes,
+            n_letters: word.n_letters() as u8,
+        })
+    }
+
+    /// The letter of a code
+    pub fn letter(&self, code: u8) -> Option<char> {
+        self.letters.get(code as usize).copied()
+    }
+}
+
+/// Pack all words with the alphabet of the words, or `None` if one of them
+/// cannot be packed
+pub fn pack_words(words: &[Word]) -> Option<Vec<PackedWord>> {
+    let alphabet = Alphabet::new(words)?;
+    words.iter().map(|word| alphabet.pack(word)).collect()
+}
+
+/// The highest bit of every byte of `x` that is zero
+fn zero_bytes(x: u64) -> u64 {
+    // Adding 0x7f to the low bits of a byte sets its high bit if any of them
+    // is set, without a carry into the next byte
+    !(((x & !HIGH_BITS) + !HIGH_BITS) | x) & HIGH_BITS
+}
+
+/// The highest bits of the bytes of `x` as the bits of a byte
+fn byte_mask(x: u64) -> usize {
+    // Every highest bit is shifted to its own bit of the highest byte
+    (((x >> 7).wrapping_mul(0x0102_0408_1020_4080)) >> 56) as usize
+}
+
+impl PackedWord {
+    /// The code of every letter
+    pub fn codes(&self) -> Vec<u8> {
+        self.bytes.to_le_bytes()[..self.n_letters()].to_vec()
+    }
+
+    pub fn n_letters(&self) -> usize {
+        self.n_letters as usize
+    }
+
+    /// Compares the word to a guess of the same length like
+    /// [`Word::pattern`]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wordlebot::solver::packed::*;
+    /// use wordlebot::wordle::*;
+    /// let words = [create_word_from_string("tarse"), create_word_from_string("slate")];
+    /// let packed = pack_words(&words).unwrap();
+    /// assert_eq!(packed[0].pattern(&packed[1]), words[0].pattern(&words[1]));
+    /// ```
+    pub fn pattern(&self, guess: &PackedWord) -> Pattern {
+        let n = guess.n_letters();
+        let letters = HIGH_BITS >> (8 * (MAX_LETTERS - n));
+        let correct = zero_bytes(self.bytes ^ guess.bytes) & letters;
+
+        // Every letter of the answer that is not green colors one guess
+        // letter yellow, from the left. There are no branches on the
+        // letters, which the CPU could not predict.
+        let mut unused = letters & !correct;
+        let mut misplaced = 0;
+        for i in 0..n {
+            let shift = 8 * i + 7;
+            let letter = (guess.bytes >> (8 * i)) & 0xff;
+            let mut same = zero_bytes(self.bytes ^ letter.wrapping_mul(LOW_BITS)) & unused;
+            // All ones unless the letter is green
+            same &= ((correct >> shift) & 1).wrapping_sub(1);
+            let first = same & same.wrapping_neg();
+            unused ^= first;
+            misplaced |= ((first != 0) as u64) << shift;
+        }
+
+        2 * DIGITS[byte_mask(correct)] + DIGITS[byte_mask(misplaced)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern() {
+        let words: Vec<Word> = [
+            "abbey", "keeps", "eerie", "speed", "geese", "bobby", "llama", "alley", "array",
+            "tarse", "slate", "xylyl",
+        ]
+        .iter()
+        .map(|w| create_word_from_string(w))
+        .collect();
+        let packed = pack_words(&words).unwrap();
+        for (answer, packed_answer) in words.iter().zip(&packed) {
+            for (guess, packed_guess) in words.iter().zip(&packed) {
+                assert_eq!(
+                    packed_answer.pattern(packed_guess),
+                    answer.pattern(guess),
+                    "{} {}",
+                    answer,
+                    guess
+                );
+            }
+        }
+
+        // Other lengths and letters
+        for n in [MIN_LETTERS, MAX_LETTERS] {
+            let words: Vec<Word> = ["äöüßäöüß", "ßßääöüüö", "aäaäaäaä"]
+                .iter()
+                .map(|w| create_word_from_string(&w.chars().take(n).collect::<String>()))
+                .collect();
+            let packed = pack_words(&words).unwrap();
+            for i in 0..words.len() {
+                for j in 0..words.len() {
+                    assert_eq!(packed[i].pattern(&packed[j]), words[i].pattern(&words[j]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_alphabet() {
+        let words: Vec<Word> = ["crane", "zebra"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let alphabet = Alphabet::new(&words).unwrap();
+        let packed = alphabet.pack(&words[1]).unwrap();
+        // a b c e n r z
+        assert_eq!(packed.codes(), [6, 3, 1, 5, 0]);
+        assert_eq!(alphabet.letter(6), Some('z'));
+        assert_eq!(alphabet.pack(&create_word_from_string("slate")), None);
+        assert_eq!(alphabet.pack(&Word::new()), None);
+    }
+}
@@ -0,0 +1,153 @@
+use anyhow::{bail, Context, Error, Result};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// The weights of the default ranking of the guesses, see
+/// [`RankingConfig::rank`]. Higher prior weights and bonuses play for the
+/// answer sooner, lower ones gather more information first.
+///
+/// A config has one `name = value` per line or separated by commas, with
+/// `#` comments. Missing names keep their default.
+///
+/// # Example
+///
+/// ```
+/// use wordlebot::solver::ranking::RankingConfig;
+/// let config: RankingConfig = "prior_weight = 0.1, possible_bonus = 0.5".parse().unwrap();
+/// assert_eq!(config.rank(5.0, 2.0, 1.0, false), 5.0);
+/// assert_eq!(config.rank(5.0, 2.0, 1.0, true), 5.7);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RankingConfig {
+    /// Weight of the expected bits
+    pub entropy_weight: f32,
+    /// Weight of the prior of possible answers, times the penalty of the
+    /// round
+    pub prior_weight: f32,
+    /// Bits added to possible answers
+    pub possible_bonus: f32,
+    /// Factor of the penalty when ranking by the two level bits, which are
+    /// about twice the bits of one guess
+    pub two_level_weight: f32,
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        RankingConfig {
+            entropy_weight: 1.0,
+            prior_weight: 0.05,
+            possible_bonus: 0.0,
+            two_level_weight: 2.0,
+        }
+    }
+}
+
+impl RankingConfig {
+    /// The rank of a guess from its expected bits. Higher is better.
+    pub fn rank(&self, entropy: f32, prior: f32, penalty: f32, possible: bool) -> f32 {
+        let bits = self.entropy_weight * entropy;
+        if !possible {
+            return bits;
+        }
+        bits + self.prior_weight * prior * penalty + self.possible_bonus
+    }
+
+    /// The rank of a guess from its two level bits
+    pub fn rank_two_level(&self, bits: f32, prior: f32, penalty: f32, possible: bool) -> f32 {
+        self.rank(bits, prior, penalty * self.two_level_weight, possible)
+    }
+
+    /// Read a config from a file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<RankingConfig> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Error reading ranking config {}", path.display()))?;
+        text.parse()
+            .with_context(|| format!("Error parsing ranking config {}", path.display()))
+    }
+
+    /// Set a weight by its name in the config
+    pub fn set(&mut self, name: &str, value: f32) -> Result<()> {
+        if !value.is_finite() {
+            bail!("The value of {} is not a finite number", name)
+        }
+        let weight = match name {
+            "entropy_weight" => &mut self.entropy_weight,
+            "prior_weight" => &mut self.prior_weight,
+            "possible_bonus" => &mut self.possible_bonus,
+            "two_level_weight" => &mut self.two_level_weight,
+            _ => bail!("Unknown weight '{}'", name),
+        };
+        *weight = value;
+        Ok(())
+    }
+}
+
+impl FromStr for RankingConfig {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut config = RankingConfig::default();
+        let entries = s
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default())
+            .flat_map(|line| line.split(','))
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty());
+        for entry in entries {
+            let Some((name, value)) = entry.split_once('=') else {
+                bail!("Expected name = value, got '{}'", entry)
+            };
+            let value: f32 = value
+                .trim()
+                .parse()
+                .with_context(|| format!("Invalid number in '{}'", entry))?;
+            config.set(name.trim(), value)?;
+        }
+        Ok(config)
+    }
+}
+
+impl fmt::Display for RankingConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "entropy_weight = {}", self.entropy_weight)?;
+        writeln!(f, "prior_weight = {}", self.prior_weight)?;
+        writeln!(f, "possible_bonus = {}", self.possible_bonus)?;
+        writeln!(f, "two_level_weight = {}", self.two_level_weight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let text =
+            "# Play for the answer sooner\nprior_weight = 0.2\n\npossible_bonus=0.1 # bits\n";
+        let config: RankingConfig = text.parse().unwrap();
+        assert_eq!(config.prior_weight, 0.2);
+        assert_eq!(config.possible_bonus, 0.1);
+        assert_eq!(config.entropy_weight, 1.0);
+        assert_eq!(config.to_string().parse::<RankingConfig>().unwrap(), config);
+        assert_eq!(
+            "".parse::<RankingConfig>().unwrap(),
+            RankingConfig::default()
+        );
+
+        assert!("prior_weight".parse::<RankingConfig>().is_err());
+        assert!("prior = 1".parse::<RankingConfig>().is_err());
+        assert!("prior_weight = x".parse::<RankingConfig>().is_err());
+        assert!("prior_weight = inf".parse::<RankingConfig>().is_err());
+    }
+
+    #[test]
+    fn test_two_level() {
+        let config = RankingConfig::default();
+        assert_eq!(
+            config.rank_two_level(8.0, 1.0, 1.0, true),
+            config.rank(8.0, 1.0, 2.0, true)
+        );
+    }
+}
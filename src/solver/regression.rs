@@ -0,0 +1,274 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write;
+
+use crate::solver::Solver;
+use crate::wordle::*;
+
+/// What the solver does with one version of a word list, to see how an
+/// update of the list changes the strategy, see [`diff`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    pub n_words: usize,
+    pub answers: Vec<Word>,
+    /// The best opener, `None` without answers
+    pub opener: Option<Word>,
+    /// The best guesses after each position. `None` if a guess of the
+    /// position is not in the word list.
+    pub suggestions: Vec<(Vec<Guess>, Option<Vec<Word>>)>,
+    pub benchmark: Option<BenchmarkStats>,
+}
+
+/// The result of playing every answer
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BenchmarkStats {
+    /// The answers that were not solved
+    pub failed: Vec<Word>,
+    /// The number of answers per number of guesses
+    pub distribution: BTreeMap<usize, usize>,
+}
+
+impl BenchmarkStats {
+    /// The stats from the number of guesses for every answer, 0 if it was
+    /// not solved
+    pub fn new(answers: &[Word], solved_in: &[usize]) -> BenchmarkStats {
+        let mut stats = BenchmarkStats::default();
+        for (&answer, &n) in answers.iter().zip(solved_in) {
+            match n {
+                0 => stats.failed.push(answer),
+                n => *stats.distribution.entry(n).or_default() += 1,
+            }
+        }
+        stats
+    }
+
+    /// The average number of guesses of the solved answers
+    pub fn average(&self) -> f64 {
+        let solved: usize = self.distribution.values().sum();
+        let guesses: usize = self.distribution.iter().map(|(n, count)| n * count).sum();
+        guesses as f64 / solved.max(1) as f64
+    }
+}
+
+impl Solver {
+    /// The empty board and the `n` most common responses to the best
+    /// opener, to compare the suggestions of two word lists for them
+    pub fn common_positions(&self, n: usize) -> Vec<Vec<Guess>> {
+        let answers = self.get_frequent_word_idx();
+        let mut positions = vec![vec![]];
+        let Ok(opener) = self.guess(1, &answers, 0.0) else {
+            return positions;
+        };
+        let Ok(evaluation) = self.evaluate_guess(&opener[0], &answers, None, false) else {
+            return positions;
+        };
+        positions.extend(evaluation.group_sizes.iter().take(n).map(|&(status, _)| {
+            vec![Guess {
+                word: opener[0],
+                status,
+            }]
+        }));
+        positions
+    }
+
+    /// The opener and the `n` best guesses for every position
+    pub fn snapshot(&self, positions: &[Vec<Guess>], n: usize) -> Snapshot {
+        let answers = self.get_frequent_word_idx();
+        let opener = self.guess(1, &answers, 0.0).ok().map(|words| words[0]);
+        let suggestions = positions
+            .iter()
+            .map(|guesses| {
+                let Ok(mut remaining) = self.get_remaining_words_idx(guesses) else {
+                    return (guesses.clone(), None);
+                };
+                remaining.sort_unstable();
+                let penalty = if guesses.is_empty() { 0.0 } else { 0.1 };
+                let best = self.guess(n, &remaining, penalty).unwrap_or_default();
+                (guesses.clone(), Some(best))
+            })
+            .collect();
+        Snapshot {
+            n_words: self.n_words(),
+            answers: self.get_words_from_idx(&answers),
+            opener,
+            suggestions,
+            benchmark: None,
+        }
+    }
+}
+
+/// A readable report of the changes from `old` to `new`. Lines of the old
+/// version start with `- `, lines of the new version with `+ `.
+pub fn diff(old: &Snapshot, new: &Snapshot) -> String {
+    let mut text = String::new();
+    let change =
+        |old: usize, new: usize| format!("{} → {} ({:+})", old, new, new as i64 - old as i64);
+    writeln!(text, "Words: {}", change(old.n_words, new.n_words)).unwrap();
+    writeln!(
+        text,
+        "Answers: {}",
+        change(old.answers.len(), new.answers.len())
+    )
+    .unwrap();
+    let (removed, added) = set_diff(&old.answers, &new.answers);
+    if !removed.is_empty() {
+        writeln!(text, "- {}", join_words(&removed)).unwrap();
+    }
+    if !added.is_empty() {
+        writeln!(text, "+ {}", join_words(&added)).unwrap();
+    }
+
+    let opener = |opener: Option<Word>| opener.map(|w| w.to_string()).unwrap_or("-".into());
+    match old.opener == new.opener {
+        true => writeln!(text, "\nOpener: {} (unchanged)", opener(old.opener)).unwrap(),
+        false => writeln!(
+            text,
+            "\nOpener:\n- {}\n+ {}",
+            opener(old.opener),
+            opener(new.opener)
+        )
+        .unwrap(),
+    }
+
+    for ((guesses, old), (_, new)) in old.suggestions.iter().zip(&new.suggestions) {
+        let position = match guesses.is_empty() {
+            true => "the empty board".to_string(),
+            false => guesses
+                .iter()
+                .map(|g| {
+                    format!(
+                        "{} {}",
+                        g.word,
+                        pattern_to_string(g.status, g.word.n_letters())
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        let words = |words: &Option<Vec<Word>>| match words {
+            Some(words) => join_words(words),
+            None => "(a guess is not in the word list)".to_string(),
+        };
+        if old == new {
+            writeln!(
+                text,
+                "\nSuggestions for {} (unchanged)\n  {}",
+                position,
+                words(old)
+            )
+            .unwrap();
+            continue;
+        }
+        writeln!(
+            text,
+            "\nSuggestions for {}\n- {}\n+ {}",
+            position,
+            words(old),
+            words(new)
+        )
+        .unwrap();
+        if let (Some(old), Some(new)) = (old, new) {
+            let (dropped, entered) = set_diff(old, new);
+            if !entered.is_empty() {
+                writeln!(text, "  new: {}", join_words(&entered)).unwrap();
+            }
+            if !dropped.is_empty() {
+                writeln!(text, "  dropped: {}", join_words(&dropped)).unwrap();
+            }
+        }
+    }
+
+    if let (Some(old), Some(new)) = (&old.benchmark, &new.benchmark) {
+        writeln!(
+            text,
+            "\nBenchmark: average {:.4} → {:.4} ({:+.4}), failed {}",
+            old.average(),
+            new.average(),
+            new.average() - old.average(),
+            change(old.failed.len(), new.failed.len())
+        )
+        .unwrap();
+        let (fixed, broken) = set_diff(&old.failed, &new.failed);
+        if !fixed.is_empty() {
+            writeln!(text, "- failed: {}", join_words(&fixed)).unwrap();
+        }
+        if !broken.is_empty() {
+            writeln!(text, "+ failed: {}", join_words(&broken)).unwrap();
+        }
+        let steps: BTreeSet<usize> = old
+            .distribution
+            .keys()
+            .chain(new.distribution.keys())
+            .copied()
+            .collect();
+        for n in steps {
+            let count = |stats: &BenchmarkStats| stats.distribution.get(&n).copied().unwrap_or(0);
+            writeln!(text, "  {} guesses: {}", n, change(count(old), count(new))).unwrap();
+        }
+    }
+    text
+}
+
+/// The words only in `old` and the words only in `new`, in their order
+fn set_diff(old: &[Word], new: &[Word]) -> (Vec<Word>, Vec<Word>) {
+    let old_set: HashSet<&Word> = old.iter().collect();
+    let new_set: HashSet<&Word> = new.iter().collect();
+    (
+        old.iter()
+            .filter(|w| !new_set.contains(w))
+            .copied()
+            .collect(),
+        new.iter()
+            .filter(|w| !old_set.contains(w))
+            .copied()
+            .collect(),
+    )
+}
+
+fn join_words(words: &[Word]) -> String {
+    words
+        .iter()
+        .map(|w| w.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::solver_for;
+
+    #[test]
+    fn test_benchmark_stats() {
+        let words: Vec<Word> = ["slate", "crane", "water"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let stats = BenchmarkStats::new(&words, &[3, 0, 4]);
+        assert_eq!(stats.failed, vec![words[1]]);
+        assert_eq!(stats.average(), 3.5);
+        assert_eq!(stats.distribution, BTreeMap::from([(3, 1), (4, 1)]));
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = solver_for(&["bills", "fills", "hills", "slate", "crane", "crate"]);
+        let new = solver_for(&["bills", "fills", "hills", "slate", "crane", "grate"]);
+        let positions = old.common_positions(2);
+        assert_eq!(positions.len(), 3);
+        let old_snapshot = old.snapshot(&positions, 3);
+        assert_eq!(
+            diff(&old_snapshot, &old_snapshot)
+                .matches("unchanged")
+                .count(),
+            4
+        );
+
+        let text = diff(&old_snapshot, &new.snapshot(&positions, 3));
+        assert!(text.contains("Answers: 6 → 6 (+0)\n- CRATE\n+ GRATE\n"));
+        // The positions after the old opener can not be evaluated if it is
+        // no longer in the list
+        if !new.is_valid_guess(&positions[1][0].word) {
+            assert!(text.contains("(a guess is not in the word list)"));
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use crate::solver::{GuessEvaluation, Solver};
+use crate::wordle::*;
+
+/// The size of each section of the strategy guide
+#[derive(Clone, Copy, Debug)]
+pub struct GuideOptions {
+    /// Number of openers in the opener table
+    pub openers: usize,
+    /// Number of responses to the best opener
+    pub patterns: usize,
+    /// Number of endgame families
+    pub families: usize,
+}
+
+impl Default for GuideOptions {
+    fn default() -> Self {
+        GuideOptions {
+            openers: 10,
+            patterns: 10,
+            families: 10,
+        }
+    }
+}
+
+/// A markdown table
+struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn new(header: &[&str]) -> Table {
+        Table {
+            header: header.iter().map(|h| h.to_string()).collect(),
+            rows: vec![],
+        }
+    }
+
+    fn push(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    fn render(&self) -> String {
+        let line = |cells: &[String]| format!("| {} |\n", cells.join(" | "));
+        let separator: Vec<String> = self.header.iter().map(|_| "---".to_string()).collect();
+        let mut text = line(&self.header) + &line(&separator);
+        for row in &self.rows {
+            text += &line(row);
+        }
+        text
+    }
+}
+
+/// A group of answers that only differ in one position, e.g. `_ILLS`
+struct Family {
+    name: String,
+    members: Vec<Word>,
+}
+
+impl Solver {
+    /// A markdown strategy guide computed from the word list: the best
+    /// openers, the most common responses to the best opener and the
+    /// endgame families. The same word list always gives the same guide.
+    pub fn strategy_guide(&self, options: &GuideOptions) -> String {
+        let answers = self.get_frequent_word_idx();
+        let mut guide = "# Wordle strategy guide\n\n".to_string();
+        guide += &format!(
+            "Generated with `wordlebot docs-gen` from a word list of {} allowed guesses and {} \
+             possible answers with {} letters. All answers are equally likely.\n",
+            self.words.len(),
+            answers.len(),
+            self.n_letters
+        );
+
+        // Without answers there is nothing to rank
+        let Ok(openers) = self.guess(options.openers, &answers, 0.0) else {
+            return guide;
+        };
+        // The openers are words of the list
+        let openers: Vec<GuessEvaluation> = openers
+            .iter()
+            .filter_map(|word| self.evaluate_guess(word, &answers, None, false).ok())
+            .collect();
+        guide += "\n## Best openers\n\n";
+        guide += "The guesses with the highest entropy over all answers. The expected \
+                   remaining answers are the average size of the group left after the guess.\n\n";
+        guide += &self.opener_table(&openers, answers.len()).render();
+
+        if let Some(best) = openers.first() {
+            guide += &format!("\n## Responses to {}\n\n", best.word);
+            guide += "The most common patterns after the best opener (B = absent, Y = \
+                       misplaced, G = correct) and the best second guess for them.\n\n";
+            guide += &self.response_table(best, options.patterns).render();
+        }
+
+        guide += "\n## Endgame families\n\n";
+        guide += "Answers that only differ in one letter. Guessing them one by one can take \
+                   as many guesses as there are words, so a guess that tests several of the \
+                   letters at once is usually better.\n\n";
+        let mut table = Table::new(&["Family", "Answers", "Words"]);
+        for family in self.families(&answers).into_iter().take(options.families) {
+            table.push(vec![
+                format!("`{}`", family.name),
+                family.members.len().to_string(),
+                join_words(&family.members),
+            ]);
+        }
+        guide += &table.render();
+        guide
+    }
+
+    fn opener_table(&self, openers: &[GuessEvaluation], n_answers: usize) -> Table {
+        let mut table = Table::new(&[
+            "Rank",
+            "Opener",
+            "Bits",
+            "Groups",
+            "Largest group",
+            "Expected remaining",
+        ]);
+        for (i, opener) in openers.iter().enumerate() {
+            let squares: usize = opener.group_sizes.iter().map(|(_, n)| n * n).sum();
+            table.push(vec![
+                (i + 1).to_string(),
+                opener.word.to_string(),
+                format!("{:.3}", opener.expected_bits),
+                opener.groups.to_string(),
+                opener.max_group_size.to_string(),
+                format!("{:.1}", squares as f32 / n_answers.max(1) as f32),
+            ]);
+        }
+        table
+    }
+
+    fn response_table(&self, opener: &GuessEvaluation, n: usize) -> Table {
+        let mut table = Table::new(&["Pattern", "Answers", "Chance", "Second guess", "Examples"]);
+        // Groups of the same size are in no particular order
+        let mut groups = opener.group_sizes.clone();
+        groups.sort_by_key(|&(pattern, size)| (Reverse(size), pattern));
+        for (pattern, size) in groups.into_iter().take(n) {
+            let guess = Guess {
+                word: opener.word,
+                status: pattern,
+            };
+            let mut remaining = self.get_remaining_words_idx(&[guess]).unwrap_or_default();
+            remaining.sort_unstable();
+            let second = self
+                .guess(1, &remaining, 0.1)
+                .map(|words| words[0].to_string())
+                .unwrap_or_default();
+            let examples = self.get_words_from_idx(&remaining[..remaining.len().min(3)]);
+            table.push(vec![
+                format!("`{}`", pattern_to_string(pattern, self.n_letters)),
+                size.to_string(),
+                format!(
+                    "{:.1}%",
+                    100.0 * size as f32 / opener.n_remaining_before as f32
+                ),
+                second,
+                join_words(&examples),
+            ]);
+        }
+        table
+    }
+
+    /// The families with at least three answers, largest first
+    fn families(&self, answers: &[usize]) -> Vec<Family> {
+        let mut groups: BTreeMap<String, Vec<Word>> = BTreeMap::new();
+        for &id in answers {
+            let letters: Vec<char> = self.words[id].to_string().chars().collect();
+            for i in 0..letters.len() {
+                let name: String = letters
+                    .iter()
+                    .enumerate()
+                    .map(|(j, &c)| if i == j { '_' } else { c })
+                    .collect();
+                groups.entry(name).or_default().push(self.words[id]);
+            }
+        }
+        let mut families: Vec<Family> = groups
+            .into_iter()
+            .filter(|(_, members)| members.len() >= 3)
+            .map(|(name, members)| Family { name, members })
+            .collect();
+        families.sort_by_key(|family| Reverse(family.members.len()));
+        families
+    }
+}
+
+fn join_words(words: &[Word]) -> String {
+    words
+        .iter()
+        .map(|w| w.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::solver_for;
+
+    #[test]
+    fn test_table() {
+        let mut table = Table::new(&["Word", "Bits"]);
+        table.push(vec!["SLATE".to_string(), "5.8".to_string()]);
+        assert_eq!(
+            table.render(),
+            "| Word | Bits |\n| --- | --- |\n| SLATE | 5.8 |\n"
+        );
+    }
+
+    #[test]
+    fn test_families() {
+        let solver = solver_for(&["bills", "fills", "hills", "hilly", "slate", "crane"]);
+        let families = solver.families(&solver.get_frequent_word_idx());
+        assert_eq!(families.len(), 1);
+        assert_eq!(families[0].name, "_ILLS");
+        assert_eq!(join_words(&families[0].members), "BILLS, FILLS, HILLS");
+    }
+
+    #[test]
+    fn test_strategy_guide() {
+        let solver = solver_for(&["bills", "fills", "hills", "slate", "crane", "crate"]);
+        let options = GuideOptions::default();
+        let guide = solver.strategy_guide(&options);
+        assert!(guide.contains("6 allowed guesses and 6 possible answers"));
+        assert!(guide.contains("## Best openers"));
+        assert!(guide.contains("| `_ILLS` | 3 | BILLS, FILLS, HILLS |"));
+        // The guide only depends on the word list
+        assert_eq!(guide, solver.strategy_guide(&options));
+    }
+}
@@ -0,0 +1,152 @@
+use anyhow::{bail, Error};
+use std::fmt;
+use std::str::FromStr;
+
+/// What the solver optimizes when it ranks the guesses.
+///
+/// # Example
+///
+/// ```
+/// use wordlebot::solver::score::ScoreStrategy;
+/// let score: ScoreStrategy = "expected-turns".parse().unwrap();
+/// assert_eq!(score, ScoreStrategy::ExpectedTurns);
+/// assert_eq!(score.to_string(), "expected-turns");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScoreStrategy {
+    /// The expected information of the guess, with a penalty for the prior
+    /// of possible answers
+    #[default]
+    Entropy,
+    /// The expected number of turns to find the answer, see
+    /// [`expected_turns`]
+    ExpectedTurns,
+    /// The fewest remaining words in the worst case: the smallest largest
+    /// group, then the most groups
+    Minimax,
+}
+
+impl FromStr for ScoreStrategy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "entropy" => Ok(ScoreStrategy::Entropy),
+            "expected-turns" => Ok(ScoreStrategy::ExpectedTurns),
+            "minimax" => Ok(ScoreStrategy::Minimax),
+            _ => bail!(
+                "Unknown score '{}', expected entropy, expected-turns or minimax",
+                s
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ScoreStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScoreStrategy::Entropy => write!(f, "entropy"),
+            ScoreStrategy::ExpectedTurns => write!(f, "expected-turns"),
+            ScoreStrategy::Minimax => write!(f, "minimax"),
+        }
+    }
+}
+
+// The approximate number of guesses a good strategy needs to find the
+// answer among the remaining words, including the guess that finds it. Two
+// words take 1.5 guesses on average, a full answer list a bit less than 4
+// after the opener.
+const TURNS_LEFT: [(usize, f32); 15] = [
+    (1, 1.0),
+    (2, 1.5),
+    (3, 1.7),
+    (4, 1.9),
+    (5, 2.05),
+    (7, 2.25),
+    (10, 2.4),
+    (20, 2.7),
+    (50, 3.05),
+    (100, 3.35),
+    (200, 3.65),
+    (500, 4.05),
+    (1000, 4.35),
+    (2500, 4.8),
+    (15000, 5.6),
+];
+
+/// The expected number of guesses to find the answer among `n` remaining
+/// words, including the guess that finds it. Between the steps of the
+/// lookup table the value is interpolated by the logarithm of `n`.
+///
+/// ```
+/// use wordlebot::solver::score::turns_left;
+/// assert_eq!(turns_left(1), 1.0);
+/// assert_eq!(turns_left(2), 1.5);
+/// assert!(turns_left(30) > turns_left(20));
+/// ```
+pub fn turns_left(n: usize) -> f32 {
+    if n == 0 {
+        return 0.0;
+    }
+    let i = TURNS_LEFT.partition_point(|&(size, _)| size < n);
+    match (TURNS_LEFT.get(i.wrapping_sub(1)), TURNS_LEFT.get(i)) {
+        (_, Some(&(size, turns))) if size == n => turns,
+        (Some(&(a, turns_a)), Some(&(b, turns_b))) => {
+            let t = (n as f32 / a as f32).log2() / (b as f32 / a as f32).log2();
+            turns_a + t * (turns_b - turns_a)
+        }
+        // Beyond the table, one more guess per 16 times the words
+        _ => {
+            let (size, turns) = TURNS_LEFT[TURNS_LEFT.len() - 1];
+            turns + (n as f32 / size as f32).log2() / 4.0
+        }
+    }
+}
+
+/// The expected number of turns to find the answer with a guess, including
+/// the guess, from the groups of remaining words it leaves. Every group is
+/// given by whether the guess solves it, its size and its probability. The
+/// probabilities have to sum to 1.
+///
+/// ```
+/// use wordlebot::solver::score::expected_turns;
+/// // A guess that is one of two possible answers
+/// assert_eq!(expected_turns([(true, 1, 0.5), (false, 1, 0.5)]), 1.5);
+/// ```
+pub fn expected_turns(groups: impl IntoIterator<Item = (bool, usize, f32)>) -> f32 {
+    groups
+        .into_iter()
+        .map(|(solved, size, probability)| match solved {
+            true => probability,
+            false => probability * (1.0 + turns_left(size)),
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turns_left() {
+        assert_eq!(turns_left(0), 0.0);
+        for &(size, turns) in TURNS_LEFT.iter() {
+            assert_eq!(turns_left(size), turns);
+        }
+        // Monotonic, also beyond the table
+        let sizes = [1, 2, 6, 8, 15, 300, 14000, 15000, 20000, 100000];
+        assert!(sizes
+            .windows(2)
+            .all(|w| turns_left(w[0]) < turns_left(w[1])));
+        assert!((turns_left(240000) - 6.6).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            " Entropy ".parse::<ScoreStrategy>().unwrap(),
+            ScoreStrategy::Entropy
+        );
+        assert!("turns".parse::<ScoreStrategy>().is_err());
+    }
+}
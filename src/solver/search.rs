@@ -0,0 +1,199 @@
+//! Lookahead over several guesses, the two level bits generalized to any
+//! depth.
+//!
+//! The lookahead bits of a guess are its entropy plus, for every pattern it
+//! can get, the probability of the pattern times the lookahead bits of the
+//! best guess for the words left by it, one level shallower. Trying every
+//! guess at every level is far too slow, so only the `beam` best guesses of
+//! the default ranking are tried below the first level.
+use crate::solver::parallel::*;
+use crate::solver::{EmptyRemaining, Solver, SolverError};
+use crate::wordle::*;
+
+/// How deep and wide the lookahead searches
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Search {
+    /// The number of guesses looked at, including the first. 1 is the
+    /// entropy and 2 about the two level bits
+    pub depth: usize,
+    /// The number of guesses tried for the words left at every later level
+    pub beam: usize,
+}
+
+/// The penalty of the ranking that picks the guesses of later levels, like
+/// the two level bits
+const BEAM_PENALTY: f32 = 0.1;
+
+impl Solver {
+    /// The expected bits of the word and the best guesses after it, see
+    /// the [module](self).
+    ///
+    /// Fails if the word is not in the word list.
+    pub fn lookahead_bits(
+        &self,
+        word: &Word,
+        remaining_words: &[usize],
+        search: Search,
+    ) -> Result<f32, SolverError> {
+        let id = self.word_id(word)?;
+        Ok(self.lookahead(id, remaining_words, search.depth, search.beam))
+    }
+
+    /// The n best guesses for the remaining words with their lookahead
+    /// bits, see [`Solver::lookahead_bits`]. They are ranked like the two
+    /// level bits, see [`Solver::two_level_score`].
+    ///
+    /// Only the `width` best guesses of the default ranking are searched,
+    /// but at least n.
+    pub fn guess_lookahead(
+        &self,
+        n: usize,
+        remaining_words: &[usize],
+        penalty: f32,
+        width: usize,
+        search: Search,
+    ) -> Result<Vec<(Word, f32)>, EmptyRemaining> {
+        self.guess_lookahead_where(n, remaining_words, penalty, width, search, |_| true)
+    }
+
+    /// Like [`Solver::guess_lookahead`], among the words that `keep`
+    /// accepts. See [`Solver::guess_where`].
+    pub fn guess_lookahead_where(
+        &self,
+        n: usize,
+        remaining_words: &[usize],
+        penalty: f32,
+        width: usize,
+        search: Search,
+        keep: impl Fn(&Word) -> bool,
+    ) -> Result<Vec<(Word, f32)>, EmptyRemaining> {
+        let candidates = self.guess_where(width.max(n), remaining_words, penalty, keep)?;
+        let mut ranked: Vec<(f32, Word, f32)> = candidates
+            .par_iter()
+            .map(|word| {
+                let id = self.get_id_for_word(word).unwrap();
+                let bits = self.lookahead(id, remaining_words, search.depth, search.beam);
+                let possible = remaining_words.contains(&id);
+                let rank = self
+                    .ranking
+                    .rank_two_level(bits, self.priors[id], penalty, possible);
+                (rank, *word, bits)
+            })
+            .collect();
+        // The sort is stable, so ties keep the order of the default ranking
+        ranked.sort_by(|(a, _, _), (b, _, _)| b.total_cmp(a));
+        Ok(ranked
+            .into_iter()
+            .take(n)
+            .map(|(_, word, bits)| (word, bits))
+            .collect())
+    }
+
+    fn lookahead(&self, id: usize, remaining_words: &[usize], depth: usize, beam: usize) -> f32 {
+        let n_patterns = solved_pattern(self.n_letters) as usize + 1;
+        let mut groups: Vec<Vec<usize>> = vec![vec![]; n_patterns];
+        self.mappings
+            .row(id)
+            .for_each(remaining_words, |i, pattern| {
+                groups[pattern as usize].push(remaining_words[i])
+            });
+
+        // Weighted by the priors of the remaining words like the entropy of
+        // the ranking, so words without priors give no bits either
+        let weight = |words: &[usize]| -> f32 { words.iter().map(|&i| self.priors[i]).sum() };
+        let total = weight(remaining_words);
+        if total == 0.0 {
+            return 0.0;
+        }
+        let probability = |words: &[usize]| weight(words) / total;
+
+        let solved = solved_pattern(self.n_letters) as usize;
+        let mut bits = 0.0;
+        for (pattern, group) in groups.iter().enumerate() {
+            let p = probability(group);
+            if p == 0.0 {
+                continue;
+            }
+            bits -= p * p.log2();
+            // The answer is known once a single word is left
+            if depth > 1 && pattern != solved && group.len() > 1 {
+                bits += p * self.best_lookahead(group, depth - 1, beam);
+            }
+        }
+        bits
+    }
+
+    /// The lookahead bits of the best of the `beam` best guesses of the
+    /// default ranking. The exact endgame of [`Solver::guess`] is skipped,
+    /// since it would search every group to the end.
+    fn best_lookahead(&self, remaining_words: &[usize], depth: usize, beam: usize) -> f32 {
+        self.ranked_guesses(remaining_words, BEAM_PENALTY, |_| true)
+            .into_iter()
+            .take(beam)
+            .map(|id| self.lookahead(id, remaining_words, depth, beam))
+            .fold(0.0, f32::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::{solver_for, solver_with, SMALL_WORDS};
+    use approx::*;
+
+    #[test]
+    fn test_lookahead_bits() {
+        let priors = [1.0, 2.0, 3.0, 1.0, 1.0, 4.0];
+        let solver = solver_with(&SMALL_WORDS, &priors);
+        let remaining: Vec<usize> = (0..6).collect();
+        let slate = create_word_from_string("slate");
+
+        // One level is the entropy
+        let entropy = solver
+            .evaluate_guess(&slate, &remaining, None, false)
+            .unwrap()
+            .expected_bits;
+        let one = Search { depth: 1, beam: 1 };
+        let bits = solver.lookahead_bits(&slate, &remaining, one).unwrap();
+        assert_relative_eq!(bits, entropy);
+        let xxxxx = create_word_from_string("xxxxx");
+        let err = solver.lookahead_bits(&xxxxx, &remaining, one);
+        assert_eq!(err, Err(SolverError::UnknownWord(xxxxx)));
+
+        // Words without priors give no bits, like in the entropy
+        let zero = solver_with(&SMALL_WORDS, &[0.0, 0.0, 1.0, 1.0, 1.0, 1.0]);
+        let bits = zero.lookahead_bits(&slate, &[0, 1], one).unwrap();
+        let entropy = zero
+            .evaluate_guess(&slate, &[0, 1], None, false)
+            .unwrap()
+            .expected_bits;
+        assert_eq!((bits, entropy), (0.0, 0.0));
+
+        // Looking further ahead finds more bits, up to all of them
+        let mut last = 0.0;
+        for depth in 1..=4 {
+            let search = Search { depth, beam: 6 };
+            let bits = solver.lookahead_bits(&slate, &remaining, search).unwrap();
+            assert!(bits >= last);
+            last = bits;
+        }
+        let total: f32 = priors.iter().sum();
+        let all: f32 = priors.iter().map(|p| -p / total * (p / total).log2()).sum();
+        assert_relative_eq!(last, all, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn test_guess_lookahead() {
+        let solver = solver_for(&SMALL_WORDS);
+        let remaining: Vec<usize> = (0..6).collect();
+        let search = Search { depth: 3, beam: 2 };
+        let best = solver
+            .guess_lookahead(3, &remaining, 0.1, 6, search)
+            .unwrap();
+        assert_eq!(best.len(), 3);
+        for (word, bits) in &best {
+            assert_eq!(Ok(*bits), solver.lookahead_bits(word, &remaining, search));
+        }
+        assert!(solver.guess_lookahead(1, &[], 0.1, 6, search).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+//! The outcome of playing a guess: how many guesses the games take if the
+//! guess is played against every remaining word and the solver picks the
+//! guesses after it.
+//!
+//! Games that got the same patterns so far get the same next guess, so the
+//! games are played as a tree and the solver only picks one guess for every
+//! group of answers instead of one per answer and round.
+use std::collections::BTreeMap;
+
+use crate::solver::Solver;
+use crate::wordle::*;
+
+/// How many guesses the games take after a guess, see [`Solver::simulate`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Simulation {
+    pub guess: Word,
+    /// The number of answers that are found with each total number of
+    /// guesses, including the earlier guesses, starting with 1 guess
+    pub solved: Vec<usize>,
+    /// How likely the game takes each total number of guesses, by the
+    /// priors of the answers
+    pub probabilities: Vec<f32>,
+    /// The answers that are not found within the rounds
+    pub failed: usize,
+    pub failed_probability: f32,
+}
+
+impl Simulation {
+    /// The expected total number of guesses of the games that find the
+    /// answer, by the priors of the answers
+    pub fn expected_guesses(&self) -> f32 {
+        let solved = 1.0 - self.failed_probability;
+        if solved <= 0.0 {
+            return 0.0;
+        }
+        let total = self
+            .probabilities
+            .iter()
+            .enumerate()
+            .fold(0.0, |total, (i, p)| total + (i + 1) as f32 * p);
+        total / solved
+    }
+
+    /// The most guesses any found answer takes
+    pub fn worst_case(&self) -> Option<usize> {
+        self.solved.iter().rposition(|&n| n > 0).map(|i| i + 1)
+    }
+}
+
+impl Solver {
+    /// Play `guess` after the guesses against each of the remaining words
+    /// and then the guesses that `pick` chooses for the guesses so far and
+    /// the words left after them, until the answer is found or `max_rounds`
+    /// guesses are played.
+    ///
+    /// `progress` is told how many of the groups of the first guess are
+    /// played, of how many. The simulation stops and returns `None` as soon
+    /// as it returns false.
+    pub fn simulate(
+        &self,
+        guesses: &[Guess],
+        remaining_words: &[usize],
+        guess: Word,
+        max_rounds: usize,
+        pick: impl Fn(&[Guess], &[usize]) -> Option<Word>,
+        mut progress: impl FnMut(usize, usize) -> bool,
+    ) -> Option<Simulation> {
+        let mut simulation = Simulation {
+            guess,
+            solved: vec![0; max_rounds],
+            probabilities: vec![0.0; max_rounds],
+            failed: 0,
+            failed_probability: 0.0,
+        };
+        let mut played = guesses.to_vec();
+        match self.get_id_for_word(&guess) {
+            Some(id) if guesses.len() < max_rounds => {
+                let groups = self.groups_by_pattern(id, remaining_words);
+                for (i, (status, group)) in groups.iter().enumerate() {
+                    played.push(Guess {
+                        word: guess,
+                        status: *status,
+                    });
+                    self.play_group(&mut played, group, &pick, &mut simulation);
+                    played.pop();
+                    if !progress(i + 1, groups.len()) {
+                        return None;
+                    }
+                }
+            }
+            _ => simulation.fail(remaining_words, self),
+        }
+
+        let total = remaining_words
+            .iter()
+            .fold(0.0, |total, &id| total + self.priors[id]);
+        if total > 0.0 {
+            simulation
+                .probabilities
+                .iter_mut()
+                .for_each(|p| *p /= total);
+            simulation.failed_probability /= total;
+        }
+        Some(simulation)
+    }
+
+    /// Play the games of the answers that got the same patterns so far
+    fn play_group(
+        &self,
+        played: &mut Vec<Guess>,
+        group: &[usize],
+        pick: &impl Fn(&[Guess], &[usize]) -> Option<Word>,
+        simulation: &mut Simulation,
+    ) {
+        let rounds = played.len();
+        if played.last().map(|guess| guess.status) == Some(solved_pattern(self.n_letters)) {
+            simulation.solved[rounds - 1] += group.len();
+            simulation.probabilities[rounds - 1] +=
+                group.iter().fold(0.0, |total, &id| total + self.priors[id]);
+            return;
+        }
+        let next = pick(played, group).and_then(|word| Some((word, self.get_id_for_word(&word)?)));
+        let Some((word, id)) = next.filter(|_| rounds < simulation.solved.len()) else {
+            simulation.fail(group, self);
+            return;
+        };
+        for (status, group) in self.groups_by_pattern(id, group) {
+            played.push(Guess { word, status });
+            self.play_group(played, &group, pick, simulation);
+            played.pop();
+        }
+    }
+
+    /// The words grouped by their pattern for the guess
+    fn groups_by_pattern(&self, id: usize, words: &[usize]) -> BTreeMap<Pattern, Vec<usize>> {
+        let mut groups: BTreeMap<Pattern, Vec<usize>> = BTreeMap::new();
+        self.mappings.row(id).for_each(words, |i, pattern| {
+            groups.entry(pattern).or_default().push(words[i])
+        });
+        groups
+    }
+}
+
+impl Simulation {
+    /// Count the games of the words as not found
+    fn fail(&mut self, words: &[usize], solver: &Solver) {
+        self.failed += words.len();
+        self.failed_probability += words
+            .iter()
+            .fold(0.0, |total, &id| total + solver.priors[id]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::{solver_with, SMALL_WORDS};
+
+    #[test]
+    fn test_simulate() {
+        let solver = solver_with(&SMALL_WORDS, &[1.0, 1.0, 1.0, 1.0, 1.0, 3.0]);
+        let remaining = solver.get_frequent_word_idx();
+        let crate_ = create_word_from_string("crate");
+        let pick =
+            |_: &[Guess], remaining: &[usize]| Some(solver.guess(1, remaining, 0.0).ok()?[0]);
+        let simulation = solver
+            .simulate(&[], &remaining, crate_, 6, pick, |_, _| true)
+            .unwrap();
+        assert_eq!(simulation.guess, crate_);
+        assert_eq!(simulation.solved.iter().sum::<usize>(), 6);
+        assert_eq!(simulation.failed, 0);
+        // CRATE finds itself with the first guess
+        assert_eq!(simulation.solved[0], 1);
+        assert_eq!(simulation.probabilities[0], 1.0 / 8.0);
+        let total: f32 = simulation.probabilities.iter().sum();
+        assert!((total - 1.0).abs() < 1e-6);
+        assert!(simulation.expected_guesses() > 1.0);
+        assert!(simulation.worst_case().unwrap() <= 6);
+
+        // Every game is played like try_to_solve would
+        let mut solved = vec![0; 6];
+        for &answer in &remaining {
+            let mut guesses = vec![];
+            let mut word = crate_;
+            loop {
+                let status = solver.get_words_from_idx(&[answer])[0].pattern(&word);
+                guesses.push(Guess { word, status });
+                if status == solved_pattern(5) {
+                    break;
+                }
+                let remaining = solver.get_remaining_words_idx(&guesses).unwrap();
+                word = pick(&guesses, &remaining).unwrap();
+            }
+            solved[guesses.len() - 1] += 1;
+        }
+        assert_eq!(simulation.solved, solved);
+
+        // Too few rounds fail the games, and the progress can stop it
+        let short = solver
+            .simulate(&[], &remaining, crate_, 1, pick, |_, _| true)
+            .unwrap();
+        assert_eq!(short.failed, 5);
+        assert_eq!(short.solved, [1]);
+        assert!(solver
+            .simulate(&[], &remaining, crate_, 6, pick, |_, _| false)
+            .is_none());
+    }
+}
@@ -0,0 +1,293 @@
+//! Named snapshots of a strategy, to compare it with later changes.
+//!
+//! A snapshot records the settings of a strategy, its opener and the
+//! fingerprint of the word list it was saved with, in a small TOML file,
+//! e.g.
+//!
+//! ```toml
+//! # Strategy snapshot of wordlebot
+//! name = "yesterday"
+//! opener = "salet"
+//! fingerprint = "1f2e3d4c5b6a7988"
+//! weighting = "priors"
+//!
+//! [ranking]
+//! entropy_weight = 1
+//! prior_weight = 0.05
+//!
+//! [settings]
+//! two-level = "true"
+//! width = "1000:20,0:5"
+//! penalty = "0.1,0.3"
+//! ```
+//!
+//! The settings are the flags of the strategy without their dashes, a flag
+//! without a value is `"true"`. Which flags there are is up to the caller.
+//! Missing weights of the ranking keep their default.
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+
+use crate::solver::ranking::RankingConfig;
+use crate::solver::regression::BenchmarkStats;
+use crate::solver::Weighting;
+use crate::wordle::*;
+
+/// A saved strategy, see the [module](self)
+#[derive(Clone, Debug, PartialEq)]
+pub struct StrategySnapshot {
+    pub name: String,
+    /// The first guess of the strategy
+    pub opener: Word,
+    /// The [fingerprint](crate::solver::Solver::fingerprint) of the word
+    /// list
+    pub fingerprint: u64,
+    pub weighting: Weighting,
+    pub ranking: RankingConfig,
+    /// The flags of the strategy by their names, e.g. `width`
+    pub settings: BTreeMap<String, String>,
+}
+
+impl StrategySnapshot {
+    /// Parse a snapshot from its TOML text
+    pub fn parse(text: &str) -> Result<StrategySnapshot> {
+        let mut name = None;
+        let mut opener = None;
+        let mut fingerprint = None;
+        let mut weighting = Weighting::Priors;
+        let mut ranking = String::new();
+        let mut settings = BTreeMap::new();
+        let mut table = "";
+        for (i, line) in text.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let context = || format!("Line {}", i + 1);
+            if line.starts_with('[') {
+                table = match line {
+                    "[ranking]" => "ranking",
+                    "[settings]" => "settings",
+                    _ => bail!("{}: unknown table {}", context(), line),
+                };
+                continue;
+            }
+            if table == "ranking" {
+                ranking.push_str(line);
+                ranking.push('\n');
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                bail!("{}: expected key = \"value\"", context())
+            };
+            let (key, value) = (key.trim(), value.trim());
+            let Some(value) = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+            else {
+                bail!("{}: the value {} must be quoted", context(), value)
+            };
+            let value = value.replace("\\\"", "\"").replace("\\\\", "\\");
+            match (table, key) {
+                ("settings", _) => {
+                    if settings.insert(key.to_string(), value).is_some() {
+                        bail!(
+                            "{}: the setting {} is in the snapshot twice",
+                            context(),
+                            key
+                        )
+                    }
+                }
+                (_, "name") => name = Some(value),
+                (_, "opener") => opener = Some(create_word_from_string(&value.to_lowercase())),
+                (_, "fingerprint") => {
+                    let value = u64::from_str_radix(&value, 16)
+                        .with_context(|| format!("{}: invalid fingerprint", context()))?;
+                    fingerprint = Some(value)
+                }
+                (_, "weighting") => {
+                    weighting = match value.as_str() {
+                        "priors" => Weighting::Priors,
+                        "uniform" => Weighting::Uniform,
+                        _ => bail!("{}: unknown weighting '{}'", context(), value),
+                    }
+                }
+                _ => bail!("{}: unknown key '{}'", context(), key),
+            }
+        }
+        let (Some(name), Some(opener), Some(fingerprint)) = (name, opener, fingerprint) else {
+            bail!("The snapshot needs a name, an opener and a fingerprint")
+        };
+        let ranking = ranking.parse().context("Error parsing the ranking")?;
+        Ok(StrategySnapshot {
+            name,
+            opener,
+            fingerprint,
+            weighting,
+            ranking,
+            settings,
+        })
+    }
+
+    /// The TOML text of the snapshot
+    pub fn to_toml(&self) -> String {
+        let weighting = match self.weighting {
+            Weighting::Priors => "priors",
+            Weighting::Uniform => "uniform",
+        };
+        let mut text = format!(
+            "# Strategy snapshot of wordlebot, see src/solver/snapshot.rs\n\
+             name = {}\nopener = \"{}\"\nfingerprint = \"{:016x}\"\nweighting = \"{}\"\n\n\
+             [ranking]\n{}\n[settings]\n",
+            quote(&self.name),
+            self.opener.to_string().to_lowercase(),
+            self.fingerprint,
+            weighting,
+            self.ranking
+        );
+        for (key, value) in &self.settings {
+            text += &format!("{} = {}\n", key, quote(value));
+        }
+        text
+    }
+
+    /// The settings as command line flags, e.g. `--width=10`, and only the
+    /// name for `"true"`
+    pub fn args(&self) -> Vec<String> {
+        self.settings
+            .iter()
+            .filter(|(_, value)| *value != "false")
+            .map(|(key, value)| match value.as_str() {
+                "true" => format!("--{}", key),
+                _ => format!("--{}={}", key, value),
+            })
+            .collect()
+    }
+}
+
+/// A TOML string with its quotes
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// The line without a `#` comment outside of quotes
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Two benchmarks of the same answers side by side
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Comparison {
+    pub a: BenchmarkStats,
+    pub b: BenchmarkStats,
+    /// The answers that `b` solves in fewer guesses than `a`, or solves and
+    /// `a` does not
+    pub better: Vec<Word>,
+    /// The answers that `b` needs more guesses for, or does not solve
+    pub worse: Vec<Word>,
+}
+
+impl Comparison {
+    /// The comparison from the number of guesses of both strategies for
+    /// every answer, 0 if it was not solved
+    pub fn new(answers: &[Word], solved_a: &[usize], solved_b: &[usize]) -> Comparison {
+        let mut comparison = Comparison {
+            a: BenchmarkStats::new(answers, solved_a),
+            b: BenchmarkStats::new(answers, solved_b),
+            ..Default::default()
+        };
+        // Failing is worse than any number of guesses
+        let cost = |n: usize| if n == 0 { usize::MAX } else { n };
+        for ((&answer, &a), &b) in answers.iter().zip(solved_a).zip(solved_b) {
+            match cost(b).cmp(&cost(a)) {
+                std::cmp::Ordering::Less => comparison.better.push(answer),
+                std::cmp::Ordering::Greater => comparison.worse.push(answer),
+                std::cmp::Ordering::Equal => {}
+            }
+        }
+        comparison
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SNAPSHOT: &str = "\
+# Yesterday's strategy
+name = \"yesterday\"
+opener = \"SALET\"
+fingerprint = \"00000000000000ff\"
+weighting = \"uniform\"
+
+[ranking]
+prior_weight = 0.2 # played sooner
+
+[settings]
+two-level = \"true\"
+eco = \"false\"
+formula = \"entropy - max_group / 1000 # not a comment\"
+";
+
+    #[test]
+    fn test_parse_and_serialize() {
+        let snapshot = StrategySnapshot::parse(SNAPSHOT).unwrap();
+        assert_eq!(snapshot.name, "yesterday");
+        assert_eq!(snapshot.opener, create_word_from_string("salet"));
+        assert_eq!(snapshot.fingerprint, 255);
+        assert_eq!(snapshot.weighting, Weighting::Uniform);
+        assert_eq!(snapshot.ranking.prior_weight, 0.2);
+        assert_eq!(snapshot.ranking.entropy_weight, 1.0);
+        assert_eq!(
+            snapshot.args(),
+            [
+                "--formula=entropy - max_group / 1000 # not a comment",
+                "--two-level"
+            ]
+        );
+        assert_eq!(
+            StrategySnapshot::parse(&snapshot.to_toml()).unwrap(),
+            snapshot
+        );
+
+        let mut quoted = snapshot.clone();
+        quoted.name = "say \"hi\" \\".to_string();
+        assert_eq!(StrategySnapshot::parse(&quoted.to_toml()).unwrap(), quoted);
+
+        for text in [
+            "",
+            "name = \"a\"\nopener = \"salet\"",
+            "name = \"a\"\nopener = \"salet\"\nfingerprint = \"xyz\"",
+            "name = a\nopener = \"salet\"\nfingerprint = \"ff\"",
+            "name = \"a\"\nopener = \"salet\"\nfingerprint = \"ff\"\nwidth = \"10\"",
+            "name = \"a\"\nopener = \"salet\"\nfingerprint = \"ff\"\n[book]",
+            "name = \"a\"\nopener = \"salet\"\nfingerprint = \"ff\"\n[ranking]\nbonus = 1",
+        ] {
+            assert!(StrategySnapshot::parse(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_comparison() {
+        let answers: Vec<Word> = ["slate", "crane", "crate", "grate"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let comparison = Comparison::new(&answers, &[3, 4, 0, 2], &[3, 3, 6, 0]);
+        assert_eq!(comparison.better, [answers[1], answers[2]]);
+        assert_eq!(comparison.worse, [answers[3]]);
+        assert_eq!(comparison.a.failed, [answers[2]]);
+        assert_eq!(comparison.b.failed, [answers[3]]);
+        assert_eq!(comparison.b.average(), 4.0);
+    }
+}
@@ -0,0 +1,180 @@
+//! Soft constraints for feedback that may be remembered wrong.
+//!
+//! With a confidence below 1 every yellow or gray tile of a guess is only
+//! right with that probability, and otherwise was one of the two other
+//! colors. Green tiles are always trusted. A word that explains the guesses
+//! only with `m` misremembered tiles is `((1 - c) / 2c)^m` times as likely
+//! as one that explains them all, before its prior.
+use std::iter::zip;
+
+use crate::solver::{Solver, SolverError};
+use crate::wordle::*;
+
+/// Words that are less likely than this, relative to a word that explains
+/// every tile, are not kept
+const MIN_RELATIVE_LIKELIHOOD: f32 = 1e-3;
+
+/// How likely a misremembered tile is relative to a right one
+fn error_ratio(confidence: f32) -> f32 {
+    let confidence = confidence.clamp(0.5, 1.0);
+    (1.0 - confidence) / (2.0 * confidence)
+}
+
+/// The most misremembered tiles a kept word may need at the confidence
+pub fn max_misremembered_tiles(confidence: f32) -> usize {
+    let ratio = error_ratio(confidence);
+    if ratio <= 0.0 {
+        return 0;
+    }
+    (MIN_RELATIVE_LIKELIHOOD.ln() / ratio.ln()).floor() as usize
+}
+
+impl Solver {
+    /// The tiles of the guesses that must have been misremembered if the
+    /// word is the answer, or `None` if a green tile rules it out
+    pub fn misremembered_tiles(&self, id: usize, guesses: &[Guess]) -> Option<usize> {
+        let mut tiles = 0;
+        for guess in guesses {
+            let guess_id = self.get_id_for_word(&guess.word)?;
+            let pattern = self.mappings.get(guess_id, id);
+            if pattern == guess.status {
+                continue;
+            }
+            let actual = decode_status(pattern, self.n_letters);
+            for (reported, actual) in zip(guess.get_status(), actual) {
+                match reported {
+                    _ if reported == actual => {}
+                    LetterStatus::Correct => return None,
+                    _ => tiles += 1,
+                }
+            }
+        }
+        Some(tiles)
+    }
+
+    /// The remaining words if the yellow and gray tiles are only right with
+    /// the probability `confidence`, see the [module](self): the possible
+    /// answers that need at most [`max_misremembered_tiles`] of them to be
+    /// wrong. With a confidence of 1 they are the same as
+    /// [`Solver::get_remaining_words_idx`]. Fails if a guess is not in the
+    /// word list.
+    pub fn get_soft_remaining_words_idx(
+        &self,
+        guesses: &[Guess],
+        confidence: f32,
+    ) -> Result<Vec<usize>, SolverError> {
+        if confidence >= 1.0 {
+            return self.get_remaining_words_idx(guesses);
+        }
+        for guess in guesses {
+            self.word_id(&guess.word)?;
+        }
+        let max_tiles = max_misremembered_tiles(confidence);
+        Ok(self
+            .get_frequent_word_idx()
+            .into_iter()
+            .filter(|&id| {
+                self.misremembered_tiles(id, guesses)
+                    .is_some_and(|tiles| tiles <= max_tiles)
+            })
+            .collect())
+    }
+
+    /// How likely each of the words is the answer if the yellow and gray
+    /// tiles are only right with the probability `confidence`: its prior
+    /// times the likelihood of its misremembered tiles, as a share of all
+    /// of them. Like [`Solver::get_likelihoods`] with a confidence of 1.
+    pub fn get_soft_likelihoods(
+        &self,
+        idx: &[usize],
+        guesses: &[Guess],
+        confidence: f32,
+    ) -> Vec<f32> {
+        if confidence >= 1.0 {
+            return self.get_likelihoods(idx);
+        }
+        let ratio = error_ratio(confidence);
+        let weights: Vec<f32> = idx
+            .iter()
+            .map(|&id| match self.misremembered_tiles(id, guesses) {
+                Some(tiles) => self.priors[id] * ratio.powi(tiles as i32),
+                None => 0.0,
+            })
+            .collect();
+        let total = weights.iter().fold(0.0, |total, w| total + w);
+        weights
+            .iter()
+            .map(|w| match total > 0.0 {
+                true => w / total,
+                false => 0.0,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::{solver_for, SMALL_WORDS};
+
+    #[test]
+    fn test_max_misremembered_tiles() {
+        assert_eq!(max_misremembered_tiles(1.0), 0);
+        assert_eq!(max_misremembered_tiles(0.95), 1);
+        assert_eq!(max_misremembered_tiles(0.9), 2);
+        assert_eq!(max_misremembered_tiles(0.8), 3);
+    }
+
+    #[test]
+    fn test_soft_remaining_words() {
+        let solver = solver_for(&SMALL_WORDS);
+        let crane = create_word_from_string("crane");
+        // CRANE against CRATE, but the N was remembered yellow
+        let mut status = create_word_from_string("crate").pattern(&crane);
+        let mut statuses = decode_status(status, 5);
+        statuses[3] = LetterStatus::Misplaced;
+        status = encode_status(&statuses);
+        let guesses = [Guess {
+            word: crane,
+            status,
+        }];
+
+        assert!(solver.get_remaining_words_idx(&guesses).unwrap().is_empty());
+        assert!(solver
+            .get_soft_remaining_words_idx(&guesses, 1.0)
+            .unwrap()
+            .is_empty());
+        let crate_id = solver.get_id_for_word(&create_word_from_string("crate"));
+        assert_eq!(
+            solver.misremembered_tiles(crate_id.unwrap(), &guesses),
+            Some(1)
+        );
+        // CRANE itself only needs the N to be green
+        let remaining = solver.get_soft_remaining_words_idx(&guesses, 0.95).unwrap();
+        assert_eq!(remaining, [1, crate_id.unwrap()]);
+
+        // GRATE contradicts the green C
+        let grate_id = solver.get_id_for_word(&create_word_from_string("grate"));
+        assert_eq!(
+            solver.misremembered_tiles(grate_id.unwrap(), &guesses),
+            None
+        );
+        let remaining = solver.get_soft_remaining_words_idx(&guesses, 0.8).unwrap();
+        assert!(!remaining.contains(&grate_id.unwrap()));
+
+        // The fewer misremembered tiles, the more likely
+        let all: Vec<usize> = (0..6).collect();
+        let likelihoods = solver.get_soft_likelihoods(&all, &guesses, 0.8);
+        let total: f32 = likelihoods.iter().sum();
+        assert!((total - 1.0).abs() < 1e-5);
+        assert!(likelihoods[1] > 0.0);
+        assert_eq!(likelihoods[1], likelihoods[2]);
+        // The other words contradict a green tile
+        assert_eq!(likelihoods[grate_id.unwrap()], 0.0);
+        assert_eq!(likelihoods[0], 0.0);
+        assert_eq!(
+            solver.get_soft_likelihoods(&remaining, &guesses, 1.0),
+            solver.get_likelihoods(&remaining)
+        );
+    }
+}
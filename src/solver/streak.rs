@@ -0,0 +1,299 @@
+//! Streak protection: the guesses most likely to find the answer within the
+//! rounds that are left, instead of in the fewest guesses on average.
+//!
+//! The success probability of a guess with `k` rounds left is the
+//! probability that it is the answer plus, for every other pattern it can
+//! get, the probability of the pattern times the success probability of the
+//! best guess for the words left by it with `k - 1` rounds. With one round
+//! left the best guess is the most likely word, and with two rounds every
+//! guess of the pool is tried, so both are exact. With more rounds only the
+//! `beam` best guesses of the default ranking and the most likely word are
+//! tried, like in the [lookahead](crate::solver::search), so the
+//! probability may be a bit too low.
+//!
+//! At most [`MAX_STREAK_ROUNDS`] rounds are searched. With more rounds left
+//! the probability is that of solving within these rounds, a lower bound.
+use crate::solver::parallel::*;
+use crate::solver::{EmptyRemaining, Solver, SolverError};
+use crate::wordle::*;
+
+/// The most rounds that are searched
+pub const MAX_STREAK_ROUNDS: usize = 4;
+
+/// The penalty of the ranking that picks the guesses of later rounds, like
+/// in the lookahead
+const BEAM_PENALTY: f32 = 0.1;
+
+/// A guess with its probability to find the answer within the rounds
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StreakAdvice {
+    pub word: Word,
+    /// The probability to find the answer within the rounds, including
+    /// this guess
+    pub probability: f32,
+    /// The guess is one of the remaining words
+    pub possible: bool,
+}
+
+impl Solver {
+    /// The probability that the word and the best guesses after it find the
+    /// answer within the rounds, see the [module](self).
+    ///
+    /// Fails if the word is not in the word list.
+    pub fn success_probability(
+        &self,
+        word: &Word,
+        remaining_words: &[usize],
+        rounds: usize,
+        beam: usize,
+    ) -> Result<f32, SolverError> {
+        let id = self.word_id(word)?;
+        Ok(self.success(id, remaining_words, rounds.min(MAX_STREAK_ROUNDS), beam))
+    }
+
+    /// The n guesses most likely to find the answer within the rounds, see
+    /// the [module](self). Ties prefer possible answers and then higher
+    /// priors.
+    ///
+    /// With one or two rounds left every guess is tried. With more, the
+    /// `width` best guesses of the default ranking and the `width` most
+    /// likely words are tried, but at least n of each.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wordlebot::prelude::*;
+    /// let words: Vec<Word> = ["bight", "fight", "light", "might", "flaky"]
+    ///     .iter()
+    ///     .map(|w| create_word_from_string(w))
+    ///     .collect();
+    /// let solver = Solver::from_words(&words, &[1.0, 1.0, 1.0, 1.0, 0.0]).unwrap();
+    /// // FLAKY finds FIGHT and LIGHT, but only one of BIGHT and MIGHT
+    /// let advice = solver.streak_advice(1, &[0, 1, 2, 3], 2, 10, 3).unwrap();
+    /// assert_eq!(advice[0].probability, 0.75);
+    /// ```
+    pub fn streak_advice(
+        &self,
+        n: usize,
+        remaining_words: &[usize],
+        rounds: usize,
+        width: usize,
+        beam: usize,
+    ) -> Result<Vec<StreakAdvice>, EmptyRemaining> {
+        if remaining_words.is_empty() {
+            return Err(EmptyRemaining);
+        }
+        let rounds = rounds.min(MAX_STREAK_ROUNDS);
+        let mut candidates = match rounds {
+            0 | 1 => remaining_words.to_vec(),
+            2 => self.candidates(remaining_words, |_| true),
+            _ => {
+                let width = width.max(n);
+                let mut ids: Vec<usize> = self
+                    .ranked_guesses(remaining_words, BEAM_PENALTY, |_| true)
+                    .into_iter()
+                    .take(width)
+                    .collect();
+                ids.extend(self.most_likely(remaining_words).into_iter().take(width));
+                ids
+            }
+        };
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        let mut sorted = remaining_words.to_vec();
+        sorted.sort_unstable();
+        let mut ranked: Vec<StreakAdvice> = candidates
+            .par_iter()
+            .map(|&id| StreakAdvice {
+                word: self.words[id],
+                probability: self.success(id, remaining_words, rounds, beam),
+                possible: sorted.binary_search(&id).is_ok(),
+            })
+            .collect();
+        ranked.sort_by(|a, b| {
+            b.probability
+                .total_cmp(&a.probability)
+                .then(b.possible.cmp(&a.possible))
+                .then_with(|| self.prior_of(&b.word).total_cmp(&self.prior_of(&a.word)))
+        });
+        ranked.truncate(n);
+        Ok(ranked)
+    }
+
+    /// The probability of every remaining word to be the answer, by their
+    /// priors, or alike if none has a prior like in the lookahead
+    fn answer_probabilities(&self, remaining_words: &[usize]) -> Vec<f32> {
+        let total: f32 = remaining_words.iter().map(|&i| self.priors[i]).sum();
+        remaining_words
+            .iter()
+            .map(|&i| match total {
+                0.0 => 1.0 / remaining_words.len() as f32,
+                _ => self.priors[i] / total,
+            })
+            .collect()
+    }
+
+    /// The remaining words, the most likely answer first
+    fn most_likely(&self, remaining_words: &[usize]) -> Vec<usize> {
+        let mut ids = remaining_words.to_vec();
+        ids.sort_by(|&a, &b| self.priors[b].total_cmp(&self.priors[a]));
+        ids
+    }
+
+    fn prior_of(&self, word: &Word) -> f32 {
+        self.get_id_for_word(word).map_or(0.0, |id| self.priors[id])
+    }
+
+    fn success(&self, id: usize, remaining_words: &[usize], rounds: usize, beam: usize) -> f32 {
+        if rounds == 0 {
+            return 0.0;
+        }
+        let probabilities = self.answer_probabilities(remaining_words);
+        let n_patterns = solved_pattern(self.n_letters) as usize + 1;
+        let solved = n_patterns - 1;
+
+        // With two rounds left the most likely word of every group is the
+        // best last guess, so the groups are not needed
+        if rounds <= 2 {
+            let mut best = vec![0.0f32; n_patterns];
+            self.mappings
+                .row(id)
+                .for_each(remaining_words, |i, pattern| {
+                    let best = &mut best[pattern as usize];
+                    *best = best.max(probabilities[i]);
+                });
+            return match rounds {
+                1 => best[solved],
+                _ => best.iter().sum(),
+            };
+        }
+
+        let mut groups: Vec<Vec<usize>> = vec![vec![]; n_patterns];
+        let mut mass = vec![0.0f32; n_patterns];
+        self.mappings
+            .row(id)
+            .for_each(remaining_words, |i, pattern| {
+                groups[pattern as usize].push(remaining_words[i]);
+                mass[pattern as usize] += probabilities[i];
+            });
+        let mut probability = mass[solved];
+        for (pattern, group) in groups.iter().enumerate() {
+            if pattern != solved && mass[pattern] > 0.0 {
+                probability += mass[pattern] * self.best_success(group, rounds - 1, beam);
+            }
+        }
+        probability
+    }
+
+    /// The success probability of the best guess for the remaining words,
+    /// see the [module](self)
+    fn best_success(&self, remaining_words: &[usize], rounds: usize, beam: usize) -> f32 {
+        match (remaining_words.len(), rounds) {
+            (0, _) | (_, 0) => return 0.0,
+            // Guess the word, or one of the two words and then the other
+            (1, _) | (2, 2..) => return 1.0,
+            (_, 1) => {
+                let probabilities = self.answer_probabilities(remaining_words);
+                return probabilities.into_iter().fold(0.0, f32::max);
+            }
+            _ => {}
+        }
+        let mut candidates = self.candidates(remaining_words, |_| true);
+        if rounds > 2 {
+            candidates = self
+                .ranked_guesses(remaining_words, BEAM_PENALTY, |_| true)
+                .into_iter()
+                .take(beam)
+                .chain(self.most_likely(remaining_words).into_iter().take(1))
+                .collect();
+        }
+        let mut best = 0.0f32;
+        for id in candidates {
+            best = best.max(self.success(id, remaining_words, rounds, beam));
+            // Nothing beats finding every answer
+            if best >= 1.0 {
+                break;
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::*;
+
+    fn solver() -> Solver {
+        let words: Vec<Word> = ["bight", "fight", "light", "might", "sight", "flaky"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        Solver::from_words(&words, &[4.0, 3.0, 2.0, 1.0, 1.0, 0.0]).unwrap()
+    }
+
+    #[test]
+    fn test_success_probability() {
+        let solver = solver();
+        let remaining: Vec<usize> = (0..5).collect();
+        let word = |w: &str| create_word_from_string(w);
+
+        // With one round only the answer counts
+        let p = |w: &str, rounds: usize| {
+            solver
+                .success_probability(&word(w), &remaining, rounds, 3)
+                .unwrap()
+        };
+        assert_relative_eq!(p("bight", 1), 4.0 / 11.0);
+        assert_eq!(p("flaky", 1), 0.0);
+        assert_eq!(p("bight", 0), 0.0);
+        let err = solver.success_probability(&word("xxxxx"), &remaining, 2, 3);
+        assert_eq!(err, Err(SolverError::UnknownWord(word("xxxxx"))));
+
+        // FLAKY finds FIGHT, LIGHT and the likeliest of BIGHT, MIGHT and
+        // SIGHT in the next round
+        assert_relative_eq!(p("flaky", 2), 9.0 / 11.0);
+        // BIGHT tells MIGHT and SIGHT apart from the other words then
+        assert_relative_eq!(p("flaky", 3), 10.0 / 11.0);
+        // BIGHT leaves four words that all look alike
+        assert_relative_eq!(p("bight", 2), 7.0 / 11.0);
+
+        // More rounds never hurt, and enough find every answer
+        let mut last = 0.0;
+        for rounds in 1..=MAX_STREAK_ROUNDS + 2 {
+            let p = p("flaky", rounds);
+            assert!(p >= last);
+            last = p;
+        }
+        assert_relative_eq!(last, 1.0);
+    }
+
+    #[test]
+    fn test_streak_advice() {
+        let solver = solver();
+        let remaining: Vec<usize> = (0..5).collect();
+
+        // The most likely answer with one round left, the word that tells
+        // the answers apart with two
+        let advice = solver.streak_advice(2, &remaining, 1, 10, 3).unwrap();
+        assert_eq!(advice[0].word.to_string(), "BIGHT");
+        assert!(advice[0].possible);
+        assert_eq!(advice[1].word.to_string(), "FIGHT");
+        let advice = solver.streak_advice(6, &remaining, 2, 10, 3).unwrap();
+        assert_eq!(advice.len(), 6);
+        assert_eq!(advice[0].word.to_string(), "FLAKY");
+        assert!(!advice[0].possible);
+        assert!(advice
+            .windows(2)
+            .all(|w| w[0].probability >= w[1].probability));
+        for a in &advice {
+            let p = solver.success_probability(&a.word, &remaining, 2, 3);
+            assert_eq!(Ok(a.probability), p);
+        }
+
+        let advice = solver.streak_advice(1, &remaining, 4, 10, 3).unwrap();
+        assert_relative_eq!(advice[0].probability, 1.0);
+        assert!(solver.streak_advice(1, &[], 3, 1, 1).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+
+use crate::wordle::*;
+
+/// A decision tree that fixes the next guess for every sequence of
+/// patterns.
+///
+/// The text format is the one used by published optimal Wordle trees:
+/// every line is the path to one answer, made of the guesses followed by
+/// their pattern and the step number, e.g.
+///
+/// ```text
+/// salet BBBYB1 courd BGBBB2 ... tight GGGGG4
+/// ```
+///
+/// Patterns use `B` for absent, `Y` for misplaced and `G` for correct
+/// letters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecisionTree {
+    root: Node,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Node {
+    guess: Word,
+    // The guess is the answer for this path
+    solves: bool,
+    children: BTreeMap<Pattern, Node>,
+}
+
+impl Node {
+    fn new(guess: Word) -> Node {
+        Node {
+            guess,
+            solves: false,
+            children: BTreeMap::new(),
+        }
+    }
+}
+
+impl DecisionTree {
+    /// Parse a tree from its text representation
+    pub fn parse(text: &str) -> Result<DecisionTree> {
+        let paths = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| parse_line(line).with_context(|| format!("Line {}", i + 1)))
+            .collect::<Result<Vec<_>>>()?;
+        DecisionTree::from_games(&paths)
+    }
+
+    /// Build a tree from played games. Every game must end with the
+    /// solution and all games must agree on the guess after the same
+    /// patterns.
+    pub fn from_games(games: &[Vec<Guess>]) -> Result<DecisionTree> {
+        let Some(first) = games.iter().find_map(|game| game.first()) else {
+            bail!("Decision tree is empty")
+        };
+        let mut root = Node::new(first.word);
+        for game in games {
+            let mut node = &mut root;
+            for (i, guess) in game.iter().enumerate() {
+                if node.guess != guess.word {
+                    bail!(
+                        "Conflicting guesses {} and {} after the same patterns",
+                        node.guess,
+                        guess.word
+                    )
+                }
+                if guess.status == solved_pattern(guess.word.n_letters()) {
+                    node.solves = true;
+                    break;
+                }
+                let Some(next) = game.get(i + 1) else {
+                    bail!("Game does not end with the solution")
+                };
+                node = node
+                    .children
+                    .entry(guess.status)
+                    .or_insert_with(|| Node::new(next.word));
+            }
+        }
+        Ok(DecisionTree { root })
+    }
+
+    /// The word to guess after the given guesses, or `None` if the tree does
+    /// not cover the guesses
+    pub fn next_guess(&self, guesses: &[Guess]) -> Option<Word> {
+        let mut node = &self.root;
+        for guess in guesses {
+            if guess.word != node.guess {
+                return None;
+            }
+            node = node.children.get(&guess.status)?;
+        }
+        Some(node.guess)
+    }
+
+    /// All words that are guessed somewhere in the tree
+    pub fn guesses(&self) -> Vec<Word> {
+        let mut words = vec![];
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            words.push(node.guess);
+            stack.extend(node.children.values());
+        }
+        words
+    }
+
+    /// The average number of guesses over all answers of the tree
+    pub fn average_guesses(&self) -> f32 {
+        let mut n_answers = 0;
+        let mut n_guesses = 0;
+        let mut stack = vec![(&self.root, 1)];
+        while let Some((node, step)) = stack.pop() {
+            if node.solves {
+                n_answers += 1;
+                n_guesses += step;
+            }
+            stack.extend(node.children.values().map(|child| (child, step + 1)));
+        }
+        n_guesses as f32 / n_answers as f32
+    }
+
+    /// The text representation of the tree, with one line per answer
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![];
+        write_node(&self.root, String::new(), 1, &mut lines);
+        lines.join("\n") + "\n"
+    }
+}
+
+fn write_node(node: &Node, prefix: String, step: usize, lines: &mut Vec<String>) {
+    let word = node.guess.to_string().to_lowercase();
+    let n_letters = node.guess.n_letters();
+    if node.solves {
+        lines.push(format!(
+            "{}{} {}{}",
+            prefix,
+            word,
+            pattern_to_string(solved_pattern(n_letters), n_letters),
+            step
+        ));
+    }
+    for (&status, child) in &node.children {
+        let prefix = format!(
+            "{}{} {}{} ",
+            prefix,
+            word,
+            pattern_to_string(status, n_letters),
+            step
+        );
+        write_node(child, prefix, step + 1, lines);
+    }
+}
+
+fn parse_line(line: &str) -> Result<Vec<Guess>> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    if !tokens.len().is_multiple_of(2) {
+        bail!("Expected pairs of guess and pattern")
+    }
+    tokens
+        .chunks(2)
+        .map(|pair| {
+            let pattern = pair[1].trim_end_matches(|c: char| c.is_ascii_digit());
+            format!("{}:{}", pair[0], pattern).parse::<Guess>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LetterStatus::*;
+    use super::*;
+
+    const TREE: &str = "\
+salet BBBBB1 courd GGGGG2
+salet BBBBB1 courd BGBBB2 mopey GGGGG3
+salet YBBBG1 those GGGGG2
+";
+
+    #[test]
+    fn test_parse_and_serialize() {
+        let tree = DecisionTree::parse(TREE).unwrap();
+        assert_eq!(tree.to_text(), TREE);
+        assert_eq!(DecisionTree::parse(&tree.to_text()).unwrap(), tree);
+        assert_eq!(tree.average_guesses(), 7.0 / 3.0);
+    }
+
+    #[test]
+    fn test_next_guess() {
+        let tree = DecisionTree::parse(TREE).unwrap();
+        assert_eq!(tree.next_guess(&[]), Some(create_word_from_string("salet")));
+
+        let salet = Guess::new("salet", [Absent, Absent, Absent, Absent, Absent]);
+        assert_eq!(
+            tree.next_guess(&[salet]),
+            Some(create_word_from_string("courd"))
+        );
+
+        let courd = Guess::new("courd", [Absent, Correct, Absent, Absent, Absent]);
+        assert_eq!(
+            tree.next_guess(&[salet, courd]),
+            Some(create_word_from_string("mopey"))
+        );
+
+        let crane = Guess::new("crane", [Absent, Absent, Absent, Absent, Absent]);
+        assert_eq!(tree.next_guess(&[crane]), None);
+    }
+
+    #[test]
+    fn test_conflicting_tree() {
+        let tree = "salet BBBBB1 courd GGGGG2\nsalet BBBBB1 crane GGGGG2\n";
+        assert!(DecisionTree::parse(tree).is_err());
+        assert!(DecisionTree::parse("salet BBBBB1\n").is_err());
+    }
+}
@@ -0,0 +1,102 @@
+use anyhow::{bail, Context, Error, Result};
+use std::cmp::Reverse;
+use std::fmt;
+use std::str::FromStr;
+
+/// How many candidates are evaluated in depth, depending on the number of
+/// remaining words.
+///
+/// A schedule is written as a comma separated list of `MIN:WIDTH` steps.
+/// The width of the first step whose `MIN` is not larger than the number of
+/// remaining words is used. A single number is a constant width.
+///
+/// # Example
+///
+/// ```
+/// use wordlebot::solver::width::WidthSchedule;
+/// let schedule: WidthSchedule = "1000:20,50:10,0:5".parse().unwrap();
+/// assert_eq!(schedule.width(3189), 20);
+/// assert_eq!(schedule.width(100), 10);
+/// assert_eq!(schedule.width(12), 5);
+/// assert_eq!(schedule.to_string(), "1000:20,50:10,0:5");
+///
+/// let schedule: WidthSchedule = "10".parse().unwrap();
+/// assert_eq!(schedule.width(3189), 10);
+/// assert_eq!(schedule.capped(3).width(3189), 3);
+/// assert_eq!(schedule.to_string(), "10");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WidthSchedule {
+    // Pairs of minimal number of remaining words and width, sorted by the
+    // minimal number of remaining words in descending order
+    steps: Vec<(usize, usize)>,
+}
+
+impl WidthSchedule {
+    pub fn constant(width: usize) -> WidthSchedule {
+        WidthSchedule {
+            steps: vec![(0, width)],
+        }
+    }
+
+    /// The schedule with every width limited to `max`
+    pub fn capped(&self, max: usize) -> WidthSchedule {
+        WidthSchedule {
+            steps: self
+                .steps
+                .iter()
+                .map(|&(min, width)| (min, width.min(max.max(1))))
+                .collect(),
+        }
+    }
+
+    /// The number of candidates for the number of remaining words
+    pub fn width(&self, n_remaining: usize) -> usize {
+        self.steps
+            .iter()
+            .find(|(min, _)| *min <= n_remaining)
+            .map(|(_, width)| *width)
+            .unwrap_or(1)
+    }
+}
+
+impl FromStr for WidthSchedule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut steps = s
+            .split(',')
+            .map(|step| {
+                let (min, width) = step.split_once(':').unwrap_or(("0", step));
+                let min = min
+                    .trim()
+                    .parse::<usize>()
+                    .with_context(|| format!("Invalid number of words in '{}'", step))?;
+                let width = width
+                    .trim()
+                    .parse::<usize>()
+                    .with_context(|| format!("Invalid width in '{}'", step))?;
+                if width == 0 {
+                    bail!("Width in '{}' must be at least 1", step)
+                }
+                Ok((min, width))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        steps.sort_by_key(|&(min, _)| Reverse(min));
+        Ok(WidthSchedule { steps })
+    }
+}
+
+impl fmt::Display for WidthSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let [(0, width)] = self.steps[..] {
+            return write!(f, "{}", width);
+        }
+        let steps: Vec<String> = self
+            .steps
+            .iter()
+            .map(|(min, width)| format!("{}:{}", min, width))
+            .collect();
+        write!(f, "{}", steps.join(","))
+    }
+}
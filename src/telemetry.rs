@@ -0,0 +1,241 @@
+//! Opt-in telemetry of game outcomes for comparing strategies.
+//!
+//! Nothing is recorded unless a telemetry file is configured. Outcomes are
+//! only appended to that local file and never sent anywhere. The solution
+//! itself is not recorded, only the date, the strategy, the opener and the
+//! number of guesses.
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::wordle::{parse_word, Word, MAX_GUESSES};
+
+/// The outcome of a single game
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outcome {
+    /// The puzzle date as YYYY-MM-DD
+    pub date: String,
+    pub strategy: String,
+    pub opener: Word,
+    /// The number of guesses, 0 if the game was not solved
+    pub guesses: usize,
+}
+
+impl Outcome {
+    /// An outcome for a game played today
+    pub fn today(strategy: &str, opener: Word, guesses: usize) -> Outcome {
+        Outcome {
+            date: today(),
+            strategy: strategy.to_string(),
+            opener,
+            guesses,
+        }
+    }
+
+    fn parse(line: &str) -> Result<Outcome> {
+        let cells: Vec<&str> = line.split('\t').collect();
+        if cells.len() != 4 {
+            bail!("Expected 4 columns, got {}", cells.len())
+        }
+        Ok(Outcome {
+            date: cells[0].to_string(),
+            strategy: cells[1].to_string(),
+            opener: parse_word(cells[2])?,
+            guesses: cells[3].parse().context("Parsing number of guesses")?,
+        })
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}",
+            self.date, self.strategy, self.opener, self.guesses
+        )
+    }
+}
+
+/// Appends game outcomes to a local file
+#[derive(Clone, Debug)]
+pub struct Telemetry {
+    path: PathBuf,
+}
+
+impl Telemetry {
+    pub fn new(path: PathBuf) -> Telemetry {
+        Telemetry { path }
+    }
+
+    pub fn record(&self, outcome: &Outcome) -> Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Error opening telemetry file {}", self.path.display()))?;
+        writeln!(file, "{}", outcome).context("Error writing telemetry")
+    }
+
+    pub fn read(&self) -> Result<Vec<Outcome>> {
+        let text = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Error reading telemetry file {}", self.path.display()))?;
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| Outcome::parse(line).with_context(|| format!("Line {}", i + 1)))
+            .collect()
+    }
+}
+
+/// Aggregates outcomes per strategy and opener into a tab separated table
+/// with the number of games, the number of solved games, the average
+/// number of guesses of the solved games and the counts for 1 to 6 guesses.
+/// Games that took more than `MAX_GUESSES` guesses, e.g. analysed in the
+/// TUI, are lost by the rules and do not count as solved.
+pub fn aggregate(outcomes: &[Outcome]) -> String {
+    let mut groups: BTreeMap<(String, String), Vec<usize>> = BTreeMap::new();
+    for outcome in outcomes {
+        groups
+            .entry((outcome.strategy.clone(), outcome.opener.to_string()))
+            .or_default()
+            .push(outcome.guesses);
+    }
+
+    let columns: Vec<String> = (1..=MAX_GUESSES).map(|n| n.to_string()).collect();
+    let mut lines = vec![format!(
+        "strategy\topener\tgames\tsolved\tmean\t{}",
+        columns.join("\t")
+    )];
+    for ((strategy, opener), guesses) in groups {
+        let solved: Vec<usize> = guesses
+            .iter()
+            .copied()
+            .filter(|g| (1..=MAX_GUESSES).contains(g))
+            .collect();
+        let mean = solved.iter().sum::<usize>() as f64 / solved.len().max(1) as f64;
+        let counts: Vec<String> = (1..=MAX_GUESSES)
+            .map(|n| solved.iter().filter(|&&g| g == n).count().to_string())
+            .collect();
+        lines.push(format!(
+            "{}\t{}\t{}\t{}\t{:.2}\t{}",
+            strategy,
+            opener,
+            guesses.len(),
+            solved.len(),
+            mean,
+            counts.join("\t")
+        ));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// The current date (UTC) as YYYY-MM-DD
+pub fn today() -> String {
+    date_from_days(day_number())
+}
+
+/// The number of days (UTC) since 1970-01-01
+pub fn day_number() -> u64 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    seconds / 86400
+}
+
+/// Converts a civil date as YYYY-MM-DD to days since 1970-01-01, the
+/// inverse of [`date_from_days`]
+pub fn days_from_date(date: &str) -> Result<u64> {
+    let parts: Vec<&str> = date.split('-').collect();
+    let [year, month, day] = parts[..] else {
+        bail!("Expected a date as YYYY-MM-DD, got '{}'", date)
+    };
+    let parse = |part: &str| -> Result<i64> {
+        part.parse()
+            .with_context(|| format!("Expected a date as YYYY-MM-DD, got '{}'", date))
+    };
+    let (year, month, day) = (parse(year)?, parse(month)?, parse(day)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || year < 1970 {
+        bail!("'{}' is not a date after 1970-01-01", date)
+    }
+    // Algorithm from http://howardhinnant.github.io/date_algorithms.html
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Ok((era * 146097 + doe - 719468) as u64)
+}
+
+/// Converts days since 1970-01-01 to a civil date
+pub fn date_from_days(days: u64) -> String {
+    // Algorithm from http://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_from_days() {
+        assert_eq!(date_from_days(0), "1970-01-01");
+        assert_eq!(date_from_days(18797), "2021-06-19");
+        assert_eq!(date_from_days(19782), "2024-02-29");
+        for days in [0, 18797, 19782, 19783] {
+            assert_eq!(days_from_date(&date_from_days(days)).unwrap(), days);
+        }
+        assert!(days_from_date("2024-13-01").is_err());
+        assert!(days_from_date("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_outcome_roundtrip() {
+        let outcome = Outcome {
+            date: "2024-05-01".to_string(),
+            strategy: "two-level".to_string(),
+            opener: parse_word("tarse").unwrap(),
+            guesses: 4,
+        };
+        assert_eq!(outcome.to_string(), "2024-05-01\ttwo-level\tTARSE\t4");
+        assert_eq!(Outcome::parse(&outcome.to_string()).unwrap(), outcome);
+        assert!(Outcome::parse("2024-05-01\ttwo-level").is_err());
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let outcome = |strategy: &str, guesses| Outcome {
+            date: "2024-05-01".to_string(),
+            strategy: strategy.to_string(),
+            opener: parse_word("tarse").unwrap(),
+            guesses,
+        };
+        let outcomes = vec![
+            outcome("entropy", 3),
+            outcome("entropy", 4),
+            outcome("entropy", 0),
+            outcome("entropy", 8),
+            outcome("two-level", 3),
+        ];
+        assert_eq!(
+            aggregate(&outcomes),
+            "strategy\topener\tgames\tsolved\tmean\t1\t2\t3\t4\t5\t6\n\
+             entropy\tTARSE\t4\t2\t3.50\t0\t0\t1\t1\t0\t0\n\
+             two-level\tTARSE\t1\t1\t3.00\t0\t0\t1\t0\t0\t0\n"
+        );
+    }
+}
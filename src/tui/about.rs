@@ -0,0 +1,35 @@
+use crate::wordlebot::about::{self, Release};
+
+use super::*;
+
+/// The check for a newer release on the About screen
+pub enum UpdateCheck {
+    /// Not enabled with `--check-updates`
+    Disabled,
+    /// Checked when the About screen is first opened
+    Pending,
+    Checking,
+    Done(Result<Release, String>),
+}
+
+impl App {
+    /// Look for a newer release when the About screen is opened
+    pub fn set_check_updates(&mut self, check: bool) {
+        self.update_check = match check {
+            true => UpdateCheck::Pending,
+            false => UpdateCheck::Disabled,
+        };
+    }
+
+    pub fn toggle_about(&mut self) {
+        self.show_about = !self.show_about;
+        if self.show_about && matches!(self.update_check, UpdateCheck::Pending) {
+            self.update_check = UpdateCheck::Checking;
+            let tx = self.action_tx.clone();
+            tokio::task::spawn_blocking(move || {
+                let release = about::latest_release().map_err(|e| format!("{:#}", e));
+                tx.send(Some(Action::UpdateRelease(release))).ok();
+            });
+        }
+    }
+}
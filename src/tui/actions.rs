@@ -1,4 +1,15 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use super::streak::StreakResult;
 use super::*;
+use crate::wordlebot::about::Release;
+use crate::wordlebot::platform;
+use crate::wordlebot::solver::cache::remaining_key;
+use crate::wordlebot::solver::multi::MultiEvaluation;
+use crate::wordlebot::solver::simulate::Simulation;
 
 pub enum Action {
     Exit,
@@ -10,23 +21,198 @@ pub enum Action {
     EnterChar(char),
     DeleteChar,
     ToggleStatus,
-    UpdateGuesses,
+    // Color every letter of a row at once
+    SetStatus(usize, Vec<LetterStatus>),
+    Explain,
+    ToggleRankingDiff,
+    // Rank the suggestions by the next strategy
+    NextStrategy,
+    ToggleNgrams,
+    ToggleHeatmap,
+    // Explain the selected suggestion
+    ToggleWhy,
+    // Simulate the games after the selected suggestion
+    Simulate,
+    // Show the guesses most likely to win within the rounds left
+    ToggleStreak,
+    // Show the timings of the main loop
+    ToggleProfile,
+    // Show the statistics of the history
+    ToggleStats,
+    // Scroll the remaining words by a page
+    PageUp,
+    PageDown,
+    ToggleSort,
+    // Trust the yellow and gray tiles less or more
+    LessConfidence,
+    MoreConfidence,
+    EditFilter,
+    ToggleProbe,
+    // Suggest guesses of untried letters only
+    ToggleBurn,
+    // A character that is only used in the filter
+    FilterChar(char),
+    // Edit the letters known without a guess
+    EditKnown,
+    // Export the remaining words to a file
+    Export,
+    // A character that is only used in a file name
+    TypeChar(char),
+    ScrubBack,
+    ScrubForward,
+    PreviousBar,
+    NextBar,
+    ToggleBranch,
+    Hint,
+    // Type the suggestion with this rank, counted from 0, into the next row
+    UseSuggestion(usize),
+    // Pasted text, e.g. the emoji grid of a shared result
+    Paste(String),
+    // Paste the clipboard, for terminals that do not paste themselves
+    PasteClipboard,
+    // Copy the emoji grid of the guesses
+    CopyShare,
+    Notice(Result<String, String>),
+    ToggleAbout,
+    // The latest release, or why it could not be fetched
+    UpdateRelease(Result<Release, String>),
+    Reset,
     GetSuggestions(Vec<Guess>),
-    UpdateSuggestions(Vec<GuessEvaluation>),
+    GetBranch(Vec<Guess>),
+    UpdateBranch(Vec<Guess>, Vec<GuessEvaluation>),
+    // How many groups of the simulation after the guesses are played, and
+    // how many there are
+    SimulationProgress(Vec<Guess>, usize, usize),
+    UpdateSimulation(Vec<Guess>, Simulation),
+    UpdateStreak(Vec<Guess>, StreakResult),
+    UpdateSuggestions(Vec<Guess>, Vec<GuessEvaluation>),
+    // How many of the guesses that take long are evaluated for the
+    // suggestions of the guesses, and how many there are
+    Progress(Vec<Guess>, usize, usize),
+    // Stop ranking the suggestions of the visible guesses
+    Cancel,
+    // The suggestions of the compared snapshot
+    UpdateCompare(Vec<Guess>, Vec<GuessEvaluation>),
+    PreviousBoard,
+    NextBoard,
+    UpdateBoardSuggestions(Vec<Vec<Guess>>, Vec<MultiEvaluation>),
+    ReloadOverlay,
+    UpdateSolver(Result<Arc<Solver>, String>),
+}
+
+impl Action {
+    /// The character a key typed, for the prompts that take any text
+    fn typed_char(&self) -> Option<char> {
+        match self {
+            Action::EnterChar(x) | Action::FilterChar(x) | Action::TypeChar(x) => Some(*x),
+            Action::UseSuggestion(rank) => char::from_digit(*rank as u32 + 1, 10),
+            Action::PreviousBar => Some(','),
+            Action::NextBar => Some('.'),
+            Action::PreviousBoard => Some('['),
+            Action::NextBoard => Some(']'),
+            Action::Explain => Some('?'),
+            Action::LessConfidence => Some('<'),
+            Action::MoreConfidence => Some('>'),
+            _ => None,
+        }
+    }
 }
 
 impl App {
     pub fn update(&mut self, msg: Option<Action>) {
         if let Some(msg) = msg {
             match msg {
+                Action::Exit if self.show_about => {
+                    self.show_about = false;
+                }
+                Action::ToggleAbout => {
+                    self.toggle_about();
+                }
+                Action::Exit if self.stats.is_some() => {
+                    self.stats = None;
+                }
+                Action::ToggleStats => {
+                    self.toggle_stats();
+                }
+                Action::UpdateRelease(release) => {
+                    self.update_check = UpdateCheck::Done(release);
+                }
+                // The export prompt takes the keys while it is open
+                Action::Exit if self.export_input.is_some() => {
+                    self.export_input = None;
+                }
+                Action::Enter if self.export_input.is_some() => {
+                    self.export_remaining();
+                }
+                Action::DeleteChar if self.export_input.is_some() => {
+                    self.export_char(None);
+                }
+                _ if self.export_input.is_some() && msg.typed_char().is_some() => {
+                    self.export_char(msg.typed_char());
+                }
+                // The known letters editor takes the keys while it is open
+                Action::Exit if self.known_input.is_some() => {
+                    self.known_input = None;
+                }
+                Action::Enter if self.known_input.is_some() => {
+                    self.apply_known();
+                }
+                Action::DeleteChar if self.known_input.is_some() => {
+                    self.known_char(None);
+                }
+                _ if self.known_input.is_some() && msg.typed_char().is_some() => {
+                    self.known_char(msg.typed_char());
+                }
+                // A game is played by its guesses only
+                Action::EditKnown if self.game.is_some() || self.boards.is_some() => {}
+                Action::EditKnown => {
+                    self.edit_known();
+                }
+                // The remaining words would give the answer away
+                Action::Export if self.game.is_some() => {}
+                Action::Export => {
+                    self.edit_export();
+                }
+                Action::TypeChar(_) => {}
+                // The filter editor takes the keys while it is open
+                Action::Exit if self.filter_input.is_some() => {
+                    self.filter_input = None;
+                }
+                Action::EnterChar(x) | Action::FilterChar(x) if self.filter_input.is_some() => {
+                    self.filter_char(Some(x));
+                }
+                Action::DeleteChar if self.filter_input.is_some() => {
+                    self.filter_char(None);
+                }
+                Action::Enter if self.filter_input.is_some() => {
+                    self.apply_filter();
+                }
+                Action::EditFilter => {
+                    self.edit_filter();
+                }
+                // Every guess of a game uses a turn
+                Action::ToggleProbe if self.game.is_some() => {}
+                Action::ToggleProbe => {
+                    self.toggle_probe();
+                }
+                Action::ToggleBurn => {
+                    self.toggle_burn();
+                }
+                Action::FilterChar(_) | Action::UseSuggestion(_) if self.filter_input.is_some() => {
+                }
+                Action::FilterChar(_) => {}
                 Action::Exit => {
                     self.token.cancel();
                     self.exit = true;
                 }
+                // A game is played row by row
+                Action::MoveUp | Action::MoveDown if self.game.is_some() => {}
                 Action::MoveUp => {
+                    self.commit_selected_row();
                     self.move_up();
                 }
                 Action::MoveDown => {
+                    self.commit_selected_row();
                     self.move_down();
                 }
                 Action::MoveLeft => {
@@ -35,30 +221,209 @@ impl App {
                 Action::MoveRight => {
                     self.move_right();
                 }
+                Action::Enter if self.game.is_some() => {
+                    self.play_selected_row();
+                }
                 Action::Enter => {
-                    self.move_down();
-                    self.selected_letter = 0;
+                    // Only a confirmed row moves on to the next one
+                    self.commit_selected_row();
+                    if self.rows[self.selected_word] == RowState::Committed {
+                        self.move_down();
+                        self.selected_letter = 0;
+                    }
                 }
-                Action::EnterChar(x) => {
-                    let res = self.set_letter(Some(x));
-                    self.action_tx.send(res).unwrap();
-                    self.move_right();
+                Action::EnterChar(x) if self.is_editable() => {
+                    // Letters outside the alphabet of the word list can
+                    // never form a word
+                    let letter = x.to_lowercase().next().unwrap_or(x);
+                    if self.solver.alphabet().contains(&letter) {
+                        self.set_letter(Some(letter));
+                        self.move_right();
+                    }
                 }
-                Action::DeleteChar => {
-                    let res = self.set_letter(None);
-                    self.action_tx.send(res).unwrap();
+                Action::DeleteChar if self.is_editable() => {
+                    self.set_letter(None);
                     self.move_left();
                 }
+                Action::EnterChar(_) | Action::DeleteChar => {}
                 Action::ToggleStatus => {
-                    let res = self.toggle_status();
-                    self.action_tx.send(res).unwrap()
+                    self.toggle_status();
+                }
+                // The game colors the guesses itself
+                Action::SetStatus(..) if self.game.is_some() => {}
+                Action::SetStatus(row, status) => {
+                    self.set_row_status(row, &status);
+                }
+                Action::Paste(text) => {
+                    self.paste_share(&text);
+                }
+                Action::PasteClipboard => {
+                    let tx = self.action_tx.clone();
+                    tokio::task::spawn_blocking(move || {
+                        let action = match platform::read_clipboard() {
+                            Ok(text) => Action::Paste(text),
+                            Err(e) => Action::Notice(Err(e.to_string())),
+                        };
+                        tx.send(Some(action)).ok();
+                    });
+                }
+                Action::CopyShare => {
+                    self.copy_share();
+                }
+                Action::Notice(notice) => {
+                    self.notice = Some(notice);
+                }
+                Action::Explain => {
+                    self.explain_selected_word();
+                }
+                Action::ToggleRankingDiff => {
+                    self.show_ranking_diff = !self.show_ranking_diff;
+                    self.request_compare_suggestions(&self.visible_guesses());
+                    // The diff needs the two level bits of the suggestions,
+                    // the comparison with a snapshot does not
+                    if self.show_ranking_diff && !self.two_level && self.compare.is_none() {
+                        self.suggestion_cache.clear();
+                        self.show_visible_state();
+                    }
+                }
+                Action::NextStrategy => {
+                    self.next_strategy();
+                }
+                Action::ToggleNgrams => {
+                    self.toggle_panel(Panel::Ngrams);
+                }
+                Action::ToggleHeatmap => {
+                    self.toggle_panel(Panel::Heatmap);
+                }
+                Action::ToggleWhy => {
+                    self.toggle_panel(Panel::Why);
+                }
+                Action::Simulate => {
+                    self.toggle_simulation();
                 }
-                Action::UpdateGuesses => {
-                    self.update_guesses();
+                Action::ToggleStreak => {
+                    self.toggle_streak();
+                }
+                Action::ToggleProfile => {
+                    self.toggle_profile();
+                }
+                Action::PageUp => {
+                    self.scroll_remaining(-1);
+                }
+                Action::PageDown => {
+                    self.scroll_remaining(1);
+                }
+                Action::ToggleSort => {
+                    self.toggle_remaining_sort();
+                }
+                // A game colors the guesses itself, and the boards are
+                // filtered together
+                Action::LessConfidence | Action::MoreConfidence
+                    if self.game.is_some() || self.boards.is_some() => {}
+                Action::LessConfidence => {
+                    self.change_confidence(1);
+                }
+                Action::MoreConfidence => {
+                    self.change_confidence(-1);
+                }
+                Action::Reset if self.game.is_some() => {
+                    self.next_game();
+                }
+                Action::Reset => {
+                    self.reset();
+                }
+                Action::ScrubBack => {
+                    let rounds = self.scrub.unwrap_or(self.committed_guesses().len());
+                    if rounds > 0 {
+                        self.scrub(Some(rounds - 1));
+                    }
+                }
+                Action::ScrubForward => {
+                    if let Some(rounds) = self.scrub {
+                        self.scrub(Some(rounds + 1));
+                    }
+                }
+                Action::PreviousBar => {
+                    self.select_bar(-1);
+                }
+                Action::NextBar => {
+                    self.select_bar(1);
+                }
+                Action::ToggleBranch => {
+                    self.toggle_branch();
+                }
+                Action::Hint => {
+                    self.hint();
+                }
+                Action::UseSuggestion(rank) => {
+                    self.use_suggestion(rank);
+                }
+                Action::GetBranch(guesses) => {
+                    let solver = Arc::clone(&self.solver);
+                    let formula = self.formula.clone();
+                    let score = self.score;
+                    let filter = self.filter.clone();
+                    let turn = self.turns(guesses.len());
+                    let tx = self.action_tx.clone();
+                    // The pattern of the branch is certain, like its words
+                    let task = Task {
+                        guesses,
+                        confidence: 1.0,
+                        known: self.known.clone(),
+                        tx: None,
+                        token: self.token.child_token(),
+                    };
+                    tokio::spawn(async move {
+                        // The what-if branch is ranked without the slow two
+                        // level bits
+                        let ranking = Ranking::new(formula.as_deref(), score, false, None);
+                        let suggestions =
+                            get_suggestions(&solver, &task, turn, false, None, ranking, &filter)
+                                .await;
+                        tx.send(Some(Action::UpdateBranch(task.guesses, suggestions)))
+                            .ok();
+                    });
+                }
+                Action::UpdateBranch(guesses, suggestions) => {
+                    self.update_branch(guesses, suggestions);
+                }
+                Action::SimulationProgress(guesses, done, total) => {
+                    self.update_simulation_progress(guesses, done, total);
+                }
+                Action::UpdateSimulation(guesses, simulation) => {
+                    self.update_simulation(guesses, simulation);
+                }
+                Action::UpdateStreak(guesses, result) => {
+                    self.update_streak(guesses, result);
+                }
+                // The boards are ranked together
+                Action::GetSuggestions(_) if self.boards.is_some() => {}
+                // The opening table saves ranking all words in eco mode
+                Action::GetSuggestions(guesses)
+                    if self.eco
+                        && guesses.is_empty()
+                        && self.known.is_empty()
+                        && self.has_opening_table() =>
+                {
+                    let suggestions = self.opening_suggestions();
+                    self.action_tx
+                        .send(Some(Action::UpdateSuggestions(guesses, suggestions)))
+                        .unwrap();
                 }
                 Action::GetSuggestions(guesses) => {
-                    let sovler = self.solver.clone();
-                    let two_level = self.two_level;
+                    let solver = Arc::clone(&self.solver);
+                    // The diff of the rankings shows the two level bits, but
+                    // only the two level strategy ranks by them
+                    let two_level =
+                        self.two_level || (self.show_ranking_diff && self.compare.is_none());
+                    let ranks_two_level = self.two_level;
+                    let search = self.search;
+                    let fixed = self.fixed_guess(&guesses);
+                    let formula = self.formula.clone();
+                    let score = self.score;
+                    let burn = self.burn;
+                    let filter = self.filter.clone();
+                    let turn = self.turns(guesses.len());
                     let tx = self.action_tx.clone();
 
                     if let Some(token) = self.child_token.take() {
@@ -68,35 +433,87 @@ impl App {
                     let child = self.token.child_token();
                     let child_clone = child.clone();
                     self.child_token = Some(child.clone());
+                    let task = Task {
+                        guesses: guesses.clone(),
+                        confidence: self.confidence,
+                        known: self.known.clone(),
+                        tx: Some(tx.clone()),
+                        token: child.clone(),
+                    };
 
                     tokio::spawn(async move {
+                        let ranking = match burn {
+                            true => Ranking::Burn,
+                            false => {
+                                Ranking::new(formula.as_deref(), score, ranks_two_level, search)
+                            }
+                        };
                         let suggestions = tokio::select! {
                             biased;
                             _ = child_clone.cancelled() => {
                                 // The token was cancelled
                                 None
                             }
-                            x = get_suggestions(&sovler, guesses, two_level) => {
+                            x = get_suggestions(&solver, &task, turn, two_level, fixed, ranking, &filter) => {
                                 Some(x)
                             }
                         };
                         if !child.is_cancelled() {
                             if let Some(suggestions) = suggestions {
-                                tx.send(Some(Action::UpdateSuggestions(suggestions)))
+                                tx.send(Some(Action::UpdateSuggestions(guesses, suggestions)))
                                     .unwrap();
                             }
                         }
                     });
                 }
-                Action::UpdateSuggestions(suggestions) => {
-                    self.suggestions = suggestions;
+                Action::UpdateSuggestions(guesses, suggestions) => {
+                    if guesses == self.visible_guesses() {
+                        self.suggestions = suggestions.clone();
+                    }
+                    if matches!(&self.progress, Some((g, _, _)) if *g == guesses) {
+                        self.progress = None;
+                    }
+                    self.record_recommendation(&guesses, &suggestions);
+                    self.suggestion_cache.retain(|(g, _)| *g != guesses);
+                    self.suggestion_cache.push((guesses, suggestions));
+                }
+                Action::Progress(guesses, done, total) => {
+                    if guesses == self.visible_guesses() {
+                        self.progress = Some((guesses, done, total));
+                    }
+                }
+                Action::Cancel => {
+                    self.cancel_suggestions();
+                }
+                Action::UpdateCompare(guesses, suggestions) => {
+                    self.update_compare(guesses, suggestions);
+                }
+                Action::PreviousBoard => {
+                    if let Some(boards) = &self.boards {
+                        let n = boards.len();
+                        self.select_board((boards.selected + n - 1) % n);
+                    }
+                }
+                Action::NextBoard => {
+                    if let Some(boards) = &self.boards {
+                        self.select_board((boards.selected + 1) % boards.len());
+                    }
+                }
+                Action::UpdateBoardSuggestions(guesses, suggestions) => {
+                    self.update_board_suggestions(guesses, suggestions);
+                }
+                Action::ReloadOverlay => {
+                    self.reload_overlay();
+                }
+                Action::UpdateSolver(result) => {
+                    self.update_solver(result);
                 }
             }
         }
     }
 
     fn move_right(&mut self) {
-        if self.selected_letter < 4 {
+        if self.selected_letter + 1 < self.solver.n_letters() {
             self.selected_letter += 1;
         }
     }
@@ -107,7 +524,14 @@ impl App {
         }
     }
 
-    fn move_down(&mut self) {
+    pub fn move_down(&mut self) {
+        // Games longer than the rules allow get another row
+        let last = self.guesses.len() - 1;
+        if self.selected_word == last && self.rows[last] == RowState::Committed {
+            self.guesses.push(Guess::empty(self.solver.n_letters()));
+            self.rows.push(RowState::Empty);
+            self.probes.push(false);
+        }
         if self.selected_word < self.guesses.len() - 1 {
             self.selected_word += 1;
         }
@@ -119,90 +543,637 @@ impl App {
         }
     }
 
-    fn set_letter(&mut self, letter: Option<char>) -> Option<Action> {
+    fn set_letter(&mut self, letter: Option<char>) {
         self.guesses[self.selected_word].set_letter(letter, self.selected_letter);
         if letter.is_none() {
             self.guesses[self.selected_word]
                 .update_status(LetterStatus::Absent, self.selected_letter)
         }
-        // self.update_guesses();
-        Some(Action::UpdateGuesses)
+        let state = RowState::after_edit(&self.guesses[self.selected_word].word, &self.solver);
+        self.set_row_state(self.selected_word, state);
     }
 
-    fn toggle_status(&mut self) -> Option<Action> {
-        if self.guesses[self.selected_word].word.chars[self.selected_letter].is_some() {
+    fn toggle_status(&mut self) {
+        // The game colors the guesses itself
+        if self.rows[self.selected_word].allows_status() && self.game.is_none() {
             use LetterStatus::*;
-            let current =
-                decode_status(self.guesses[self.selected_word].status)[self.selected_letter];
+            let current = self.guesses[self.selected_word].get_status()[self.selected_letter];
             let new = match current {
                 Absent => Misplaced,
                 Misplaced => Correct,
                 Correct => Absent,
             };
             self.guesses[self.selected_word].update_status(new, self.selected_letter);
-            Some(Action::UpdateGuesses)
-        } else {
-            None
+            // The changed status needs to be confirmed again
+            self.set_row_state(self.selected_word, RowState::ValidPendingStatus);
         }
     }
 
-    fn update_guesses(&mut self) {
-        let mut tmp = [Guess::empty(); 6];
+    /// Type the suggestion with the given rank into the row after the
+    /// committed guesses, which the suggestions are for, and select it.
+    /// Letters that were typed into that row are replaced.
+    fn use_suggestion(&mut self, rank: usize) {
+        // The suggestions of a game are hidden, and those of an earlier
+        // round are not for the next row
+        if self.game.is_some() || self.scrub.is_some() {
+            return;
+        }
+        let word = match &self.boards {
+            Some(boards) => boards.suggestions.get(rank).map(|w| w.word),
+            None => self.suggestions.get(rank).map(|w| w.word),
+        };
+        let Some(word) = word else {
+            return;
+        };
+        let row = self.committed_guesses().len();
+        // All rows are used, so a row is added
+        if row == self.rows.len() {
+            self.selected_word = row - 1;
+            self.move_down();
+        }
+        self.guesses[row] = Guess { word, status: 0 };
+        self.set_row_state(row, RowState::after_edit(&word, &self.solver));
+        self.selected_word = row;
+        self.selected_letter = 0;
+        self.selected_suggestion = Some(rank);
+    }
 
-        for (i, item) in tmp.iter_mut().enumerate() {
-            let current_guess = self.guesses[i];
-            if self.solver.is_valid_guess(&current_guess.word) {
-                *item = current_guess
-            } else {
+    /// Copy the emoji grid of the committed guesses to the clipboard, e.g.
+    /// to share a game
+    fn copy_share(&mut self) {
+        let guesses = self.committed_guesses();
+        if guesses.is_empty() {
+            return;
+        }
+        let grid = share_grid(&guesses);
+        let tx = self.action_tx.clone();
+        tokio::task::spawn_blocking(move || {
+            let notice = match platform::write_clipboard(&grid) {
+                Ok(()) => Ok("Copied the grid".to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            tx.send(Some(Action::Notice(notice))).ok();
+        });
+    }
+
+    /// Color the rows with the emoji grid of a shared result, from the top.
+    /// The words are typed into the rows, since the grid does not contain
+    /// them. Rows without a valid word stop the import. A single row of
+    /// tiles colors the selected row instead.
+    fn paste_share(&mut self, text: &str) {
+        // The game colors the guesses itself, and the boards are colored
+        // per board
+        if self.game.is_some() || self.boards.is_some() {
+            return;
+        }
+        let Ok(grid) = parse_share_grid(text) else {
+            return;
+        };
+        if let [status] = &grid[..] {
+            if self.selected_word > 0 {
+                self.set_row_status(self.selected_word, status);
+                return;
+            }
+        }
+        let mut n_colored = 0;
+        for (i, status) in grid.iter().enumerate().take(self.rows.len()) {
+            if !self.apply_row_status(i, status) {
                 break;
             }
+            n_colored += 1;
+        }
+        // The solver state is recomputed once for all rows
+        if n_colored > 0 {
+            self.selected_word = n_colored.min(self.rows.len() - 1);
+            self.selected_letter = 0;
+            self.update_guesses();
+        }
+    }
+
+    /// Recompute the solver state for the committed guesses
+    pub fn update_guesses(&mut self) {
+        self.scrub = None;
+        self.notice = None;
+        let guesses = self.committed_guesses();
+        // Only the suggestions for earlier rounds of this game stay valid
+        self.suggestion_cache
+            .retain(|(g, _)| g.len() <= guesses.len() && g[..] == guesses[..g.len()]);
+        self.show_visible_state();
+        self.update_evaluations(&guesses);
+        self.record_game(&guesses);
+    }
+
+    /// The guesses for which the solver state is shown. These are all
+    /// committed guesses, unless an earlier round is selected with the
+    /// scrubber.
+    pub fn visible_guesses(&self) -> Vec<Guess> {
+        let mut guesses = self.committed_guesses();
+        if let Some(rounds) = self.scrub {
+            guesses.truncate(rounds);
         }
+        guesses
+    }
 
-        if tmp != self.cached_guesses {
-            self.cached_guesses = tmp;
-            let tmp: Vec<Guess> = tmp
-                .into_iter()
-                .filter(|guess| guess.word.chars.iter().all(|c| c.is_some()))
-                .collect();
-            self.action_tx
-                .send(Some(Action::GetSuggestions(tmp.clone())))
-                .unwrap();
-            self.remaining_words = self.solver.get_remaining_words_idx(&tmp);
-            // self.update_solutions(&tmp);
-            self.update_evaluations(&tmp);
+    /// Show the solver state after the first `rounds` guesses. Later
+    /// guesses are kept. Scrubbing to the last round returns to the live
+    /// state.
+    fn scrub(&mut self, rounds: Option<usize>) {
+        let n = self.committed_guesses().len();
+        self.scrub = rounds.filter(|&rounds| rounds < n);
+        self.show_visible_state();
+    }
+
+    /// Update the remaining words and suggestions for the visible guesses
+    pub fn show_visible_state(&mut self) {
+        let guesses = self.visible_guesses();
+        self.selected_suggestion = None;
+        self.conflict = None;
+        // Every board is ranked at once
+        if let Some(boards) = &self.boards {
+            let selected = boards.selected;
+            let remaining_words = self
+                .multi_solver()
+                .map(|multi| multi.remaining(selected).to_vec());
+            let remaining_words = self.or_notice(remaining_words);
+            self.set_remaining_words(remaining_words);
+            self.request_board_suggestions();
+            return;
+        }
+        let remaining_words = self
+            .solver
+            .get_soft_remaining_words_idx(&guesses, self.confidence);
+        let remaining_words = self.or_notice(remaining_words);
+        let remaining_words = self.solver.apply_constraints(&remaining_words, &self.known);
+        self.set_remaining_words(remaining_words);
+        // With soft tiles some words may remain, but the guesses still
+        // contradict each other
+        if let Ok(Some(conflict)) = self.solver.find_conflicts(&guesses) {
+            self.notice = Some(Err(conflict.to_string()));
+            self.conflict = Some(conflict);
+        }
+        self.request_compare_suggestions(&guesses);
+        if self.panel == Panel::Streak {
+            self.advise_streak();
+        }
+        match self.suggestion_cache.iter().find(|(g, _)| *g == guesses) {
+            Some((_, suggestions)) => self.suggestions = suggestions.clone(),
+            None => {
+                if guesses.is_empty() && self.known.is_empty() {
+                    self.suggestions = self.opening_suggestions();
+                }
+                self.action_tx
+                    .send(Some(Action::GetSuggestions(guesses)))
+                    .unwrap()
+            }
+        }
+    }
+
+    /// The remaining words, or none if the solver cannot handle the guesses.
+    /// The error is shown as the notice.
+    fn or_notice(&mut self, remaining_words: Result<Vec<usize>, SolverError>) -> Vec<usize> {
+        remaining_words.unwrap_or_else(|e| {
+            self.notice = Some(Err(e.to_string()));
+            vec![]
+        })
+    }
+
+    /// Stop ranking the suggestions of the visible guesses. They are ranked
+    /// again when the guesses are shown again.
+    fn cancel_suggestions(&mut self) {
+        let guesses = self.visible_guesses();
+        let done = self.suggestion_cache.iter().any(|(g, _)| *g == guesses);
+        if done || self.boards.is_some() {
+            return;
+        }
+        let Some(token) = self.child_token.take() else {
+            return;
+        };
+        token.cancel();
+        self.progress = None;
+        // The suggestions of other guesses are not shown for these
+        self.suggestions = match guesses.is_empty() && self.known.is_empty() {
+            true => self.opening_suggestions(),
+            false => vec![],
+        };
+        self.notice = Some(Ok("Cancelled the suggestions".to_string()));
+    }
+
+    /// The guess of the decision tree or the opening book, if they cover
+    /// the guesses
+    fn fixed_guess(&self, guesses: &[Guess]) -> Option<Word> {
+        match (&self.tree, &self.book) {
+            (Some(tree), _) => tree.next_guess(guesses),
+            (None, Some(book)) => book.next_guess(guesses),
+            (None, None) => None,
+        }
+    }
+
+    /// The suggestions for the first guess from the embedded opening table.
+    /// Evaluating a few words is fast, so they are shown while all words are
+    /// ranked in the background. A custom formula or score ranks
+    /// differently, so there are none for them.
+    pub fn opening_suggestions(&self) -> Vec<GuessEvaluation> {
+        if self.formula.is_some() || self.score != ScoreStrategy::Entropy {
+            return vec![];
+        }
+        let mut words = self.solver.openers().to_vec();
+        if let Some(word) = self.fixed_guess(&[]) {
+            words.retain(|w| *w != word);
+            words.insert(0, word);
+        }
+        words.retain(|w| self.filter.matches(w, &[]));
+        words.truncate(N_SUGGESTIONS);
+        let remaining_words = self.solver.get_frequent_word_idx();
+        // The openers and the fixed guess may not be in a custom word list
+        words
+            .iter()
+            .filter_map(|w| {
+                self.solver
+                    .evaluate_guess(w, &remaining_words, None, false)
+                    .ok()
+            })
+            .collect()
+    }
+
+    /// Whether the opening table ranks the first guesses like the solver
+    fn has_opening_table(&self) -> bool {
+        let default_ranking = self.formula.is_none() && self.score == ScoreStrategy::Entropy;
+        default_ranking && !self.solver.openers().is_empty()
+    }
+
+    /// Clear all rows and start a new game
+    pub fn reset(&mut self) {
+        let empty = Guess::empty(self.solver.n_letters());
+        self.guesses = vec![empty; MAX_GUESSES];
+        self.rows = vec![RowState::Empty; MAX_GUESSES];
+        self.probes = vec![false; MAX_GUESSES];
+        self.selected_word = 0;
+        self.selected_letter = 0;
+        self.explanation = None;
+        self.selected_bar = None;
+        self.branch = None;
+        self.recorded_game = None;
+        // The known letters are of the previous answer
+        if !self.known.is_empty() {
+            self.known = ManualConstraints::default();
+            self.suggestion_cache.clear();
+            self.compare_suggestions = None;
+        }
+        self.reset_boards();
+        self.update_guesses();
+    }
+
+    /// Mark the selected row as a practice probe, or as a guess again. A
+    /// probe narrows down the words, but does not use a turn, so the
+    /// suggestions after it are ranked for the same turn as before it.
+    fn toggle_probe(&mut self) {
+        self.probes[self.selected_word] = !self.probes[self.selected_word];
+        // The suggestions depend on the turn
+        self.suggestion_cache.clear();
+        self.update_guesses();
+    }
+
+    /// Suggest only guesses of untried letters, ranked by their bits, or
+    /// the ranking of the strategy again
+    fn toggle_burn(&mut self) {
+        self.burn = !self.burn;
+        self.suggestion_cache.clear();
+        self.show_visible_state();
+    }
+
+    /// The number of turns the first `n` rows use, without the probes
+    pub fn turns(&self, n: usize) -> usize {
+        self.probes[..n].iter().filter(|&&probe| !probe).count()
+    }
+
+    /// Record a solved game once, with telemetry if it is enabled and in
+    /// the history
+    fn record_game(&mut self, guesses: &[Guess]) {
+        // The outcome of a single board is not a game
+        if self.boards.is_some() {
+            return;
+        }
+        let Some(solved) = guesses
+            .iter()
+            .position(|g| g.get_status().iter().all(|s| *s == LetterStatus::Correct))
+        else {
+            return;
+        };
+        // Solving with a probe does not win the game
+        if self.probes[solved] {
+            return;
+        }
+        let game = guesses[0..=solved].to_vec();
+        let Some(opener) = (0..=solved).find(|&i| !self.probes[i]) else {
+            return;
+        };
+        if self.recorded_game.as_ref() != Some(&game) {
+            let strategy = match &self.game {
+                Some(game) => game.strategy(),
+                None => "tui".to_string(),
+            };
+            if let Some(telemetry) = &self.telemetry {
+                let outcome = Outcome::today(&strategy, game[opener].word, self.turns(solved + 1));
+                // Telemetry must never interrupt the game, so errors are ignored
+                telemetry.record(&outcome).ok();
+            }
+            self.record_history(&strategy, game[solved].word, solved);
+            self.recorded_game = Some(game);
+        }
+    }
+
+    /// Explain why the word in the selected row is not a possible solution
+    /// given the guesses above it.
+    fn explain_selected_word(&mut self) {
+        let word = self.guesses[self.selected_word].word;
+        if word.chars().iter().any(|c| c.is_none()) {
+            self.explanation = None;
+            return;
         }
+        let mut previous = self.committed_guesses();
+        previous.truncate(self.selected_word);
+        self.explanation = Some((word, self.solver.explain_elimination(&word, &previous)));
     }
 
     fn update_evaluations(&mut self, guesses: &[Guess]) {
         let mut eva: Vec<GuessEvaluation> = vec![];
 
+        // The error of guesses the solver cannot handle is shown as the
+        // notice, so the evaluations stop before them
         for (i, g) in guesses.iter().enumerate() {
-            let remaining_words = self.solver.get_remaining_words_idx(&guesses[0..i]);
-            let e = self.solver.evalute_guess(
-                &g.word,
-                &remaining_words,
-                Some(decode_status(g.status)),
-                false,
-            );
+            let Ok(remaining_words) = self.solver.get_remaining_words_idx(&guesses[0..i]) else {
+                break;
+            };
+            let status = Some(g.get_status());
+            let Ok(e) = self
+                .solver
+                .evaluate_guess(&g.word, &remaining_words, status, false)
+            else {
+                break;
+            };
             eva.push(e)
         }
         self.evaludations = eva;
     }
 }
 
-async fn get_suggestions(
+/// How the suggestions are ranked
+pub(super) enum Ranking<'a> {
+    Entropy,
+    TwoLevel,
+    Lookahead(Search),
+    Formula(&'a Formula),
+    ExpectedTurns,
+    Minimax,
+    // Only words of untried letters, see `Solver::best_probe_words`
+    Burn,
+}
+
+impl<'a> Ranking<'a> {
+    /// A custom formula replaces the score
+    pub(super) fn new(
+        formula: Option<&'a Formula>,
+        score: ScoreStrategy,
+        two_level: bool,
+        search: Option<Search>,
+    ) -> Ranking<'a> {
+        match (formula, score) {
+            (Some(formula), _) => Ranking::Formula(formula),
+            (None, ScoreStrategy::Entropy) if search.is_some() => {
+                Ranking::Lookahead(search.unwrap())
+            }
+            (None, ScoreStrategy::Entropy) if two_level => Ranking::TwoLevel,
+            (None, ScoreStrategy::Entropy) => Ranking::Entropy,
+            (None, ScoreStrategy::ExpectedTurns) => Ranking::ExpectedTurns,
+            (None, ScoreStrategy::Minimax) => Ranking::Minimax,
+        }
+    }
+}
+
+impl fmt::Display for Ranking<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ranking::Entropy => write!(f, "entropy"),
+            Ranking::TwoLevel => write!(f, "two-level"),
+            Ranking::Lookahead(search) => write!(f, "lookahead {:?}", search),
+            Ranking::Formula(formula) => write!(f, "formula {}", formula),
+            Ranking::ExpectedTurns => write!(f, "expected turns"),
+            Ranking::Minimax => write!(f, "minimax"),
+            Ranking::Burn => write!(f, "burn"),
+        }
+    }
+}
+
+/// A computation of suggestions in the background: the guesses it is for,
+/// how much their yellow and gray tiles are trusted, the letters known
+/// without a guess, where it reports its progress and the token that
+/// cancels it
+pub(super) struct Task {
+    pub guesses: Vec<Guess>,
+    pub confidence: f32,
+    pub known: ManualConstraints,
+    pub tx: Option<mpsc::UnboundedSender<Option<Action>>>,
+    pub token: CancellationToken,
+}
+
+impl Task {
+    /// Report that `done` of `total` steps are finished. False once the
+    /// task is cancelled.
+    fn report(&self, done: usize, total: usize) -> bool {
+        if let Some(tx) = &self.tx {
+            tx.send(Some(Action::Progress(self.guesses.clone(), done, total)))
+                .ok();
+        }
+        !self.token.is_cancelled()
+    }
+}
+
+/// The suggestions after the guesses of the task, for the turn after them.
+/// On the last turn only the remaining words are suggested, since any other
+/// guess loses the game. The two level bits report their progress to the
+/// task and stop early when it is cancelled.
+pub(super) async fn get_suggestions(
     solver: &Solver,
-    guesses: Vec<Guess>,
+    task: &Task,
+    turn: usize,
     two_level: bool,
+    fixed: Option<Word>,
+    ranking: Ranking<'_>,
+    filter: &GuessFilter,
 ) -> Vec<GuessEvaluation> {
-    let remaining_words = solver.get_remaining_words_idx(&guesses);
-
-    let penalty = if guesses.is_empty() { 0.0 } else { 0.1 };
+    // The error is shown when the guesses are committed
+    let Ok(remaining_words) = solver.get_soft_remaining_words_idx(&task.guesses, task.confidence)
+    else {
+        return vec![];
+    };
+    let remaining_words = solver.apply_constraints(&remaining_words, &task.known);
+    // The same words are often ranked again, e.g. for a what-if branch or
+    // after switching the strategy back
+    let key = suggestions_key(
+        &remaining_words,
+        task,
+        turn,
+        two_level,
+        fixed,
+        &ranking,
+        filter,
+    );
+    if let Some(suggestions) = solver.cache().suggestions.get(&key) {
+        return suggestions;
+    }
 
-    let suggestions: Vec<GuessEvaluation> = solver
-        .guess(N_SUGGESTIONS, &remaining_words, penalty)
-        .iter()
-        .map(|w| solver.evalute_guess(w, &remaining_words, None, two_level))
+    let penalty = suggestion_penalty(turn);
+    let filter = filter.predicate(&task.guesses);
+    let answers: HashSet<Word> = solver
+        .get_words_from_idx(&remaining_words)
+        .into_iter()
         .collect();
+    let last_turn = turn + 1 == MAX_GUESSES;
+    let keep = |word: &Word| filter(word) && (!last_turn || answers.contains(word));
+
+    // The two level ranking evaluates its guesses already
+    let mut evaluated = vec![];
+    let mut lookahead_bits = vec![];
+    let search = match ranking {
+        Ranking::Lookahead(search) => Some(search),
+        _ => None,
+    };
+    // The exact endgame beats every ranking
+    let exact = solver.endgame_where(N_SUGGESTIONS, &remaining_words, keep);
+    let words = match ranking {
+        // Burn guesses are asked for even when the endgame is known
+        Ranking::Burn => {
+            let used: Vec<char> = task
+                .guesses
+                .iter()
+                .flat_map(|guess| guess.word.chars())
+                .flatten()
+                .copied()
+                .collect();
+            solver
+                .best_probe_words(&remaining_words, &used, N_SUGGESTIONS)
+                .map(|words| words.into_iter().filter(|word| keep(word)).collect())
+        }
+        _ if exact.is_some() => Ok(exact.iter().flatten().map(|&(word, _)| word).collect()),
+        Ranking::Entropy => solver.guess_where(N_SUGGESTIONS, &remaining_words, penalty, keep),
+        Ranking::TwoLevel => solver
+            .guess_two_level_progress(
+                N_SUGGESTIONS,
+                &remaining_words,
+                penalty,
+                N_SUGGESTIONS,
+                keep,
+                |done, total| task.report(done, total),
+            )
+            .map(|evaluations| {
+                let words = evaluations.iter().map(|e| e.word).collect();
+                evaluated = evaluations;
+                words
+            }),
+        Ranking::Lookahead(search) => solver
+            .guess_lookahead_where(
+                N_SUGGESTIONS,
+                &remaining_words,
+                penalty,
+                N_SUGGESTIONS,
+                search,
+                keep,
+            )
+            .map(|best| {
+                let words = best.iter().map(|&(word, _)| word).collect();
+                lookahead_bits = best;
+                words
+            }),
+        Ranking::Formula(formula) => solver.guess_by_formula_where(
+            N_SUGGESTIONS,
+            &remaining_words,
+            formula,
+            penalty,
+            N_SUGGESTIONS,
+            keep,
+        ),
+        Ranking::ExpectedTurns => {
+            solver.guess_by_expected_turns_where(N_SUGGESTIONS, &remaining_words, keep)
+        }
+        Ranking::Minimax => solver.guess_by_max_group_where(N_SUGGESTIONS, &remaining_words, keep),
+    };
+    let Ok(mut words) = words else {
+        return vec![];
+    };
+    // The guess of the decision tree or the opening book comes first, if
+    // it passes the filter
+    if let Some(word) = fixed.filter(|word| keep(word)) {
+        words.retain(|w| *w != word);
+        words.insert(0, word);
+        words.truncate(N_SUGGESTIONS);
+    }
+
+    let mut suggestions = Vec::with_capacity(words.len());
+    for (i, w) in words.iter().enumerate() {
+        let mut evaluation = match evaluated.iter().find(|e| e.word == *w) {
+            Some(evaluation) => evaluation.clone(),
+            None => match solver.evaluate_guess(w, &remaining_words, None, two_level) {
+                Ok(evaluation) => evaluation,
+                // The guess of a decision tree for another word list
+                Err(_) => continue,
+            },
+        };
+        // The guess of the decision tree or the opening book was not
+        // searched by the ranking
+        evaluation.lookahead_bits = match lookahead_bits.iter().find(|(word, _)| word == w) {
+            Some(&(_, bits)) => Some(bits),
+            None => {
+                search.and_then(|search| solver.lookahead_bits(w, &remaining_words, search).ok())
+            }
+        };
+        evaluation.exact = exact
+            .iter()
+            .flatten()
+            .find(|(word, _)| word == w)
+            .map(|&(_, cost)| cost);
+        suggestions.push(evaluation);
+        // The two level bits of the suggestions that were not evaluated by
+        // the ranking take a while
+        if two_level && evaluated.is_empty() && !task.report(i + 1, words.len()) {
+            return vec![];
+        }
+    }
+    // The ranking of a cancelled task may be incomplete
+    if !task.token.is_cancelled() {
+        solver.cache().suggestions.insert(key, suggestions.clone());
+    }
     suggestions
 }
+
+/// The key of the suggestions in the caches of the solver, from everything
+/// they depend on
+fn suggestions_key(
+    remaining_words: &[usize],
+    task: &Task,
+    turn: usize,
+    two_level: bool,
+    fixed: Option<Word>,
+    ranking: &Ranking,
+    filter: &GuessFilter,
+) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    remaining_key(remaining_words).hash(&mut hasher);
+    (turn, two_level, fixed, ranking.to_string()).hash(&mut hasher);
+    // The filter depends on the letters the guesses found
+    if !filter.is_empty() {
+        filter.to_string().hash(&mut hasher);
+        for guess in &task.guesses {
+            (guess.word, guess.status).hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+/// The prior penalty used to rank the suggestions of a turn, counted from 0
+pub fn suggestion_penalty(turn: usize) -> f32 {
+    if turn == 0 {
+        0.0
+    } else {
+        0.1
+    }
+}
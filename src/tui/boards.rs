@@ -0,0 +1,150 @@
+use crate::wordlebot::solver::multi::{MultiEvaluation, MultiSolver};
+
+use super::*;
+
+/// Several boards that are played with the same guesses, like Quordle. The
+/// rows of the TUI show the statuses of the selected board.
+pub struct Boards {
+    // The statuses of the rows, one entry per board. The entry of the
+    // selected board is only updated when another board is selected.
+    statuses: Vec<Vec<Pattern>>,
+    pub selected: usize,
+    pub suggestions: Vec<MultiEvaluation>,
+    // The guesses of the last suggestion request
+    pub requested: Option<Vec<Vec<Guess>>>,
+}
+
+impl Boards {
+    pub fn len(&self) -> usize {
+        self.statuses.len()
+    }
+}
+
+impl App {
+    /// Assist with `n` boards at once
+    pub fn start_boards(&mut self, n: usize) {
+        self.boards = Some(Boards {
+            statuses: vec![vec![]; n],
+            selected: 0,
+            suggestions: vec![],
+            requested: None,
+        });
+        self.reset();
+    }
+
+    /// The committed guesses of every board, up to the reviewed round
+    pub fn board_guesses(&self) -> Vec<Vec<Guess>> {
+        let Some(boards) = &self.boards else {
+            return vec![];
+        };
+        let guesses = self.visible_guesses();
+        (0..boards.len())
+            .map(|b| match b == boards.selected {
+                true => guesses.clone(),
+                false => guesses
+                    .iter()
+                    .enumerate()
+                    .map(|(i, guess)| Guess {
+                        word: guess.word,
+                        status: boards.statuses[b].get(i).copied().unwrap_or(0),
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// The solver state of all boards for the visible guesses
+    pub fn multi_solver(&self) -> Result<MultiSolver<'_>, SolverError> {
+        MultiSolver::new(&self.solver, &self.board_guesses())
+    }
+
+    /// Show the board `board` in the rows
+    pub fn select_board(&mut self, board: usize) {
+        let Some(boards) = &mut self.boards else {
+            return;
+        };
+        if board >= boards.len() || board == boards.selected {
+            return;
+        }
+        boards.statuses[boards.selected] = self.guesses.iter().map(|g| g.status).collect();
+        boards.selected = board;
+        for (i, guess) in self.guesses.iter_mut().enumerate() {
+            guess.status = boards.statuses[board].get(i).copied().unwrap_or(0);
+        }
+        // A status that was not confirmed on the other board is dropped
+        for (guess, row) in self.guesses.iter().zip(self.rows.iter_mut()) {
+            if *row == RowState::ValidPendingStatus {
+                *row = RowState::after_edit(&guess.word, &self.solver);
+            }
+        }
+        self.explanation = None;
+        self.suggestion_cache.clear();
+        self.update_guesses();
+    }
+
+    /// Rank the guesses for all boards in the background
+    pub fn request_board_suggestions(&mut self) {
+        let guesses = self.board_guesses();
+        let Some(boards) = &mut self.boards else {
+            return;
+        };
+        if boards.requested.as_ref() == Some(&guesses) {
+            return;
+        }
+        boards.requested = Some(guesses.clone());
+
+        if let Some(token) = self.child_token.take() {
+            token.cancel();
+        }
+        let child = self.token.child_token();
+        self.child_token = Some(child.clone());
+
+        let solver = Arc::clone(&self.solver);
+        let tx = self.action_tx.clone();
+        tokio::spawn(async move {
+            let task = {
+                let guesses = guesses.clone();
+                tokio::task::spawn_blocking(move || {
+                    MultiSolver::new(&solver, &guesses)
+                        .ok()
+                        .and_then(|multi| multi.guess(N_SUGGESTIONS).ok())
+                        .unwrap_or_default()
+                })
+            };
+            let suggestions = tokio::select! {
+                biased;
+                _ = child.cancelled() => None,
+                x = task => x.ok(),
+            };
+            if let Some(suggestions) = suggestions.filter(|_| !child.is_cancelled()) {
+                tx.send(Some(Action::UpdateBoardSuggestions(guesses, suggestions)))
+                    .ok();
+            }
+        });
+    }
+
+    pub fn update_board_suggestions(
+        &mut self,
+        guesses: Vec<Vec<Guess>>,
+        suggestions: Vec<MultiEvaluation>,
+    ) {
+        if guesses != self.board_guesses() {
+            return;
+        }
+        if let Some(boards) = &mut self.boards {
+            boards.suggestions = suggestions;
+        }
+    }
+
+    /// Forget the statuses of all boards
+    pub fn reset_boards(&mut self) {
+        if let Some(boards) = &mut self.boards {
+            for statuses in boards.statuses.iter_mut() {
+                statuses.clear();
+            }
+            boards.selected = 0;
+            boards.suggestions.clear();
+            boards.requested = None;
+        }
+    }
+}
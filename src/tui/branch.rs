@@ -0,0 +1,162 @@
+use ratatui::widgets::{block::*, *};
+use std::iter::zip;
+
+use super::actions::Action;
+use super::*;
+
+/// A what-if branch of the histogram: the words that would be left if a
+/// guess had gotten the pattern of the selected bar, and the best guesses
+/// after it
+pub struct Branch {
+    // The guesses before the charted guess, and the charted guess with the
+    // selected pattern
+    pub guesses: Vec<Guess>,
+    pub words: Vec<Word>,
+    // The best next guesses, `None` until they are computed
+    pub suggestions: Option<Vec<GuessEvaluation>>,
+}
+
+impl App {
+    /// The index of the committed guess whose histogram is shown
+    pub fn chart_index(&self) -> Option<usize> {
+        let i = match self.scrub {
+            // Nothing has been guessed in round 0
+            Some(0) => return None,
+            Some(rounds) => rounds - 1,
+            None => self
+                .selected_word
+                .min(self.evaludations.len().saturating_sub(1)),
+        };
+        (i < self.evaludations.len()).then_some(i)
+    }
+
+    /// Select the bar `offset` bars to the right of the selected one, and
+    /// follow it with the open branch
+    pub fn select_bar(&mut self, offset: isize) {
+        let Some(eval) = self.chart_index().map(|i| &self.evaludations[i]) else {
+            return;
+        };
+        let n = eval.group_sizes.len();
+        if n == 0 {
+            return;
+        }
+        self.selected_bar = Some(match self.selected_bar {
+            Some(bar) => (bar.min(n - 1) as isize + offset).rem_euclid(n as isize) as usize,
+            None => 0,
+        });
+        if self.branch.is_some() {
+            self.open_branch();
+        }
+    }
+
+    /// Open the branch of the selected bar, or close it if it is open
+    pub fn toggle_branch(&mut self) {
+        match self.branch.take() {
+            // A branch of another histogram is replaced
+            Some(branch) if self.is_current_branch(&branch) => {}
+            _ => self.open_branch(),
+        }
+    }
+
+    fn open_branch(&mut self) {
+        // The words would give away the answer of a game
+        let Some(guesses) = self.selected_branch().filter(|_| !self.is_hidden()) else {
+            self.branch = None;
+            return;
+        };
+        let Ok(mut remaining) = self.solver.get_remaining_words_idx(&guesses) else {
+            self.branch = None;
+            return;
+        };
+        remaining.sort_unstable();
+        let words = self.solver.get_words_from_idx(&remaining);
+        self.action_tx
+            .send(Some(Action::GetBranch(guesses.clone())))
+            .unwrap();
+        self.branch = Some(Branch {
+            guesses,
+            words,
+            suggestions: None,
+        });
+    }
+
+    /// The guesses of the branch of the selected bar in the histogram
+    fn selected_branch(&self) -> Option<Vec<Guess>> {
+        let i = self.chart_index()?;
+        let eval = &self.evaludations[i];
+        let bar = self.selected_bar.unwrap_or(0);
+        let &(status, _) = eval.group_sizes.get(bar).or(eval.group_sizes.last())?;
+        let mut guesses = self.committed_guesses();
+        guesses.truncate(i);
+        guesses.push(Guess {
+            word: eval.word,
+            status,
+        });
+        Some(guesses)
+    }
+
+    fn is_current_branch(&self, branch: &Branch) -> bool {
+        self.selected_branch().as_ref() == Some(&branch.guesses)
+    }
+
+    pub fn update_branch(&mut self, guesses: Vec<Guess>, suggestions: Vec<GuessEvaluation>) {
+        if let Some(branch) = self.branch.as_mut().filter(|b| b.guesses == guesses) {
+            branch.suggestions = Some(suggestions);
+        }
+    }
+
+    /// The open branch, if it belongs to the shown histogram
+    pub fn current_branch(&self) -> Option<&Branch> {
+        self.branch
+            .as_ref()
+            .filter(|branch| self.is_current_branch(branch) && !self.is_hidden())
+    }
+
+    pub fn render_branch(&self, branch: &Branch, area: Rect, buf: &mut Buffer) {
+        let Some(guess) = branch.guesses.last() else {
+            return;
+        };
+        let pattern: Vec<Span> = zip(guess.word.chars(), guess.get_status())
+            .map(|(letter, status)| {
+                let letter = letter.unwrap_or(' ').to_uppercase().to_string();
+                Span::styled(letter, self.theme.tile(status))
+            })
+            .collect();
+        let mut lines = vec![Line::from(pattern)];
+        match &branch.suggestions {
+            None => lines.push("Searching the best guess...".dark_gray().into()),
+            Some(suggestions) if suggestions.is_empty() => {
+                lines.push("No guess left".dark_gray().into())
+            }
+            Some(suggestions) => {
+                for (i, suggestion) in suggestions.iter().take(3).enumerate() {
+                    lines.push(Line::from(vec![
+                        format!("{}. ", i + 1).dark_gray(),
+                        suggestion.word.to_string().bold(),
+                        format!(" {:.2} bits", suggestion.expected_bits).into(),
+                    ]));
+                }
+            }
+        }
+        let words: Vec<String> = branch.words.iter().map(|w| w.to_string()).collect();
+        lines.push(Line::from(""));
+        lines.push(Line::from(words.join(" ")));
+
+        let title = format!(" What if: {} words ", branch.words.len());
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .title(Title::from(title.bold()).alignment(Alignment::Center))
+                    .title(
+                        Title::from(" <,/.> bar, <F5> close ".dark_gray())
+                            .position(Position::Bottom)
+                            .alignment(Alignment::Center),
+                    )
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::new().dark_gray()),
+            )
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,83 @@
+use super::actions::{get_suggestions, Ranking, Task};
+use super::*;
+
+/// The strategy of a saved snapshot, whose suggestions the F2 panel shows
+/// next to the current ones
+pub struct CompareStrategy {
+    pub name: String,
+    /// The solver with the ranking of the snapshot, `None` if it is the
+    /// same as the ranking of the app
+    pub solver: Option<Arc<Solver>>,
+    pub opener: Word,
+    pub two_level: bool,
+    pub formula: Option<Arc<Formula>>,
+    pub score: ScoreStrategy,
+    pub search: Option<Search>,
+    /// Whether the snapshot was saved with the same word list
+    pub same_words: bool,
+}
+
+impl App {
+    /// Compare the suggestions with the strategy of a snapshot in the F2
+    /// panel instead of the one and two level rankings
+    pub fn set_compare(&mut self, compare: Option<CompareStrategy>) {
+        self.compare = compare.map(Arc::new);
+        self.compare_suggestions = None;
+    }
+
+    /// Rank the suggestions of the snapshot for the guesses, while the
+    /// panel is shown. Unlike the current suggestions they ignore the
+    /// filter, to show what the snapshot plays.
+    pub fn request_compare_suggestions(&mut self, guesses: &[Guess]) {
+        let Some(compare) = &self.compare else {
+            return;
+        };
+        let cached = matches!(&self.compare_suggestions, Some((g, _)) if g == guesses);
+        if !self.show_ranking_diff || self.boards.is_some() || cached {
+            return;
+        }
+        let compare = Arc::clone(compare);
+        let solver = compare
+            .solver
+            .clone()
+            .unwrap_or_else(|| Arc::clone(&self.solver));
+        let guesses = guesses.to_vec();
+        let fixed = guesses.is_empty().then_some(compare.opener);
+        let turn = self.turns(guesses.len());
+        let tx = self.action_tx.clone();
+        // Only the current suggestions show their progress
+        let task = Task {
+            guesses,
+            confidence: self.confidence,
+            known: self.known.clone(),
+            tx: None,
+            token: self.token.child_token(),
+        };
+        tokio::spawn(async move {
+            let ranking = Ranking::new(
+                compare.formula.as_deref(),
+                compare.score,
+                compare.two_level,
+                compare.search,
+            );
+            let suggestions = get_suggestions(
+                &solver,
+                &task,
+                turn,
+                compare.two_level,
+                fixed,
+                ranking,
+                &GuessFilter::default(),
+            )
+            .await;
+            tx.send(Some(Action::UpdateCompare(task.guesses, suggestions)))
+                .ok();
+        });
+    }
+
+    pub fn update_compare(&mut self, guesses: Vec<Guess>, suggestions: Vec<GuessEvaluation>) {
+        if guesses == self.visible_guesses() {
+            self.compare_suggestions = Some((guesses, suggestions));
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use super::*;
+use crate::wordlebot::solver::soft::max_misremembered_tiles;
+
+/// The steps of the confidence in the yellow and gray tiles, from the
+/// exact filter to the loosest
+const CONFIDENCE_STEPS: [f32; 5] = [1.0, 0.99, 0.95, 0.9, 0.8];
+
+impl App {
+    /// Trust the yellow and gray tiles of the guesses `steps` steps less,
+    /// or more for negative steps, and filter the words again
+    pub fn change_confidence(&mut self, steps: isize) {
+        let current = CONFIDENCE_STEPS
+            .iter()
+            .position(|&c| c <= self.confidence)
+            .unwrap_or(0) as isize;
+        let step = (current + steps).clamp(0, CONFIDENCE_STEPS.len() as isize - 1);
+        let confidence = CONFIDENCE_STEPS[step as usize];
+        if confidence == self.confidence {
+            return;
+        }
+        self.confidence = confidence;
+        // The suggestions are cached by the guesses alone
+        self.suggestion_cache.clear();
+        self.compare_suggestions = None;
+        self.show_visible_state();
+    }
+
+    /// The confidence for the header of the remaining words, nothing while
+    /// the tiles are trusted
+    pub fn confidence_spans(&self) -> Vec<Span<'static>> {
+        if self.confidence >= 1.0 {
+            return vec![];
+        }
+        vec![
+            format!(
+                "  {:.0}% sure of yellow/gray, ≤{} wrong",
+                self.confidence * 100.0,
+                max_misremembered_tiles(self.confidence)
+            )
+            .yellow(),
+            " <</>>".dark_gray(),
+        ]
+    }
+}
@@ -1,8 +1,9 @@
+use crossterm::event::Event;
 use tokio::sync::mpsc;
 
 use super::actions::*;
 use super::*;
-use crossterm::event::{KeyCode, KeyEvent};
+use crate::wordlebot::input::Key;
 
 impl App {
     pub fn handle_events(
@@ -13,10 +14,9 @@ impl App {
         tokio::spawn(async move {
             loop {
                 let action = if crossterm::event::poll(tick_rate).unwrap() {
-                    if let crossterm::event::Event::Key(key) = crossterm::event::read().unwrap() {
-                        handle_key_event(key)
-                    } else {
-                        None
+                    match crossterm::event::read().unwrap() {
+                        Event::Paste(text) => Some(Action::Paste(text)),
+                        event => Key::from_event(&event).and_then(handle_key),
                     }
                 } else {
                     None
@@ -29,26 +29,62 @@ impl App {
     }
 }
 
-fn handle_key_event(key: KeyEvent) -> Option<Action> {
-    if key.kind == crossterm::event::KeyEventKind::Press {
-        let action = match key.code {
-            KeyCode::Esc => Action::Exit,
+/// The action of a key, see [`Key`] for how the terminals' keys are
+/// normalized
+fn handle_key(key: Key) -> Option<Action> {
+    let action = match key {
+        Key::Esc => Action::Exit,
+        Key::Ctrl('r') => Action::Reset,
+        Key::Ctrl('p') => Action::ToggleProbe,
+        Key::Ctrl('b') => Action::ToggleBurn,
+        Key::Ctrl('v') => Action::PasteClipboard,
+        Key::Ctrl('y') => Action::CopyShare,
+        Key::Ctrl('s') => Action::Export,
+        Key::Ctrl('k') => Action::EditKnown,
+        Key::Ctrl('x') => Action::Cancel,
+        Key::Ctrl('t') => Action::NextStrategy,
+        Key::Ctrl('g') => Action::ToggleStreak,
 
-            // Navigation
-            KeyCode::Right => Action::MoveRight,
-            KeyCode::Left => Action::MoveLeft,
-            KeyCode::Down => Action::MoveDown,
-            KeyCode::Up => Action::MoveUp,
-            KeyCode::Enter => Action::Enter,
+        // Review earlier rounds
+        Key::ShiftRight => Action::ScrubForward,
+        Key::ShiftLeft => Action::ScrubBack,
 
-            // Enter words
-            KeyCode::Char(x) if x.is_ascii_alphabetic() => Action::EnterChar(x),
-            KeyCode::Backspace => Action::DeleteChar,
-            KeyCode::Tab => Action::ToggleStatus,
-            _ => return None,
-        };
-        Some(action)
-    } else {
-        None
-    }
+        // Navigation
+        Key::Right => Action::MoveRight,
+        Key::Left => Action::MoveLeft,
+        Key::Down => Action::MoveDown,
+        Key::Up => Action::MoveUp,
+        Key::Enter => Action::Enter,
+
+        // Enter words
+        Key::Char(x) if x.is_alphabetic() => Action::EnterChar(x),
+        Key::Backspace | Key::Delete => Action::DeleteChar,
+        Key::Tab => Action::ToggleStatus,
+        Key::Char('?') => Action::Explain,
+        Key::F(1) => Action::ToggleAbout,
+        Key::F(2) => Action::ToggleRankingDiff,
+        Key::F(3) => Action::ToggleNgrams,
+        Key::F(4) => Action::EditFilter,
+        Key::Char(x @ ('+' | '-' | '!' | ' ')) => Action::FilterChar(x),
+        Key::Char(',') => Action::PreviousBar,
+        Key::Char('.') => Action::NextBar,
+        Key::F(5) => Action::ToggleBranch,
+        Key::F(6) => Action::Hint,
+        Key::F(7) => Action::ToggleSort,
+        Key::F(8) => Action::ToggleHeatmap,
+        Key::F(9) => Action::ToggleWhy,
+        Key::F(10) => Action::Simulate,
+        Key::F(11) => Action::ToggleStats,
+        Key::F(12) => Action::ToggleProfile,
+        Key::PageUp => Action::PageUp,
+        Key::PageDown => Action::PageDown,
+        Key::Char(x @ '1'..='9') => Action::UseSuggestion(x as usize - '1' as usize),
+        Key::Char('<') => Action::LessConfidence,
+        Key::Char('>') => Action::MoreConfidence,
+        Key::Char('[') => Action::PreviousBoard,
+        Key::Char(']') => Action::NextBoard,
+        Key::Char(x) if x.is_ascii_graphic() => Action::TypeChar(x),
+        _ => return None,
+    };
+    Some(action)
 }
@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+
+use crate::wordlebot::solver::export::ExportFormat;
+
+use super::*;
+
+/// The file name of an export while it is typed
+pub struct ExportInput {
+    pub text: String,
+    // Why the file could not be written
+    pub error: Option<String>,
+}
+
+impl App {
+    /// Ask for the file to export the remaining words to
+    pub fn edit_export(&mut self) {
+        self.export_input = Some(ExportInput {
+            text: "remaining.csv".to_string(),
+            error: None,
+        });
+    }
+
+    pub fn export_char(&mut self, c: Option<char>) {
+        if let Some(input) = &mut self.export_input {
+            match c {
+                Some(c) => input.text.push(c),
+                None => {
+                    input.text.pop();
+                }
+            }
+            input.error = None;
+        }
+    }
+
+    /// Write the remaining words of the visible state to the typed file, as
+    /// JSON for a `.json` file and as CSV otherwise
+    pub fn export_remaining(&mut self) {
+        let Some(input) = &mut self.export_input else {
+            return;
+        };
+        let path = PathBuf::from(input.text.trim());
+        if path.as_os_str().is_empty() {
+            input.error = Some("Type a file name".to_string());
+            return;
+        }
+        self.export_input = None;
+        let solver = self.solver.clone();
+        let guesses = self.visible_guesses();
+        let tx = self.action_tx.clone();
+        tokio::task::spawn_blocking(move || {
+            let notice = match solver.remaining_words(&guesses) {
+                Ok(words) => {
+                    let text = ExportFormat::from_path(&path).format(&words);
+                    match std::fs::write(&path, text) {
+                        Ok(()) => Ok(format!(
+                            "Exported {} words to {}",
+                            words.len(),
+                            path.display()
+                        )),
+                        Err(e) => Err(format!("Error writing {}: {}", path.display(), e)),
+                    }
+                }
+                Err(e) => Err(e.to_string()),
+            };
+            tx.send(Some(Action::Notice(notice))).ok();
+        });
+    }
+}
@@ -0,0 +1,52 @@
+use crate::wordlebot::solver::filter::GuessFilter;
+
+use super::*;
+
+/// The suggestion filter while it is edited
+pub struct FilterInput {
+    pub text: String,
+    // Why the text could not be applied
+    pub error: Option<String>,
+}
+
+impl App {
+    /// Edit the suggestion filter, starting with the current one
+    pub fn edit_filter(&mut self) {
+        let mut text = self.filter.to_string();
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        self.filter_input = Some(FilterInput { text, error: None });
+    }
+
+    pub fn filter_char(&mut self, c: Option<char>) {
+        if let Some(input) = &mut self.filter_input {
+            match c {
+                Some(c) => input.text.push(c),
+                None => {
+                    input.text.pop();
+                }
+            }
+            input.error = None;
+        }
+    }
+
+    /// Rank the suggestions again with the edited filter. A filter that
+    /// cannot be parsed stays in the editor.
+    pub fn apply_filter(&mut self) {
+        let Some(input) = &mut self.filter_input else {
+            return;
+        };
+        match input.text.parse::<GuessFilter>() {
+            Ok(filter) => {
+                self.filter_input = None;
+                if filter != self.filter {
+                    self.filter = filter;
+                    self.suggestion_cache.clear();
+                    self.show_visible_state();
+                }
+            }
+            Err(e) => input.error = Some(format!("{:#}", e)),
+        }
+    }
+}
@@ -0,0 +1,354 @@
+use super::*;
+use crate::wordlebot::history::SessionGame;
+use crate::wordlebot::solver::hint::Hint;
+use crate::wordlebot::solver::oracle::{FeedbackOracle, FixedAnswer};
+use crate::wordlebot::telemetry::day_number;
+use rand::{rngs::StdRng, SeedableRng};
+
+/// The lifecycle of a game against a secret answer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameState {
+    /// Guesses are still allowed
+    Playing,
+    /// The answer was guessed with the given number of guesses
+    Won(usize),
+    /// All guesses are used up
+    Lost,
+}
+
+/// The results of all games of the session
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Stats {
+    pub played: usize,
+    pub won: usize,
+    pub streak: usize,
+    pub max_streak: usize,
+}
+
+/// A game where the app colors the guesses
+pub struct Game {
+    pub name: &'static str,
+    // Colors the guesses, usually with a secret answer
+    pub oracle: Box<dyn FeedbackOracle + Send>,
+    pub state: GameState,
+    pub stats: Stats,
+    // Revealed hints have to be used in later guesses
+    pub hard: bool,
+    // Why the last guess was rejected
+    pub message: Option<String>,
+    // The last hint that was asked for, and how many were asked for
+    pub hint: Option<Hint>,
+    pub n_hints: usize,
+    // Where the answer was drawn, if the session has a seed
+    pub session: Option<SessionGame>,
+    n_guesses: usize,
+}
+
+/// Draws the answers of the random games. With a seed the answers come in
+/// the same order in every session.
+pub struct Session {
+    rng: StdRng,
+    seed: Option<u32>,
+    n_games: usize,
+}
+
+impl Session {
+    pub fn new(seed: Option<u32>) -> Session {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed as u64),
+            None => StdRng::from_rng(rand::thread_rng()).expect("Error seeding the answers"),
+        };
+        Session {
+            rng,
+            seed,
+            n_games: 0,
+        }
+    }
+
+    /// The next random answer, weighted by the priors, and its game in the
+    /// session
+    fn next_answer(&mut self, solver: &Solver) -> Option<(Word, Option<SessionGame>)> {
+        let answer = solver.random_answer(&mut self.rng)?;
+        self.n_games += 1;
+        let session = self.seed.map(|seed| SessionGame {
+            seed,
+            game: self.n_games,
+        });
+        Some((answer, session))
+    }
+}
+
+/// A puzzle of the daily pack
+#[derive(Clone, Copy, Debug)]
+pub struct Puzzle {
+    pub name: &'static str,
+    pub secret: Word,
+    pub hard: bool,
+    pub session: Option<SessionGame>,
+}
+
+/// Several puzzles that are played one after the other
+pub struct Pack {
+    pub puzzles: Vec<Puzzle>,
+    // The outcomes of the finished puzzles, in order
+    pub results: Vec<GameState>,
+}
+
+impl Pack {
+    /// Today's puzzle, today's hard mode puzzle and a random word of the
+    /// session
+    fn daily(solver: &Solver, session: &mut Session) -> Option<Pack> {
+        let day = day_number();
+        let (random, random_session) = session.next_answer(solver)?;
+        let puzzles = vec![
+            Puzzle {
+                name: "Daily",
+                secret: solver.daily_answer(day, 0)?,
+                hard: false,
+                session: None,
+            },
+            Puzzle {
+                name: "Daily hard",
+                secret: solver.daily_answer(day, 1)?,
+                hard: true,
+                session: None,
+            },
+            Puzzle {
+                name: "Random",
+                secret: random,
+                hard: false,
+                session: random_session,
+            },
+        ];
+        Some(Pack {
+            puzzles,
+            results: vec![],
+        })
+    }
+
+    /// The puzzle that is played, or `None` if the pack is done
+    pub fn current(&self) -> Option<&Puzzle> {
+        self.puzzles.get(self.results.len())
+    }
+}
+
+impl Game {
+    /// The name of the game in the telemetry, e.g. `play-daily-hard`
+    pub fn strategy(&self) -> String {
+        format!("play-{}", self.name.to_lowercase().replace(' ', "-"))
+    }
+
+    fn new(
+        name: &'static str,
+        oracle: Box<dyn FeedbackOracle + Send>,
+        hard: bool,
+        stats: Stats,
+        session: Option<SessionGame>,
+    ) -> Game {
+        Game {
+            name,
+            oracle,
+            state: GameState::Playing,
+            stats,
+            hard,
+            message: None,
+            hint: None,
+            n_hints: 0,
+            session,
+            n_guesses: 0,
+        }
+    }
+
+    /// Color a guess after the earlier guesses and move the game on. The
+    /// game is lost if the oracle can not color it.
+    fn play(&mut self, guess: &mut Guess, guesses: &[Guess], solver: &Solver) {
+        let Some(pattern) = self.oracle.respond(&guess.word, guesses, solver) else {
+            return self.give_up();
+        };
+        guess.status = pattern;
+        self.n_guesses += 1;
+        if pattern == solved_pattern(guess.word.n_letters()) {
+            self.state = GameState::Won(self.n_guesses);
+            self.stats.won += 1;
+            self.stats.streak += 1;
+            self.stats.max_streak = self.stats.max_streak.max(self.stats.streak);
+        } else if self.n_guesses == MAX_GUESSES {
+            self.state = GameState::Lost;
+            self.stats.streak = 0;
+        }
+        if self.state != GameState::Playing {
+            self.stats.played += 1;
+        }
+    }
+
+    /// Lose the game on purpose
+    fn give_up(&mut self) {
+        self.state = GameState::Lost;
+        self.stats.played += 1;
+        self.stats.streak = 0;
+    }
+
+    /// The stats after giving up, which counts as a loss once a guess was
+    /// made
+    fn abandon(&self) -> Stats {
+        let mut stats = self.stats;
+        if self.state == GameState::Playing && self.n_guesses > 0 {
+            stats.played += 1;
+            stats.streak = 0;
+        }
+        stats
+    }
+}
+
+impl App {
+    /// Switch to playing against a random answer of the session, weighted
+    /// by the priors. Suggestions are hidden until the game is over.
+    pub fn start_game(&mut self) {
+        self.reset();
+        let stats = self.game.as_ref().map(Game::abandon).unwrap_or_default();
+        self.game = self
+            .session
+            .next_answer(&self.solver)
+            .map(|(secret, session)| {
+                Game::new(
+                    "Random",
+                    Box::new(FixedAnswer(secret)),
+                    false,
+                    stats,
+                    session,
+                )
+            });
+    }
+
+    /// Draw the answers of the random games from a seed, so that the
+    /// session can be played again or shared
+    pub fn set_seed(&mut self, seed: Option<u32>) {
+        self.session = Session::new(seed);
+    }
+
+    /// Give the next, stronger hint about the answer of the game, see
+    /// [`Solver::hints`]
+    pub fn hint(&mut self) {
+        let guesses = self.committed_guesses();
+        let Some(game) = self.game.as_mut() else {
+            return;
+        };
+        let Some(answer) = game.oracle.answer() else {
+            return;
+        };
+        if game.state != GameState::Playing {
+            return;
+        }
+        let hints = match self.solver.hints(&answer, &guesses) {
+            Ok(hints) => hints,
+            Err(e) => {
+                self.notice = Some(Err(e.to_string()));
+                return;
+            }
+        };
+        game.n_hints = (game.n_hints + 1).min(hints.len());
+        game.hint = hints.get(game.n_hints - 1).copied();
+    }
+
+    /// Play the daily pack. Afterwards the games continue with random
+    /// answers.
+    pub fn start_pack(&mut self) {
+        self.pack = Pack::daily(&self.solver, &mut self.session);
+        self.next_game();
+    }
+
+    /// Start the next puzzle of the pack, or a random game once the pack is
+    /// done. A puzzle that was given up counts as lost.
+    pub fn next_game(&mut self) {
+        let Some(pack) = &mut self.pack else {
+            return self.start_game();
+        };
+        if let Some(game) = self.game.as_mut() {
+            if game.state == GameState::Playing {
+                game.give_up();
+                pack.results.push(game.state);
+                // Show the summary of the pack first
+                if pack.current().is_none() {
+                    return;
+                }
+            }
+        }
+        let stats = self
+            .game
+            .as_ref()
+            .map(|game| game.stats)
+            .unwrap_or_default();
+        self.reset();
+        match self.pack.as_ref().and_then(Pack::current) {
+            Some(puzzle) => {
+                let oracle = Box::new(FixedAnswer(puzzle.secret));
+                let game = Game::new(puzzle.name, oracle, puzzle.hard, stats, puzzle.session);
+                self.game = Some(game)
+            }
+            None => {
+                self.pack = None;
+                self.start_game();
+            }
+        }
+    }
+
+    /// Whether the rows can be edited. During a game only the next guess
+    /// can be entered, and nothing after the game is over.
+    pub fn is_editable(&self) -> bool {
+        match &self.game {
+            Some(game) => {
+                game.state == GameState::Playing
+                    && self.selected_word == self.committed_guesses().len()
+            }
+            None => true,
+        }
+    }
+
+    /// Whether the solver state would give the answer away
+    pub fn is_hidden(&self) -> bool {
+        self.game
+            .as_ref()
+            .is_some_and(|game| game.state == GameState::Playing)
+    }
+
+    /// Play the word in the selected row, if it is a valid guess
+    pub fn play_selected_row(&mut self) {
+        let row = self.selected_word;
+        if !self.is_editable() || self.rows[row] != RowState::ValidPendingStatus {
+            return;
+        }
+        let Some(game) = &mut self.game else {
+            return;
+        };
+        let word = self.guesses[row].word;
+        game.message = None;
+        if game.hard {
+            let violation = self.guesses[..row]
+                .iter()
+                .find_map(|guess| word.hard_mode_violation(guess));
+            if let Some(violation) = violation {
+                game.message = Some(format!("Hard mode: {}", violation));
+                return;
+            }
+        }
+        let (played, rest) = self.guesses.split_at_mut(row);
+        game.play(&mut rest[0], played, &self.solver);
+        let playing = game.state == GameState::Playing;
+        // Won games are recorded with the solved guesses
+        let lost = match (game.state, game.oracle.answer()) {
+            (GameState::Lost, Some(answer)) => Some((game.strategy(), answer)),
+            _ => None,
+        };
+        if let Some(pack) = self.pack.as_mut().filter(|_| !playing) {
+            pack.results.push(game.state);
+        }
+        if let Some((mode, answer)) = lost {
+            self.record_history(&mode, answer, row);
+        }
+        self.set_row_state(row, RowState::Committed);
+        if playing {
+            self.move_down();
+            self.selected_letter = 0;
+        }
+    }
+}
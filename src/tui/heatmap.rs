@@ -0,0 +1,90 @@
+use ratatui::widgets::*;
+
+use super::*;
+
+/// The letters of the remaining words by position: one row per position
+/// and one column per letter, shaded by the share of the words that have
+/// the letter there
+pub struct LetterHeatmap {
+    // The letters of the columns
+    pub alphabet: Vec<char>,
+    // The counts of the letters at every position
+    pub frequencies: Vec<Vec<usize>>,
+    pub n_words: usize,
+}
+
+// The width of a cell, for a share of up to `100`
+const CELL_WIDTH: usize = 3;
+
+impl LetterHeatmap {
+    /// The style of a cell: the more common the letter, the brighter, on
+    /// the grayscale ramp of 256 color terminals
+    fn style(share: f32) -> Style {
+        if share == 0.0 {
+            return Style::default().dark_gray();
+        }
+        let level = (share * 23.0).round() as u8;
+        let fg = if level > 12 {
+            Color::Black
+        } else {
+            Color::White
+        };
+        Style::default().fg(fg).bg(Color::Indexed(232 + level))
+    }
+}
+
+impl Widget for LetterHeatmap {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut lines = vec![Line::from(
+            std::iter::once(Span::from("    "))
+                .chain(
+                    self.alphabet
+                        .iter()
+                        .map(|c| format!(" {} ", c.to_uppercase()).bold()),
+                )
+                .collect::<Vec<_>>(),
+        )];
+        for (position, counts) in self.frequencies.iter().enumerate() {
+            let mut spans = vec![format!("{:>3} ", position + 1).bold()];
+            for &count in counts {
+                let share = match self.n_words {
+                    0 => 0.0,
+                    n => count as f32 / n as f32,
+                };
+                let width = CELL_WIDTH;
+                let text = match count {
+                    0 => format!("{:>1$}", "·", width),
+                    _ if share < 0.005 => format!("{:>1$}", "<1", width),
+                    _ => format!("{:>1$.0}", share * 100.0, width),
+                };
+                spans.push(Span::styled(text, LetterHeatmap::style(share)));
+            }
+            lines.push(Line::from(spans));
+        }
+        Paragraph::new(lines).render(area, buf);
+    }
+}
+
+impl App {
+    /// Render the heatmap of the remaining words below a header line
+    pub fn render_heatmap(&self, area: Rect, buf: &mut Buffer) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(2), Constraint::Fill(1)])
+            .split(area);
+        let header = Line::from(vec![
+            "Letters by position, % of ".bold(),
+            self.remaining_words.len().to_string().bold().magenta(),
+            " words".bold(),
+        ]);
+        Paragraph::new(header).render(rows[0], buf);
+        LetterHeatmap {
+            alphabet: self.solver.alphabet().to_vec(),
+            frequencies: self
+                .solver
+                .positional_letter_frequencies(&self.remaining_words),
+            n_words: self.remaining_words.len(),
+        }
+        .render(rows[1], buf);
+    }
+}
@@ -0,0 +1,135 @@
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+
+use super::App;
+use crate::wordlebot::wordle::{letter_states, LetterStatus};
+use ratatui::{prelude::*, widgets::*};
+
+const QWERTY: &str = include_str!("../../data/layouts/qwerty.txt");
+const AZERTY: &str = include_str!("../../data/layouts/azerty.txt");
+const QWERTZ: &str = include_str!("../../data/layouts/qwertz.txt");
+
+/// The rows of letters of a keyboard layout.
+///
+/// Layouts are plain text files with one keyboard row per line. Empty lines
+/// and lines starting with `#` are ignored. Every letter from a to z has to
+/// appear exactly once, and other letters like `ä` or `ñ` at most once.
+/// Terminals already translate physical keys to the characters of the
+/// active layout, so the layout only changes how the keyboard is drawn.
+/// Letters of the word list that the layout lacks are drawn in an extra
+/// row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyboardLayout {
+    pub rows: Vec<Vec<char>>,
+}
+
+impl KeyboardLayout {
+    /// Parse a layout from its text representation
+    pub fn parse(text: &str) -> Result<KeyboardLayout> {
+        let rows: Vec<Vec<char>> = text
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.chars().flat_map(char::to_lowercase).collect())
+            .collect();
+
+        let mut seen: Vec<char> = rows.iter().flatten().copied().collect();
+        seen.sort();
+        if let Some(c) = seen.iter().find(|c| !c.is_alphabetic()) {
+            bail!("Invalid key '{}' in keyboard layout", c)
+        }
+        if let Some(pair) = seen.windows(2).find(|pair| pair[0] == pair[1]) {
+            bail!(
+                "Key '{}' appears more than once in keyboard layout",
+                pair[0]
+            )
+        }
+        if ('a'..='z').any(|c| seen.binary_search(&c).is_err()) {
+            bail!("Keyboard layout must contain all letters from a to z")
+        }
+        Ok(KeyboardLayout { rows })
+    }
+
+    /// Get one of the built-in layouts (qwerty, azerty, qwertz) or load a
+    /// layout from a file
+    pub fn load(name: &str) -> Result<KeyboardLayout> {
+        let text = match name.to_lowercase().as_str() {
+            "qwerty" => QWERTY.to_string(),
+            "azerty" => AZERTY.to_string(),
+            "qwertz" => QWERTZ.to_string(),
+            _ => std::fs::read_to_string(name)
+                .with_context(|| format!("Error reading keyboard layout '{}'", name))?,
+        };
+        KeyboardLayout::parse(&text).with_context(|| format!("Invalid keyboard layout '{}'", name))
+    }
+}
+
+impl App {
+    /// The rows of the keyboard: the rows of the layout, and a row for the
+    /// letters of the word list that the layout lacks
+    pub fn keyboard_rows(&self) -> Vec<Vec<char>> {
+        let mut rows = self.layout.rows.clone();
+        let extra: Vec<char> = self
+            .solver
+            .alphabet()
+            .iter()
+            .filter(|c| !rows.iter().flatten().any(|key| key == *c))
+            .copied()
+            .collect();
+        if !extra.is_empty() {
+            rows.push(extra);
+        }
+        rows
+    }
+
+    /// Render the keyboard like the one of Wordle: the letters of the
+    /// guesses in the color of their status. The other letters are a
+    /// heatmap of how many of the remaining words contain them, unless the
+    /// solver is hidden in a game.
+    pub fn render_keyboard(&self, area: Rect, buf: &mut Buffer) {
+        let frequencies: HashMap<char, usize> =
+            self.solver.get_letter_frequencies(&self.remaining_words);
+        let total = self.remaining_words.len().max(1);
+        let states = letter_states(&self.visible_guesses());
+
+        let lines: Vec<Line<'_>> = self
+            .keyboard_rows()
+            .iter()
+            .map(|row| {
+                let keys: Vec<Span<'_>> = row
+                    .iter()
+                    .map(|c| {
+                        let style = match states.get(c) {
+                            Some(LetterStatus::Absent) => Style::default().dark_gray(),
+                            Some(&status) => self.theme.tile(status).bold(),
+                            // The heatmap would give the answer away
+                            None if self.is_hidden() => Style::default(),
+                            None => {
+                                let n = frequencies.get(c).copied().unwrap_or(0);
+                                heat_style(n as f32 / total as f32)
+                            }
+                        };
+                        Span::styled(format!(" {} ", c.to_uppercase()), style)
+                    })
+                    .collect();
+                Line::from(keys)
+            })
+            .collect();
+
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .render(area, buf);
+    }
+}
+
+/// Style of a key, based on the fraction of remaining words that contain it
+fn heat_style(fraction: f32) -> Style {
+    if fraction <= 0.0 {
+        return Style::default().dark_gray();
+    }
+    let level = (55. + 200. * fraction) as u8;
+    Style::default()
+        .bg(Color::Rgb(level, 0, level))
+        .white()
+        .bold()
+}
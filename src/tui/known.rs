@@ -0,0 +1,63 @@
+use crate::wordlebot::solver::constraint::ManualConstraints;
+
+use super::*;
+
+/// The letters known without a guess while they are edited
+pub struct KnownInput {
+    pub text: String,
+    // Why the text could not be applied
+    pub error: Option<String>,
+}
+
+impl App {
+    /// Edit the letters known from elsewhere, starting with the current ones
+    pub fn edit_known(&mut self) {
+        let mut text = self.known.to_string();
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        self.known_input = Some(KnownInput { text, error: None });
+    }
+
+    pub fn known_char(&mut self, c: Option<char>) {
+        if let Some(input) = &mut self.known_input {
+            match c {
+                Some(c) => input.text.push(c),
+                None => {
+                    input.text.pop();
+                }
+            }
+            input.error = None;
+        }
+    }
+
+    /// Filter the remaining words by the edited letters as well. Letters
+    /// that cannot be parsed stay in the editor.
+    pub fn apply_known(&mut self) {
+        let Some(input) = &mut self.known_input else {
+            return;
+        };
+        let known = input.text.parse::<ManualConstraints>().and_then(|known| {
+            match known.invalid_position(self.solver.n_letters()) {
+                Some(pos) => anyhow::bail!(
+                    "Position {} is not in words of {} letters",
+                    pos,
+                    self.solver.n_letters()
+                ),
+                None => Ok(known),
+            }
+        });
+        match known {
+            Ok(known) => {
+                self.known_input = None;
+                if known != self.known {
+                    self.known = known;
+                    self.suggestion_cache.clear();
+                    self.compare_suggestions = None;
+                    self.show_visible_state();
+                }
+            }
+            Err(e) => input.error = Some(format!("{:#}", e)),
+        }
+    }
+}
@@ -1,21 +1,86 @@
 use std::io::{self, stdout, Stdout};
+use std::sync::Arc;
+use std::time::Instant;
 
-use crate::wordlebot::solver::*;
+use crate::wordlebot::history::{History, Summary};
+use crate::wordlebot::solver::{
+    book::OpeningBook, constraint::ManualConstraints, filter::GuessFilter, formula::Formula,
+    score::ScoreStrategy, search::Search, tree::DecisionTree, *,
+};
+use crate::wordlebot::telemetry::{Outcome, Telemetry};
 use crate::wordlebot::wordle::*;
 
-use crossterm::{execute, terminal::*};
+use crossterm::{
+    event::{DisableBracketedPaste, EnableBracketedPaste},
+    execute,
+    terminal::*,
+};
 use ratatui::prelude::*;
 use tokio::sync::mpsc;
 
+use about::UpdateCheck;
 use actions::Action;
+use boards::Boards;
+use branch::Branch;
+pub use compare::CompareStrategy;
+use export::ExportInput;
+use filter::FilterInput;
+use game::{Game, GameState, Pack, Session};
+pub use keyboard::KeyboardLayout;
+use known::KnownInput;
+use overlay::Overlay;
+use profile::Profile;
+use remaining::RemainingList;
+use row::RowState;
+use simulation::SimulationRun;
+use strategy::Strategy;
+use streak::StreakRun;
+pub use theme::Theme;
 use tokio_util::sync::CancellationToken;
 
+mod about;
 mod actions;
+mod boards;
+mod branch;
+mod compare;
+mod confidence;
 mod events;
+mod export;
+mod filter;
+mod game;
+mod heatmap;
+mod keyboard;
+mod known;
+mod overlay;
+mod profile;
+mod remaining;
+mod row;
+mod simulation;
+mod stats;
+mod strategy;
+mod streak;
+mod theme;
 mod ui;
+mod why;
 
 const N_SUGGESTIONS: usize = 15;
 
+/// What the solver shows below the suggestions
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Panel {
+    #[default]
+    RemainingWords,
+    Ngrams,
+    // The letters of the remaining words by position
+    Heatmap,
+    // Why the selected suggestion is suggested
+    Why,
+    // How many guesses the games take after the selected suggestion
+    Simulation,
+    // The guesses most likely to win within the rounds left
+    Streak,
+}
+
 /// A type alias for the terminal type used in this application
 pub type Tui = Terminal<CrosstermBackend<Stdout>>;
 
@@ -29,14 +94,14 @@ pub fn initialize_panic_handler() {
 
 /// Initialize the terminal
 pub fn init() -> io::Result<Tui> {
-    execute!(stdout(), EnterAlternateScreen)?;
+    execute!(stdout(), EnterAlternateScreen, EnableBracketedPaste)?;
     enable_raw_mode()?;
     Terminal::new(CrosstermBackend::new(stdout()))
 }
 
 /// Restore the terminal to its original state
 pub fn restore() -> io::Result<()> {
-    execute!(stdout(), LeaveAlternateScreen)?;
+    execute!(stdout(), DisableBracketedPaste, LeaveAlternateScreen)?;
     disable_raw_mode()?;
     Ok(())
 }
@@ -44,58 +109,261 @@ pub fn restore() -> io::Result<()> {
 pub struct App {
     exit: bool,
     two_level: bool,
-    guesses: [Guess; 6],
-    cached_guesses: [Guess; 6],
+    // Custom ranking of the suggestions
+    formula: Option<Arc<Formula>>,
+    score: ScoreStrategy,
+    show_ranking_diff: bool,
+    // What is shown below the suggestions
+    panel: Panel,
+    // How much the yellow and gray tiles are trusted, see
+    // `solver::soft`
+    confidence: f32,
+    // Save energy: no ranking of all words where the opening table is
+    // enough
+    eco: bool,
+    // Suggest burn guesses of untried letters instead of the strategy
+    burn: bool,
+    // Rank the suggestions by a deeper lookahead
+    search: Option<Search>,
+    layout: KeyboardLayout,
+    theme: Theme,
+    tree: Option<Arc<DecisionTree>>,
+    // The strategies that rank the suggestions, switched with Ctrl+T, and
+    // the index of the current one
+    strategies: Vec<Strategy>,
+    strategy: usize,
+    // The best suggestion of each round by the guesses before it, and the
+    // strategy that ranked it
+    recommendations: Vec<(Vec<Guess>, Word, &'static str)>,
+    // The first two guesses come from this book
+    book: Option<Arc<OpeningBook>>,
+    telemetry: Option<Telemetry>,
+    // The solved game that was last recorded with telemetry and in the
+    // history
+    recorded_game: Option<Vec<Guess>>,
+    // The finished games are kept here, see `wordlebot stats`
+    history: Option<History>,
+    // The statistics of the history while they are shown with F11
+    stats: Option<Result<Summary, String>>,
+    // Playing against a secret answer instead of assisting
+    game: Option<Game>,
+    // The daily pack that is played
+    pack: Option<Pack>,
+    // Draws the answers of the random games
+    session: Session,
+    // The word list overlay that is reloaded when it changes
+    overlay: Option<Overlay>,
+    // The name of the word pack of a Wordle variant, shown in the title
+    word_pack: Option<String>,
+    // The timings of the main loop, shown with F12
+    profile: Profile,
+    // The outcome of the last clipboard action, or why it failed
+    notice: Option<Result<String, String>>,
+    // Several boards that are played with the same guesses
+    boards: Option<Boards>,
+    // Letters the suggestions have to test or avoid
+    filter: GuessFilter,
+    filter_input: Option<FilterInput>,
+    // Letters known without a guess, e.g. from another source, and their
+    // editor while it is open
+    known: ManualConstraints,
+    known_input: Option<KnownInput>,
+    // The file name the remaining words are exported to
+    export_input: Option<ExportInput>,
+    // The version and the word list are shown over the solver
+    show_about: bool,
+    update_check: UpdateCheck,
+    // The strategy of a snapshot and its suggestions for the visible guesses
+    compare: Option<Arc<CompareStrategy>>,
+    compare_suggestions: Option<(Vec<Guess>, Vec<GuessEvaluation>)>,
+    // One entry per row. The game has MAX_GUESSES rows, and a row is added
+    // after the last one is confirmed to analyse longer games.
+    guesses: Vec<Guess>,
+    rows: Vec<RowState>,
+    // Rows that are practice probes and do not use a turn of the game
+    probes: Vec<bool>,
+    // The visible guesses that no answer fits together, e.g. after a
+    // mistyped pattern
+    conflict: Option<Conflict>,
     selected_word: usize,
     selected_letter: usize,
-    solver: Solver,
+    solver: Arc<Solver>,
     remaining_words: Vec<usize>,
+    // How the remaining words are sorted and scrolled
+    remaining: RemainingList,
     suggestions: Vec<GuessEvaluation>,
+    // The suggestion that was typed into the next row
+    selected_suggestion: Option<usize>,
+    // Suggestions of earlier rounds, by the guesses they were computed for
+    suggestion_cache: Vec<(Vec<Guess>, Vec<GuessEvaluation>)>,
+    // The number of guesses shown while reviewing an earlier round
+    scrub: Option<usize>,
     evaludations: Vec<GuessEvaluation>,
+    // The bar of the histogram that is selected, and its what-if branch
+    selected_bar: Option<usize>,
+    branch: Option<Branch>,
+    simulation: Option<SimulationRun>,
+    // The guesses that protect a streak, for the visible guesses
+    streak: Option<StreakRun>,
+    explanation: Option<(Word, Option<Elimination>)>,
     action_tx: mpsc::UnboundedSender<Option<Action>>,
     action_rx: mpsc::UnboundedReceiver<Option<Action>>,
     token: CancellationToken,
     child_token: Option<CancellationToken>,
+    // The guesses whose suggestions are ranked, and how many of the guesses
+    // that take long are evaluated out of how many
+    progress: Option<(Vec<Guess>, usize, usize)>,
 }
 
 impl App {
-    pub fn init(solver: Solver, two_level: bool) -> Self {
+    pub fn init(
+        solver: Arc<Solver>,
+        two_level: bool,
+        formula: Option<Arc<Formula>>,
+        score: ScoreStrategy,
+        layout: KeyboardLayout,
+        tree: Option<Arc<DecisionTree>>,
+        telemetry: Option<Telemetry>,
+    ) -> Self {
         let (action_tx, action_rx) = mpsc::unbounded_channel();
         let remaining_words = solver.get_frequent_word_idx();
-        let suggestions = vec![];
+        let empty = Guess::empty(solver.n_letters());
+        let (strategies, strategy) =
+            Strategy::available(two_level, formula.as_ref(), score, tree.as_ref());
 
         // Get Suggestions in the background
         action_tx
             .send(Some(Action::GetSuggestions(vec![])))
             .unwrap();
 
-        App {
+        let mut app = App {
             exit: false,
             two_level,
-            guesses: [Guess::empty(); 6],
-            cached_guesses: [Guess::empty(); 6],
+            formula,
+            score,
+            show_ranking_diff: false,
+            panel: Panel::default(),
+            confidence: 1.0,
+            eco: false,
+            burn: false,
+            search: None,
+            layout,
+            theme: Theme::default(),
+            tree,
+            strategies,
+            strategy,
+            recommendations: vec![],
+            book: None,
+            telemetry,
+            recorded_game: None,
+            history: None,
+            stats: None,
+            game: None,
+            pack: None,
+            session: Session::new(None),
+            overlay: None,
+            word_pack: None,
+            profile: Profile::default(),
+            notice: None,
+            boards: None,
+            filter: GuessFilter::default(),
+            filter_input: None,
+            known: ManualConstraints::default(),
+            known_input: None,
+            export_input: None,
+            show_about: false,
+            update_check: UpdateCheck::Disabled,
+            compare: None,
+            compare_suggestions: None,
+            guesses: vec![empty; MAX_GUESSES],
+            rows: vec![RowState::Empty; MAX_GUESSES],
+            probes: vec![false; MAX_GUESSES],
+            conflict: None,
             selected_word: 0,
             selected_letter: 0,
             solver,
             remaining_words,
-            suggestions,
+            remaining: RemainingList::default(),
+            suggestions: vec![],
+            selected_suggestion: None,
+            suggestion_cache: vec![],
+            scrub: None,
             action_rx,
             action_tx,
             token: CancellationToken::new(),
             child_token: None,
+            progress: None,
             evaludations: vec![],
+            selected_bar: None,
+            branch: None,
+            simulation: None,
+            streak: None,
+            explanation: None,
+        };
+        // Show the opening table until the solver is done
+        app.suggestions = app.opening_suggestions();
+        app
+    }
+
+    /// Show a panel below the suggestions instead of the remaining words, or
+    /// the remaining words again if it is shown
+    fn toggle_panel(&mut self, panel: Panel) {
+        self.panel = match self.panel == panel {
+            true => Panel::RemainingWords,
+            false => panel,
+        };
+    }
+
+    /// Save energy: show the opening table instead of ranking all words for
+    /// the first guess
+    pub fn set_eco(&mut self, eco: bool) {
+        self.eco = eco;
+    }
+
+    /// Rank the suggestions by the bits of a lookahead search instead, see
+    /// [`Solver::guess_lookahead`]
+    pub fn set_search(&mut self, search: Option<Search>) {
+        self.search = search;
+        if let Some(search) = search {
+            self.strategies.push(Strategy::Lookahead(search));
+            // The lookahead replaces the ranking by the bits
+            if self.strategy <= 1 {
+                self.strategy = self.strategies.len() - 1;
+            }
         }
     }
 
+    /// Show the name of the word pack the solver was loaded from
+    pub fn set_word_pack(&mut self, name: Option<String>) {
+        self.word_pack = name;
+    }
+
+    /// Suggest the guesses of an opening book first
+    pub fn set_book(&mut self, book: Option<Arc<OpeningBook>>) {
+        self.book = book;
+        self.suggestions = self.opening_suggestions();
+    }
+
     /// runs the application's main loop until the user quits
     pub async fn run(&mut self, terminal: &mut Tui) -> io::Result<()> {
         let task = self.handle_events(self.action_tx.clone());
 
         while !self.exit {
+            let start = Instant::now();
             terminal.draw(|frame| self.render_frame(frame))?;
+            self.profile.record_frame(start.elapsed());
 
             if let Some(action) = self.action_rx.recv().await {
+                // The ticks of the event loop do nothing
+                if let Some(action) = &action {
+                    self.profile.record_action(action, self.action_rx.len());
+                }
+                let start = Instant::now();
+                let tick = action.is_none();
                 self.update(action);
+                if !tick {
+                    self.profile.record_update(start.elapsed());
+                }
             }
         }
         task.abort();
@@ -0,0 +1,121 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use super::*;
+
+/// A word list overlay that is applied again whenever its file changes
+pub struct Overlay {
+    pub path: PathBuf,
+    // The number of answers after the last reload, or why it failed
+    pub status: Result<usize, String>,
+    // Watching stops when the watcher is dropped
+    _watcher: RecommendedWatcher,
+    // Cancels a reload that is still waiting for the file to settle
+    token: Option<CancellationToken>,
+}
+
+impl App {
+    /// Reload the overlay whenever its file changes. The solver passed to
+    /// [`App::init`] must already have the overlay applied.
+    pub fn watch_overlay(&mut self, path: PathBuf) -> Result<()> {
+        let tx = self.action_tx.clone();
+        let name = path.file_name().map(|name| name.to_os_string());
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else {
+                    return;
+                };
+                let changed = event
+                    .paths
+                    .iter()
+                    .any(|p| p.file_name().map(|n| n.to_os_string()) == name);
+                if changed && !matches!(event.kind, EventKind::Access(_)) {
+                    tx.send(Some(Action::ReloadOverlay)).ok();
+                }
+            })?;
+        // Editors often replace the file instead of writing to it, so the
+        // directory is watched
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Error watching {}", dir.display()))?;
+        self.overlay = Some(Overlay {
+            path,
+            status: Ok(self.solver.get_frequent_word_idx().len()),
+            _watcher: watcher,
+            token: None,
+        });
+        Ok(())
+    }
+
+    /// Apply the overlay file to the solver in the background. A change that
+    /// follows shortly after replaces the pending reload.
+    pub fn reload_overlay(&mut self) {
+        let Some(overlay) = &mut self.overlay else {
+            return;
+        };
+        if let Some(token) = overlay.token.take() {
+            token.cancel();
+        }
+        let token = self.token.child_token();
+        overlay.token = Some(token.clone());
+
+        let solver = Arc::clone(&self.solver);
+        let path = overlay.path.clone();
+        let tx = self.action_tx.clone();
+        tokio::spawn(async move {
+            // Saving a file often takes several writes
+            tokio::select! {
+                _ = token.cancelled() => return,
+                _ = tokio::time::sleep(Duration::from_millis(200)) => {}
+            }
+            let result = tokio::task::spawn_blocking(move || solver.with_overlay_file(&path))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|result| result.map_err(|e| format!("{:#}", e)));
+            if !token.is_cancelled() {
+                tx.send(Some(Action::UpdateSolver(result.map(Arc::new))))
+                    .ok();
+            }
+        });
+    }
+
+    /// Switch to the solver with the reloaded overlay. Rows that are not
+    /// confirmed are validated again, and confirmed rows with words that are
+    /// no longer in the word list have to be confirmed again.
+    pub fn update_solver(&mut self, result: Result<Arc<Solver>, String>) {
+        let Some(overlay) = &mut self.overlay else {
+            return;
+        };
+        overlay.token = None;
+        let solver = match result {
+            Ok(solver) => solver,
+            Err(e) => {
+                overlay.status = Err(e);
+                return;
+            }
+        };
+        overlay.status = Ok(solver.get_frequent_word_idx().len());
+        self.solver = solver;
+        // The priors of the words may have changed
+        self.update_remaining_bits();
+        for (guess, row) in self.guesses.iter().zip(self.rows.iter_mut()) {
+            if *row != RowState::Committed || !self.solver.is_valid_guess(&guess.word) {
+                *row = RowState::after_edit(&guess.word, &self.solver);
+            }
+        }
+        self.explanation = None;
+        self.suggestion_cache.clear();
+        self.compare_suggestions = None;
+        if let Some(boards) = &mut self.boards {
+            boards.requested = None;
+        }
+        self.update_guesses();
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use ratatui::widgets::{block::*, Clear, *};
+
+use super::*;
+
+/// The number of recent timings that are kept of each kind
+const HISTORY: usize = 120;
+
+/// Recent timings of the main loop, to find out why the terminal feels slow
+#[derive(Default)]
+pub struct Profile {
+    pub visible: bool,
+    // How long the frames took to draw, the latest last
+    frames: VecDeque<Duration>,
+    // How long the actions took to update the state
+    updates: VecDeque<Duration>,
+    // The actions that were waiting after the latest one, and the most
+    queue_depth: usize,
+    max_queue_depth: usize,
+    // The guesses whose suggestions were requested last, and when
+    requested: Option<(Vec<Guess>, Instant)>,
+    // How long the suggestions of the guesses took to arrive
+    suggestion_latency: Option<Duration>,
+}
+
+/// Keep the latest timings only
+fn push(timings: &mut VecDeque<Duration>, timing: Duration) {
+    if timings.len() == HISTORY {
+        timings.pop_front();
+    }
+    timings.push_back(timing);
+}
+
+/// The latest, average and longest timing
+fn summary(timings: &VecDeque<Duration>) -> String {
+    let Some(last) = timings.back() else {
+        return "-".to_string();
+    };
+    let total = timings.iter().sum::<Duration>();
+    let max = timings.iter().max().unwrap_or(last);
+    format!(
+        "last {:>8.2?}  avg {:>8.2?}  max {:>8.2?}",
+        last,
+        total / timings.len() as u32,
+        max
+    )
+}
+
+impl Profile {
+    pub fn record_frame(&mut self, duration: Duration) {
+        push(&mut self.frames, duration);
+    }
+
+    /// Note an action before it is handled, with the number of actions that
+    /// wait behind it
+    pub fn record_action(&mut self, action: &Action, queue_depth: usize) {
+        self.queue_depth = queue_depth;
+        self.max_queue_depth = self.max_queue_depth.max(queue_depth);
+        match action {
+            Action::GetSuggestions(guesses) => {
+                self.requested = Some((guesses.clone(), Instant::now()));
+            }
+            // The suggestions may arrive more than once while they are
+            // ranked, the latest counts
+            Action::UpdateSuggestions(guesses, _) => {
+                if let Some((requested, start)) = &self.requested {
+                    if requested == guesses {
+                        self.suggestion_latency = Some(start.elapsed());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn record_update(&mut self, duration: Duration) {
+        push(&mut self.updates, duration);
+    }
+}
+
+impl App {
+    pub fn toggle_profile(&mut self) {
+        self.profile.visible = !self.profile.visible;
+    }
+
+    /// Render the timings of the main loop in the top right corner, with
+    /// the recent frames as a sparkline
+    pub fn render_profile(&self, area: Rect, buf: &mut Buffer) {
+        let profile = &self.profile;
+        if !profile.visible {
+            return;
+        }
+        let latency = match profile.suggestion_latency {
+            Some(latency) => format!("{:.2?}", latency),
+            None => "-".to_string(),
+        };
+        let mut lines = vec![
+            Line::from(vec!["Render  ".bold(), summary(&profile.frames).into()]),
+            Line::from(vec!["Update  ".bold(), summary(&profile.updates).into()]),
+            Line::from(vec![
+                "Queue   ".bold(),
+                format!(
+                    "{} waiting, at most {}",
+                    profile.queue_depth, profile.max_queue_depth
+                )
+                .into(),
+            ]),
+            Line::from(vec!["Suggest ".bold(), latency.into()]),
+        ];
+        // The caches of the solver are shared with its threads and tasks
+        for (i, (name, stats)) in self.solver.cache_stats().into_iter().enumerate() {
+            let label = if i == 0 { "Caches  " } else { "        " };
+            lines.push(Line::from(vec![
+                label.bold(),
+                format!(
+                    "{:<12} {:>3.0}% of {} hit, {}/{}",
+                    name,
+                    100.0 * stats.hit_rate(),
+                    stats.hits + stats.misses,
+                    stats.len,
+                    stats.capacity
+                )
+                .into(),
+            ]));
+        }
+        let width = 56.min(area.width);
+        let height = (lines.len() as u16 + 4).min(area.height);
+        let popup_area = Rect::new(area.right() - width, area.top(), width, height);
+        let block = Block::default()
+            .title(Title::from(" Profile ".bold()).alignment(Alignment::Center))
+            .title(
+                Title::from(" <F12> close ".dark_gray())
+                    .position(Position::Bottom)
+                    .alignment(Alignment::Center),
+            )
+            .borders(Borders::ALL);
+        Clear.render(popup_area, buf);
+        let inner = block.inner(popup_area);
+        block.render(popup_area, buf);
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![
+                Constraint::Length(lines.len() as u16),
+                Constraint::Fill(1),
+            ])
+            .split(inner);
+        Paragraph::new(lines).render(rows[0], buf);
+        // The latest frames that fit, in microseconds
+        let frames: Vec<u64> = profile
+            .frames
+            .iter()
+            .rev()
+            .take(rows[1].width as usize)
+            .rev()
+            .map(|frame| frame.as_micros() as u64)
+            .collect();
+        Sparkline::default()
+            .data(&frames)
+            .style(Style::default().magenta())
+            .render(rows[1], buf);
+    }
+}
@@ -0,0 +1,170 @@
+use ratatui::widgets::*;
+use std::cell::Cell;
+
+use super::*;
+
+/// The order of the remaining words
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RemainingSort {
+    /// The most likely answer first, ties in word list order
+    #[default]
+    Likelihood,
+    Alphabetical,
+    /// The most expected bits as a guess first, up to [`BITS_CUTOFF`]
+    /// remaining words
+    Bits,
+}
+
+/// The remaining words are sorted by their bits only up to this many, since
+/// every word is evaluated against all others
+pub const BITS_CUTOFF: usize = 500;
+
+/// The scroll position and order of the remaining words
+#[derive(Debug, Default)]
+pub struct RemainingList {
+    pub sort: RemainingSort,
+    // The first word that is shown
+    pub offset: usize,
+    // How many words fit, as of the last render, to scroll by a page
+    pub page: Cell<usize>,
+    // The expected bits of the remaining words, in their order, while they
+    // are sorted by them
+    bits: Vec<f32>,
+}
+
+impl App {
+    /// Scroll the remaining words by `pages` pages, down for positive ones
+    pub fn scroll_remaining(&mut self, pages: isize) {
+        let page = self.remaining.page.get().max(1);
+        let last = self.remaining_words.len().saturating_sub(page);
+        let offset = self.remaining.offset.min(last) as isize + pages * page as isize;
+        self.remaining.offset = offset.clamp(0, last as isize) as usize;
+    }
+
+    /// Sort the remaining words alphabetically, then by their bits as a
+    /// guess and then by likelihood again, from the top
+    pub fn toggle_remaining_sort(&mut self) {
+        self.remaining.sort = match self.remaining.sort {
+            RemainingSort::Likelihood => RemainingSort::Alphabetical,
+            RemainingSort::Alphabetical => RemainingSort::Bits,
+            RemainingSort::Bits => RemainingSort::Likelihood,
+        };
+        self.remaining.offset = 0;
+        self.update_remaining_bits();
+    }
+
+    /// Show new remaining words from the top
+    pub fn set_remaining_words(&mut self, remaining_words: Vec<usize>) {
+        if remaining_words != self.remaining_words {
+            self.remaining.offset = 0;
+            self.remaining_words = remaining_words;
+            self.update_remaining_bits();
+        }
+    }
+
+    /// Evaluate the remaining words as guesses if they are sorted by their
+    /// bits and there are not too many
+    pub fn update_remaining_bits(&mut self) {
+        let n = self.remaining_words.len();
+        self.remaining.bits = match self.remaining.sort {
+            RemainingSort::Bits if n <= BITS_CUTOFF => {
+                self.solver.remaining_bits(&self.remaining_words)
+            }
+            _ => vec![],
+        };
+    }
+
+    /// The remaining words in the order of the list, each with how likely
+    /// it is the answer and its expected bits as a guess if they are known
+    pub fn sorted_remaining(&self) -> Vec<(Word, f32, Option<f32>)> {
+        let words = self.solver.get_words_from_idx(&self.remaining_words);
+        let likelihoods = self.solver.get_soft_likelihoods(
+            &self.remaining_words,
+            &self.visible_guesses(),
+            self.confidence,
+        );
+        let bits = (0..words.len()).map(|i| self.remaining.bits.get(i).copied());
+        let mut words: Vec<(Word, f32, Option<f32>)> = words
+            .into_iter()
+            .zip(likelihoods)
+            .zip(bits)
+            .map(|((word, likelihood), bits)| (word, likelihood, bits))
+            .collect();
+        match self.remaining.sort {
+            RemainingSort::Alphabetical => words.sort_by_key(|(word, _, _)| word.to_string()),
+            // Too many words to evaluate are sorted by likelihood
+            RemainingSort::Bits if !self.remaining.bits.is_empty() => words.sort_by(|a, b| {
+                let bits = |w: &(Word, f32, Option<f32>)| w.2.unwrap_or(0.0);
+                bits(b).total_cmp(&bits(a)).then(b.1.total_cmp(&a.1))
+            }),
+            _ => words.sort_by(|a, b| b.1.total_cmp(&a.1)),
+        }
+        words
+    }
+
+    /// Render the remaining words below a header line, one per line with
+    /// its likelihood. The list scrolls if they do not all fit.
+    pub fn render_remaining(
+        &self,
+        mut header: Vec<Span<'_>>,
+        empty: bool,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        let words = self.sorted_remaining();
+        let page = (area.height as usize).saturating_sub(1);
+        self.remaining.page.set(page);
+        let offset = self.remaining.offset.min(words.len().saturating_sub(page));
+
+        if words.len() > page {
+            header.push(
+                format!(
+                    "  {}-{} <PgUp/PgDn>",
+                    offset + 1,
+                    (offset + page).min(words.len())
+                )
+                .dark_gray(),
+            );
+        }
+        let sort = match self.remaining.sort {
+            RemainingSort::Likelihood => "  by likelihood <F7>".to_string(),
+            RemainingSort::Alphabetical => "  A-Z <F7>".to_string(),
+            RemainingSort::Bits if self.remaining.bits.is_empty() => {
+                format!("  by bits at ≤{} words <F7>", BITS_CUTOFF)
+            }
+            RemainingSort::Bits => "  by bits as a guess <F7>".to_string(),
+        };
+        header.push(sort.dark_gray());
+        let mut lines = vec![Line::from(header)];
+        if empty {
+            lines.push(Line::from(
+                "No words match your guesses. Check the letter statuses.".red(),
+            ));
+        }
+        for (word, likelihood, bits) in words.iter().skip(offset).take(page) {
+            let mut line = vec![
+                format!("{} ", word).into(),
+                format_likelihood(*likelihood).dark_gray(),
+            ];
+            if let Some(bits) = bits {
+                line.push(format!(" {:.2} bits", bits).into());
+            }
+            lines.push(Line::from(line));
+        }
+        Paragraph::new(lines).render(area, buf);
+    }
+}
+
+/// A likelihood as a percentage, e.g. `12.3%`, with two significant digits
+/// below 1%, e.g. `0.031%`, down to `<0.0001%`
+fn format_likelihood(likelihood: f32) -> String {
+    let percent = likelihood * 100.0;
+    if percent > 0.0 && percent < 0.0001 {
+        return "<0.0001%".to_string();
+    }
+    let decimals = match percent > 0.0 {
+        true => (1.0 - percent.log10().floor()).clamp(1.0, 5.0) as usize,
+        false => 1,
+    };
+    format!("{:.*}%", decimals, percent)
+}
@@ -0,0 +1,107 @@
+use super::*;
+
+/// The lifecycle of a guess row. The state decides which edits are allowed
+/// and whether the row is part of the solver state.
+///
+/// Letters move a row between `Empty`, `Typing`, `CompleteUnvalidated` and
+/// `ValidPendingStatus`. Only a valid word accepts a status, and only a
+/// confirmed row is `Committed`. The solver state is computed from the
+/// committed rows at the top, so typing never triggers a recomputation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RowState {
+    /// No letters are entered
+    Empty,
+    /// Some, but not all letters are entered
+    Typing,
+    /// All letters are entered, but the word is not in the word list
+    CompleteUnvalidated,
+    /// A valid word whose status is still being entered
+    ValidPendingStatus,
+    /// A confirmed guess, which is part of the solver state
+    Committed,
+}
+
+impl RowState {
+    /// The state of a row after its letters changed
+    pub fn after_edit(word: &Word, solver: &Solver) -> RowState {
+        let n_entered = word.chars().iter().filter(|c| c.is_some()).count();
+        match n_entered {
+            0 => RowState::Empty,
+            n if n < word.n_letters() => RowState::Typing,
+            _ if solver.is_valid_guess(word) => RowState::ValidPendingStatus,
+            _ => RowState::CompleteUnvalidated,
+        }
+    }
+
+    /// Whether the status of the letters can be set
+    pub fn allows_status(self) -> bool {
+        matches!(self, RowState::ValidPendingStatus | RowState::Committed)
+    }
+
+    /// The state after the row is confirmed. Only a valid word can be
+    /// committed.
+    pub fn commit(self) -> RowState {
+        match self {
+            RowState::ValidPendingStatus => RowState::Committed,
+            state => state,
+        }
+    }
+}
+
+impl App {
+    /// The committed guesses at the top, which make up the solver state
+    pub fn committed_guesses(&self) -> Vec<Guess> {
+        self.guesses
+            .iter()
+            .zip(&self.rows)
+            .take_while(|(_, state)| **state == RowState::Committed)
+            .map(|(guess, _)| *guess)
+            .collect()
+    }
+
+    /// Move a row to a new state. The solver state is only recomputed if
+    /// the committed guesses at the top change.
+    pub fn set_row_state(&mut self, row: usize, state: RowState) {
+        let n_committed = self.committed_guesses().len();
+        let was_committed = self.rows[row] == RowState::Committed;
+        self.rows[row] = state;
+        if row <= n_committed && was_committed != (state == RowState::Committed) {
+            self.update_guesses();
+        }
+    }
+
+    /// Confirm the selected row
+    pub fn commit_selected_row(&mut self) {
+        let state = self.rows[self.selected_word].commit();
+        self.set_row_state(self.selected_word, state);
+    }
+
+    /// Color every letter of a row at once and confirm it, without
+    /// recomputing the solver state. Returns false if the row has no valid
+    /// word or the status does not fit it.
+    pub fn apply_row_status(&mut self, row: usize, status: &[LetterStatus]) -> bool {
+        let guess = &mut self.guesses[row];
+        if !self.rows[row].allows_status() || status.len() != guess.word.n_letters() {
+            return false;
+        }
+        guess.set_status(status);
+        self.rows[row] = RowState::Committed;
+        true
+    }
+
+    /// Color every letter of a row at once and confirm it. The solver state
+    /// is recomputed once, instead of after every toggled letter.
+    pub fn set_row_status(&mut self, row: usize, status: &[LetterStatus]) {
+        let n_committed = self.committed_guesses().len();
+        let unchanged =
+            self.rows[row] == RowState::Committed && self.guesses[row].get_status() == status;
+        if unchanged || !self.apply_row_status(row, status) {
+            return;
+        }
+        // A committed row changes the solver state even though it stays
+        // committed
+        if row <= n_committed {
+            self.update_guesses();
+        }
+    }
+}
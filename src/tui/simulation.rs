@@ -0,0 +1,187 @@
+use ratatui::widgets::*;
+
+use super::actions::{suggestion_penalty, Action};
+use super::*;
+use crate::wordlebot::solver::simulate::Simulation;
+
+/// A simulation of a suggestion against every remaining word, see
+/// [`Solver::simulate`]
+pub struct SimulationRun {
+    pub guesses: Vec<Guess>,
+    pub word: Word,
+    // How many groups of the suggestion are played, of how many
+    pub progress: (usize, usize),
+    // `None` while it runs
+    pub result: Option<Simulation>,
+    // The rows a game may take, the probes do not use a turn
+    pub max_rows: usize,
+    pub token: CancellationToken,
+}
+
+impl App {
+    /// Simulate the selected suggestion, or the best one, and show the
+    /// outcome below the suggestions. Closes the panel if it already shows
+    /// that suggestion.
+    pub fn toggle_simulation(&mut self) {
+        let guesses = self.visible_guesses();
+        let rank = self.selected_suggestion.unwrap_or(0);
+        let Some(word) = self.suggestions.get(rank).map(|s| s.word) else {
+            self.toggle_panel(Panel::Simulation);
+            return;
+        };
+        // Without a selection the best suggestion may change while it is
+        // ranked, so the panel closes for any word
+        let current = matches!(&self.simulation, Some(run) if run.guesses == guesses
+            && (run.word == word || self.selected_suggestion.is_none()));
+        if current && self.panel == Panel::Simulation {
+            self.toggle_panel(Panel::Simulation);
+            return;
+        }
+        self.panel = Panel::Simulation;
+        self.simulate(guesses, word);
+    }
+
+    /// Play the word after the guesses against every remaining word in the
+    /// background, and then the best guess of the one level ranking
+    fn simulate(&mut self, guesses: Vec<Guess>, word: Word) {
+        if let Some(run) = self.simulation.take() {
+            run.token.cancel();
+        }
+        let probes = guesses.len() - self.turns(guesses.len());
+        let max_rows = MAX_GUESSES + probes;
+        let token = self.token.child_token();
+        self.simulation = Some(SimulationRun {
+            guesses: guesses.clone(),
+            word,
+            progress: (0, 0),
+            result: None,
+            max_rows,
+            token: token.clone(),
+        });
+
+        let solver = Arc::clone(&self.solver);
+        let remaining_words = self.remaining_words.clone();
+        let tx = self.action_tx.clone();
+        tokio::task::spawn_blocking(move || {
+            let pick = |played: &[Guess], remaining: &[usize]| {
+                let penalty = suggestion_penalty(played.len() - probes);
+                Some(solver.guess(1, remaining, penalty).ok()?[0])
+            };
+            let progress = |done, total| {
+                tx.send(Some(Action::SimulationProgress(
+                    guesses.clone(),
+                    done,
+                    total,
+                )))
+                .ok();
+                !token.is_cancelled()
+            };
+            // Games that take longer than the rules allow are played out,
+            // to show how long they would take
+            let rounds = max_rows + 2;
+            let simulation =
+                solver.simulate(&guesses, &remaining_words, word, rounds, pick, progress);
+            if let Some(simulation) = simulation {
+                tx.send(Some(Action::UpdateSimulation(guesses, simulation)))
+                    .ok();
+            }
+        });
+    }
+
+    pub fn update_simulation_progress(&mut self, guesses: Vec<Guess>, done: usize, total: usize) {
+        if let Some(run) = self
+            .simulation
+            .as_mut()
+            .filter(|run| run.guesses == guesses)
+        {
+            run.progress = (done, total);
+        }
+    }
+
+    pub fn update_simulation(&mut self, guesses: Vec<Guess>, simulation: Simulation) {
+        if let Some(run) = self
+            .simulation
+            .as_mut()
+            .filter(|run| run.guesses == guesses && run.word == simulation.guess)
+        {
+            run.result = Some(simulation);
+        }
+    }
+
+    /// Render the outcome of the simulation: how likely each number of
+    /// guesses is, as a bar per number
+    pub fn render_simulation(&self, area: Rect, buf: &mut Buffer) {
+        let guesses = self.visible_guesses();
+        let run = self
+            .simulation
+            .as_ref()
+            .filter(|run| run.guesses == guesses);
+        let Some(run) = run else {
+            let lines = vec![
+                Line::from(vec!["Simulation".bold(), "  <F10> close".dark_gray()]),
+                Line::from("Press F10 to simulate the selected suggestion".dark_gray()),
+            ];
+            Paragraph::new(lines).render(area, buf);
+            return;
+        };
+        let mut lines = vec![Line::from(vec![
+            "Playing ".bold(),
+            run.word.to_string().bold().magenta(),
+            format!(" against {} words", self.remaining_words.len()).bold(),
+            "  <F10> close".dark_gray(),
+        ])];
+        let Some(simulation) = &run.result else {
+            let (done, total) = run.progress;
+            lines.push(Line::from(
+                format!("Simulating... {} of {} groups", done, total).dark_gray(),
+            ));
+            Paragraph::new(lines).render(area, buf);
+            return;
+        };
+
+        let lost = |rows: usize| rows > run.max_rows;
+        let mut stats =
+            vec![format!("{:.2} guesses expected", simulation.expected_guesses()).into()];
+        if let Some(worst) = simulation.worst_case() {
+            let text = format!(", worst case {}", worst);
+            stats.push(match lost(worst) {
+                true => text.red(),
+                false => text.into(),
+            });
+        }
+        if simulation.failed > 0 {
+            stats.push(format!(", {} not found", simulation.failed).red());
+        }
+        lines.push(Line::from(stats));
+
+        let max = simulation
+            .probabilities
+            .iter()
+            .fold(simulation.failed_probability, |max: f32, &p| max.max(p));
+        let bar_width = (area.width as usize).saturating_sub(24);
+        let bar =
+            |p: f32| "█".repeat((p / max.max(f32::EPSILON) * bar_width as f32).ceil() as usize);
+        let first = simulation.solved.iter().position(|&n| n > 0).unwrap_or(0);
+        let last = simulation.worst_case().unwrap_or(0);
+        for rows in first + 1..=last {
+            let (n, p) = (
+                simulation.solved[rows - 1],
+                simulation.probabilities[rows - 1],
+            );
+            let label = format!("{:>2} {:>5.1}% {:>5} ", rows, p * 100.0, n);
+            lines.push(Line::from(vec![
+                label.into(),
+                match lost(rows) {
+                    true => bar(p).red(),
+                    false => bar(p).magenta(),
+                },
+            ]));
+        }
+        if simulation.failed > 0 {
+            let p = simulation.failed_probability;
+            let label = format!(" X {:>5.1}% {:>5} ", p * 100.0, simulation.failed);
+            lines.push(Line::from(vec![label.into(), bar(p).red()]));
+        }
+        Paragraph::new(lines).render(area, buf);
+    }
+}
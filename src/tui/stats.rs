@@ -0,0 +1,125 @@
+use ratatui::widgets::{block::*, Clear, *};
+
+use crate::wordlebot::history::{GameRecord, History, Summary};
+
+use super::*;
+
+impl App {
+    /// Keep the finished games in the history, for the Stats screen and
+    /// `wordlebot stats`
+    pub fn set_history(&mut self, history: Option<History>) {
+        self.history = history;
+    }
+
+    /// Show the statistics of the history, read again every time the
+    /// screen is opened
+    pub fn toggle_stats(&mut self) {
+        if self.stats.take().is_some() {
+            return;
+        }
+        self.stats = Some(match &self.history {
+            Some(history) => history
+                .read()
+                .map(|games| Summary::new(&games))
+                .map_err(|e| format!("{:#}", e)),
+            None => Err("The history is off, start without --no-history".to_string()),
+        });
+    }
+
+    /// Record a finished game in the history. The rows up to `last` that
+    /// used a turn are the guesses of the game.
+    pub fn record_history(&self, mode: &str, answer: Word, last: usize) {
+        let Some(history) = &self.history else {
+            return;
+        };
+        let guesses: Vec<Guess> = (0..=last)
+            .filter(|&row| !self.probes[row])
+            .map(|row| self.guesses[row])
+            .collect();
+        let mut record = GameRecord::today(mode, answer, &guesses);
+        record.session = self.game.as_ref().and_then(|game| game.session);
+        // The history must never interrupt the game, so errors are ignored
+        history.record(&record).ok();
+    }
+
+    /// Render the statistics of the history over everything else
+    pub fn render_stats(&self, area: Rect, buf: &mut Buffer) {
+        let Some(stats) = &self.stats else {
+            return;
+        };
+        let lines: Vec<Line> = match stats {
+            Ok(summary) if summary.played == 0 => {
+                vec![Line::from("No game has been finished yet".dark_gray())]
+            }
+            Ok(summary) => summary_lines(summary),
+            Err(e) => vec![Line::from(e.clone().red())],
+        };
+        let block = Block::default()
+            .title(Title::from(" Stats ".bold()).alignment(Alignment::Center))
+            .title(
+                Title::from(" <F11>/<Esc> close ".dark_gray())
+                    .position(Position::Bottom)
+                    .alignment(Alignment::Center),
+            )
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1));
+        let width = 60.min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let popup_area = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+        Clear.render(popup_area, buf);
+        Paragraph::new(lines).block(block).render(popup_area, buf);
+    }
+}
+
+/// The streaks, the distribution of the turns as bars and the best and
+/// worst openers
+fn summary_lines(summary: &Summary) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(format!(
+        "Played {}  Won {}%  Streak {}  Best streak {}",
+        summary.played,
+        summary.win_rate(),
+        summary.streak,
+        summary.max_streak
+    ))];
+    if let Some(mean) = summary.mean_turns {
+        lines.push(Line::from(format!(
+            "Average turns of the games won: {:.2}",
+            mean
+        )));
+    }
+    lines.push(Line::from(""));
+    let most = summary
+        .distribution
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    for (i, &count) in summary.distribution.iter().enumerate() {
+        let bar = " ".repeat((40 * count).div_ceil(most).max(1));
+        lines.push(Line::from(vec![
+            format!("{} ", i + 1).bold(),
+            bar.on_green(),
+            format!(" {}", count).into(),
+        ]));
+    }
+    let (best, worst) = summary.best_and_worst_openers(3);
+    for (title, openers) in [("Best openers", best), ("Worst openers", worst)] {
+        if openers.is_empty() {
+            continue;
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(title.bold()));
+        lines.extend(
+            openers
+                .iter()
+                .map(|opener| Line::from(format!("  {}", opener))),
+        );
+    }
+    lines
+}
@@ -0,0 +1,134 @@
+use super::*;
+
+/// A ranking of the suggestions that can be switched to during a game
+#[derive(Clone)]
+pub enum Strategy {
+    Entropy,
+    TwoLevel,
+    Minimax,
+    ExpectedTurns,
+    // The ranking of --depth
+    Lookahead(Search),
+    // The ranking of --formula
+    Formula(Arc<Formula>),
+    // The optimal guesses of --tree, and the bits for words it does not
+    // cover
+    Tree(Arc<DecisionTree>),
+}
+
+impl Strategy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strategy::Entropy => "entropy",
+            Strategy::TwoLevel => "two-level",
+            Strategy::Minimax => "minimax",
+            Strategy::ExpectedTurns => "expected turns",
+            Strategy::Lookahead(_) => "lookahead",
+            Strategy::Formula(_) => "formula",
+            Strategy::Tree(_) => "optimal",
+        }
+    }
+
+    /// The built-in strategies and those of the command line, with the
+    /// index of the one the app starts with
+    pub fn available(
+        two_level: bool,
+        formula: Option<&Arc<Formula>>,
+        score: ScoreStrategy,
+        tree: Option<&Arc<DecisionTree>>,
+    ) -> (Vec<Strategy>, usize) {
+        let mut strategies = vec![
+            Strategy::Entropy,
+            Strategy::TwoLevel,
+            Strategy::Minimax,
+            Strategy::ExpectedTurns,
+        ];
+        let mut current = match score {
+            ScoreStrategy::Entropy if two_level => 1,
+            ScoreStrategy::Entropy => 0,
+            ScoreStrategy::Minimax => 2,
+            ScoreStrategy::ExpectedTurns => 3,
+        };
+        if let Some(formula) = formula {
+            current = strategies.len();
+            strategies.push(Strategy::Formula(Arc::clone(formula)));
+        }
+        if let Some(tree) = tree {
+            current = strategies.len();
+            strategies.push(Strategy::Tree(Arc::clone(tree)));
+        }
+        (strategies, current)
+    }
+}
+
+impl App {
+    /// The name of the strategy that ranks the suggestions
+    pub fn strategy_name(&self) -> &'static str {
+        self.strategies[self.strategy].name()
+    }
+
+    /// Rank the suggestions by the next strategy. The suggestions of the
+    /// current round are ranked again, the recommendations of the earlier
+    /// rounds keep the strategy they were made by.
+    pub fn next_strategy(&mut self) {
+        if self.boards.is_some() {
+            self.notice = Some(Err("The boards are ranked together".to_string()));
+            return;
+        }
+        self.strategy = (self.strategy + 1) % self.strategies.len();
+        let strategy = self.strategies[self.strategy].clone();
+        self.two_level = matches!(strategy, Strategy::TwoLevel);
+        self.score = match strategy {
+            Strategy::Minimax => ScoreStrategy::Minimax,
+            Strategy::ExpectedTurns => ScoreStrategy::ExpectedTurns,
+            _ => ScoreStrategy::Entropy,
+        };
+        self.search = match strategy {
+            Strategy::Lookahead(search) => Some(search),
+            _ => None,
+        };
+        self.formula = match &strategy {
+            Strategy::Formula(formula) => Some(Arc::clone(formula)),
+            _ => None,
+        };
+        self.tree = match &strategy {
+            Strategy::Tree(tree) => Some(Arc::clone(tree)),
+            _ => None,
+        };
+
+        let guesses = self.committed_guesses();
+        self.recommendations.retain(|(g, _, _)| *g != guesses);
+        self.suggestion_cache.clear();
+        self.suggestions = self.opening_suggestions();
+        self.show_visible_state();
+        self.notice = Some(Ok(format!("Ranking by {}", strategy.name())));
+    }
+
+    /// Remember the best suggestion of a round and the strategy it was
+    /// ranked by, the first time it arrives
+    pub fn record_recommendation(&mut self, guesses: &[Guess], suggestions: &[GuessEvaluation]) {
+        let committed = self.committed_guesses();
+        // Rounds of edited guesses are gone
+        self.recommendations
+            .retain(|(g, _, _)| committed.starts_with(g));
+        let Some(best) = suggestions.first() else {
+            return;
+        };
+        let known = self.recommendations.iter().any(|(g, _, _)| g == guesses);
+        if committed.starts_with(guesses) && !known {
+            self.recommendations
+                .push((guesses.to_vec(), best.word, self.strategy_name()));
+        }
+    }
+
+    /// The recommendation for the guess of a row and the strategy it was
+    /// ranked by
+    pub fn recommendation(&self, row: usize) -> Option<(Word, &'static str)> {
+        let committed = self.committed_guesses();
+        let guesses = committed.get(..row)?;
+        self.recommendations
+            .iter()
+            .find(|(g, _, _)| g == guesses)
+            .map(|&(_, word, name)| (word, name))
+    }
+}
@@ -0,0 +1,170 @@
+use ratatui::widgets::*;
+
+use super::actions::{suggestion_penalty, Action};
+use super::*;
+use crate::wordlebot::solver::streak::{StreakAdvice, MAX_STREAK_ROUNDS};
+
+// The guesses whose success is computed with two or more rounds left
+const STREAK_WIDTH: usize = 10;
+const STREAK_BEAM: usize = 3;
+
+/// The guesses most likely to find the answer within the rounds that are
+/// left, see [`Solver::streak_advice`]
+pub struct StreakRun {
+    pub guesses: Vec<Guess>,
+    pub remaining_words: Vec<usize>,
+    pub rounds: usize,
+    // `None` while it runs
+    pub result: Option<StreakResult>,
+    pub token: CancellationToken,
+}
+
+/// The advice, and the success probability of the best guess by bits if
+/// it is not among it
+pub struct StreakResult {
+    pub advice: Vec<StreakAdvice>,
+    pub best: Option<(Word, f32)>,
+}
+
+impl App {
+    /// Show the guesses that protect a streak below the suggestions, or
+    /// close the panel
+    pub fn toggle_streak(&mut self) {
+        if self.boards.is_some() {
+            self.notice = Some(Err("The boards are ranked together".to_string()));
+            return;
+        }
+        self.toggle_panel(Panel::Streak);
+        if self.panel == Panel::Streak {
+            self.advise_streak();
+        }
+    }
+
+    /// Compute the advice for the visible guesses in the background, unless
+    /// it is known
+    pub fn advise_streak(&mut self) {
+        let guesses = self.visible_guesses();
+        let known =
+            |run: &StreakRun| run.guesses == guesses && run.remaining_words == self.remaining_words;
+        if self.streak.as_ref().is_some_and(known) {
+            return;
+        }
+        if let Some(run) = self.streak.take() {
+            run.token.cancel();
+        }
+        let turn = self.turns(guesses.len());
+        let rounds = MAX_GUESSES.saturating_sub(turn);
+        let token = self.token.child_token();
+        self.streak = Some(StreakRun {
+            guesses: guesses.clone(),
+            remaining_words: self.remaining_words.clone(),
+            rounds,
+            result: None,
+            token: token.clone(),
+        });
+        if self.remaining_words.is_empty() {
+            return;
+        }
+
+        let solver = Arc::clone(&self.solver);
+        let remaining_words = self.remaining_words.clone();
+        let tx = self.action_tx.clone();
+        tokio::task::spawn_blocking(move || {
+            let n = N_SUGGESTIONS;
+            let Ok(advice) =
+                solver.streak_advice(n, &remaining_words, rounds, STREAK_WIDTH, STREAK_BEAM)
+            else {
+                return;
+            };
+            // What the guess with the most bits risks
+            let best = solver
+                .guess(1, &remaining_words, suggestion_penalty(turn))
+                .ok()
+                .and_then(|words| words.first().copied())
+                .filter(|word| advice.iter().all(|a| a.word != *word));
+            let best = match best {
+                Some(word) => {
+                    let Ok(p) =
+                        solver.success_probability(&word, &remaining_words, rounds, STREAK_BEAM)
+                    else {
+                        return;
+                    };
+                    Some((word, p))
+                }
+                None => None,
+            };
+            if !token.is_cancelled() {
+                let result = StreakResult { advice, best };
+                tx.send(Some(Action::UpdateStreak(guesses, result))).ok();
+            }
+        });
+    }
+
+    pub fn update_streak(&mut self, guesses: Vec<Guess>, result: StreakResult) {
+        if let Some(run) = self.streak.as_mut().filter(|run| run.guesses == guesses) {
+            run.result = Some(result);
+        }
+    }
+
+    /// Render the guesses by their probability to find the answer within
+    /// the rounds that are left
+    pub fn render_streak(&self, area: Rect, buf: &mut Buffer) {
+        let guesses = self.visible_guesses();
+        let Some(run) = self.streak.as_ref().filter(|run| run.guesses == guesses) else {
+            return;
+        };
+        let plural = if run.rounds == 1 { "" } else { "s" };
+        let mut lines = vec![Line::from(vec![
+            "Keep the streak: ".bold(),
+            run.rounds.to_string().bold().magenta(),
+            format!(" round{} left", plural).bold(),
+            "  <Ctrl+G> close".dark_gray(),
+        ])];
+        if run.rounds == 0 || self.remaining_words.is_empty() {
+            lines.push(Line::from("Nothing left to protect".dark_gray()));
+            Paragraph::new(lines).render(area, buf);
+            return;
+        }
+        let Some(result) = &run.result else {
+            lines.push(Line::from("Searching...".dark_gray()));
+            Paragraph::new(lines).render(area, buf);
+            return;
+        };
+        if run.rounds > MAX_STREAK_ROUNDS {
+            let text = format!(
+                "Within the next {} rounds, the chance with all of them is higher",
+                MAX_STREAK_ROUNDS
+            );
+            lines.push(Line::from(text.dark_gray()));
+        }
+
+        let bar_width = (area.width as usize).saturating_sub(16);
+        let bar = |p: f32| "█".repeat((p * bar_width as f32).ceil() as usize);
+        let row = |word: Word, p: f32, possible: bool| {
+            let word = match possible {
+                true => word.to_string().green(),
+                false => word.to_string().into(),
+            };
+            Line::from(vec![
+                word,
+                format!(" {:>5.1}% ", p * 100.0).into(),
+                match p >= 1.0 {
+                    true => bar(p).green(),
+                    false => bar(p).magenta(),
+                },
+            ])
+        };
+        let n_rows = (area.height as usize).saturating_sub(lines.len() + 1);
+        for advice in result.advice.iter().take(n_rows) {
+            lines.push(row(advice.word, advice.probability, advice.possible));
+        }
+        if let Some((word, p)) = result.best {
+            lines.push(Line::from(vec![
+                "Most bits ".dark_gray(),
+                word.to_string().dark_gray(),
+                format!(" {:.1}%", p * 100.0).dark_gray(),
+            ]));
+        }
+        Paragraph::new(lines).render(area, buf);
+    }
+}
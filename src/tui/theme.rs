@@ -0,0 +1,76 @@
+use anyhow::{bail, Result};
+use ratatui::prelude::*;
+
+use super::App;
+use crate::wordlebot::wordle::LetterStatus;
+
+/// The colors of the letter statuses. The high contrast theme uses orange
+/// and blue instead of green and yellow, which are hard to tell apart for
+/// colorblind players.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+    // Letters and borders of the guesses
+    correct: Color,
+    misplaced: Color,
+    // Background of the letters on the boards
+    correct_bg: Color,
+    misplaced_bg: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            correct: Color::LightGreen,
+            misplaced: Color::LightYellow,
+            correct_bg: Color::Green,
+            misplaced_bg: Color::Yellow,
+        }
+    }
+}
+
+impl Theme {
+    const ORANGE: Color = Color::Rgb(245, 121, 58);
+    const BLUE: Color = Color::Rgb(133, 192, 249);
+
+    /// Get one of the built-in themes (default, high-contrast)
+    pub fn load(name: &str) -> Result<Theme> {
+        match name.to_lowercase().as_str() {
+            "default" => Ok(Theme::default()),
+            "high-contrast" => Ok(Theme {
+                correct: Theme::ORANGE,
+                misplaced: Theme::BLUE,
+                correct_bg: Theme::ORANGE,
+                misplaced_bg: Theme::BLUE,
+            }),
+            _ => bail!(
+                "Unknown theme '{}', expected default or high-contrast",
+                name
+            ),
+        }
+    }
+
+    /// The color of a letter of a guess
+    pub fn color(&self, status: LetterStatus) -> Color {
+        match status {
+            LetterStatus::Absent => Color::White,
+            LetterStatus::Misplaced => self.misplaced,
+            LetterStatus::Correct => self.correct,
+        }
+    }
+
+    /// The style of a letter on a small board
+    pub fn tile(&self, status: LetterStatus) -> Style {
+        match status {
+            LetterStatus::Absent => Style::default().white(),
+            LetterStatus::Misplaced => Style::default().black().bg(self.misplaced_bg),
+            LetterStatus::Correct => Style::default().black().bg(self.correct_bg),
+        }
+    }
+}
+
+impl App {
+    /// Draw the letter statuses in the colors of a theme
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+}
@@ -1,13 +1,16 @@
 use std::iter::zip;
 use tokio::runtime::Handle;
 
-use super::{App, N_SUGGESTIONS};
-use crate::wordlebot::wordle::{Guess, LetterStatus};
+use super::actions::suggestion_penalty;
+use super::{App, GameState, Pack, Panel, RowState, Theme, UpdateCheck, N_SUGGESTIONS};
+use crate::wordlebot::about;
+use crate::wordlebot::solver::{GuessEvaluation, Luck, Weighting};
+use crate::wordlebot::wordle::{Guess, LetterStatus, MAX_GUESSES};
 use ratatui::{
     prelude::*,
     widgets::{block::*, *},
 };
-use wordlebot::wordle::{decode_status, encode_status};
+use wordlebot::wordle::encode_status;
 
 impl Widget for &App {
     fn render(self, area: Rect, buf: &mut Buffer) {
@@ -20,14 +23,22 @@ impl Widget for &App {
 
         let columns = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints(vec![Constraint::Length(55), Constraint::Fill(1)])
+            .constraints(vec![
+                // Leave room for a guess with a box of 7 cells per letter
+                Constraint::Length((7 * self.solver.n_letters() as u16 + 6).max(55)),
+                Constraint::Fill(1),
+            ])
             .split(rows[0]);
 
         self.render_guess_area(columns[0], buf);
         self.render_solver_area(columns[1], buf);
         self.render_chart(rows[1], buf);
 
+        let inner = border.inner(area);
         border.render(area, buf);
+        self.render_profile(inner, buf);
+        self.render_stats(area, buf);
+        self.render_about(area, buf);
     }
 }
 
@@ -57,14 +68,51 @@ fn centered_rect(x: u16, y: u16, r: Rect) -> Rect {
 
 impl App {
     fn create_border(&self) -> Block<'_> {
-        let title = Title::from(" Wordlebot ".bold());
-        let instructions = Title::from(Line::from(vec![
+        let name = match &self.word_pack {
+            Some(pack) => format!("Wordlebot: {}", pack),
+            None => "Wordlebot".to_string(),
+        };
+        let title = match self.solver.weighting() {
+            Weighting::Priors => Title::from(format!(" {} ", name).bold()),
+            Weighting::Uniform => Title::from(format!(" {} (uniform) ", name).bold()),
+        };
+        // The game colors the guesses itself
+        let (status, status_key) = match self.game {
+            Some(_) => (" New game ", "<Ctrl+R> "),
+            None => (" Toggle status ", "<Tab> "),
+        };
+        let mut instructions = vec![
             " Quit ".into(),
             "<Esc> ".blue().bold(),
-            " Toggle status ".into(),
-            "<Tab> ".blue().bold(),
-        ]));
-        let block = Block::default()
+            status.into(),
+            status_key.blue().bold(),
+            " Confirm ".into(),
+            "<Enter> ".blue().bold(),
+            " Why not? ".into(),
+            "<?> ".blue().bold(),
+            " Compare rankings ".into(),
+            "<F2> ".blue().bold(),
+            " N-grams ".into(),
+            "<F3> ".blue().bold(),
+            " Filter ".into(),
+            "<F4> ".blue().bold(),
+            " Review rounds ".into(),
+            "<Shift+←/→> ".blue().bold(),
+        ];
+        if self.game.is_some() {
+            instructions.push(" Hint ".into());
+            instructions.push("<F6> ".blue().bold());
+        }
+        if self.game.is_none() {
+            instructions.push(" Probe ".into());
+            instructions.push("<Ctrl+P> ".blue().bold());
+        }
+        if self.boards.is_some() {
+            instructions.push(" Board ".into());
+            instructions.push("<[/]> ".blue().bold());
+        }
+        let instructions = Title::from(Line::from(instructions));
+        let mut block = Block::default()
             .title(title.alignment(Alignment::Center))
             .title(
                 instructions
@@ -73,6 +121,25 @@ impl App {
             )
             .borders(Borders::ALL)
             .border_set(symbols::border::PLAIN);
+        if let Some(overlay) = &self.overlay {
+            let name = overlay
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy())
+                .unwrap_or_default();
+            let status = match &overlay.status {
+                Ok(n) => format!(" {}: {} answers ", name, n).green(),
+                Err(e) => format!(" {}: {} ", name, e).red(),
+            };
+            block = block.title(Title::from(status).alignment(Alignment::Right));
+        }
+        if let Some(notice) = &self.notice {
+            let notice = match notice {
+                Ok(message) => format!(" {} ", message).green(),
+                Err(e) => format!(" {} ", e).red(),
+            };
+            block = block.title(Title::from(notice).alignment(Alignment::Left));
+        }
         block
     }
 
@@ -99,27 +166,123 @@ impl App {
             .split(block.inner(area));
 
         self.render_evaluation(rows[1], buf);
+        let bottom = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![
+                Constraint::Length(self.keyboard_rows().len() as u16),
+                Constraint::Fill(1),
+            ])
+            .split(rows[2]);
+        self.render_keyboard(bottom[0], buf);
+        match self.game {
+            Some(_) => {
+                let bottom = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints(vec![Constraint::Length(3), Constraint::Fill(1)])
+                    .split(bottom[1]);
+                self.render_game(bottom[0], buf);
+                self.render_explanation(bottom[1], buf);
+            }
+            None => self.render_explanation(bottom[1], buf),
+        }
 
-        // Create the guess area
+        // Create the guess area. If there are more rows than fit, it
+        // scrolls to the selected row.
+        let n_visible = (rows[0].height / 3).max(1) as usize;
+        let first = (self.selected_word + 1).saturating_sub(n_visible);
         let word_rows = Layout::default()
             .direction(Direction::Vertical)
-            .constraints(vec![Constraint::Length(3); 6])
+            .constraints(vec![Constraint::Length(3); n_visible])
             .split(rows[0]);
-        for i in 0..6 {
+        let n_committed = self.committed_guesses().len();
+        for (i, area) in (first..self.guesses.len()).zip(word_rows.iter()) {
             let selected_letter = match i {
                 _ if i == self.selected_word => Some(self.selected_letter),
                 _ => None,
             };
-            let valid = self.solver.is_valid_guess(&self.cached_guesses[i].word);
-            self.guesses[i].render(word_rows[i], buf, selected_letter, valid)
+            // Only guesses of the solver state and the one whose status is
+            // being entered are colored. Guesses after the reviewed round
+            // are greyed out.
+            let valid = (i < n_committed || self.rows[i] == RowState::ValidPendingStatus)
+                && self.scrub.is_none_or(|rounds| i < rounds);
+            self.guesses[i].render(*area, buf, selected_letter, valid, &self.theme);
+            if self.probes[i] {
+                let label = Rect::new(area.x + 1, area.y + 1, 5.min(area.width), 1);
+                Paragraph::new("probe".italic().dark_gray()).render(label, buf);
+            }
+            if self
+                .conflict
+                .as_ref()
+                .is_some_and(|c| c.guesses.contains(&i))
+            {
+                let width = 8.min(area.width);
+                let x = area.right().saturating_sub(width + 1).max(area.x);
+                let label = Rect::new(x, area.y + 1, width, 1);
+                Paragraph::new("conflict".bold().red()).render(label, buf);
+            }
+            if i == self.selected_word && self.rows[i] == RowState::Typing {
+                self.render_partial_counts(i, *area, buf);
+            }
         }
         block.render(area, buf);
     }
 
+    /// Render how many words fit the letters typed so far on the right of
+    /// the row, and how many answers remain if they all turn out gray or
+    /// green
+    fn render_partial_counts(&self, i: usize, area: Rect, buf: &mut Buffer) {
+        // The counts would tell the answer of a game apart
+        if self.is_hidden() {
+            return;
+        }
+        let counts = self
+            .solver
+            .partial_counts(&self.remaining_words, &self.guesses[i].word);
+        let lines = vec![
+            Line::from(format!("{} words", counts.words)),
+            Line::from(format!("{} gray", counts.all_gray)).dark_gray(),
+            Line::from(format!("{} green", counts.all_green)).green(),
+        ];
+        let width = 10.min(area.width);
+        let x = area.right().saturating_sub(width).max(area.x);
+        let badge = Rect::new(x, area.y, width, area.height.min(3));
+        Paragraph::new(lines).render(badge, buf);
+    }
+
     fn render_solver_area(&self, area: Rect, buf: &mut Buffer) {
-        let title = Title::from("Solver".bold());
+        let title = match self.scrub {
+            Some(rounds) => Title::from(
+                format!(
+                    " Solver after guess {} of {} ",
+                    rounds,
+                    self.committed_guesses().len()
+                )
+                .bold()
+                .black()
+                .on_yellow(),
+            ),
+            None => Title::from(Line::from(vec![
+                "Solver".bold(),
+                format!(" by {} <Ctrl+T>", self.strategy_name()).dark_gray(),
+            ])),
+        };
         let block = Block::new().title(title.alignment(Alignment::Center));
 
+        if self.is_hidden() {
+            Paragraph::new("The solver is hidden until the game is over.")
+                .alignment(Alignment::Center)
+                .block(Block::new().padding(Padding::new(0, 0, 2, 0)))
+                .render(block.inner(area), buf);
+            block.render(area, buf);
+            return;
+        }
+
+        if self.boards.is_some() {
+            self.render_boards(block.inner(area), buf);
+            block.render(area, buf);
+            return;
+        }
+
         // Create two rows
         let rows = Layout::default()
             .direction(Direction::Vertical)
@@ -131,38 +294,193 @@ impl App {
 
         self.render_suggestions(rows[0], buf);
 
-        // Plot all solutions
-        let mut lines: Vec<Line<'_>> = vec![Line::from(vec![
-            "Remaining words: ".bold(),
-            self.remaining_words.len().to_string().bold().magenta(),
-        ])];
-        let solutions = self.solver.get_words_from_idx(&self.remaining_words);
-        for item in solutions {
-            lines.push(format!("{}", item).into())
+        match self.panel {
+            Panel::Ngrams => self.render_ngrams(rows[1], buf),
+            Panel::Heatmap => self.render_heatmap(rows[1], buf),
+            Panel::Why => self.render_why(rows[1], buf),
+            Panel::Simulation => self.render_simulation(rows[1], buf),
+            Panel::Streak => self.render_streak(rows[1], buf),
+            Panel::RemainingWords => {
+                // Plot all solutions
+                let mut header = vec![
+                    "Remaining words: ".bold(),
+                    self.remaining_words.len().to_string().bold().magenta(),
+                ];
+                header.extend(self.confidence_spans());
+                self.render_remaining(header, self.remaining_words.is_empty(), rows[1], buf);
+            }
         }
-        Paragraph::new(lines)
-            // .scroll((self.scroll, 0))
-            .render(rows[1], buf);
 
         block.render(area, buf);
     }
 
+    /// Render the combined suggestions, all boards side by side and the
+    /// remaining words of the selected board
+    fn render_boards(&self, area: Rect, buf: &mut Buffer) {
+        let Some(boards) = &self.boards else {
+            return;
+        };
+        let Ok(multi) = self.multi_solver() else {
+            return;
+        };
+        let n_rows = (0..boards.len())
+            .map(|b| multi.guesses(b).len())
+            .max()
+            .unwrap_or(0);
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![
+                Constraint::Length(N_SUGGESTIONS as u16 + 4),
+                Constraint::Length(n_rows as u16 + 2),
+                Constraint::Fill(1),
+            ])
+            .split(area);
+
+        let suggestions: Vec<_> = boards
+            .suggestions
+            .iter()
+            .map(|w| {
+                let style = if w.possible.is_empty() {
+                    Style::default()
+                } else {
+                    Style::default().white()
+                };
+                let possible: Vec<String> =
+                    w.possible.iter().map(|b| (b + 1).to_string()).collect();
+                Row::new(vec![
+                    Text::from(format!("{}", w.word)).alignment(Alignment::Left),
+                    Text::from(format!("{:.2}", w.total_bits)).alignment(Alignment::Center),
+                    Text::from(format!("{:.2}", w.solves)).alignment(Alignment::Center),
+                    Text::from(possible.join(" ")).alignment(Alignment::Left),
+                ])
+                .style(style)
+            })
+            .collect();
+        let widths = [
+            Constraint::Length(10),
+            Constraint::Length(9),
+            Constraint::Length(8),
+            Constraint::Fill(1),
+        ];
+        let table = Table::new(suggestions, widths)
+            .column_spacing(1)
+            .header(Row::new(vec![
+                Cell::from("Suggestion").underlined(),
+                Cell::from("Exp. Bits").underlined(),
+                Cell::from("Solves").underlined(),
+                Cell::from("Possible on board").underlined(),
+            ]))
+            .block(Block::new().padding(Padding::new(0, 0, 1, 0)))
+            .highlight_style(Style::new().reversed());
+        let mut state = TableState::new().with_selected(self.selected_suggestion);
+        StatefulWidget::render(table, rows[0], buf, &mut state);
+        self.render_working_popup(rows[0], buf);
+
+        // One column per board with its guesses up to the one that solved it
+        let width = (self.solver.n_letters() as u16 + 3).max(8);
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Length(width); boards.len()])
+            .split(rows[1]);
+        for (b, area) in columns.iter().enumerate() {
+            let header = match multi.is_solved(b) {
+                true => format!("{} ✓", b + 1),
+                false => format!("{}: {}", b + 1, multi.remaining(b).len()),
+            };
+            let header = match b == boards.selected {
+                true => header.bold().black().on_magenta(),
+                false => header.bold(),
+            };
+            let mut lines = vec![Line::from(header)];
+            for guess in multi.guesses(b) {
+                let spans: Vec<Span> = zip(guess.word.chars(), guess.get_status())
+                    .map(|(letter, status)| {
+                        let letter = letter.unwrap_or(' ').to_uppercase().to_string();
+                        Span::styled(letter, self.theme.tile(status))
+                    })
+                    .collect();
+                lines.push(Line::from(spans));
+            }
+            Paragraph::new(lines).render(*area, buf);
+        }
+
+        let header = vec![
+            format!("Remaining words on board {}: ", boards.selected + 1).bold(),
+            self.remaining_words.len().to_string().bold().magenta(),
+        ];
+        let empty = self.remaining_words.is_empty() && !multi.is_solved(boards.selected);
+        self.render_remaining(header, empty, rows[2], buf);
+    }
+
+    /// Render the most common bigrams and trigrams of the remaining words,
+    /// each with a guess that tests it
+    fn render_ngrams(&self, area: Rect, buf: &mut Buffer) {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Length(26), Constraint::Length(26)])
+            .split(area);
+        let n_rows = area.height.saturating_sub(2) as usize;
+
+        for (n, column) in [2, 3].into_iter().zip(columns.iter()) {
+            let counts = self
+                .solver
+                .get_ngram_frequencies(&self.remaining_words, n, n_rows);
+            let rows: Vec<Row<'_>> = counts
+                .iter()
+                .map(|c| {
+                    Row::new(vec![
+                        Text::from(c.ngram.to_uppercase()).magenta(),
+                        Text::from(c.count.to_string()).alignment(Alignment::Right),
+                        Text::from(c.probe.map(|w| w.to_string()).unwrap_or_default())
+                            .alignment(Alignment::Center),
+                    ])
+                })
+                .collect();
+            let widths = [
+                Constraint::Length(7),
+                Constraint::Length(6),
+                Constraint::Length(8),
+            ];
+            let name = if n == 2 { "Bigram" } else { "Trigram" };
+            let table = Table::new(rows, widths)
+                .column_spacing(1)
+                .header(Row::new(vec![
+                    Cell::from(name).underlined(),
+                    Cell::from("words").underlined(),
+                    Cell::from("probe").underlined(),
+                ]));
+            ratatui::widgets::Widget::render(table, *column, buf);
+        }
+    }
+
     fn render_chart(&self, area: Rect, buf: &mut Buffer) {
-        let i = if self.selected_word > self.evaludations.len() - 1 {
-            self.evaludations.len() - 1
-        } else {
-            self.selected_word
+        let Some(i) = self.chart_index() else {
+            return;
+        };
+        let area = match self.current_branch() {
+            Some(branch) => {
+                let columns = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints(vec![Constraint::Fill(1), Constraint::Length(44)])
+                    .split(area);
+                self.render_branch(branch, columns[1], buf);
+                columns[0]
+            }
+            None => area,
         };
         if let Some(eval) = self.evaludations.get(i) {
-            let status = match eval.status {
-                Some(x) => encode_status(&x),
+            let status = match &eval.status {
+                Some(x) => encode_status(x),
                 None => 0,
             };
             let sizes: Vec<_> = eval
                 .group_sizes
                 .iter()
-                .map(|(s, size)| {
-                    let style = if s == &status {
+                .enumerate()
+                .map(|(bar, (s, size))| {
+                    let style = if self.selected_bar == Some(bar) {
+                        Style::new().magenta()
+                    } else if s == &status {
                         Style::new().red()
                     } else {
                         Style::new().dark_gray()
@@ -174,7 +492,7 @@ impl App {
                 })
                 .collect();
 
-            let width = match area.width / sizes.len() as u16 {
+            let width = match area.width / sizes.len().max(1) as u16 {
                 0 => 1,
                 x if x > 10 => 10,
                 x => x,
@@ -194,6 +512,11 @@ impl App {
                             )
                             .alignment(Alignment::Center),
                         )
+                        .title(
+                            Title::from(" <,/.> select a bar, <F5> what if ".dark_gray())
+                                .position(Position::Bottom)
+                                .alignment(Alignment::Center),
+                        )
                         .borders(Borders::ALL)
                         .border_type(BorderType::Rounded)
                         .border_style(Style::new().dark_gray()),
@@ -206,9 +529,10 @@ impl App {
     }
 
     fn render_evaluation(&self, area: Rect, buf: &mut Buffer) {
+        let word_width = self.solver.n_letters().max(5) as u16;
         let area = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints(vec![Constraint::Length(51)])
+            .constraints(vec![Constraint::Length(51 + word_width)])
             .flex(layout::Flex::Center)
             .split(area);
 
@@ -217,31 +541,56 @@ impl App {
             .iter()
             .enumerate()
             .map(|(i, w)| {
-                let style = if self.selected_word == i {
+                let mut style = if self.selected_word == i {
                     Style::new().white()
                 } else {
                     Style::new()
                 };
+                // Probes do not use a turn
+                if self.probes.get(i) == Some(&true) {
+                    style = style.dark_gray().italic();
+                }
                 Row::new(vec![
                     Text::from(format!("{}", w.word)).alignment(Alignment::Left),
                     Text::from(format!("{:.2}", w.expected_bits)).alignment(Alignment::Center),
-                    Text::from(format!("{:.2}", w.real_bits.unwrap())).alignment(Alignment::Center),
+                    Text::from(match w.real_bits {
+                        Some(bits) => format!("{:.2}", bits),
+                        None => "-".to_string(),
+                    })
+                    .alignment(Alignment::Center),
+                    Text::from(match w.luck() {
+                        Some(Luck::Lucky) => "🍀",
+                        Some(Luck::Unlucky) => "😬",
+                        _ => "",
+                    })
+                    .alignment(Alignment::Center),
                     Text::from(w.groups.to_string()).alignment(Alignment::Center),
                     Text::from(w.max_group_size.to_string()).alignment(Alignment::Center),
-                    Text::from(w.n_remaining_after.unwrap().to_string())
+                    Text::from(w.n_remaining_after.unwrap_or(0).to_string())
                         .alignment(Alignment::Center),
                 ])
                 .style(style)
             })
             .collect();
         let widths = [
-            Constraint::Length(5),
+            Constraint::Length(word_width),
             Constraint::Length(8),
             Constraint::Length(9),
+            Constraint::Length(4),
             Constraint::Length(6),
             Constraint::Length(9),
             Constraint::Length(9),
         ];
+        let n_rows = rows.len();
+        // What was recommended for the selected guess, and by which strategy
+        let recommendation = match self.recommendation(self.selected_word) {
+            Some((word, name)) => Title::from(
+                format!(" Suggested {} by {} ", word, name)
+                    .dark_gray()
+                    .not_bold(),
+            ),
+            None => Title::from(""),
+        };
         let table = Table::new(rows, widths)
             // ...and they can be separated by a fixed spacing.
             .column_spacing(1)
@@ -253,6 +602,7 @@ impl App {
                     Cell::from("Guess").underlined(),
                     Cell::from("Exp. Bits").underlined(),
                     Cell::from("Act. Bits").underlined(),
+                    Cell::from("Luck").underlined(),
                     Cell::from("groups").underlined(),
                     Cell::from("max group").underlined(),
                     Cell::from("remaining").underlined(),
@@ -264,14 +614,346 @@ impl App {
                     .title(
                         Title::from("Evaluation of previous guesses").alignment(Alignment::Center),
                     )
+                    .title(
+                        recommendation
+                            .position(Position::Bottom)
+                            .alignment(Alignment::Center),
+                    )
                     .bold()
                     .padding(Padding::new(0, 0, 1, 0)),
             );
-        ratatui::widgets::Widget::render(table, area[0], buf);
+        // Scrolls to the selected guess in long games
+        let mut state =
+            TableState::new().with_selected(Some(self.selected_word.min(n_rows.saturating_sub(1))));
+        StatefulWidget::render(table, area[0], buf, &mut state);
+    }
+
+    /// Render the state of the game and the stats of the session
+    fn render_game(&self, area: Rect, buf: &mut Buffer) {
+        let Some(game) = &self.game else {
+            return;
+        };
+        let state = match (&game.message, game.state) {
+            (Some(message), _) => Line::from(message.clone().red()),
+            (None, GameState::Playing) => Line::from(format!(
+                "{}{}{}: guess {} of {}",
+                game.name,
+                if game.hard { " (hard mode)" } else { "" },
+                game.session
+                    .map_or(String::new(), |session| format!(" ({})", session)),
+                self.committed_guesses().len() + 1,
+                MAX_GUESSES
+            )),
+            (None, GameState::Won(n)) => Line::from(vec![
+                format!("Solved in {}! ", n).bold().green(),
+                "New game with <Ctrl+R>".into(),
+            ]),
+            (None, GameState::Lost) => match game.oracle.answer() {
+                Some(answer) => Line::from(vec![
+                    "The word was ".into(),
+                    format!("{}", answer).bold().magenta(),
+                    ". New game with <Ctrl+R>".into(),
+                ]),
+                None => Line::from("Lost. New game with <Ctrl+R>"),
+            },
+        };
+        let stats = game.stats;
+        let win_rate = 100 * stats.won / stats.played.max(1);
+        let stats = Line::from(format!(
+            "Played {}  Won {}%  Streak {}  Best streak {}",
+            stats.played, win_rate, stats.streak, stats.max_streak
+        ));
+        // The results of the pack replace the stats of the session
+        let progress = match &self.pack {
+            Some(pack) => pack_summary(pack),
+            None => stats,
+        };
+        // A hint replaces them while the game is on
+        let progress = match (game.hint, game.state) {
+            (Some(hint), GameState::Playing) => Line::from(vec![
+                format!("Hint {}: ", game.n_hints).bold(),
+                hint.to_string().into(),
+            ]),
+            _ => progress,
+        };
+        Paragraph::new(vec![state, progress])
+            .alignment(Alignment::Center)
+            .render(area, buf);
+    }
+
+    fn render_explanation(&self, area: Rect, buf: &mut Buffer) {
+        if let Some((word, elimination)) = &self.explanation {
+            let line = match elimination {
+                Some(elimination) => Line::from(vec![
+                    format!("{}", word).bold().magenta(),
+                    format!(" was {}", elimination).into(),
+                ]),
+                None => Line::from(vec![
+                    format!("{}", word).bold().magenta(),
+                    " is still possible".into(),
+                ]),
+            };
+            Paragraph::new(line)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true })
+                .render(area, buf);
+        }
     }
 
     fn render_suggestions(&self, area: Rect, buf: &mut Buffer) {
-        let two_level_style = if self.two_level { 7 } else { 0 };
+        if self.show_ranking_diff && self.compare.is_some() {
+            self.render_compare(area, buf);
+        } else if self.show_ranking_diff {
+            self.render_ranking_diff(area, buf);
+        } else {
+            self.render_suggestion_table(area, buf);
+        }
+        self.render_filter(area, buf);
+        self.render_known(area, buf);
+        self.render_export(area, buf);
+        self.render_working_popup(area, buf);
+    }
+
+    /// Render the suggestion filter, or its editor, above the suggestions
+    fn render_filter(&self, area: Rect, buf: &mut Buffer) {
+        let line = match &self.filter_input {
+            Some(input) => {
+                let hint = match &input.error {
+                    Some(e) => format!("  {}", e).red(),
+                    None => "  +test -avoid ! no greens, <Enter> apply, <Esc> cancel".dark_gray(),
+                };
+                Line::from(vec![
+                    "Filter: ".bold(),
+                    input.text.clone().into(),
+                    "_".slow_blink(),
+                    hint,
+                ])
+            }
+            None if !self.filter.is_empty() => {
+                Line::from(vec!["Filter: ".bold(), self.filter.to_string().yellow()])
+            }
+            None => return,
+        };
+        let bar = Rect::new(area.x, area.y, area.width, 1);
+        Paragraph::new(line).render(bar, buf);
+    }
+
+    /// Render the letters known without a guess on the right of the filter,
+    /// or their editor over it
+    fn render_known(&self, area: Rect, buf: &mut Buffer) {
+        let bar = Rect::new(area.x, area.y, area.width, 1);
+        let Some(input) = &self.known_input else {
+            if !self.known.is_empty() {
+                let line = Line::from(vec!["Known: ".bold(), self.known.to_string().yellow()]);
+                Paragraph::new(line.right_aligned()).render(bar, buf);
+            }
+            return;
+        };
+        let hint = match &input.error {
+            Some(e) => format!("  {}", e).red(),
+            None => "  +in -out r@3 at r!3 not at, <Enter> apply, <Esc> cancel".dark_gray(),
+        };
+        let line = Line::from(vec![
+            "Known: ".bold(),
+            input.text.clone().into(),
+            "_".slow_blink(),
+            hint,
+        ]);
+        Clear.render(bar, buf);
+        Paragraph::new(line).render(bar, buf);
+    }
+
+    /// Render the file name prompt of an export over the filter
+    fn render_export(&self, area: Rect, buf: &mut Buffer) {
+        let Some(input) = &self.export_input else {
+            return;
+        };
+        let hint = match &input.error {
+            Some(e) => format!("  {}", e).red(),
+            None => "  .csv or .json, <Enter> write, <Esc> cancel".dark_gray(),
+        };
+        let line = Line::from(vec![
+            "Export: ".bold(),
+            input.text.clone().into(),
+            "_".slow_blink(),
+            hint,
+        ]);
+        let bar = Rect::new(area.x, area.y, area.width, 1);
+        Clear.render(bar, buf);
+        Paragraph::new(line).render(bar, buf);
+    }
+
+    /// Compare the ranking of the suggestions by expected bits with the
+    /// ranking by two level bits
+    fn render_ranking_diff(&self, area: Rect, buf: &mut Buffer) {
+        let penalty = suggestion_penalty(self.turns(self.visible_guesses().len()));
+        // The rank of every suggestion by a score, best first. The
+        // suggestions may be in the order of either ranking.
+        let ranks = |score: &dyn Fn(&GuessEvaluation) -> f32| {
+            let mut order: Vec<usize> = (0..self.suggestions.len()).collect();
+            order.sort_by(|&a, &b| {
+                score(&self.suggestions[b]).total_cmp(&score(&self.suggestions[a]))
+            });
+            let mut ranks = vec![0; order.len()];
+            for (rank, &i) in order.iter().enumerate() {
+                ranks[i] = rank;
+            }
+            ranks
+        };
+        let ranking = self.solver.ranking();
+        let one_level_rank =
+            ranks(&|w| ranking.rank(w.expected_bits, w.prior, penalty, w.is_possible));
+        let two_level_rank = ranks(&|w| self.solver.two_level_score(w, penalty));
+
+        let rows: Vec<_> = self
+            .suggestions
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                let style = if w.is_possible {
+                    Style::default().white()
+                } else {
+                    Style::default()
+                };
+                let change = one_level_rank[i] as i64 - two_level_rank[i] as i64;
+                let change = match change {
+                    0 => Text::from("=".to_string()),
+                    x if x > 0 => Text::from(format!("▲ {}", x)).green(),
+                    x => Text::from(format!("▼ {}", -x)).red(),
+                };
+                Row::new(vec![
+                    Text::from(format!("{}", w.word))
+                        .alignment(Alignment::Left)
+                        .style(style),
+                    Text::from(format!("{:.2}", w.expected_bits))
+                        .alignment(Alignment::Center)
+                        .style(style),
+                    Text::from(format!("{:.2}", w.two_level_bits.unwrap_or(0.)))
+                        .alignment(Alignment::Center)
+                        .style(style),
+                    Text::from((one_level_rank[i] + 1).to_string())
+                        .alignment(Alignment::Center)
+                        .style(style),
+                    Text::from((two_level_rank[i] + 1).to_string())
+                        .alignment(Alignment::Center)
+                        .style(style),
+                    change.alignment(Alignment::Center),
+                ])
+            })
+            .collect();
+        let widths = [
+            Constraint::Length(10),
+            Constraint::Length(9),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(6),
+        ];
+        let table = Table::new(rows, widths)
+            .column_spacing(1)
+            .style(Style::new())
+            .header(Row::new(vec![
+                Cell::from("Suggestion").underlined(),
+                Cell::from("Exp. Bits").underlined(),
+                Cell::from("2-l Bits").underlined(),
+                Cell::from("1-l rank").underlined(),
+                Cell::from("2-l rank").underlined(),
+                Cell::from("change").underlined(),
+            ]))
+            .block(Block::new().padding(Padding::new(0, 0, 1, 0)));
+        ratatui::widgets::Widget::render(table, area, buf);
+    }
+
+    /// Show the suggestions of the current strategy (A) next to the ones of
+    /// the compared snapshot (B)
+    fn render_compare(&self, area: Rect, buf: &mut Buffer) {
+        let Some(compare) = &self.compare else {
+            return;
+        };
+        let guesses = self.visible_guesses();
+        let other = match &self.compare_suggestions {
+            Some((g, suggestions)) if *g == guesses => suggestions.as_slice(),
+            _ => &[],
+        };
+        let style = |w: &GuessEvaluation| match w.is_possible {
+            true => Style::default().white(),
+            false => Style::default(),
+        };
+        let rows: Vec<_> = (0..self.suggestions.len().max(other.len()))
+            .map(|i| {
+                let mut cells = vec![Text::from((i + 1).to_string()).alignment(Alignment::Center)];
+                for (suggestions, mine) in [(self.suggestions.as_slice(), true), (other, false)] {
+                    let Some(w) = suggestions.get(i) else {
+                        cells.extend([Text::default(), Text::default()]);
+                        continue;
+                    };
+                    let mut word = Text::from(format!("{}", w.word)).style(style(w));
+                    // The guesses of B that A ranks elsewhere
+                    if !mine && self.suggestions.get(i).map(|a| a.word) != Some(w.word) {
+                        word = word.yellow();
+                    }
+                    cells.push(word);
+                    cells.push(
+                        Text::from(format!("{:.2}", w.expected_bits))
+                            .alignment(Alignment::Center)
+                            .style(style(w)),
+                    );
+                }
+                let rank_in_a = other.get(i).map(|b| {
+                    match self.suggestions.iter().position(|a| a.word == b.word) {
+                        Some(rank) => (rank + 1).to_string(),
+                        None => "-".to_string(),
+                    }
+                });
+                cells.push(Text::from(rank_in_a.unwrap_or_default()).alignment(Alignment::Center));
+                Row::new(cells)
+            })
+            .collect();
+        let widths = [
+            Constraint::Length(4),
+            Constraint::Length(10),
+            Constraint::Length(9),
+            Constraint::Length(10),
+            Constraint::Length(9),
+            Constraint::Length(6),
+        ];
+        let name: String = compare.name.chars().take(10).collect();
+        let table = Table::new(rows, widths)
+            .column_spacing(1)
+            .style(Style::new())
+            .header(Row::new(vec![
+                Cell::from("Rank").underlined(),
+                Cell::from("Current").underlined(),
+                Cell::from("Exp. Bits").underlined(),
+                Cell::from(name).underlined(),
+                Cell::from("Exp. Bits").underlined(),
+                Cell::from("A rank").underlined(),
+            ]))
+            .block(Block::new().padding(Padding::new(0, 0, 1, 0)));
+        ratatui::widgets::Widget::render(table, area, buf);
+        let note = match (other.is_empty(), compare.same_words) {
+            (true, _) => format!("Ranking the suggestions of {}...", compare.name).dark_gray(),
+            (false, false) => format!("{} was saved with another word list", compare.name).yellow(),
+            (false, true) => return,
+        };
+        let bar = Rect::new(area.x, area.y, area.width, 1);
+        Paragraph::new(Line::from(note)).render(bar, buf);
+    }
+
+    fn render_suggestion_table(&self, area: Rect, buf: &mut Buffer) {
+        // The deeper lookahead replaces the two level bits
+        let two_level_style = if self.two_level || self.search.is_some() {
+            7
+        } else {
+            0
+        };
+        let deep_header = match self.search {
+            Some(search) => format!("{}-l Bits", search.depth),
+            None => "2-l Bits".to_string(),
+        };
+        // The exact cost replaces the estimated turns in the endgame
+        let exact = self.suggestions.iter().any(|w| w.exact.is_some());
+        let turns_header = if exact { "Exact" } else { "Exp. Turns" };
         let rows: Vec<_> = self
             .suggestions
             .iter()
@@ -282,7 +964,10 @@ impl App {
                     Style::default()
                 };
 
-                let two_level_bits = w.two_level_bits.unwrap_or(0.);
+                let two_level_bits = match self.search {
+                    Some(_) => w.lookahead_bits.unwrap_or(0.),
+                    None => w.two_level_bits.unwrap_or(0.),
+                };
 
                 Row::new(vec![
                     Text::from(format!("{}", w.word))
@@ -291,6 +976,12 @@ impl App {
                     Text::from(format!("{:.2}", w.expected_bits))
                         .alignment(Alignment::Center)
                         .style(style),
+                    Text::from(match w.exact {
+                        Some(cost) => format!("{:.2} ≤{}", cost.expected, cost.worst),
+                        None => format!("{:.2}", w.expected_turns),
+                    })
+                    .alignment(Alignment::Center)
+                    .style(style),
                     Text::from(format!("{:.2?}", two_level_bits))
                         .alignment(Alignment::Center)
                         .style(style),
@@ -309,6 +1000,7 @@ impl App {
         let widths = [
             Constraint::Length(10),
             Constraint::Length(8),
+            Constraint::Length(10),
             Constraint::Length(two_level_style),
             Constraint::Length(8),
             Constraint::Length(9),
@@ -321,58 +1013,194 @@ impl App {
             .style(Style::new())
             // It has an optional header, which is simply a Row always visible at the top.
             .header(Row::new(vec![
-                Cell::from("Suggestion").underlined(),
+                Cell::from(if self.burn {
+                    "Burn guess"
+                } else {
+                    "Suggestion"
+                })
+                .underlined(),
                 Cell::from("Exp. Bits").underlined(),
-                Cell::from("2-l Bits").underlined(),
+                Cell::from(turns_header).underlined(),
+                Cell::from(deep_header).underlined(),
                 Cell::from("n groups").underlined(),
                 Cell::from("max group").underlined(),
                 Cell::from("prior").underlined(),
             ]))
-            .block(Block::new().padding(Padding::new(0, 0, 1, 0)));
-        ratatui::widgets::Widget::render(table, area, buf);
+            .block(Block::new().padding(Padding::new(0, 0, 1, 0)))
+            .highlight_style(Style::new().reversed());
+        // The suggestion that was typed into the next row
+        let mut state = TableState::new().with_selected(self.selected_suggestion);
+        StatefulWidget::render(table, area, buf, &mut state);
+    }
 
+    /// Render the version, the word list and the latest release over
+    /// everything else
+    fn render_about(&self, area: Rect, buf: &mut Buffer) {
+        if !self.show_about {
+            return;
+        }
+        let mut lines = vec![
+            Line::from(format!("wordlebot {}", about::VERSION).bold()),
+            Line::from(""),
+            Line::from(vec![
+                "Word list: ".bold(),
+                about::data_version(&self.solver).into(),
+            ]),
+            Line::from(""),
+        ];
+        match &self.update_check {
+            UpdateCheck::Disabled => lines.push(Line::from(
+                "Start with --check-updates to look for a newer release".dark_gray(),
+            )),
+            UpdateCheck::Pending | UpdateCheck::Checking => {
+                lines.push(Line::from("Looking for a newer release...".dark_gray()))
+            }
+            UpdateCheck::Done(Ok(release)) if release.is_newer() => {
+                lines.push(Line::from(
+                    format!("Version {} is available:", release.version)
+                        .green()
+                        .bold(),
+                ));
+                lines.extend(release.notes.iter().map(|line| Line::from(line.clone())));
+            }
+            UpdateCheck::Done(Ok(_)) => {
+                lines.push(Line::from("This is the latest release".green()))
+            }
+            UpdateCheck::Done(Err(e)) => lines.push(Line::from(e.clone().red())),
+        }
+        let block = Block::default()
+            .title(Title::from(" About ".bold()).alignment(Alignment::Center))
+            .title(
+                Title::from(" <F1>/<Esc> close ".dark_gray())
+                    .position(Position::Bottom)
+                    .alignment(Alignment::Center),
+            )
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1));
+        // Long lines wrap within the borders and the padding
+        let width = 80;
+        let height: usize = lines
+            .iter()
+            .map(|line| line.width().max(1).div_ceil(width - 4))
+            .sum();
+        let popup_area = centered_rect(width as u16, height as u16 + 2, area);
+        Clear.render(popup_area, buf);
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(block)
+            .render(popup_area, buf);
+    }
+
+    fn render_working_popup(&self, area: Rect, buf: &mut Buffer) {
         // Check if active task
         let metrics = Handle::current().metrics();
         let n = metrics.active_tasks_count();
-        if n > 1 {
+        // The share of the guesses that take long which are evaluated, if
+        // the ranking reports it
+        let percent = match &self.progress {
+            Some((guesses, done, total)) if *guesses == self.visible_guesses() && *total > 0 => {
+                Some((done * 100 / total).min(100))
+            }
+            _ => None,
+        };
+        if n > 1 && self.visible_guesses().is_empty() && !self.suggestions.is_empty() {
+            // The opening table stays readable while the solver works
+            let bar = Rect::new(area.x, area.bottom().saturating_sub(1), area.width, 1);
+            let text = match percent {
+                Some(percent) => format!(
+                    "Working on the best solutions for you: {}%  <Ctrl+X> cancel",
+                    percent
+                ),
+                None => "Working on the best solutions for you  <Ctrl+X> cancel".to_string(),
+            };
+            Paragraph::new(text)
+                .alignment(Alignment::Center)
+                .white()
+                .on_red()
+                .render(bar, buf);
+        } else if n > 1 {
             let popup_block = Block::default()
                 .borders(Borders::NONE)
                 .style(Style::default().bg(Color::Red))
                 .padding(Padding::uniform(1));
-            let popup_area = centered_rect(30, 4, area);
-
-            Clear.render(popup_area, buf);
-            Paragraph::new(vec![
+            let mut lines = vec![
                 Line::from("Working on the best"),
                 Line::from("solutions for you"),
-            ])
-            .alignment(Alignment::Center)
-            .white()
-            .block(popup_block)
-            .render(popup_area, buf);
+            ];
+            if let Some(percent) = percent {
+                let filled = percent / 5;
+                lines.push(Line::from(format!(
+                    "{}{} {:>3}%",
+                    "█".repeat(filled),
+                    "░".repeat(20 - filled),
+                    percent
+                )));
+            }
+            lines.push(Line::from("<Ctrl+X> cancel"));
+            let popup_area = centered_rect(30, lines.len() as u16 + 2, area);
+
+            Clear.render(popup_area, buf);
+            Paragraph::new(lines)
+                .alignment(Alignment::Center)
+                .white()
+                .block(popup_block)
+                .render(popup_area, buf);
         }
     }
 }
 
+/// The results of the puzzles of the pack so far, e.g.
+/// `Daily 3/6 Daily hard X/6 Random -`
+fn pack_summary(pack: &Pack) -> Line<'static> {
+    let mut spans: Vec<Span> = vec![];
+    if pack.current().is_none() {
+        let solved = pack
+            .results
+            .iter()
+            .filter(|state| matches!(state, GameState::Won(_)))
+            .count();
+        spans.push(format!("Solved {}/{} |", solved, pack.puzzles.len()).bold());
+    }
+    for (i, puzzle) in pack.puzzles.iter().enumerate() {
+        let result = match pack.results.get(i) {
+            Some(GameState::Won(n)) => format!("{}/{}", n, MAX_GUESSES).green(),
+            Some(_) => format!("X/{}", MAX_GUESSES).red(),
+            None => "-".into(),
+        };
+        spans.push(format!(" {} ", puzzle.name).into());
+        spans.push(result);
+    }
+    Line::from(spans)
+}
+
 trait RenderGuess {
-    fn render(&self, area: Rect, buf: &mut Buffer, selected_letter: Option<usize>, valid: bool);
+    fn render(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        selected_letter: Option<usize>,
+        valid: bool,
+        theme: &Theme,
+    );
 }
 
 impl RenderGuess for Guess {
-    fn render(&self, area: Rect, buf: &mut Buffer, selected_letter: Option<usize>, valid: bool) {
+    fn render(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        selected_letter: Option<usize>,
+        valid: bool,
+        theme: &Theme,
+    ) {
         let row_layout = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints(vec![Constraint::Length(7); 5])
+            .constraints(vec![Constraint::Length(7); self.word.n_letters()])
             .flex(layout::Flex::Center)
             .split(area);
-        let decoded_status = decode_status(self.status);
-        for (i, (letter, status)) in zip(self.word.chars, decoded_status).enumerate() {
+        for (i, (letter, status)) in zip(self.word.chars(), self.get_status()).enumerate() {
             let border_style = if valid {
-                match status {
-                    LetterStatus::Absent => Style::default().white(),
-                    LetterStatus::Misplaced => Style::default().light_yellow(),
-                    LetterStatus::Correct => Style::default().light_green(),
-                }
+                Style::default().fg(theme.color(status))
             } else {
                 Style::default().dark_gray()
             };
@@ -380,8 +1208,8 @@ impl RenderGuess for Guess {
             let text_style = if valid {
                 match status {
                     LetterStatus::Absent => Style::default().bg(Color::Black),
-                    LetterStatus::Misplaced => Style::default().fg(Color::LightYellow),
-                    LetterStatus::Correct => Style::default().fg(Color::LightGreen).bold(),
+                    LetterStatus::Misplaced => Style::default().fg(theme.color(status)),
+                    LetterStatus::Correct => Style::default().fg(theme.color(status)).bold(),
                 }
             } else {
                 Style::default().dark_gray()
@@ -0,0 +1,94 @@
+use ratatui::widgets::*;
+use std::iter::zip;
+
+use super::*;
+
+// The width of the bar of the largest group
+const BAR_WIDTH: usize = 12;
+
+impl App {
+    /// Render why the selected suggestion, or the best one, is suggested:
+    /// how likely it is the answer, its worst case and its largest groups
+    /// with some of their words
+    pub fn render_why(&self, area: Rect, buf: &mut Buffer) {
+        let rank = self.selected_suggestion.unwrap_or(0);
+        let Some(suggestion) = self.suggestions.get(rank) else {
+            let lines = vec![
+                Line::from(vec!["Why this guess?".bold(), "  <F9> close".dark_gray()]),
+                Line::from("No suggestion to explain".dark_gray()),
+            ];
+            Paragraph::new(lines).render(area, buf);
+            return;
+        };
+        let word = suggestion.word;
+
+        let mut stats = vec![format!("{:.2} bits", suggestion.expected_bits).into()];
+        if let Some(bits) = suggestion.two_level_bits {
+            stats.push(format!(", {:.2} with the next guess", bits).into());
+        }
+        stats.push(format!(", {} groups", suggestion.groups).into());
+        stats.push(format!(", {:.2} turns expected", suggestion.expected_turns).into());
+
+        let answer = match suggestion.is_possible {
+            true => format!("{:.1}% the answer", suggestion.answer_probability() * 100.0).green(),
+            false => "not a possible answer".dark_gray(),
+        };
+        let worst = suggestion
+            .groups_with_probabilities()
+            .find(|&(_, size, _)| size == suggestion.max_group_size)
+            .map_or(0.0, |(_, _, probability)| probability);
+
+        let mut lines = vec![
+            Line::from(vec![
+                "Why ".bold(),
+                word.to_string().bold().magenta(),
+                "?".bold(),
+                "  <F9> close".dark_gray(),
+            ]),
+            Line::from(stats),
+            Line::from(vec!["Chance: ".bold(), answer]),
+            Line::from(vec![
+                "Worst case: ".bold(),
+                format!(
+                    "{} words left, {:.1}% likely",
+                    suggestion.max_group_size,
+                    worst * 100.0
+                )
+                .into(),
+            ]),
+        ];
+
+        // The largest groups, as many as fit
+        let n_rows = (area.height as usize).saturating_sub(lines.len());
+        let n_letters = word.n_letters();
+        let sample_width = (area.width as usize).saturating_sub(n_letters + BAR_WIDTH + 15);
+        for (pattern, size, probability) in suggestion.groups_with_probabilities().take(n_rows) {
+            let mut spans: Vec<Span> = zip(word.chars(), decode_status(pattern, n_letters))
+                .map(|(letter, status)| {
+                    let letter = letter.unwrap_or(' ').to_uppercase().to_string();
+                    Span::styled(letter, self.theme.tile(status))
+                })
+                .collect();
+            spans.push(format!(" {:>4} {:>5.1}% ", size, probability * 100.0).into());
+            let bar = (size * BAR_WIDTH).div_ceil(suggestion.max_group_size.max(1));
+            spans.push(format!("{:<1$} ", "█".repeat(bar), BAR_WIDTH).magenta());
+
+            // As many words as fit, and how many more there are
+            let words = self
+                .solver
+                .get_group_words(&word, &self.remaining_words, pattern);
+            let mut sample = String::new();
+            for (i, w) in words.iter().enumerate() {
+                let more = format!(" +{}", words.len() - i);
+                if sample.len() + n_letters + 1 + more.len() > sample_width && i + 1 < words.len() {
+                    sample += &more;
+                    break;
+                }
+                sample += &format!(" {}", w);
+            }
+            spans.push(sample.trim_start().to_string().dark_gray());
+            lines.push(Line::from(spans));
+        }
+        Paragraph::new(lines).render(area, buf);
+    }
+}
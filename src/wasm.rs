@@ -0,0 +1,122 @@
+//! JavaScript bindings of the solver for WebAssembly.
+//!
+//! Build them with `wasm-pack build --no-default-features --features wasm`:
+//!
+//! ```js
+//! import init, { SolverHandle } from "./pkg/wordlebot.js";
+//! await init();
+//! const solver = new SolverHandle();
+//! const suggestions = JSON.parse(solver.suggest('["slate:bybbg"]'));
+//! ```
+//!
+//! Computing the pattern matrix of the embedded word list takes a while
+//! without threads. [`SolverHandle::from_mappings`] loads it from a
+//! precomputed asset instead, e.g. the cache file of the command line.
+use anyhow::{bail, Context, Result};
+use wasm_bindgen::prelude::*;
+
+use crate::json::{self, Value};
+use crate::solver::Solver;
+use crate::wordle::*;
+
+/// Number of suggestions of [`SolverHandle::suggest`]
+const N_SUGGESTIONS: usize = 10;
+
+/// A solver for the embedded word list
+#[wasm_bindgen]
+pub struct SolverHandle {
+    solver: Solver,
+}
+
+#[wasm_bindgen]
+impl SolverHandle {
+    /// Create the solver and compute the pattern matrix
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Result<SolverHandle, JsError> {
+        let solver = Solver::new().map_err(js_error)?;
+        Ok(SolverHandle { solver })
+    }
+
+    /// Create the solver with the pattern matrix from an asset, in the
+    /// format of the cache file
+    #[wasm_bindgen(js_name = fromMappings)]
+    pub fn from_mappings(asset: &[u8]) -> Result<SolverHandle, JsError> {
+        let solver = Solver::from_mappings(asset).map_err(js_error)?;
+        Ok(SolverHandle { solver })
+    }
+
+    /// The best guesses after the guesses, which are a JSON array of
+    /// strings like `["slate:bybbg"]` (b = absent, y = misplaced,
+    /// g = correct). Returns a JSON array of objects with the `word`, its
+    /// expected `bits` and whether it is `possible`.
+    pub fn suggest(&self, guesses_json: &str) -> Result<String, JsError> {
+        suggest(&self.solver, guesses_json).map_err(js_error)
+    }
+}
+
+fn js_error(error: anyhow::Error) -> JsError {
+    JsError::new(&format!("{:#}", error))
+}
+
+fn suggest(solver: &Solver, guesses_json: &str) -> Result<String> {
+    let guesses = parse_guesses(guesses_json)?;
+    let mut remaining = solver.get_remaining_words_idx(&guesses)?;
+    remaining.sort_unstable();
+    let penalty = if guesses.is_empty() { 0.0 } else { 0.1 };
+    let words = solver.guess(N_SUGGESTIONS, &remaining, penalty)?;
+    let suggestions: Vec<String> = words
+        .iter()
+        .map(|word| {
+            let evaluation = solver.evaluate_guess(word, &remaining, None, false)?;
+            let possible = solver
+                .get_id_for_word(word)
+                .is_some_and(|id| remaining.binary_search(&id).is_ok());
+            Ok(format!(
+                r#"{{"word":"{}","bits":{},"possible":{}}}"#,
+                word.to_string().to_lowercase(),
+                evaluation.expected_bits,
+                possible
+            ))
+        })
+        .collect::<Result<_>>()?;
+    Ok(format!("[{}]", suggestions.join(",")))
+}
+
+/// The guesses of a JSON array of strings like `"slate:bybbg"`
+fn parse_guesses(text: &str) -> Result<Vec<Guess>> {
+    let Ok(Value::Array(items)) = json::parse(text) else {
+        bail!("Expected a JSON array of guesses, got {}", text)
+    };
+    items
+        .into_iter()
+        .map(|item| {
+            let Value::Str(guess) = item else {
+                bail!("Expected a string, got {}", item.to_json())
+            };
+            guess
+                .parse()
+                .with_context(|| format!("Invalid guess {}", guess))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest() {
+        let words: Vec<Word> = ["slate", "crane", "goose", "water"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let solver = Solver::from_words(&words, &[1.0, 1.0, 1.0, 0.0]).unwrap();
+        let json = suggest(&solver, r#"[ "crane:bbgbg" ]"#).unwrap();
+        assert!(json.starts_with(r#"[{"word":"slate","bits":0,"possible":true}"#));
+
+        assert_eq!(parse_guesses("[]").unwrap(), vec![]);
+        assert!(parse_guesses("crane:bbybg").is_err());
+        assert!(parse_guesses("[crane:bbybg]").is_err());
+        assert!(suggest(&solver, r#"["quirk:bbbbb"]"#).is_err());
+    }
+}
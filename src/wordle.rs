@@ -1,6 +1,21 @@
+use anyhow::{bail, Context, Error};
+use std::collections::HashMap;
 use std::fmt;
+use std::iter::zip;
+use std::str::FromStr;
 
-const NLETTER: usize = 5;
+/// The number of letters of classic Wordle
+pub const NLETTER: usize = 5;
+/// The shortest supported words
+pub const MIN_LETTERS: usize = 4;
+/// The longest supported words
+pub const MAX_LETTERS: usize = 8;
+/// The number of guesses in a game by the rules of Wordle
+pub const MAX_GUESSES: usize = 6;
+
+/// A pattern encoded in base 3, with one digit per letter. Patterns of up to
+/// eight letters fit into 16 bits.
+pub type Pattern = u16;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum LetterStatus {
@@ -9,15 +24,67 @@ pub enum LetterStatus {
     Correct = 2,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// How a host colors the letters of a guess that the answer has fewer
+/// copies of. Wordle clones differ here, so the patterns must be computed
+/// like the clone does for the solver to filter the words correctly.
+///
+/// # Example
+///
+/// ```
+/// use wordlebot::wordle::*;
+///
+/// let rule: ScoringRule = "greedy-yellow".parse().unwrap();
+/// assert_eq!(rule, ScoringRule::GreedyYellow);
+/// assert_eq!(rule.to_string(), "greedy-yellow");
+/// assert_eq!(ScoringRule::default(), ScoringRule::NytStandard);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ScoringRule {
+    /// Like the New York Times: a letter of the answer colors at most one
+    /// letter of the guess, green first and then yellow from the left
+    #[default]
+    NytStandard,
+    /// Every letter of the guess that is in the answer, but not at its
+    /// position, is yellow, even extra copies
+    GreedyYellow,
+}
+
+impl FromStr for ScoringRule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "nyt" | "nyt-standard" => Ok(ScoringRule::NytStandard),
+            "greedy-yellow" => Ok(ScoringRule::GreedyYellow),
+            _ => bail!(
+                "Unknown scoring rule '{}', expected nyt or greedy-yellow",
+                s
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ScoringRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScoringRule::NytStandard => write!(f, "nyt"),
+            ScoringRule::GreedyYellow => write!(f, "greedy-yellow"),
+        }
+    }
+}
+
+/// A word with between `MIN_LETTERS` and `MAX_LETTERS` letters. Letters
+/// that are not entered yet are `None`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct Word {
-    pub chars: [Option<char>; NLETTER],
+    chars: [Option<char>; MAX_LETTERS],
+    n_letters: usize,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Guess {
     pub word: Word,
-    pub status: u8,
+    pub status: Pattern,
 }
 
 impl Default for Word {
@@ -27,9 +94,40 @@ impl Default for Word {
 }
 
 impl Word {
-    /// Create a new word with empty letters
+    /// Create a new five letter word with empty letters
     pub fn new() -> Word {
-        Word { chars: [None; 5] }
+        Word::with_length(NLETTER)
+    }
+
+    /// Create a new word with `n_letters` empty letters
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wordlebot::wordle::Word;
+    /// let word = Word::with_length(6);
+    /// assert_eq!(word.chars(), &[None; 6]);
+    /// ```
+    pub fn with_length(n_letters: usize) -> Word {
+        assert!(
+            n_letters <= MAX_LETTERS,
+            "Words can have at most {} letters",
+            MAX_LETTERS
+        );
+        Word {
+            chars: [None; MAX_LETTERS],
+            n_letters,
+        }
+    }
+
+    /// The letters of the word
+    pub fn chars(&self) -> &[Option<char>] {
+        &self.chars[..self.n_letters]
+    }
+
+    /// The number of letters of the word
+    pub fn n_letters(&self) -> usize {
+        self.n_letters
     }
 
     /// Set the letter at a position of the word
@@ -40,10 +138,10 @@ impl Word {
     /// use wordlebot::wordle::Word;
     /// let mut word = Word::new();
     /// word.set_letter(Some('e'), 0);
-    /// assert_eq!(word.chars[0], Some('e'));
+    /// assert_eq!(word.chars()[0], Some('e'));
     /// ```
     pub fn set_letter(&mut self, char: Option<char>, position: usize) {
-        self.chars[position] = char
+        self.chars[..self.n_letters][position] = char
     }
 
     /// Compares the word to a guess, and returns the status code for the
@@ -60,37 +158,91 @@ impl Word {
     /// assert_eq!(solution.compare(&guess), expected);
     ///
     /// ```
-    pub fn compare(&self, guess: &Word) -> [LetterStatus; NLETTER] {
-        let mut result = [LetterStatus::Absent; 5];
-        let mut remaining_positions: Vec<usize> = vec![];
+    pub fn compare(&self, guess: &Word) -> Vec<LetterStatus> {
+        decode_status(self.pattern(guess), guess.n_letters)
+    }
+
+    /// Compares the word to a guess like [`Word::compare`], but returns the
+    /// encoded pattern. This does not allocate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wordlebot::wordle::*;
+    /// let solution = create_word_from_string("tarse");
+    /// let guess = create_word_from_string("slate");
+    /// assert_eq!(solution.pattern(&guess), 199);
+    /// ```
+    pub fn pattern(&self, guess: &Word) -> Pattern {
+        let n = guess.n_letters;
+        let mut result = [LetterStatus::Absent; MAX_LETTERS];
 
         // Find all correct letters
-        guess
-            .chars
-            .iter()
-            .enumerate()
-            .for_each(|(i, guessed_char)| {
-                if guessed_char == &self.chars[i] {
-                    result[i] = LetterStatus::Correct;
-                } else {
-                    remaining_positions.push(i);
-                }
-            });
+        for (i, item) in result[..n].iter_mut().enumerate() {
+            if guess.chars[i] == self.chars[i] {
+                *item = LetterStatus::Correct;
+            }
+        }
 
         // Loop though remeining
         let mut word = self.chars;
-        for &pos in &remaining_positions {
+        for pos in 0..n {
+            if result[pos] == LetterStatus::Correct {
+                continue;
+            }
             let guess_letter = guess.chars[pos];
-            if let Some(&word_pos) = remaining_positions
-                .iter()
-                .find(|&word_pos| guess_letter == word[*word_pos])
-            {
+            if let Some(word_pos) = (0..n).find(|&word_pos| {
+                result[word_pos] != LetterStatus::Correct && guess_letter == word[word_pos]
+            }) {
                 result[pos] = LetterStatus::Misplaced;
                 word[word_pos] = None;
             }
         }
 
-        result
+        encode_status(&result[..n])
+    }
+
+    /// Compares the word to a guess like [`Word::compare`], with the
+    /// letters colored by a scoring rule
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wordlebot::wordle::*;
+    /// use wordlebot::wordle::LetterStatus::*;
+    /// let solution = create_word_from_string("crane");
+    /// let guess = create_word_from_string("eerie");
+    /// assert_eq!(
+    ///     solution.compare_by(&guess, ScoringRule::NytStandard),
+    ///     [Absent, Absent, Misplaced, Absent, Correct]
+    /// );
+    /// assert_eq!(
+    ///     solution.compare_by(&guess, ScoringRule::GreedyYellow),
+    ///     [Misplaced, Misplaced, Misplaced, Absent, Correct]
+    /// );
+    /// ```
+    pub fn compare_by(&self, guess: &Word, scoring: ScoringRule) -> Vec<LetterStatus> {
+        decode_status(self.pattern_by(guess, scoring), guess.n_letters)
+    }
+
+    /// Compares the word to a guess like [`Word::pattern`], with the
+    /// letters colored by a scoring rule
+    pub fn pattern_by(&self, guess: &Word, scoring: ScoringRule) -> Pattern {
+        match scoring {
+            ScoringRule::NytStandard => self.pattern(guess),
+            ScoringRule::GreedyYellow => {
+                let n = guess.n_letters;
+                let mut result = [LetterStatus::Absent; MAX_LETTERS];
+                for (pos, item) in result[..n].iter_mut().enumerate() {
+                    if guess.chars[pos] == self.chars[pos] {
+                        *item = LetterStatus::Correct;
+                    } else if self.chars[..n].contains(&guess.chars[pos]) {
+                        *item = LetterStatus::Misplaced;
+                    }
+                }
+                encode_status(&result[..n])
+            }
+        }
     }
 
     /// Counts the occrences of a char in a word
@@ -106,7 +258,7 @@ impl Word {
     /// assert_eq!(word.count_char(&'o'), 2);
     /// ```
     pub fn count_char(&self, char: &char) -> usize {
-        self.chars
+        self.chars()
             .iter()
             .filter(|l| match l {
                 Some(c) => c == char,
@@ -116,7 +268,7 @@ impl Word {
     }
 
     fn has_letter_at_position(&self, char: &char, position: usize) -> bool {
-        match self.chars[position] {
+        match self.chars()[position] {
             Some(c) => c == *char,
             None => false,
         }
@@ -135,9 +287,25 @@ impl Word {
     /// assert!(create_word_from_string("songs").is_valid(&guess));
     /// ```
     pub fn is_valid(&self, guess: &Guess) -> bool {
-        let status = decode_status(guess.status);
+        self.violated_constraint(guess).is_none()
+    }
 
-        for (guess_pos, guess_letter) in guess.word.chars.iter().enumerate() {
+    /// Returns the first letter constraint of a guess that the current word
+    /// violates, or `None` if the word is still valid for the guess.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wordlebot::wordle::*;
+    /// use wordlebot::wordle::LetterStatus::*;
+    /// let guess = Guess::new("slate", [Correct, Absent, Absent, Absent, Absent]);
+    /// let word = create_word_from_string("water");
+    /// assert_eq!(word.violated_constraint(&guess), Some(LetterConstraint::At('s', 0)));
+    /// ```
+    pub fn violated_constraint(&self, guess: &Guess) -> Option<LetterConstraint> {
+        let status = guess.get_status();
+
+        for (guess_pos, guess_letter) in guess.word.chars().iter().enumerate() {
             if let Some(guess_char) = guess_letter {
                 match status[guess_pos] {
                     LetterStatus::Absent => {
@@ -145,61 +313,164 @@ impl Word {
                             // The letter must appear somewhere, but not at this position
                             n_must if n_must > 0 => {
                                 if self.has_letter_at_position(guess_char, guess_pos) {
-                                    return false;
+                                    return Some(LetterConstraint::NotAt(*guess_char, guess_pos));
                                 };
                                 let n_is = self.count_char(guess_char);
-                                // println!("Incor: {n_must} {n_is}");
                                 if n_is > n_must {
-                                    return false;
+                                    return Some(LetterConstraint::AtMost(*guess_char, n_must));
                                 };
                             }
                             // The letter must not appear at all
                             _ => {
                                 if self.count_char(guess_char) > 0 {
-                                    return false;
+                                    return Some(LetterConstraint::Absent(*guess_char));
                                 }
                             }
                         }
                     }
                     LetterStatus::Misplaced => {
                         if self.has_letter_at_position(guess_char, guess_pos) {
-                            return false;
+                            return Some(LetterConstraint::NotAt(*guess_char, guess_pos));
                         }
                         let n_must = guess.remove_absent().count_char(guess_char);
                         let n_is = self.count_char(guess_char);
                         if n_is == 0 || n_must > n_is {
-                            return false;
+                            return Some(LetterConstraint::AtLeast(*guess_char, n_must));
                         }
                     }
                     LetterStatus::Correct => {
                         if !self.has_letter_at_position(guess_char, guess_pos) {
-                            return false;
+                            return Some(LetterConstraint::At(*guess_char, guess_pos));
                         }
                     }
                 }
             }
         }
-        true
+        None
+    }
+
+    /// Returns the first hint of a guess that the current word ignores by
+    /// the hard mode rules, or `None` if the word may be played. Correct
+    /// letters have to stay in place and misplaced letters have to be used,
+    /// but unlike [`Word::violated_constraint`] absent letters are allowed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wordlebot::wordle::*;
+    /// use wordlebot::wordle::LetterStatus::*;
+    /// let guess = Guess::new("slate", [Correct, Absent, Misplaced, Absent, Absent]);
+    /// assert_eq!(create_word_from_string("stalk").hard_mode_violation(&guess), None);
+    /// assert_eq!(
+    ///     create_word_from_string("sworn").hard_mode_violation(&guess),
+    ///     Some(LetterConstraint::AtLeast('a', 1))
+    /// );
+    /// ```
+    pub fn hard_mode_violation(&self, guess: &Guess) -> Option<LetterConstraint> {
+        let status = guess.get_status();
+        let hints = guess.remove_absent();
+        for (pos, letter) in guess.word.chars().iter().enumerate() {
+            let Some(c) = letter else {
+                continue;
+            };
+            match status[pos] {
+                LetterStatus::Correct if !self.has_letter_at_position(c, pos) => {
+                    return Some(LetterConstraint::At(*c, pos));
+                }
+                LetterStatus::Correct | LetterStatus::Misplaced => {
+                    let n_must = hints.count_char(c);
+                    if self.count_char(c) < n_must {
+                        return Some(LetterConstraint::AtLeast(*c, n_must));
+                    }
+                }
+                LetterStatus::Absent => {}
+            }
+        }
+        None
+    }
+
+    /// Whether the word meets a single constraint. A position after the
+    /// last letter holds no letter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wordlebot::wordle::*;
+    /// let word = create_word_from_string("crane");
+    /// assert!(word.meets(&LetterConstraint::At('r', 1)));
+    /// assert!(!word.meets(&LetterConstraint::NotAt('r', 1)));
+    /// assert!(word.meets(&LetterConstraint::AtMost('e', 1)));
+    /// ```
+    pub fn meets(&self, constraint: &LetterConstraint) -> bool {
+        match *constraint {
+            LetterConstraint::Absent(c) => self.count_char(&c) == 0,
+            LetterConstraint::At(c, pos) => self.chars().get(pos) == Some(&Some(c)),
+            LetterConstraint::NotAt(c, pos) => self.chars().get(pos) != Some(&Some(c)),
+            LetterConstraint::AtLeast(c, n) => self.count_char(&c) >= n,
+            LetterConstraint::AtMost(c, n) => self.count_char(&c) <= n,
+        }
+    }
+}
+
+/// A single constraint on the letters of the solution that follows from the
+/// status of a guess. Positions are zero based.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LetterConstraint {
+    /// The letter does not appear in the solution
+    Absent(char),
+    /// The letter is at the position
+    At(char, usize),
+    /// The letter is not at the position
+    NotAt(char, usize),
+    /// The letter appears at least n times
+    AtLeast(char, usize),
+    /// The letter appears at most n times
+    AtMost(char, usize),
+}
+
+impl fmt::Display for LetterConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            LetterConstraint::Absent(c) => write!(f, "{} is not in the word", c.to_uppercase()),
+            LetterConstraint::At(c, pos) => {
+                write!(f, "{} must be at position {}", c.to_uppercase(), pos + 1)
+            }
+            LetterConstraint::NotAt(c, pos) => {
+                write!(
+                    f,
+                    "{} must not be at position {}",
+                    c.to_uppercase(),
+                    pos + 1
+                )
+            }
+            LetterConstraint::AtLeast(c, n) => {
+                write!(f, "{} must appear at least {} time(s)", c.to_uppercase(), n)
+            }
+            LetterConstraint::AtMost(c, n) => {
+                write!(f, "{} must appear at most {} time(s)", c.to_uppercase(), n)
+            }
+        }
     }
 }
 
 impl Guess {
     /// Create a new guess from a string
-    pub fn new(word: &str, status: [LetterStatus; 5]) -> Guess {
+    pub fn new<const N: usize>(word: &str, status: [LetterStatus; N]) -> Guess {
         let word = create_word_from_string(word);
         let status = encode_status(&status);
         Guess { word, status }
     }
 
-    pub fn empty() -> Guess {
+    /// Create a guess with `n_letters` empty letters
+    pub fn empty(n_letters: usize) -> Guess {
         Guess {
-            word: Word::new(),
+            word: Word::with_length(n_letters),
             status: 0,
         }
     }
 
-    pub fn from_word(word: Word, status: [LetterStatus; 5]) -> Guess {
-        let status = encode_status(&status);
+    pub fn from_word(word: Word, status: &[LetterStatus]) -> Guess {
+        let status = encode_status(status);
         Guess { word, status }
     }
 
@@ -208,12 +479,12 @@ impl Guess {
         self.word.set_letter(char, position)
     }
 
-    pub fn set_status(&mut self, status: &[LetterStatus; NLETTER]) {
+    pub fn set_status(&mut self, status: &[LetterStatus]) {
         self.status = encode_status(status)
     }
 
-    pub fn get_status(&self) -> [LetterStatus; 5] {
-        decode_status(self.status)
+    pub fn get_status(&self) -> Vec<LetterStatus> {
+        decode_status(self.status, self.word.n_letters)
     }
 
     pub fn update_status(&mut self, status: LetterStatus, position: usize) {
@@ -228,8 +499,7 @@ impl Guess {
 
     fn remove_absent(&self) -> Word {
         let mut word = self.word;
-        let status = decode_status(self.status);
-        for (i, s) in status.iter().enumerate() {
+        for (i, s) in self.get_status().iter().enumerate() {
             if s == &LetterStatus::Absent {
                 word.chars[i] = None;
             }
@@ -238,9 +508,173 @@ impl Guess {
     }
 }
 
+/// What the guesses tell about every letter they contain, like the keyboard
+/// of Wordle: correct if it was correct at any position, else misplaced if
+/// it was misplaced anywhere, else absent. A letter that is absent next to
+/// a copy of it that is present counts as present.
+pub fn letter_states(guesses: &[Guess]) -> HashMap<char, LetterStatus> {
+    let rank = |status: LetterStatus| match status {
+        LetterStatus::Absent => 0,
+        LetterStatus::Misplaced => 1,
+        LetterStatus::Correct => 2,
+    };
+    let mut states = HashMap::new();
+    for guess in guesses {
+        for (c, status) in zip(guess.word.chars(), guess.get_status()) {
+            let Some(c) = c else { continue };
+            let state = states.entry(*c).or_insert(status);
+            if rank(status) > rank(*state) {
+                *state = status;
+            }
+        }
+    }
+    states
+}
+
+/// Parse a guess, see [`parse_guess`]
+///
+/// # Example
+///
+/// ```
+/// use wordlebot::wordle::*;
+/// use wordlebot::wordle::LetterStatus::*;
+/// let guess: Guess = "slate:bybyg".parse().unwrap();
+/// assert_eq!(guess, Guess::new("slate", [Absent, Misplaced, Absent, Misplaced, Correct]));
+/// assert_eq!("slate:01012".parse::<Guess>().unwrap(), guess);
+/// ```
+impl FromStr for Guess {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_guess(s)
+    }
+}
+
+/// Parse a guess from the word and its pattern, separated by `:` or `=`,
+/// e.g. `crane:gybbg` or `crane=gybbg`. See [`parse_pattern`] for the
+/// pattern.
+pub fn parse_guess(s: &str) -> Result<Guess, Error> {
+    let Some((word, pattern)) = s.split_once([':', '=']) else {
+        bail!("Expected a guess in the form WORD:PATTERN, got '{}'", s)
+    };
+    let word = parse_word(word)?;
+    if pattern.chars().count() != word.n_letters() {
+        bail!("Word and pattern of '{}' must have the same length", s)
+    }
+    Ok(Guess {
+        word,
+        status: parse_pattern(pattern)?,
+    })
+}
+
+/// Parse a word of [`MIN_LETTERS`] to [`MAX_LETTERS`] letters, e.g. of a
+/// file. It is lowercased. See
+/// [`Solver::parse_word`](crate::solver::Solver::parse_word) for a word
+/// that has to fit the word list.
+///
+/// # Example
+///
+/// ```
+/// use wordlebot::wordle::*;
+/// assert_eq!(parse_word("Crane").unwrap().to_string(), "CRANE");
+/// assert!(parse_word("abcdefghijk").is_err());
+/// assert!(parse_word("cr4ne").is_err());
+/// ```
+pub fn parse_word(s: &str) -> Result<Word, Error> {
+    let word = s.to_lowercase();
+    let n_letters = word.chars().count();
+    if !(MIN_LETTERS..=MAX_LETTERS).contains(&n_letters) {
+        bail!(
+            "Word '{}' must have {} to {} letters",
+            s,
+            MIN_LETTERS,
+            MAX_LETTERS
+        )
+    }
+    if !word.chars().all(|c| c.is_alphabetic()) {
+        bail!("Word '{}' must only have letters", s)
+    }
+    Ok(create_word_from_string(&word))
+}
+
+/// Parse the guesses of a log with one guess per line, the word and its
+/// pattern separated by spaces or like [`parse_guess`], e.g. `crane gybbg`.
+/// Empty lines and lines starting with `#` are skipped. A guess after a
+/// solved one starts a new game, so only the guesses of the last game are
+/// returned.
+pub fn parse_guess_log(text: &str) -> Result<Vec<Guess>, Error> {
+    let mut guesses: Vec<Guess> = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let guess = match line.split_whitespace().collect::<Vec<_>>()[..] {
+            [word, pattern] => parse_guess(&format!("{}:{}", word, pattern)),
+            _ => parse_guess(line),
+        }
+        .with_context(|| format!("Line {}", i + 1))?;
+        if guesses
+            .last()
+            .is_some_and(|last| last.status == solved_pattern(last.word.n_letters()))
+        {
+            guesses.clear();
+        }
+        guesses.push(guess);
+    }
+    Ok(guesses)
+}
+
+/// Parse a pattern with one status per letter: `b` (or `-`, `0`) for
+/// absent, `y` (or `1`) for misplaced and `g` (or `2`) for correct letters,
+/// in any case. [`pattern_to_string`] writes it back.
+///
+/// # Example
+///
+/// ```
+/// use wordlebot::wordle::*;
+/// let pattern = parse_pattern("gybbg").unwrap();
+/// assert_eq!(pattern, parse_pattern("21-0G").unwrap());
+/// assert_eq!(pattern_to_string(pattern, 5), "GYBBG");
+/// ```
+pub fn parse_pattern(s: &str) -> Result<Pattern, Error> {
+    let n_letters = s.chars().count();
+    if !(MIN_LETTERS..=MAX_LETTERS).contains(&n_letters) {
+        bail!(
+            "Pattern '{}' must have {} to {} letters",
+            s,
+            MIN_LETTERS,
+            MAX_LETTERS
+        )
+    }
+    let status = s
+        .chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            'b' | '-' | '0' => Ok(LetterStatus::Absent),
+            'y' | '1' => Ok(LetterStatus::Misplaced),
+            'g' | '2' => Ok(LetterStatus::Correct),
+            _ => bail!("Invalid status '{}' in pattern '{}'", c, s),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(encode_status(&status))
+}
+
+/// The pattern as letters, e.g. `BBYBG` (B = absent, Y = misplaced, G =
+/// correct), which [`parse_pattern`] reads
+pub fn pattern_to_string(pattern: Pattern, n_letters: usize) -> String {
+    decode_status(pattern, n_letters)
+        .iter()
+        .map(|s| match s {
+            LetterStatus::Absent => 'B',
+            LetterStatus::Misplaced => 'Y',
+            LetterStatus::Correct => 'G',
+        })
+        .collect()
+}
+
 impl fmt::Display for Word {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for &c in &self.chars {
+        for &c in self.chars() {
             match c {
                 Some(ch) => write!(f, "{}", ch.to_uppercase())?,
                 None => break,
@@ -253,9 +687,8 @@ impl fmt::Display for Word {
 use colored::Colorize;
 impl fmt::Display for Guess {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let status = decode_status(self.status);
-        for (i, s) in status.iter().enumerate() {
-            let ch = match self.word.chars[i] {
+        for (s, c) in self.get_status().iter().zip(self.word.chars()) {
+            let ch = match c {
                 Some(ch) => ch.to_uppercase().to_string(),
                 None => "_".to_string(),
             };
@@ -269,38 +702,154 @@ impl fmt::Display for Guess {
     }
 }
 
-pub fn encode_status(status: &[LetterStatus; NLETTER]) -> u8 {
+pub fn encode_status(status: &[LetterStatus]) -> Pattern {
     status
         .iter()
         .enumerate()
-        .map(|(i, x)| 3_u8.pow(i as u32) * *x as u8)
+        .map(|(i, x)| 3_u16.pow(i as u32) * *x as Pattern)
         .sum()
 }
 
-pub fn decode_status(encoded: u8) -> [LetterStatus; NLETTER] {
-    let mut status = [LetterStatus::Absent; NLETTER];
+/// Decodes the status of the `n_letters` letters of a pattern
+pub fn decode_status(encoded: Pattern, n_letters: usize) -> Vec<LetterStatus> {
+    (0..n_letters)
+        .map(|i| {
+            let pow = 3_u16.pow(i as u32);
+            match encoded / pow % 3 {
+                0 => LetterStatus::Absent,
+                1 => LetterStatus::Misplaced,
+                2 => LetterStatus::Correct,
+                _ => panic!("Invalid encoding"),
+            }
+        })
+        .collect()
+}
 
-    for (i, item) in status.iter_mut().enumerate() {
-        let pow = 3_u8.pow(i as u32);
-        let value = encoded / pow % 3;
-        *item = match value {
-            0 => LetterStatus::Absent,
-            1 => LetterStatus::Misplaced,
-            2 => LetterStatus::Correct,
-            _ => panic!("Invalid encoding"),
-        };
-    }
-    status
+/// The pattern of a solved word with `n_letters` letters
+///
+/// # Example
+///
+/// ```
+/// use wordlebot::wordle::*;
+/// assert_eq!(solved_pattern(5), 242);
+/// ```
+pub fn solved_pattern(n_letters: usize) -> Pattern {
+    3_u16.pow(n_letters as u32) - 1
 }
 
+/// The word of a string as it is. Panics if it has more than
+/// [`MAX_LETTERS`] letters, see
+/// [`Solver::parse_word`](crate::solver::Solver::parse_word) for user input.
 pub fn create_word_from_string(word: &str) -> Word {
-    let mut res = Word::new();
+    let mut res = Word::with_length(word.chars().count());
     for (i, letter) in word.chars().enumerate() {
         res.set_letter(Some(letter), i);
     }
     res
 }
 
+/// The status of a tile of a shared result. Light mode uses white squares
+/// for absent letters, and the high contrast mode orange for correct and
+/// blue for misplaced letters.
+fn tile_status(tile: char) -> Option<LetterStatus> {
+    match tile {
+        '⬛' | '⬜' => Some(LetterStatus::Absent),
+        '🟨' | '🟦' => Some(LetterStatus::Misplaced),
+        '🟩' | '🟧' => Some(LetterStatus::Correct),
+        _ => None,
+    }
+}
+
+/// Parse the emoji grid of a shared result into the status of every row.
+/// Lines without tiles, like the `Wordle 1,234 3/6` header, are skipped.
+///
+/// # Example
+///
+/// ```
+/// use wordlebot::wordle::*;
+/// use wordlebot::wordle::LetterStatus::*;
+/// let rows = parse_share_grid("Wordle 1,234 2/6\n\n⬛🟨⬛⬛🟩\n🟩🟩🟩🟩🟩").unwrap();
+/// assert_eq!(rows[0], [Absent, Misplaced, Absent, Absent, Correct]);
+/// assert_eq!(rows[1], [Correct; 5]);
+/// ```
+pub fn parse_share_grid(share: &str) -> Result<Vec<Vec<LetterStatus>>, Error> {
+    let mut rows = vec![];
+    for line in share.lines() {
+        if !line.chars().any(|c| tile_status(c).is_some()) {
+            continue;
+        }
+        // Emoji may carry a variation selector
+        let row = line
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '\u{fe0f}')
+            .map(|c| match tile_status(c) {
+                Some(status) => Ok(status),
+                None => bail!("Unexpected '{}' in the row '{}'", c, line.trim()),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !(MIN_LETTERS..=MAX_LETTERS).contains(&row.len()) {
+            bail!(
+                "The row '{}' must have {} to {} tiles",
+                line.trim(),
+                MIN_LETTERS,
+                MAX_LETTERS
+            )
+        }
+        if rows.last().is_some_and(|last: &Vec<LetterStatus>| {
+            last.iter().all(|&s| s == LetterStatus::Correct)
+        }) {
+            bail!("The grid goes on after the solved row")
+        }
+        rows.push(row);
+    }
+    if rows.is_empty() {
+        bail!("The share string has no rows of tiles")
+    }
+    Ok(rows)
+}
+
+/// The emoji grid of the guesses, one row per guess, as a result is shared.
+/// [`parse_share_grid`] reads it back.
+pub fn share_grid(guesses: &[Guess]) -> String {
+    guesses
+        .iter()
+        .map(|guess| {
+            guess
+                .get_status()
+                .iter()
+                .map(|status| match status {
+                    LetterStatus::Absent => '⬛',
+                    LetterStatus::Misplaced => '🟨',
+                    LetterStatus::Correct => '🟩',
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The guesses of a shared result: the rows of the emoji grid (see
+/// [`parse_share_grid`]) with the words that were guessed, in order. The
+/// share string does not contain the words.
+pub fn parse_share_string(share: &str, words: &[Word]) -> Result<Vec<Guess>, Error> {
+    let rows = parse_share_grid(share)?;
+    if rows.len() != words.len() {
+        bail!(
+            "The grid has {} rows, but {} words were given",
+            rows.len(),
+            words.len()
+        )
+    }
+    zip(words, rows)
+        .map(|(&word, row)| {
+            if row.len() != word.n_letters() {
+                bail!("{} and its row must have the same length", word)
+            }
+            Ok(Guess::from_word(word, &row))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -330,21 +879,24 @@ mod tests {
 
     #[test]
     fn test_decode_status() {
-        assert_eq!(decode_status(0), [Absent, Absent, Absent, Absent, Absent]);
         assert_eq!(
-            decode_status(1),
+            decode_status(0, 5),
+            [Absent, Absent, Absent, Absent, Absent]
+        );
+        assert_eq!(
+            decode_status(1, 5),
             [Misplaced, Absent, Absent, Absent, Absent]
         );
         assert_eq!(
-            decode_status(10),
+            decode_status(10, 5),
             [Misplaced, Absent, Misplaced, Absent, Absent]
         );
         assert_eq!(
-            decode_status(242),
+            decode_status(242, 5),
             [Correct, Correct, Correct, Correct, Correct]
         );
         assert_eq!(
-            decode_status(233),
+            decode_status(233, 5),
             [Correct, Correct, Misplaced, Correct, Correct]
         );
     }
@@ -386,6 +938,33 @@ mod tests {
         assert_eq!(word.compare(&guess), expected);
     }
 
+    #[test]
+    fn test_other_lengths() {
+        let word = create_word_from_string("wasp");
+        let guess = create_word_from_string("swap");
+        assert_eq!(
+            word.compare(&guess),
+            [Misplaced, Misplaced, Misplaced, Correct]
+        );
+
+        let word = create_word_from_string("streams");
+        let guess = create_word_from_string("masters");
+        assert_eq!(
+            word.compare(&guess),
+            [Misplaced, Misplaced, Misplaced, Misplaced, Misplaced, Misplaced, Correct]
+        );
+
+        let status = [Correct; 8];
+        assert_eq!(encode_status(&status), solved_pattern(8));
+        assert_eq!(decode_status(solved_pattern(8), 8), status);
+
+        let guess: Guess = "swap:yyyg".parse().unwrap();
+        assert!(create_word_from_string("wasp").is_valid(&guess));
+        assert!(!create_word_from_string("snap").is_valid(&guess));
+        assert!("swap:yyg".parse::<Guess>().is_err());
+        assert!("ox:gg".parse::<Guess>().is_err());
+    }
+
     #[test]
     fn test_is_valid() {
         let guess = Guess::new("slate", [Absent, Correct, Correct, Correct, Correct]);
@@ -398,4 +977,195 @@ mod tests {
         let guess = Guess::new("slate", [Absent, Misplaced, Correct, Absent, Absent]);
         assert!(!create_word_from_string("least").is_valid(&guess));
     }
+
+    #[test]
+    fn test_violated_constraint() {
+        let guess = Guess::new("slate", [Absent, Correct, Correct, Correct, Correct]);
+        assert_eq!(
+            create_word_from_string("plate").violated_constraint(&guess),
+            None
+        );
+        assert_eq!(
+            create_word_from_string("water").violated_constraint(&guess),
+            Some(LetterConstraint::At('l', 1))
+        );
+
+        let guess = Guess::new("esses", [Misplaced, Absent, Absent, Absent, Absent]);
+        assert_eq!(
+            create_word_from_string("reede").violated_constraint(&guess),
+            Some(LetterConstraint::AtMost('e', 1))
+        );
+
+        let guess = Guess::new("slate", [Absent, Misplaced, Correct, Absent, Absent]);
+        assert_eq!(
+            create_word_from_string("least").violated_constraint(&guess),
+            Some(LetterConstraint::Absent('s'))
+        );
+
+        let guess = Guess::new("speed", [Absent, Absent, Misplaced, Misplaced, Absent]);
+        assert_eq!(
+            create_word_from_string("abide").violated_constraint(&guess),
+            Some(LetterConstraint::AtLeast('e', 2))
+        );
+        assert_eq!(
+            create_word_from_string("spree").violated_constraint(&guess),
+            Some(LetterConstraint::Absent('s'))
+        );
+    }
+
+    #[test]
+    fn test_hard_mode_violation() {
+        let guess = Guess::new("speed", [Absent, Absent, Misplaced, Misplaced, Absent]);
+        // Absent letters may be played again
+        assert_eq!(
+            create_word_from_string("spree").hard_mode_violation(&guess),
+            None
+        );
+        assert_eq!(
+            create_word_from_string("abide").hard_mode_violation(&guess),
+            Some(LetterConstraint::AtLeast('e', 2))
+        );
+
+        let guess = Guess::new("slate", [Absent, Correct, Correct, Absent, Absent]);
+        assert_eq!(
+            create_word_from_string("plank").hard_mode_violation(&guess),
+            None
+        );
+        assert_eq!(
+            create_word_from_string("bleak").hard_mode_violation(&guess),
+            Some(LetterConstraint::At('a', 2))
+        );
+    }
+
+    #[test]
+    fn test_parse_share_string() {
+        let share = "Wordle 1,234 3/6*\n\n⬛🟨⬛⬛🟩\n⬜️🟩⬜️🟨🟩\n🟩🟩🟩🟩🟩\n";
+        let words: Vec<Word> = ["slate", "crane", "prone"]
+            .iter()
+            .map(|w| create_word_from_string(w))
+            .collect();
+        let guesses = parse_share_string(share, &words).unwrap();
+        assert_eq!(
+            guesses,
+            [
+                "slate:bybbg".parse().unwrap(),
+                "crane:bgbyg".parse().unwrap(),
+                "prone:ggggg".parse().unwrap(),
+            ]
+        );
+        // High contrast
+        assert_eq!(
+            parse_share_grid("🟧🟦⬛⬛🟧").unwrap(),
+            [[Correct, Misplaced, Absent, Absent, Correct]]
+        );
+
+        assert!(parse_share_string(share, &words[..2]).is_err());
+        assert!(parse_share_string("⬛🟨⬛🟩", &words[..1]).is_err());
+        assert!(parse_share_grid("Wordle 1,234 X/6").is_err());
+        assert!(parse_share_grid("⬛🟨⬛⬛🟩 slate").is_err());
+        assert!(parse_share_grid("🟩🟩🟩🟩🟩\n⬛🟨⬛⬛🟩").is_err());
+
+        // Sharing the guesses gives the same grid
+        assert_eq!(share_grid(&guesses), "⬛🟨⬛⬛🟩\n⬛🟩⬛🟨🟩\n🟩🟩🟩🟩🟩");
+        assert_eq!(
+            parse_share_string(&share_grid(&guesses), &words).unwrap(),
+            guesses
+        );
+    }
+
+    #[test]
+    fn test_parse_pattern() {
+        // Writing and reading a pattern gives the same pattern
+        for n_letters in MIN_LETTERS..=MAX_LETTERS {
+            for pattern in 0..=solved_pattern(n_letters) {
+                let text = pattern_to_string(pattern, n_letters);
+                assert_eq!(parse_pattern(&text).unwrap(), pattern);
+                assert_eq!(
+                    pattern_to_string(parse_pattern(&text).unwrap(), n_letters),
+                    text
+                );
+            }
+        }
+        assert_eq!(
+            parse_pattern("bybyg").unwrap(),
+            parse_pattern("01-1G").unwrap()
+        );
+
+        for text in [
+            "",
+            "gyb",
+            "gybbgyybg",
+            "gybxg",
+            "gy bg",
+            "gybbg ",
+            "3yybg",
+            "🟩yybg",
+        ] {
+            assert!(parse_pattern(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_guess() {
+        let guess = parse_guess("crane=gybbg").unwrap();
+        assert_eq!(
+            guess,
+            Guess::new("crane", [Correct, Misplaced, Absent, Absent, Correct])
+        );
+        assert_eq!(parse_guess("CRANE:21002").unwrap(), guess);
+        assert_eq!(
+            parse_guess(&format!("crane:{}", pattern_to_string(guess.status, 5))).unwrap(),
+            guess
+        );
+
+        for text in [
+            "crane",
+            "crane gybbg",
+            "crane:gybb",
+            "crane:gybbgg",
+            "cr4ne:gybbg",
+            ":gybbg",
+            "crane:",
+            "crane=gy:bg",
+            "cranes:gybbg",
+            "ox:gg",
+        ] {
+            assert!(parse_guess(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_letter_states() {
+        let guesses = [
+            Guess::new("slate", [Absent, Misplaced, Absent, Absent, Absent]),
+            // The first L is misplaced, the second one absent
+            Guess::new("llama", [Misplaced, Absent, Absent, Absent, Absent]),
+            Guess::new("pilot", [Absent, Absent, Correct, Misplaced, Absent]),
+        ];
+        let states = letter_states(&guesses);
+        assert_eq!(states[&'l'], Correct);
+        assert_eq!(states[&'o'], Misplaced);
+        assert_eq!(states[&'s'], Absent);
+        assert_eq!(states[&'a'], Absent);
+        assert_eq!(states.get(&'z'), None);
+        assert!(letter_states(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_parse_guess_log() {
+        let log = "# first game\nslate bybbg\n\n  crane=gggbg\ncrate ggggg\n";
+        let guesses = parse_guess_log(log).unwrap();
+        assert_eq!(guesses.len(), 3);
+        assert_eq!(guesses[0], parse_guess("slate:bybbg").unwrap());
+        assert_eq!(guesses[1], parse_guess("crane:gggbg").unwrap());
+
+        // The next game starts after the solved guess
+        let guesses = parse_guess_log(&format!("{}salet 00120\n", log)).unwrap();
+        assert_eq!(guesses, [parse_guess("salet:bbygb").unwrap()]);
+        assert_eq!(parse_guess_log("\n# nothing yet\n").unwrap(), []);
+
+        let error = parse_guess_log("slate bybbg\ncrane gyb bg\n").unwrap_err();
+        assert!(error.to_string().starts_with("Line 2"));
+        assert!(parse_guess_log("slate").is_err());
+    }
 }
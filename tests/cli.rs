@@ -0,0 +1,376 @@
+//! The command line as a user runs it, on the small word list of
+//! tests/words.txt. The solver is deterministic, so the guesses, the
+//! benchmark and the JSON output are locked in; every test has its own
+//! config and cache directory.
+use assert_cmd::Command;
+use predicates::boolean::PredicateBooleanExt;
+
+fn wordlebot(test: &str) -> Command {
+    let mut cmd = without_words(test);
+    cmd.arg("--words")
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/words.txt"));
+    cmd
+}
+
+/// The command without the small word list, e.g. for a word pack
+fn without_words(test: &str) -> Command {
+    let home = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("cli-{}", test));
+    let mut cmd = Command::cargo_bin("wordlebot").unwrap();
+    cmd.env("HOME", &home)
+        .env("XDG_CACHE_HOME", home.join("cache"))
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .env("NO_COLOR", "1");
+    cmd
+}
+
+fn stdout(cmd: &mut Command) -> String {
+    let output = cmd.assert().success().get_output().stdout.clone();
+    String::from_utf8(output).unwrap()
+}
+
+/// The guesses of every game in the output of `solve`, from lines like
+/// ` RAISE - n before:   80 | n after:   12 | ...`
+fn games(output: &str) -> Vec<Vec<String>> {
+    let mut games: Vec<Vec<String>> = vec![];
+    for line in output.lines() {
+        if line.starts_with("Trying to solve") {
+            games.push(vec![]);
+        }
+        if let Some((guess, _)) = line.split_once(" - n before:") {
+            // The interactive mode asks for the pattern on the same line
+            let guess = guess.rsplit(' ').next().unwrap().to_string();
+            games.last_mut().unwrap().push(guess);
+        }
+    }
+    games
+}
+
+#[test]
+fn test_solve() {
+    let output = stdout(wordlebot("solve").args(["solve", "-s", "raise", "glare", "bough"]));
+    assert_eq!(
+        games(&output),
+        [
+            vec!["RAISE", "GLARE"],
+            vec!["RAISE", "PUDGY", "COUGH", "BOUGH"]
+        ]
+    );
+}
+
+#[test]
+fn test_solve_invalid() {
+    for (answer, error) in [
+        ("zzzzz", "ZZZZZ is not in the word list"),
+        ("ab", "AB has 2 letters, the words of the list have 5"),
+        (
+            "abcdefghijk",
+            "ABCDEFGHIJK has 11 letters, the words of the list have 5",
+        ),
+        ("", "has 0 letters"),
+        ("thale", "THALE is not a possible answer"),
+    ] {
+        wordlebot("solve_invalid")
+            .args(["solve", "-s", "raise", "glare", answer])
+            .assert()
+            .failure()
+            .stdout(predicates::str::contains("Trying to solve").not())
+            .stderr(predicates::str::contains(error));
+    }
+}
+
+#[test]
+fn test_solve_interactive() {
+    let output = stdout(
+        wordlebot("interactive")
+            .args(["solve", "--interactive", "-s", "raise"])
+            .write_stdin("bybbg\nggggg\n"),
+    );
+    assert!(output.contains("Pattern of RAISE (b/y/g):"));
+    assert_eq!(games(&output), [vec!["RAISE", "LEAVE"]]);
+
+    // An empty line stops the game before the guess is played
+    let output = stdout(
+        wordlebot("interactive_stop")
+            .args(["solve", "--interactive", "-s", "raise"])
+            .write_stdin("\n"),
+    );
+    assert!(output.trim_end().ends_with("Pattern of RAISE (b/y/g):"));
+    assert_eq!(games(&output), [Vec::<String>::new()]);
+}
+
+#[test]
+fn test_benchmark() {
+    let output = stdout(wordlebot("benchmark").args([
+        "benchmark",
+        "--max-rounds",
+        "3",
+        "--starting-word",
+        "raise",
+    ]));
+    assert!(output.contains("1 words could not be solved in 3 guesses: BOUGH"));
+    assert!(output.contains("The others have been solved in an average of 2.56 steps"));
+    for line in ["Steps 1: Count 1", "Steps 2: Count 33", "Steps 3: Count 45"] {
+        assert!(output.lines().any(|l| l == line), "{} missing", line);
+    }
+
+    // The output is piped, so the progress is a plain line instead of a bar
+    let args = ["benchmark", "--max-rounds", "3", "--progress"];
+    let output = wordlebot("benchmark")
+        .args(args)
+        .arg("auto")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("80/80 (100%), done in"), "{}", stderr);
+    let output = wordlebot("benchmark")
+        .args(args)
+        .arg("none")
+        .output()
+        .unwrap();
+    assert!(!String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("done in"));
+
+    // --eco would drop the lookahead search of --depth
+    wordlebot("benchmark")
+        .args(["--eco", "--depth", "2", "benchmark"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_benchmark_answers() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("benchmark-answers.txt");
+    std::fs::write(&path, "bough\nglare\nbough\n").unwrap();
+    let args = ["benchmark", "--max-rounds", "3", "--starting-word", "raise"];
+    let output = stdout(
+        wordlebot("benchmark_answers")
+            .args(args)
+            .arg("--answers")
+            .arg(&path),
+    );
+    assert!(output.contains("1 words could not be solved in 3 guesses: BOUGH"));
+    assert!(output.lines().any(|l| l == "Steps 2: Count 1"));
+    let set = format!("Answers: the 2 answers of {}", path.display());
+    assert!(output.lines().any(|l| l == set), "{}", output);
+
+    // The same seed draws the same sample
+    let sample = ["--sample", "10", "--seed", "4"];
+    let output = stdout(wordlebot("benchmark_answers").args(args).args(sample));
+    let set = "Answers: a sample of 10 of all 80 answers of the word list (--seed 4)";
+    assert!(output.trim_end().ends_with(set));
+    assert_eq!(
+        output,
+        stdout(wordlebot("benchmark_answers").args(args).args(sample))
+    );
+
+    std::fs::write(&path, "bough\nxylyl\n").unwrap();
+    wordlebot("benchmark_answers")
+        .args(args)
+        .arg("--answers")
+        .arg(&path)
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_suggest_json() {
+    let output =
+        stdout(wordlebot("suggest").args(["suggest", "-g", "raise:bybbg", "-n", "3", "--json"]));
+    assert_eq!(
+        output.trim(),
+        r#"{"remaining":2,"suggestions":[{"word":"leave","bits":0.9999886,"possible":true},{"word":"algae","bits":0.9999886,"possible":true},{"word":"glare","bits":0.9999886,"possible":false}]}"#
+    );
+
+    wordlebot("suggest_invalid")
+        .args(["suggest", "-g", "xxxxx:bbbbb"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("XXXXX is not in the word list"));
+}
+
+#[test]
+fn test_data_info_json() {
+    let output = stdout(wordlebot("data_info").args(["data-info", "--json"]));
+    assert!(output.starts_with(
+        r#"{"metadata":{"name":"cli tests","description":"A small sample of the embedded word list for tests/cli.rs"},"words":157,"answers":80,"letters":5,"#
+    ));
+}
+
+#[test]
+fn test_why_and_evaluate_line() {
+    let output = stdout(wordlebot("why").args(["why", "glare", "raise:bybbg"]));
+    assert_eq!(
+        output.trim(),
+        "GLARE was eliminated by guess 1 (RAISE): R is not in the word"
+    );
+
+    let output = stdout(wordlebot("evaluate_line").args([
+        "evaluate-line",
+        "--answer",
+        "bough",
+        "--line",
+        "raise,pudgy,cough,bough",
+    ]));
+    assert!(output.contains("rank     8 | solver: GLARE"));
+    assert!(output.trim_end().ends_with("Solved BOUGH in 4 guesses"));
+
+    // Words too long for any word list are an error, not a panic
+    let long = "ABCDEFGHIJ has 10 letters, the words of the list have 5";
+    for args in [
+        &["why", "abcdefghij", "raise:bbbbb"][..],
+        &["evaluate-line", "--answer", "abcdefghij", "--line", "raise"],
+        &[
+            "evaluate-line",
+            "--answer",
+            "bough",
+            "--line",
+            "raise,abcdefghij",
+        ],
+        &["hint", "--answer", "abcdefghij"],
+    ] {
+        wordlebot("long_words")
+            .args(args)
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains(long));
+    }
+}
+
+#[test]
+fn test_streak() {
+    let output = stdout(wordlebot("streak").args(["streak", "-g", "raise:bbbbb", "-r", "2"]));
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[0], "12 words remaining, 2 rounds left");
+    assert_eq!(lines[1], "  1. PUDGY  91.8% (possible)");
+    assert_eq!(lines.len(), 6);
+
+    // With one round left only the answer counts
+    let output = stdout(wordlebot("streak").args(["streak", "-g", "raise:bbbbb", "-r", "1"]));
+    assert!(output.contains("  1. COULD   9.6% (possible)"));
+    assert!(output.contains("The suggestion of the strategy, PUDGY: 9.2%"));
+}
+
+#[test]
+fn test_stats() {
+    let home = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("cli-stats");
+    std::fs::remove_dir_all(&home).ok();
+    let output = stdout(wordlebot("stats").arg("stats"));
+    assert!(output.starts_with("No game in"));
+
+    // Games solved with --interactive are kept
+    for _ in 0..2 {
+        wordlebot("stats")
+            .args(["solve", "--interactive", "-s", "raise"])
+            .write_stdin("bybbg\nggggg\n")
+            .assert()
+            .success();
+    }
+    let output = stdout(wordlebot("stats").arg("stats"));
+    assert!(output.starts_with("Played 2  Won 100%  Streak 2  Best streak 2"));
+    assert!(output.contains("RAISE  2.00 turns in 2 games, 2 won"));
+}
+
+#[test]
+fn test_import() {
+    let home = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("cli-import");
+    std::fs::remove_dir_all(&home).ok();
+    let log = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/season.log");
+    let output = stdout(wordlebot("import").args(["import", log]));
+    assert!(output.starts_with("Imported 3 games into"));
+    for line in [
+        "2024-04-29      2    2   3.00    98%  -0.84",
+        "2024-05-06      1    0      -    78%  -2.21",
+        "  Played a gray letter again: 1 times in 1 games",
+    ] {
+        assert!(output.lines().any(|l| l == line), "{} missing", line);
+    }
+
+    // Games that are already in the history are not added again
+    let output = stdout(wordlebot("import").args(["import", log]));
+    assert!(output.contains("Imported 0 games into") && output.contains("3 were already there"));
+    let output = stdout(wordlebot("import").arg("stats"));
+    assert!(output.starts_with("Played 3  Won 66%  Streak 0  Best streak 2"));
+}
+
+#[test]
+fn test_pipe() {
+    let commands = [
+        r#"{"cmd":"guess","guess":"raise:bybbg"}"#,
+        r#"{"id":"x","cmd":"suggest","n":2}"#,
+        r#"{"cmd":"guess","guess":"xxxxx:bbbbb"}"#,
+        r#"{"id":3,"cmd":"reset"}"#,
+    ];
+    let output = stdout(
+        wordlebot("pipe")
+            .arg("pipe")
+            .write_stdin(commands.join("\n") + "\n"),
+    );
+    assert_eq!(
+        output.lines().collect::<Vec<_>>(),
+        [
+            r#"{"ok":true,"remaining":2}"#,
+            r#"{"id":"x","ok":true,"remaining":2,"suggestions":[{"word":"leave","bits":0.9999886,"possible":true},{"word":"algae","bits":0.9999886,"possible":true}]}"#,
+            r#"{"ok":false,"error":"XXXXX is not in the word list"}"#,
+            r#"{"id":3,"ok":true,"remaining":80}"#,
+        ]
+    );
+}
+
+#[test]
+fn test_scoring() {
+    // By the greedy rule CREED colors both Es yellow for LEAVE, which tells
+    // fewer answers apart than the standard pattern
+    let output = stdout(wordlebot("scoring").args([
+        "--scoring",
+        "greedy-yellow",
+        "solve",
+        "-s",
+        "creed",
+        "leave",
+    ]));
+    assert_eq!(games(&output), [vec!["CREED", "LARUM", "LEAVE"]]);
+    let output = stdout(wordlebot("scoring").args(["solve", "-s", "creed", "leave"]));
+    assert_eq!(games(&output), [vec!["CREED", "LEAVE"]]);
+
+    wordlebot("scoring")
+        .args(["--scoring", "fancy", "solve", "leave"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_pack() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("cli-pack-words");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("pack.toml"),
+        "name = \"Sample\"\nwords = \"words.txt\"\nanswers = \"answers.txt\"\n",
+    )
+    .unwrap();
+    std::fs::copy(
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/words.txt"),
+        dir.join("words.txt"),
+    )
+    .unwrap();
+    std::fs::write(dir.join("answers.txt"), "leave\ncreed\nalgae\n").unwrap();
+
+    // Only the answers of the pack remain before the first guess
+    let output = stdout(
+        without_words("pack")
+            .arg("--pack")
+            .arg(&dir)
+            .args(["solve", "-s", "raise", "creed"]),
+    );
+    assert!(output.contains(" RAISE - n before:    3 |"), "{}", output);
+    assert_eq!(games(&output).last().unwrap().last().unwrap(), "CREED");
+
+    // Another word list does not go together with a pack
+    wordlebot("pack")
+        .arg("--pack")
+        .arg(&dir)
+        .args(["solve", "creed"])
+        .assert()
+        .failure();
+}
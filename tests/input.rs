@@ -0,0 +1,178 @@
+//! The same keys typed in different terminals, as the events they report,
+//! have to give the same keys.
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MouseEvent, MouseEventKind,
+};
+use wordlebot::input::Key;
+
+fn event(code: KeyCode, modifiers: KeyModifiers, kind: KeyEventKind) -> Event {
+    Event::Key(KeyEvent {
+        code,
+        modifiers,
+        kind,
+        state: KeyEventState::NONE,
+    })
+}
+
+fn press(code: KeyCode, modifiers: KeyModifiers) -> Event {
+    event(code, modifiers, KeyEventKind::Press)
+}
+
+/// Windows reports every key twice, when it is pressed and released
+fn windows(code: KeyCode, modifiers: KeyModifiers) -> [Event; 2] {
+    [
+        event(code, modifiers, KeyEventKind::Press),
+        event(code, modifiers, KeyEventKind::Release),
+    ]
+}
+
+fn keys(events: &[Event]) -> Vec<Key> {
+    events.iter().filter_map(Key::from_event).collect()
+}
+
+// Typing "ab", deleting a letter, marking a status and confirming the guess
+fn expected_word() -> Vec<Key> {
+    vec![
+        Key::Char('a'),
+        Key::Char('b'),
+        Key::Backspace,
+        Key::Tab,
+        Key::Enter,
+    ]
+}
+
+#[test]
+fn test_unix() {
+    let events = [
+        press(KeyCode::Char('a'), KeyModifiers::NONE),
+        press(KeyCode::Char('b'), KeyModifiers::NONE),
+        press(KeyCode::Backspace, KeyModifiers::NONE),
+        press(KeyCode::Tab, KeyModifiers::NONE),
+        press(KeyCode::Enter, KeyModifiers::NONE),
+    ];
+    assert_eq!(keys(&events), expected_word());
+    assert_eq!(
+        keys(&[
+            press(KeyCode::Char('r'), KeyModifiers::CONTROL),
+            press(KeyCode::Right, KeyModifiers::SHIFT),
+            press(KeyCode::BackTab, KeyModifiers::SHIFT),
+            press(KeyCode::F(2), KeyModifiers::NONE),
+            press(KeyCode::PageDown, KeyModifiers::NONE),
+            press(KeyCode::Esc, KeyModifiers::NONE),
+        ]),
+        vec![
+            Key::Ctrl('r'),
+            Key::ShiftRight,
+            Key::BackTab,
+            Key::F(2),
+            Key::PageDown,
+            Key::Esc
+        ]
+    );
+}
+
+#[test]
+fn test_windows() {
+    let events: Vec<Event> = [
+        windows(KeyCode::Char('a'), KeyModifiers::NONE),
+        windows(KeyCode::Char('b'), KeyModifiers::NONE),
+        windows(KeyCode::Backspace, KeyModifiers::NONE),
+        windows(KeyCode::Tab, KeyModifiers::NONE),
+        windows(KeyCode::Enter, KeyModifiers::NONE),
+    ]
+    .concat();
+    assert_eq!(keys(&events), expected_word());
+
+    // Ctrl+Shift+R and Shift+Tab
+    let events: Vec<Event> = [
+        windows(
+            KeyCode::Char('R'),
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+        ),
+        windows(KeyCode::Tab, KeyModifiers::SHIFT),
+    ]
+    .concat();
+    assert_eq!(keys(&events), vec![Key::Ctrl('r'), Key::BackTab]);
+
+    // AltGr+8 and AltGr+9 for [ and ] on a German keyboard
+    let altgr = KeyModifiers::CONTROL | KeyModifiers::ALT;
+    let events: Vec<Event> = [
+        windows(KeyCode::Char('['), altgr),
+        windows(KeyCode::Char(']'), altgr),
+    ]
+    .concat();
+    assert_eq!(keys(&events), vec![Key::Char('['), Key::Char(']')]);
+}
+
+#[test]
+fn test_control_characters() {
+    // Legacy consoles report some keys as ASCII control characters
+    let events = [
+        press(KeyCode::Char('a'), KeyModifiers::NONE),
+        press(KeyCode::Char('b'), KeyModifiers::NONE),
+        press(KeyCode::Char('\x08'), KeyModifiers::NONE),
+        press(KeyCode::Char('\t'), KeyModifiers::NONE),
+        press(KeyCode::Char('\r'), KeyModifiers::NONE),
+    ];
+    assert_eq!(keys(&events), expected_word());
+    assert_eq!(
+        keys(&[
+            press(KeyCode::Char('\x7f'), KeyModifiers::CONTROL),
+            press(KeyCode::Char('\x12'), KeyModifiers::CONTROL),
+            press(KeyCode::Char('\x10'), KeyModifiers::CONTROL),
+            press(KeyCode::Char('\x1b'), KeyModifiers::NONE),
+        ]),
+        vec![Key::Backspace, Key::Ctrl('r'), Key::Ctrl('p'), Key::Esc]
+    );
+}
+
+#[test]
+fn test_repeat() {
+    // Holding a letter and Backspace repeats them, holding Tab does not
+    let events = [
+        press(KeyCode::Char('a'), KeyModifiers::NONE),
+        event(KeyCode::Char('a'), KeyModifiers::NONE, KeyEventKind::Repeat),
+        event(
+            KeyCode::Char('a'),
+            KeyModifiers::NONE,
+            KeyEventKind::Release,
+        ),
+        press(KeyCode::Backspace, KeyModifiers::NONE),
+        event(KeyCode::Backspace, KeyModifiers::NONE, KeyEventKind::Repeat),
+        press(KeyCode::Tab, KeyModifiers::NONE),
+        event(KeyCode::Tab, KeyModifiers::NONE, KeyEventKind::Repeat),
+    ];
+    assert_eq!(
+        keys(&events),
+        vec![
+            Key::Char('a'),
+            Key::Char('a'),
+            Key::Backspace,
+            Key::Backspace,
+            Key::Tab
+        ]
+    );
+}
+
+#[test]
+fn test_ignored() {
+    // Letters with Ctrl or Alt are not typed, and other events are no keys
+    let events = [
+        press(KeyCode::Char('a'), KeyModifiers::CONTROL),
+        press(KeyCode::Char('a'), KeyModifiers::ALT),
+        press(
+            KeyCode::Char('a'),
+            KeyModifiers::CONTROL | KeyModifiers::ALT,
+        ),
+        press(KeyCode::Home, KeyModifiers::NONE),
+        Event::FocusGained,
+        Event::Resize(80, 24),
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Moved,
+            column: 0,
+            row: 0,
+            modifiers: KeyModifiers::NONE,
+        }),
+    ];
+    assert_eq!(keys(&events), vec![Key::Ctrl('a')]);
+}
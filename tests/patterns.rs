@@ -0,0 +1,121 @@
+//! Invariants between the patterns of [`Word::compare`], their encoding and
+//! the filters of the guesses, for random words. The words are drawn from a
+//! few letters, so that most of them repeat letters, which is where the
+//! coloring of a guess is easy to get wrong.
+use proptest::prelude::*;
+use wordlebot::solver::packed::pack_words;
+use wordlebot::wordle::*;
+
+/// The letters of the random words
+const LETTERS: &[char] = &['a', 'b', 'e', 'o', 's'];
+
+fn word(n_letters: usize) -> impl Strategy<Value = Word> {
+    prop::collection::vec(prop::sample::select(LETTERS), n_letters)
+        .prop_map(|chars| create_word_from_string(&chars.iter().collect::<String>()))
+}
+
+/// A solution, a guess and another word of the same length
+fn words() -> impl Strategy<Value = (Word, Word, Word)> {
+    (MIN_LETTERS..=MAX_LETTERS).prop_flat_map(|n| (word(n), word(n), word(n)))
+}
+
+fn status() -> impl Strategy<Value = Vec<LetterStatus>> {
+    let status = prop::sample::select(
+        &[
+            LetterStatus::Absent,
+            LetterStatus::Misplaced,
+            LetterStatus::Correct,
+        ][..],
+    );
+    prop::collection::vec(status, MIN_LETTERS..=MAX_LETTERS)
+}
+
+/// How often the letter is in the word
+fn count(word: &Word, letter: char) -> usize {
+    word.count_char(&letter)
+}
+
+proptest! {
+    #[test]
+    fn solution_is_valid_for_its_own_colors((solution, guess, _) in words()) {
+        let guess = Guess::from_word(guess, &solution.compare(&guess));
+        prop_assert!(solution.is_valid(&guess));
+        prop_assert_eq!(solution.violated_constraint(&guess), None);
+        // The solution also follows the hints of hard mode
+        prop_assert_eq!(solution.hard_mode_violation(&guess), None);
+    }
+
+    #[test]
+    fn valid_words_are_those_with_the_same_pattern((solution, guess, other) in words()) {
+        let pattern = solution.pattern(&guess);
+        let colored = Guess::from_word(guess, &decode_status(pattern, guess.n_letters()));
+        prop_assert_eq!(other.is_valid(&colored), other.pattern(&guess) == pattern);
+    }
+
+    #[test]
+    fn pattern_is_the_encoded_comparison((solution, guess, _) in words()) {
+        let status = solution.compare(&guess);
+        prop_assert_eq!(status.len(), guess.n_letters());
+        prop_assert_eq!(solution.pattern(&guess), encode_status(&status));
+        let solved = solution.pattern(&guess) == solved_pattern(guess.n_letters());
+        prop_assert_eq!(solved, solution == guess);
+    }
+
+    #[test]
+    fn packed_words_give_the_same_patterns((solution, guess, other) in words()) {
+        let words = [solution, guess, other];
+        let packed = pack_words(&words).unwrap();
+        for (answer, packed_answer) in words.iter().zip(&packed) {
+            for (guess, packed_guess) in words.iter().zip(&packed) {
+                prop_assert_eq!(packed_answer.pattern(packed_guess), answer.pattern(guess));
+            }
+        }
+    }
+
+    #[test]
+    fn tiles_match_the_letters_of_the_solution((solution, guess, _) in words()) {
+        let status = solution.compare(&guess);
+        for (i, letter) in guess.chars().iter().enumerate() {
+            let letter = letter.unwrap();
+            // Green exactly where the letters are the same
+            let correct = solution.chars()[i] == Some(letter);
+            prop_assert_eq!(status[i] == LetterStatus::Correct, correct);
+            // A letter is colored as often as the solution has it, at most
+            let colored = guess
+                .chars()
+                .iter()
+                .zip(&status)
+                .filter(|(c, s)| **c == Some(letter) && **s != LetterStatus::Absent)
+                .count();
+            prop_assert_eq!(colored, count(&guess, letter).min(count(&solution, letter)));
+        }
+    }
+
+    #[test]
+    fn status_encoding_round_trips(status in status()) {
+        let pattern = encode_status(&status);
+        prop_assert!(pattern <= solved_pattern(status.len()));
+        prop_assert_eq!(decode_status(pattern, status.len()), status);
+    }
+
+    #[test]
+    fn pattern_decoding_round_trips(n in MIN_LETTERS..=MAX_LETTERS, seed: u16) {
+        let pattern = seed % (solved_pattern(n) + 1);
+        prop_assert_eq!(encode_status(&decode_status(pattern, n)), pattern);
+    }
+
+    #[test]
+    fn share_grid_round_trips(rows in prop::collection::vec(words(), 1..6)) {
+        let guesses: Vec<Guess> = rows
+            .iter()
+            .map(|(solution, guess, _)| Guess::from_word(*guess, &solution.compare(guess)))
+            // The grid ends with the solved row
+            .take_while(|guess| guess.status != solved_pattern(guess.word.n_letters()))
+            .collect();
+        prop_assume!(!guesses.is_empty());
+        let rows = parse_share_grid(&share_grid(&guesses)).unwrap();
+        let statuses: Vec<Vec<LetterStatus>> =
+            guesses.iter().map(|guess| guess.get_status()).collect();
+        prop_assert_eq!(rows, statuses);
+    }
+}